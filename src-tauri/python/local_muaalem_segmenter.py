@@ -86,10 +86,20 @@ class DeviceContext:
     is_gpu: bool
 
 
-def emit_status_to_stderr(original_stderr_file, step: str, message: str) -> None:
+def emit_status_to_stderr(
+    original_stderr_file,
+    step: str,
+    message: str,
+    current: Optional[int] = None,
+    total: Optional[int] = None,
+) -> None:
     """Write a structured status update to the original stderr stream."""
     try:
-        status_json = json.dumps({"step": step, "message": message}, ensure_ascii=False)
+        status = {"step": step, "message": message}
+        if current is not None and total:
+            status["current"] = current
+            status["total"] = total
+        status_json = json.dumps(status, ensure_ascii=False)
         original_stderr_file.write(f"STATUS:{status_json}\n")
         original_stderr_file.flush()
     except Exception:
@@ -102,9 +112,11 @@ def parse_bool_arg(raw_value: str) -> bool:
 
 
 def configure_device_env(device: str) -> None:
-    """Force CPU execution when requested before torch is imported."""
+    """Force CPU execution or enable MPS CPU fallback before torch is imported."""
     if device.upper() == "CPU":
         os.environ["CUDA_VISIBLE_DEVICES"] = ""
+    elif device.upper() == "MPS":
+        os.environ.setdefault("PYTORCH_ENABLE_MPS_FALLBACK", "1")
 
 
 def ensure_quran_transcript_data_files() -> None:
@@ -162,6 +174,9 @@ def resolve_device(device: str) -> DeviceContext:
     requested = device.upper()
     if requested == "GPU" and torch.cuda.is_available():
         return DeviceContext(name="GPU", torch_device="cuda", is_gpu=True)
+    mps_backend = getattr(torch.backends, "mps", None)
+    if requested == "MPS" and mps_backend is not None and mps_backend.is_available():
+        return DeviceContext(name="MPS", torch_device="mps", is_gpu=True)
     return DeviceContext(name="CPU", torch_device="cpu", is_gpu=False)
 
 
@@ -348,7 +363,6 @@ def decode_phonetic_transcript(
     import torch
 
     model, feature_extractor, tokenizer = get_stt_models(device_context, status_callback)
-    status_callback("transcribing", "Transcribing segments with Muaalem...")
 
     features = feature_extractor(segment_audio, sampling_rate=sample_rate, return_tensors="pt")
     features = {
@@ -510,7 +524,7 @@ def process_audio_full(
     model_name: str,
     device: str,
     include_wbw_timestamps: bool,
-    status_callback: Callable[[str, str], None],
+    status_callback: Callable[..., None],
 ) -> dict:
     """Run the full local Muaalem pipeline and return app-compatible JSON."""
     if model_name != "Muaalem-v3.2":
@@ -549,6 +563,12 @@ def process_audio_full(
         if segment_audio.size == 0:
             continue
 
+        status_callback(
+            "transcribing",
+            f"Transcribing segment {segment_number}/{len(intervals)} with Muaalem...",
+            current=segment_number - 1,
+            total=len(intervals),
+        )
         query = decode_phonetic_transcript(segment_audio, sample_rate, device_context, status_callback)
         if not query:
             continue
@@ -614,7 +634,7 @@ def main() -> int:
     parser.add_argument("--min-speech-ms", type=int, default=1000)
     parser.add_argument("--pad-ms", type=int, default=100)
     parser.add_argument("--model-name", type=str, default="Muaalem-v3.2", choices=MUAALEM_MODEL_CHOICES)
-    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU"])
+    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU", "MPS"])
     parser.add_argument("--include-wbw-timestamps", type=str, default="true")
     parser.add_argument("--verbose", action="store_true")
     args = parser.parse_args()
@@ -663,8 +683,8 @@ def main() -> int:
             model_name=args.model_name,
             device=args.device,
             include_wbw_timestamps=parse_bool_arg(args.include_wbw_timestamps),
-            status_callback=lambda step, message: emit_status_to_stderr(
-                original_stderr_file, step, message
+            status_callback=lambda step, message, **progress: emit_status_to_stderr(
+                original_stderr_file, step, message, **progress
             ),
         )
     except Exception as error:
@@ -4,6 +4,7 @@ Local wrapper for Quran Multi-Aligner.
 
 Runs the Multi-Aligner pipeline from a local checkout and returns JSON only.
 Status updates are emitted to stderr as: STATUS:{"step":"...","message":"..."}
+with optional "percent" (global 0-100) or "current"/"total" (progress within the step).
 """
 
 import argparse
@@ -39,9 +40,20 @@ DATA_FILE_URLS = {
 }
 
 
-def emit_status(original_stderr, step: str, message: str) -> None:
+def emit_status(
+    original_stderr,
+    step: str,
+    message: str,
+    current: Optional[int] = None,
+    total: Optional[int] = None,
+    **extra,
+) -> None:
     try:
-        payload = json.dumps({"step": step, "message": message}, ensure_ascii=False)
+        status = {"step": step, "message": message, **extra}
+        if current is not None and total:
+            status["current"] = current
+            status["total"] = total
+        payload = json.dumps(status, ensure_ascii=False)
         original_stderr.write(f"STATUS:{payload}\n")
         original_stderr.flush()
     except Exception:
@@ -295,6 +307,169 @@ def validate_local_data_pickles(status_writer=None) -> Optional[str]:
     return None
 
 
+MODEL_DOWNLOAD_POLL_SECONDS = 0.5
+MODEL_DOWNLOAD_EXTENSIONS = (".json", ".safetensors", ".txt", ".model")
+
+
+def prefetch_hf_model(original_stderr, repo_id: str, token: str) -> None:
+    """Download a model repo into the HF cache while reporting byte progress.
+
+    Files are fetched one by one with hf_hub_download; a polling thread sums the
+    `.incomplete` blobs in the repo cache so the UI gets a moving byte counter.
+    Already cached files are skipped, so later runs are a no-op.
+    """
+    import threading
+
+    from huggingface_hub import HfApi, hf_hub_download, try_to_load_from_cache
+    from huggingface_hub.constants import HF_HUB_CACHE
+
+    info = HfApi(token=token or None).model_info(repo_id, files_metadata=True)
+    siblings = [(s.rfilename, s.size or 0) for s in (info.siblings or [])]
+    has_safetensors = any(name.endswith(".safetensors") for name, _ in siblings)
+    wanted = [
+        (name, size)
+        for name, size in siblings
+        if name.endswith(MODEL_DOWNLOAD_EXTENSIONS) or (name.endswith(".bin") and not has_safetensors)
+    ]
+    pending = [
+        (name, size)
+        for name, size in wanted
+        if not isinstance(try_to_load_from_cache(repo_id, name), str)
+    ]
+    if not pending:
+        return
+
+    model_label = repo_id.split("/")[-1]
+    total_bytes = sum(size for _, size in pending)
+    completed = {"bytes": 0}
+    blobs_dir = Path(HF_HUB_CACHE) / f"models--{repo_id.replace('/', '--')}" / "blobs"
+    stop = threading.Event()
+
+    def report() -> None:
+        partial = 0
+        if blobs_dir.exists():
+            partial = sum(p.stat().st_size for p in blobs_dir.glob("*.incomplete"))
+        emit_status(
+            original_stderr,
+            "model_download",
+            f"Downloading model {model_label}...",
+            current=min(completed["bytes"] + partial, total_bytes),
+            total=total_bytes,
+            model=model_label,
+        )
+
+    def poll() -> None:
+        while not stop.wait(MODEL_DOWNLOAD_POLL_SECONDS):
+            report()
+
+    poller = threading.Thread(target=poll, daemon=True)
+    poller.start()
+    try:
+        for name, size in pending:
+            hf_hub_download(repo_id, name, token=token or None)
+            completed["bytes"] += size
+    finally:
+        stop.set()
+        poller.join()
+    report()
+
+
+def prefetch_models(original_stderr, model_name: str, token: str) -> None:
+    """Pre-download the segmenter and phoneme ASR models with visible progress."""
+    from config import PHONEME_ASR_MODELS, SEGMENTER_MODEL
+
+    for repo_id in (SEGMENTER_MODEL, PHONEME_ASR_MODELS.get(model_name)):
+        if not repo_id:
+            continue
+        try:
+            prefetch_hf_model(original_stderr, repo_id, token)
+        except Exception as exc:
+            # The pipeline downloads missing models itself; only the progress is lost.
+            emit_status(
+                original_stderr,
+                "model_download",
+                f"Could not pre-download {repo_id} ({exc}); it will be fetched during loading.",
+            )
+
+
+def parse_ayah_range_hint(raw: str) -> Optional[tuple]:
+    if not raw:
+        return None
+    start, _, end = raw.partition("-")
+    return int(start), int(end or start)
+
+
+def apply_anchor_hint(surah_hint: int, ayah_range_hint: Optional[tuple]) -> None:
+    """Replace the initial n-gram anchor vote with the user-provided surah/ayah.
+
+    Only the first anchor search is overridden; later re-anchoring (after alignment
+    failures mid-recording) keeps using the original voting.
+    """
+    from src.alignment import phoneme_anchor
+
+    original_anchor = phoneme_anchor.find_anchor_by_voting
+    start_ayah = ayah_range_hint[0] if ayah_range_hint else 1
+
+    def hinted_anchor(*args, **kwargs):
+        phoneme_anchor.find_anchor_by_voting = original_anchor
+        return surah_hint, start_ayah
+
+    phoneme_anchor.find_anchor_by_voting = hinted_anchor
+
+
+def report_asr_batches(original_stderr) -> None:
+    """Emit one status per phoneme ASR batch so the inference phase reports current/total.
+
+    The vendored pipeline has no progress hook; its batch loop iterates the batch list,
+    so that list is wrapped to report each batch as it is consumed.
+    """
+    from src.alignment import phoneme_asr
+
+    original_transcribe = phoneme_asr._transcribe_batch_pytorch
+
+    class ReportingBatches(list):
+        def __iter__(self):
+            total = len(self)
+            for index, batch in enumerate(list.__iter__(self)):
+                emit_status(
+                    original_stderr,
+                    "inference",
+                    f"Transcribing batch {index + 1}/{total}...",
+                    current=index,
+                    total=total,
+                )
+                yield batch
+
+    def reporting_transcribe(segment_audios, durations, batches, *args, **kwargs):
+        return original_transcribe(segment_audios, durations, ReportingBatches(batches), *args, **kwargs)
+
+    phoneme_asr._transcribe_batch_pytorch = reporting_transcribe
+
+
+def enable_mps_backend() -> bool:
+    """Load the Multi-Aligner models on Apple MPS instead of CUDA/CPU.
+
+    The vendored model loaders only know about CUDA, so their device pickers are
+    replaced. Ops missing from MPS fall back to the CPU.
+    """
+    os.environ.setdefault("PYTORCH_ENABLE_MPS_FALLBACK", "1")
+    import torch
+
+    mps_backend = getattr(torch.backends, "mps", None)
+    if mps_backend is None or not mps_backend.is_available():
+        return False
+
+    from src.alignment import phoneme_asr
+    from src.segmenter import segmenter_model
+
+    def mps_device_and_dtype():
+        return torch.device("mps"), torch.float32
+
+    phoneme_asr._get_device_and_dtype = mps_device_and_dtype
+    segmenter_model._get_device_and_dtype = mps_device_and_dtype
+    return True
+
+
 def main() -> int:
     parser = argparse.ArgumentParser(description="Local Quran Multi-Aligner wrapper")
     parser.add_argument("audio_path", help="Path to audio file")
@@ -302,8 +477,10 @@ def main() -> int:
     parser.add_argument("--min-speech-ms", type=int, default=1000)
     parser.add_argument("--pad-ms", type=int, default=100)
     parser.add_argument("--model-name", type=str, default="Base", choices=["Base", "Large"])
-    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU"])
+    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU", "MPS"])
     parser.add_argument("--hf-token", type=str, default="")
+    parser.add_argument("--surah-hint", type=int, default=0)
+    parser.add_argument("--ayah-range-hint", type=str, default="")
     parser.add_argument("--verbose", action="store_true")
     args = parser.parse_args()
 
@@ -369,19 +546,41 @@ def main() -> int:
                 error_payload = {"error": dep_error}
                 raise RuntimeError(dep_error)
 
+            prefetch_models(original_stderr, args.model_name, token)
+
             emit_status(original_stderr, "loading", "Loading audio file...")
             sample_rate, audio = load_audio(args.audio_path)
 
             emit_status(original_stderr, "pipeline", "Running local Multi-Aligner pipeline...")
             from src.pipeline import process_audio
 
+            pipeline_device = args.device
+            if args.device == "MPS":
+                if not enable_mps_backend():
+                    emit_status(original_stderr, "pipeline", "MPS is not available, falling back to CPU...")
+                    pipeline_device = "CPU"
+                else:
+                    # The pipeline only distinguishes "gpu" from "cpu".
+                    pipeline_device = "GPU"
+
+            if args.surah_hint:
+                ayah_range_hint = parse_ayah_range_hint(args.ayah_range_hint)
+                apply_anchor_hint(args.surah_hint, ayah_range_hint)
+                emit_status(
+                    original_stderr,
+                    "pipeline",
+                    f"Using surah hint {args.surah_hint}"
+                    + (f" (ayahs {ayah_range_hint[0]}-{ayah_range_hint[1]})" if ayah_range_hint else ""),
+                )
+
+            report_asr_batches(original_stderr)
             result = process_audio(
                 (sample_rate, audio),
                 int(args.min_silence_ms),
                 int(args.min_speech_ms),
                 int(args.pad_ms),
                 args.model_name,
-                args.device,
+                pipeline_device,
             )
 
             if not isinstance(result, tuple) or len(result) < 2:
@@ -43,10 +43,20 @@ OPEN_MULTI_MODELS = {
 }
 
 
-def emit_status_to_stderr(original_stderr_file, step: str, message: str) -> None:
+def emit_status_to_stderr(
+    original_stderr_file,
+    step: str,
+    message: str,
+    current: Optional[int] = None,
+    total: Optional[int] = None,
+) -> None:
     """Write a structured status update to the original stderr stream."""
     try:
-        status_json = json.dumps({"step": step, "message": message}, ensure_ascii=False)
+        status = {"step": step, "message": message}
+        if current is not None and total:
+            status["current"] = current
+            status["total"] = total
+        status_json = json.dumps(status, ensure_ascii=False)
         original_stderr_file.write(f"STATUS:{status_json}\n")
         original_stderr_file.flush()
     except Exception:
@@ -229,8 +239,8 @@ def main() -> int:
             min_speech_ms=args.min_speech_ms,
             pad_ms=args.pad_ms,
             whisper_model=whisper_model,
-            status_callback=lambda step, message: emit_status_to_stderr(
-                original_stderr_file, step, message
+            status_callback=lambda step, message, **progress: emit_status_to_stderr(
+                original_stderr_file, step, message, **progress
             ),
         )
 
@@ -195,8 +195,14 @@ Examples:
     parser.add_argument("--pad-ms", type=int, default=50,
                         help="Padding around segments (ms)")
     parser.add_argument("--whisper-model", type=str, default="base",
-                        choices=["tiny", "base", "medium", "large"],
-                        help="Whisper model size: tiny (~60MB), base (~150MB), medium (~800MB), large (~3GB)")
+                        help="Whisper model size: tiny (~60MB), base (~150MB), medium (~800MB), large (~3GB), "
+                             "or a local fine-tuned checkpoint directory")
+    parser.add_argument("--surah-hint", type=int, default=0,
+                        help="Known surah number (accepted for API parity, not used by the legacy matcher)")
+    parser.add_argument("--ayah-range-hint", type=str, default="",
+                        help="Known ayah range as START-END (accepted for API parity)")
+    parser.add_argument("--include-wbw-timestamps", type=str, default="false",
+                        help="Add per-word timestamps (estimated from the matched Quran words) to each segment")
     parser.add_argument("--verbose", "-v", action="store_true",
                         help="Show verbose output to stderr")
     
@@ -207,6 +213,11 @@ Examples:
         print(json.dumps({"error": f"Audio file not found: {args.audio_path}"}))
         sys.exit(1)
     
+    # Whisper model must be a known size or a local checkpoint directory
+    if args.whisper_model not in ("tiny", "base", "medium", "large") and not os.path.isdir(args.whisper_model):
+        print(json.dumps({"error": f"Invalid whisper model: {args.whisper_model}"}))
+        sys.exit(1)
+    
     # Check dependencies
     if not check_dependencies():
         print(json.dumps({"error": "Missing required Python packages"}))
@@ -231,11 +242,15 @@ Examples:
     original_stderr_fd = os.dup(2)
     original_stderr_file = os.fdopen(original_stderr_fd, 'w', encoding='utf-8')
     
-    def emit_status_to_stderr(step: str, message: str):
+    def emit_status_to_stderr(step: str, message: str, current=None, total=None):
         """Write status in a parsable format to original stderr."""
         try:
             import json
-            status_json = json.dumps({"step": step, "message": message}, ensure_ascii=False)
+            status = {"step": step, "message": message}
+            if current is not None and total:
+                status["current"] = current
+                status["total"] = total
+            status_json = json.dumps(status, ensure_ascii=False)
             original_stderr_file.write(f"STATUS:{status_json}\n")
             original_stderr_file.flush()
         except:
@@ -275,6 +290,16 @@ Examples:
             status_callback=emit_status_to_stderr
         )
         
+        if str(args.include_wbw_timestamps).strip().lower() in {"1", "true", "yes", "on"}:
+            emit_status_to_stderr("wbw", "Generating local word-by-word timestamps...")
+            from local_open_multi_aligner_segmenter import inject_local_word_timestamps
+            result = inject_local_word_timestamps(result)
+
+        # segment_processor picks CUDA whenever available; report the device
+        # actually used so the backend records speed stats for the right device.
+        import torch
+        result["device"] = "GPU" if torch.cuda.is_available() else "CPU"
+        
     except Exception as e:
         import traceback
         error_details = traceback.format_exc()
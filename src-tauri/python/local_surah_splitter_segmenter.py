@@ -26,10 +26,20 @@ MODEL_IDS = {
 _MUSHAF_WORDS_CACHE: dict[int, dict[int, list[str]]] = {}
 
 
-def emit_status_to_stderr(original_stderr_file, step: str, message: str) -> None:
+def emit_status_to_stderr(
+    original_stderr_file,
+    step: str,
+    message: str,
+    current: Optional[int] = None,
+    total: Optional[int] = None,
+) -> None:
     """Écrit une mise à jour de statut structurée vers le flux stderr original."""
     try:
-        status_json = json.dumps({"step": step, "message": message}, ensure_ascii=False)
+        status = {"step": step, "message": message}
+        if current is not None and total:
+            status["current"] = current
+            status["total"] = total
+        status_json = json.dumps(status, ensure_ascii=False)
         original_stderr_file.write(f"STATUS:{status_json}\n")
         original_stderr_file.flush()
     except Exception:
@@ -76,7 +86,10 @@ def resolve_model_id(model_name: str) -> str:
 
 
 def resolve_device(device: str) -> str:
-    """Retourne le device attendu par WhisperX selon la disponibilité CUDA."""
+    """Retourne le device attendu par WhisperX selon la disponibilité CUDA.
+
+    CTranslate2 (moteur de WhisperX) n'a pas de backend MPS : `MPS` tourne sur CPU.
+    """
     if device.upper() != "GPU":
         return "cpu"
 
@@ -312,7 +325,7 @@ def main() -> int:
     parser.add_argument("--min-speech-ms", type=int, default=1000)
     parser.add_argument("--pad-ms", type=int, default=100)
     parser.add_argument("--model-name", type=str, default="SurahSplitter-Base-Quran", choices=MODEL_IDS.keys())
-    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU"])
+    parser.add_argument("--device", type=str, default="GPU", choices=["GPU", "CPU", "MPS"])
     parser.add_argument("--surah", type=int, default=0)
     parser.add_argument("--include-wbw-timestamps", type=str, default="true")
     parser.add_argument("--verbose", action="store_true")
@@ -353,8 +366,8 @@ def main() -> int:
             device=args.device,
             surah_number=args.surah if args.surah > 0 else None,
             include_wbw_timestamps=parse_bool_arg(args.include_wbw_timestamps),
-            status_callback=lambda step, message: emit_status_to_stderr(
-                original_stderr_file, step, message
+            status_callback=lambda step, message, **progress: emit_status_to_stderr(
+                original_stderr_file, step, message, **progress
             ),
         )
     except Exception as error:
@@ -533,7 +533,12 @@ def transcribe_segments_batched(
         for start_idx in range(0, total, effective_batch):
             end_idx = min(start_idx + effective_batch, total)
             if status_callback:
-                status_callback("whisper", f"Transcribing segment {start_idx + 1}/{total}...")
+                status_callback(
+                    "whisper",
+                    f"Transcribing segment {start_idx + 1}/{total}...",
+                    current=start_idx,
+                    total=total,
+                )
 
             # Resample batch to 16kHz
             resampled = []
@@ -759,14 +764,15 @@ def process_audio_full(
     Returns JSON-serializable dict with segments.
 
     Args:
-        status_callback: Optional callable(step: str, message: str) to report progress
+        status_callback: Optional callable(step: str, message: str, current=None, total=None)
+            to report progress; current/total are given per transcription batch
     """
     import time
 
-    def emit_status(step: str, message: str):
+    def emit_status(step: str, message: str, **progress):
         """Emit status update."""
         if status_callback:
-            status_callback(step, message)
+            status_callback(step, message, **progress)
         print(f"[STATUS] {step}: {message}")
 
     total_start = time.time()
@@ -807,7 +813,12 @@ def process_audio_full(
         segment_audios.append(audio[start_sample:end_sample])
 
     # Step 2: Whisper transcription (batched)
-    emit_status("whisper", f"Transcribing segment 1/{len(segment_audios)}...")
+    emit_status(
+        "whisper",
+        f"Transcribing segment 1/{len(segment_audios)}...",
+        current=0,
+        total=len(segment_audios),
+    )
     whisper_start = time.time()
     # Get model name from mapping or use default
     whisper_model_name = WHISPER_MODELS.get(whisper_model, whisper_model)
@@ -0,0 +1,125 @@
+#!/usr/bin/env python3
+"""
+Long-lived segmentation worker.
+
+Loads one local engine script (e.g. local_segmenter.py) once and runs its
+`main()` for every request, so imported libraries and the models cached by
+the engine stay in memory between segmentation runs.
+
+Usage:
+    python segmentation_worker.py <engine_script.py>
+
+Protocol (one JSON object per line):
+    stdout: {"ready": true}                                  once the engine is imported
+    stdin:  {"id": 1, "args": ["audio.wav", "--pad-ms", "50"]}
+    stdout: {"id": 1, "exitCode": 0, "output": "<what the engine printed>"}
+    stdin:  {"shutdown": true}                               (or EOF) to exit
+
+STATUS:{...} progress lines keep going to stderr exactly like one-shot runs.
+"""
+
+import importlib.util
+import io
+import json
+import os
+import sys
+import traceback
+from pathlib import Path
+
+
+def open_protocol_channel():
+    """Keep the real stdout for protocol replies and send fd 1 to stderr.
+
+    Libraries (torch, transformers, ...) sometimes print straight to fd 1; once
+    fd 1 points at stderr, those writes can no longer corrupt the protocol.
+    """
+    protocol = os.fdopen(os.dup(1), "w", encoding="utf-8")
+    os.dup2(2, 1)
+    return protocol
+
+
+def load_engine(script_path: Path):
+    """Import the engine script as a module without running its __main__ block."""
+    sys.path.insert(0, str(script_path.parent))
+    spec = importlib.util.spec_from_file_location("segmentation_engine", script_path)
+    module = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(module)
+    if not callable(getattr(module, "main", None)):
+        raise RuntimeError(f"{script_path.name} has no main() function")
+    return module
+
+
+def exit_code_of(value) -> int:
+    """Map a main() return value or SystemExit code to a process-like exit code."""
+    if value is None:
+        return 0
+    if isinstance(value, int):
+        return value
+    # sys.exit("message") prints the message and exits with 1
+    print(value, file=sys.stderr)
+    return 1
+
+
+def run_request(engine, script_path: Path, args) -> dict:
+    """Run the engine main() with the given CLI args and capture what it prints."""
+    captured = io.StringIO()
+    previous_stdout = sys.stdout
+    previous_argv = sys.argv
+    sys.stdout = captured
+    sys.argv = [str(script_path)] + [str(arg) for arg in args]
+    try:
+        exit_code = exit_code_of(engine.main())
+    except SystemExit as exc:
+        exit_code = exit_code_of(exc.code)
+    except Exception as exc:
+        exit_code = 1
+        captured = io.StringIO()
+        captured.write(json.dumps({"error": str(exc), "details": traceback.format_exc()}))
+    finally:
+        sys.stdout = previous_stdout
+        sys.argv = previous_argv
+    return {"exitCode": exit_code, "output": captured.getvalue()}
+
+
+def main() -> int:
+    if len(sys.argv) != 2:
+        print("Usage: segmentation_worker.py <engine_script.py>", file=sys.stderr)
+        return 2
+
+    protocol = open_protocol_channel()
+
+    def reply(payload: dict):
+        protocol.write(json.dumps(payload, ensure_ascii=False) + "\n")
+        protocol.flush()
+
+    script_path = Path(sys.argv[1]).absolute()
+    try:
+        engine = load_engine(script_path)
+    except Exception as exc:
+        print(f"[worker] Failed to load {script_path}: {exc}", file=sys.stderr)
+        traceback.print_exc()
+        return 1
+
+    reply({"ready": True})
+
+    requests = io.TextIOWrapper(sys.stdin.buffer, encoding="utf-8")
+    for line in requests:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            request = json.loads(line)
+        except ValueError as exc:
+            print(f"[worker] Ignoring invalid request: {exc}", file=sys.stderr)
+            continue
+        if request.get("shutdown"):
+            break
+        response = run_request(engine, script_path, request.get("args", []))
+        response["id"] = request.get("id")
+        reply(response)
+
+    return 0
+
+
+if __name__ == "__main__":
+    raise SystemExit(main())
@@ -15,28 +15,51 @@ pub fn register_invoke_handler(builder: tauri::Builder<tauri::Wry>) -> tauri::Bu
         commands::files::get_new_file_path,
         commands::files::save_binary_file,
         commands::files::save_file,
+        commands::files::read_file_head,
+        commands::files::path_info,
+        commands::files::validate_output_path,
         commands::files::copy_file,
         commands::files::copy_file_with_progress,
         commands::files::download_file,
+        commands::files::download_files,
         commands::files::delete_file,
         commands::files::move_file,
+        commands::files::cleanup_temp_files,
+        commands::projects::load_and_validate_project,
+        commands::projects::relink_assets,
+        commands::projects::save_project_transaction,
         commands::files::send_http_get,
         commands::files::send_http_text,
         commands::media::get_system_fonts,
+        commands::media::refresh_system_fonts,
+        commands::media::get_arabic_fonts,
+        commands::media::get_fonts_by_category,
         commands::media::get_system_font_sources,
+        commands::media::register_custom_font,
+        commands::media::import_font_to_project,
+        commands::media::get_font_metrics,
+        commands::media::font_covers_text,
+        commands::media::get_font_variations,
+        commands::media::render_font_preview,
         commands::media::open_directory,
         commands::media::open_explorer_with_file_selected,
         commands::media::get_video_dimensions,
         commands::media::is_constant_bitrate,
+        commands::media::probe_decodable,
         exporter::commands::export_video,
         exporter::commands::cancel_export,
         exporter::commands::concat_videos,
+        exporter::commands::write_ffconcat,
         commands::media::convert_audio_to_cbr,
         commands::media::audio_timestamp_stretch_ms,
         commands::media::normalize_audio_timestamps,
         commands::media::cut_audio,
         commands::media::cut_video,
         commands::media::concat_audio,
+        commands::media::downmix_to_stereo,
+        commands::media::split_audio_by_duration,
+        commands::media::cancel_ffmpeg_job,
+        commands::media::kill_tracked_process,
         commands::segmentation::segment_quran_audio,
         commands::segmentation::estimate_segmentation_duration,
         commands::segmentation::get_segmentation_mfa_timestamps_session,
@@ -45,20 +68,55 @@ pub fn register_invoke_handler(builder: tauri::Builder<tauri::Wry>) -> tauri::Bu
         commands::segmentation::segment_quran_audio_local_multi,
         commands::segmentation::segment_quran_audio_local_muaalem,
         commands::segmentation::segment_quran_audio_local_surah_splitter,
+        commands::segmentation::segment_quran_audio_batch,
+        commands::segmentation::cancel_segmentation_batch,
+        commands::segmentation::merge_segmentation_results,
+        commands::segmentation::segmentation_to_srt,
+        commands::segmentation::estimate_local_segmentation,
+        commands::segmentation::start_engine_health_monitor,
+        commands::segmentation::stop_engine_health_monitor,
         commands::segmentation::generate_hifz_audio,
+        commands::segmentation::set_segmentation_mock,
+        commands::segmentation::set_segmentation_worker_mode,
+        commands::segmentation::shutdown_segmentation_worker,
+        commands::segmentation::get_segmentation_logs,
+        commands::segmentation::get_latest_install_log,
+        commands::segmentation::ping_segmentation_cloud,
         commands::segmentation::preload_recitations,
         commands::segmentation::preload_segments,
         commands::segmentation::preload_audio_recitations,
         commands::segmentation::preload_audio,
         commands::segmentation::check_local_segmentation_ready,
         commands::segmentation::install_local_segmentation_deps,
+        commands::segmentation::install_local_segmentation_engines,
+        commands::segmentation::install_managed_python,
+        commands::segmentation::cancel_local_install,
+        commands::segmentation::get_local_install_manifest,
+        commands::segmentation::uninstall_local_segmentation_deps,
+        commands::segmentation::migrate_local_envs,
+        commands::segmentation::get_local_engine_storage_info,
+        commands::segmentation::get_local_engine_versions,
+        commands::segmentation::verify_multi_aligner_data,
+        commands::segmentation::get_app_storage_report,
+        commands::segmentation::clear_app_cache,
+        commands::segmentation::detect_compute_devices,
+        commands::segmentation::get_model_cache_info,
+        commands::segmentation::clear_model_cache,
+        commands::segmentation::set_hf_cache_dir,
         commands::discord::init_discord_rpc,
         commands::discord::update_discord_activity,
         commands::discord::clear_discord_activity,
         commands::discord::close_discord_rpc,
+        commands::discord::discord_rpc_status,
         commands::screenshot::capture_window_screenshot,
         commands::waveform::get_audio_waveform,
         commands::diagnostics::diagnose_media_binaries,
+        commands::diagnostics::get_ffmpeg_capabilities,
+        commands::diagnostics::diagnose_python,
+        commands::diagnostics::run_full_diagnostics,
+        commands::diagnostics::get_app_info,
+        commands::logging::set_log_level,
+        commands::logging::get_log_file_path,
         commands::stock_media::search_stock_media
     ])
 }
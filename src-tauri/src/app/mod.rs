@@ -1,6 +1,8 @@
 use tauri::Manager;
 
 use crate::binaries;
+use crate::commands::logging;
+use crate::segmentation;
 
 mod invoke;
 
@@ -40,16 +42,20 @@ pub fn run() {
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
 
-            // Activation du logging Tauri en debug pour faciliter le diagnostic local.
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // Journal tournant du backend : actif en debug, opt-in en release (`set_log_level`).
+            app.handle().plugin(logging::build_log_plugin())?;
+            logging::apply_saved_log_level(app.handle());
+
+            // Dossier de cache Hugging Face choisi lors d'une session précédente.
+            segmentation::apply_saved_hf_cache_dir(app.handle());
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Le thread de surveillance des moteurs ne doit pas survivre à l'application.
+            if let tauri::RunEvent::Exit = event {
+                let _ = segmentation::stop_engine_health_monitor();
+            }
+        });
 }
@@ -1,11 +1,21 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use sysinfo::{Disks, System};
+use tauri::Manager;
+
 use crate::binaries;
-use crate::utils::process::configure_command_no_window;
+use crate::segmentation;
+use crate::utils::process::{
+    configure_command_no_window, output_with_timeout, redact_user_data, PROBE_TIMEOUT,
+};
 
 const FFPROBE_NOT_FOUND_ERROR: &str = "FFPROBE_NOT_FOUND";
 const FFPROBE_NOT_EXECUTABLE_ERROR: &str = "FFPROBE_NOT_EXECUTABLE";
 const FFPROBE_EXEC_FAILED_ERROR_PREFIX: &str = "FFPROBE_EXEC_FAILED:";
+/// Filtres utilisés par l'export et absents de certaines builds minimales de ffmpeg.
+const KEY_FFMPEG_FILTERS: [&str; 4] = ["xfade", "subtitles", "loudnorm", "drawtext"];
 
 /// Résultat de diagnostic d'un binaire multimédia.
 #[derive(serde::Serialize)]
@@ -24,6 +34,89 @@ pub struct BinaryDiagnosticResult {
     pub version_output: Option<String>,
 }
 
+/// Résultat de diagnostic de l'interpréteur Python utilisé par la segmentation locale.
+#[derive(serde::Serialize)]
+pub struct PythonDiagnosticResult {
+    /// Nom logique (`python`).
+    pub name: String,
+    /// Exécutable de l'interpréteur retenu si disponible.
+    pub resolved_path: Option<String>,
+    /// Code d'erreur stable si échec.
+    pub error_code: Option<String>,
+    /// Détail d'erreur si échec.
+    pub error_details: Option<String>,
+    /// Candidats sondés dans l'ordre de résolution (rejetés et retenu).
+    pub attempts: Vec<binaries::BinaryResolutionAttempt>,
+    /// Version de l'interpréteur retenu.
+    pub version_output: Option<String>,
+    /// Version minimale requise (ex: `3.10`).
+    pub minimum_version: String,
+    /// `true` si un interpréteur respecte la version minimale.
+    pub meets_minimum: bool,
+}
+
+/// Informations système incluses dans le rapport de diagnostic complet.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemDiagnosticInfo {
+    /// Système d'exploitation (`windows`, `macos`, `linux`).
+    pub os: String,
+    /// Architecture (`x86_64`, `aarch64`...).
+    pub arch: String,
+    /// Version lisible du système si disponible.
+    pub os_version: Option<String>,
+    /// Version du noyau si disponible.
+    pub kernel_version: Option<String>,
+    /// Mémoire vive totale (octets).
+    pub total_memory_bytes: u64,
+    /// Nombre de cœurs logiques.
+    pub cpu_count: usize,
+}
+
+/// Version de l'application et plateforme, pour le signalement de bugs.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    /// Version de l'application (`CARGO_PKG_VERSION`).
+    pub version: String,
+    /// Système d'exploitation (`windows`, `macos`, `linux`).
+    pub os: String,
+    /// Architecture (`x86_64`, `aarch64`...).
+    pub arch: String,
+    /// Version de Tauri embarquée.
+    pub tauri_version: String,
+}
+
+/// Encodeurs, décodeurs et filtres disponibles dans la build de ffmpeg utilisée.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegCapabilities {
+    /// Chemin de l'exécutable ffmpeg interrogé.
+    pub ffmpeg_path: String,
+    /// Première ligne de `ffmpeg -version`.
+    pub version: Option<String>,
+    /// Noms des encodeurs (`libx264`, `aac`...).
+    pub encoders: Vec<String>,
+    /// Noms des décodeurs.
+    pub decoders: Vec<String>,
+    /// Noms des filtres.
+    pub filters: Vec<String>,
+    /// Disponibilité des filtres clés de l'export (`xfade`, `subtitles`, `loudnorm`, `drawtext`).
+    pub key_filters: BTreeMap<String, bool>,
+}
+
+/// Espace disque du volume qui contient les données de l'application.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpaceInfo {
+    /// Point de montage du volume.
+    pub mount_point: String,
+    /// Taille totale (octets).
+    pub total_bytes: u64,
+    /// Espace libre (octets).
+    pub available_bytes: u64,
+}
+
 /// Convertit une erreur de résolution ffprobe en message attendu côté frontend.
 pub fn map_ffprobe_resolve_error(err: binaries::BinaryResolveError) -> String {
     match err.code.as_str() {
@@ -84,3 +177,318 @@ pub fn diagnose_media_binaries() -> Vec<BinaryDiagnosticResult> {
         })
         .collect()
 }
+
+/// Extrait les noms de codecs de `ffmpeg -encoders` / `-decoders` (lignes après `------`).
+fn parse_ffmpeg_codec_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Extrait les noms de filtres de `ffmpeg -filters` (`TSC name A->A description`).
+fn parse_ffmpeg_filter_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let (_flags, name, io) = (tokens.next()?, tokens.next()?, tokens.next()?);
+            io.contains("->").then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Lance `ffmpeg -hide_banner <list_arg>` et retourne sa sortie standard.
+fn read_ffmpeg_list(ffmpeg_path: &str, list_arg: &str) -> Result<String, String> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(["-hide_banner", list_arg]);
+    configure_command_no_window(&mut cmd);
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+        .map_err(|e| format!("Failed to run ffmpeg {}: {}", list_arg, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg {} failed: {}",
+            list_arg,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Commande IPC listant les encodeurs, décodeurs et filtres de la build de ffmpeg résolue.
+///
+/// Les builds embarquées et système diffèrent : l'export peut vérifier un filtre avant de
+/// l'utiliser et afficher un message clair plutôt qu'une erreur ffmpeg.
+#[tauri::command]
+pub async fn get_ffmpeg_capabilities() -> Result<FfmpegCapabilities, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let ffmpeg_path = binaries::resolve_binary("ffmpeg")
+            .ok_or_else(|| "ffmpeg binary not found".to_string())?;
+        let encoders = parse_ffmpeg_codec_list(&read_ffmpeg_list(&ffmpeg_path, "-encoders")?);
+        let decoders = parse_ffmpeg_codec_list(&read_ffmpeg_list(&ffmpeg_path, "-decoders")?);
+        let filters = parse_ffmpeg_filter_list(&read_ffmpeg_list(&ffmpeg_path, "-filters")?);
+        let key_filters = KEY_FFMPEG_FILTERS
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    filters.iter().any(|filter| filter == name),
+                )
+            })
+            .collect();
+        Ok(FfmpegCapabilities {
+            version: get_binary_version_line(&ffmpeg_path),
+            ffmpeg_path,
+            encoders,
+            decoders,
+            filters,
+            key_filters,
+        })
+    })
+    .await
+    .map_err(|e| format!("ffmpeg capabilities task failed: {}", e))?
+}
+
+/// Commande IPC de diagnostic de l'interpréteur Python des moteurs de segmentation locale.
+#[tauri::command]
+pub async fn diagnose_python() -> Result<PythonDiagnosticResult, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let (min_major, min_minor) = (
+            segmentation::MIN_LOCAL_PYTHON_MAJOR,
+            segmentation::MIN_LOCAL_PYTHON_MINOR,
+        );
+        let (selected, probes) = segmentation::probe_system_python_candidates(min_major, min_minor);
+        let found_any = probes.iter().any(|probe| probe.interpreter.is_some());
+        let attempts = probes
+            .into_iter()
+            .map(|probe| {
+                let (outcome, detail) = match probe.interpreter {
+                    Some(interpreter)
+                        if segmentation::python_version_meets_min(
+                            interpreter.major,
+                            interpreter.minor,
+                            min_major,
+                            min_minor,
+                        ) =>
+                    {
+                        ("ok", Some(interpreter.executable))
+                    }
+                    Some(interpreter) => (
+                        "version_too_old",
+                        Some(format!(
+                            "Python {}.{}.{} ({})",
+                            interpreter.major,
+                            interpreter.minor,
+                            interpreter.patch,
+                            interpreter.executable
+                        )),
+                    ),
+                    None => ("missing", None),
+                };
+                binaries::BinaryResolutionAttempt {
+                    candidate: probe.command,
+                    source: probe.source.to_string(),
+                    outcome: outcome.to_string(),
+                    detail,
+                }
+            })
+            .collect();
+
+        let (error_code, error_details) = match (&selected, found_any) {
+            (Some(_), _) => (None, None),
+            (None, true) => (
+                Some("PYTHON_VERSION_TOO_OLD".to_string()),
+                Some(format!(
+                    "Python {}.{}+ is required, but only older interpreters were found",
+                    min_major, min_minor
+                )),
+            ),
+            (None, false) => (
+                Some("PYTHON_NOT_FOUND".to_string()),
+                Some(format!(
+                    "No usable Python interpreter found. Install Python {}.{}+ and ensure it is available in PATH.",
+                    min_major, min_minor
+                )),
+            ),
+        };
+
+        PythonDiagnosticResult {
+            name: "python".to_string(),
+            meets_minimum: selected.is_some(),
+            version_output: selected.as_ref().map(|interpreter| {
+                format!(
+                    "Python {}.{}.{}",
+                    interpreter.major, interpreter.minor, interpreter.patch
+                )
+            }),
+            resolved_path: selected.map(|interpreter| interpreter.executable),
+            error_code,
+            error_details,
+            attempts,
+            minimum_version: format!("{}.{}", min_major, min_minor),
+        }
+    })
+    .await
+    .map_err(|e| format!("Python diagnostic task failed: {}", e))
+}
+
+/// Retourne l'index du volume dont le point de montage est le plus long préfixe de `path`.
+fn disk_index_for_path(path: &Path, mount_points: &[PathBuf]) -> Option<usize> {
+    mount_points
+        .iter()
+        .enumerate()
+        .filter(|(_, mount_point)| path.starts_with(mount_point))
+        .max_by_key(|(_, mount_point)| mount_point.as_os_str().len())
+        .map(|(index, _)| index)
+}
+
+/// Mesure l'espace disque du volume qui contient `path`.
+pub(crate) fn disk_space_for_path(path: &Path) -> Option<DiskSpaceInfo> {
+    let disks = Disks::new_with_refreshed_list();
+    let mount_points: Vec<PathBuf> = disks
+        .list()
+        .iter()
+        .map(|disk| disk.mount_point().to_path_buf())
+        .collect();
+    let disk = &disks.list()[disk_index_for_path(path, &mount_points)?];
+    Some(DiskSpaceInfo {
+        mount_point: disk.mount_point().to_string_lossy().to_string(),
+        total_bytes: disk.total_space(),
+        available_bytes: disk.available_space(),
+    })
+}
+
+/// Collecte les informations système du rapport de diagnostic.
+fn system_diagnostic_info() -> SystemDiagnosticInfo {
+    let mut system = System::new();
+    system.refresh_memory();
+    SystemDiagnosticInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        os_version: System::long_os_version(),
+        kernel_version: System::kernel_version(),
+        total_memory_bytes: system.total_memory(),
+        cpu_count: std::thread::available_parallelism()
+            .map(|cores| cores.get())
+            .unwrap_or(1),
+    }
+}
+
+/// Commande IPC qui retourne la version de l'application et la plateforme.
+#[tauri::command]
+pub fn get_app_info() -> AppInfo {
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+    }
+}
+
+/// Commande IPC qui regroupe tous les diagnostics dans un seul rapport JSON à copier.
+///
+/// Agrège la version de l'application, le système, l'espace disque, les binaires média,
+/// l'interpréteur Python et l'état de la segmentation locale. Le dossier utilisateur et
+/// les jetons Hugging Face sont masqués : le rapport est destiné aux issues publiques.
+#[tauri::command]
+pub async fn run_full_diagnostics(
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let app_data_dir = app_handle.path().app_data_dir().ok();
+    let (system, disk, media_binaries) = tauri::async_runtime::spawn_blocking(move || {
+        let disk = app_data_dir.as_deref().and_then(disk_space_for_path);
+        (system_diagnostic_info(), disk, diagnose_media_binaries())
+    })
+    .await
+    .map_err(|e| format!("Diagnostic task failed: {}", e))?;
+
+    let python = diagnose_python()
+        .await
+        .map(|result| serde_json::json!(result))
+        .unwrap_or_else(|error| serde_json::json!({ "error": error }));
+    let local_segmentation =
+        segmentation::check_local_segmentation_ready(app_handle.clone(), None, None)
+            .await
+            .unwrap_or_else(|error| serde_json::json!({ "error": error }));
+
+    let mut report = serde_json::json!({
+        "appVersion": app_handle.package_info().version.to_string(),
+        "system": system,
+        "disk": disk,
+        "mediaBinaries": media_binaries,
+        "python": python,
+        "localSegmentation": local_segmentation,
+    });
+    redact_json_strings(&mut report);
+    Ok(report)
+}
+
+/// Masque les données personnelles de toutes les chaînes d'une valeur JSON.
+///
+/// Appliqué valeur par valeur : dans le JSON sérialisé, les `\` des chemins Windows
+/// sont échappés et ne correspondraient plus au dossier utilisateur.
+fn redact_json_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => *text = redact_user_data(text),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json_strings),
+        serde_json::Value::Object(map) => map.values_mut().for_each(redact_json_strings),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disk_index_for_path, parse_ffmpeg_codec_list, parse_ffmpeg_filter_list};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn app_data_disk_is_the_longest_matching_mount_point() {
+        let mount_points = vec![
+            PathBuf::from("/"),
+            PathBuf::from("/home"),
+            PathBuf::from("/home2"),
+            PathBuf::from("/mnt/data"),
+        ];
+        assert_eq!(
+            disk_index_for_path(Path::new("/home/alice/.local/share/app"), &mount_points),
+            Some(1)
+        );
+        assert_eq!(
+            disk_index_for_path(Path::new("/var/lib/app"), &mount_points),
+            Some(0)
+        );
+        assert_eq!(
+            disk_index_for_path(Path::new("relative"), &mount_points),
+            None
+        );
+    }
+
+    #[test]
+    fn ffmpeg_capability_lists_are_parsed() {
+        let encoders = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+        assert_eq!(parse_ffmpeg_codec_list(encoders), vec!["libx264", "aac"]);
+
+        let filters = "Filters:
+  T.. = Timeline support
+  A = Audio input/output
+  V = Video input/output
+  | = Source or sink filter
+ ... loudnorm          A->A       EBU R128 loudness normalization
+ TSC xfade             VV->V      Cross fade one video with another video.
+ ... abuffer           |->A       Buffer audio frames.
+";
+        assert_eq!(
+            parse_ffmpeg_filter_list(filters),
+            vec!["loudnorm", "xfade", "abuffer"]
+        );
+    }
+}
@@ -1,14 +1,52 @@
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
 
+/// Nombre maximal de boutons affichés par Discord.
+const MAX_DISCORD_BUTTONS: usize = 2;
+/// Longueur maximale du libellé d'un bouton (caractères).
+const MAX_DISCORD_BUTTON_LABEL_CHARS: usize = 32;
+/// Longueur maximale de l'URL d'un bouton (caractères).
+const MAX_DISCORD_BUTTON_URL_CHARS: usize = 512;
+/// Délai maximal entre deux tentatives de reconnexion (secondes).
+const MAX_RECONNECT_DELAY_SECS: u64 = 300;
+
+/// Connexion Discord RPC partagée par les commandes IPC.
+#[derive(Default)]
+struct DiscordRpcState {
+    /// Client connecté, absent si désactivé ou en cours de reconnexion.
+    client: Option<DiscordIpcClient>,
+    /// Identifiant d'application fourni à `init_discord_rpc`, absent si désactivé.
+    app_id: Option<String>,
+    /// Dernière présence demandée, rejouée après une reconnexion.
+    last_activity: Option<DiscordActivity>,
+    /// `true` tant qu'un thread de reconnexion tourne.
+    retrying: bool,
+    /// Incrémenté à chaque (ré)initialisation ou fermeture pour arrêter les anciens threads.
+    generation: u64,
+}
+
 lazy_static::lazy_static! {
-    /// Instance globale du client Discord RPC pour les commandes IPC.
-    static ref DISCORD_CLIENT: Arc<Mutex<Option<DiscordIpcClient>>> = Arc::new(Mutex::new(None));
+    /// État global du client Discord RPC pour les commandes IPC.
+    static ref DISCORD_STATE: Arc<Mutex<DiscordRpcState>> =
+        Arc::new(Mutex::new(DiscordRpcState::default()));
+}
+
+/// État de la connexion Discord RPC exposé à l'interface des paramètres.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscordRpcStatus {
+    /// Client connecté à Discord.
+    Connected,
+    /// Discord est injoignable : reconnexion en arrière-plan.
+    Retrying,
+    /// Rich Presence non initialisée ou fermée.
+    Disabled,
 }
 
 /// Paramètres de présence Discord reçus depuis le frontend.
-#[derive(serde::Deserialize)]
+#[derive(Clone, serde::Deserialize)]
 pub struct DiscordActivity {
     /// Ligne de détails principale.
     details: Option<String>,
@@ -28,99 +66,343 @@ pub struct DiscordActivity {
     party_max: Option<u32>,
     /// Timestamp Unix de début.
     start_timestamp: Option<i64>,
+    /// Boutons cliquables (2 au maximum).
+    #[serde(default)]
+    buttons: Vec<DiscordButton>,
+}
+
+/// Bouton cliquable de la présence Discord.
+#[derive(Clone, serde::Deserialize)]
+pub struct DiscordButton {
+    /// Libellé affiché (1 à 32 caractères).
+    label: String,
+    /// Lien ouvert au clic (`http(s)://`).
+    url: String,
+}
+
+/// Vérifie les boutons selon les règles de Discord, qui rejette sinon toute la présence.
+fn validate_discord_buttons(buttons: &[DiscordButton]) -> Result<(), String> {
+    if buttons.len() > MAX_DISCORD_BUTTONS {
+        return Err(format!(
+            "Discord allows at most {} buttons, got {}",
+            MAX_DISCORD_BUTTONS,
+            buttons.len()
+        ));
+    }
+    for button in buttons {
+        let label_chars = button.label.trim().chars().count();
+        if label_chars == 0 || label_chars > MAX_DISCORD_BUTTON_LABEL_CHARS {
+            return Err(format!(
+                "Invalid Discord button label '{}': must be 1 to {} characters",
+                button.label, MAX_DISCORD_BUTTON_LABEL_CHARS
+            ));
+        }
+        if button.url.chars().count() > MAX_DISCORD_BUTTON_URL_CHARS {
+            return Err(format!(
+                "Invalid Discord button URL for '{}': must be at most {} characters",
+                button.label, MAX_DISCORD_BUTTON_URL_CHARS
+            ));
+        }
+        let url = reqwest::Url::parse(&button.url)
+            .map_err(|e| format!("Invalid Discord button URL for '{}': {}", button.label, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!(
+                "Invalid Discord button URL for '{}': only http:// and https:// URLs are allowed",
+                button.label
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Construit la présence Discord à partir des données reçues du frontend.
+fn build_activity(activity_data: &DiscordActivity) -> activity::Activity<'_> {
+    let mut activity_builder = activity::Activity::new();
+
+    // Construction progressive des champs selon les données disponibles.
+    if let Some(ref details) = activity_data.details {
+        activity_builder = activity_builder.details(details);
+    }
+    if let Some(ref state) = activity_data.state {
+        activity_builder = activity_builder.state(state);
+    }
+    let start_time = activity_data
+        .start_timestamp
+        .unwrap_or_else(current_unix_timestamp);
+    activity_builder = activity_builder.timestamps(activity::Timestamps::new().start(start_time));
+
+    let has_large_image = activity_data.large_image_key.is_some();
+    let has_small_image = activity_data.small_image_key.is_some();
+    if has_large_image || has_small_image {
+        let mut assets_builder = activity::Assets::new();
+        if let Some(ref key) = activity_data.large_image_key {
+            assets_builder = assets_builder.large_image(key);
+            if let Some(ref text) = activity_data.large_image_text {
+                assets_builder = assets_builder.large_text(text);
+            }
+        }
+        if let Some(ref key) = activity_data.small_image_key {
+            assets_builder = assets_builder.small_image(key);
+            if let Some(ref text) = activity_data.small_image_text {
+                assets_builder = assets_builder.small_text(text);
+            }
+        }
+        activity_builder = activity_builder.assets(assets_builder);
+    }
+
+    if let (Some(party_size), Some(party_max)) = (activity_data.party_size, activity_data.party_max)
+    {
+        let party = activity::Party::new().size([party_size as i32, party_max as i32]);
+        activity_builder = activity_builder.party(party);
+    }
+
+    if !activity_data.buttons.is_empty() {
+        let buttons = activity_data
+            .buttons
+            .iter()
+            .map(|button| activity::Button::new(button.label.trim(), &button.url))
+            .collect();
+        activity_builder = activity_builder.buttons(buttons);
+    }
+    activity_builder
+}
+
+/// Timestamp Unix courant (secondes).
+fn current_unix_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Crée un client pour `app_id` et le connecte à Discord.
+fn connect_client(app_id: &str) -> Result<DiscordIpcClient, String> {
+    let mut client = DiscordIpcClient::new(app_id).map_err(|e| e.to_string())?;
+    client.connect().map_err(|e| e.to_string())?;
+    Ok(client)
+}
+
+/// Indique si une erreur du client vient du pipe IPC (Discord fermé ou redémarré).
+///
+/// Le client remonte les erreurs d'E/S du pipe telles quelles dans sa `Box<dyn Error>`.
+fn is_connection_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<std::io::Error>().is_some()
+}
+
+/// Délai avant la tentative de reconnexion `attempt` (0, 1, ...) : 2 s, 4 s, 8 s... plafonné.
+fn reconnect_delay(attempt: u32) -> Duration {
+    Duration::from_secs((2u64 << attempt.min(16)).min(MAX_RECONNECT_DELAY_SECS))
+}
+
+/// Lance la reconnexion en arrière-plan si aucune n'est en cours.
+fn schedule_reconnect(state: &mut DiscordRpcState) {
+    if state.retrying || state.app_id.is_none() {
+        return;
+    }
+    state.retrying = true;
+    let generation = state.generation;
+    let spawned = std::thread::Builder::new()
+        .name("discord-rpc-reconnect".to_string())
+        .spawn(move || run_reconnect_loop(generation));
+    if let Err(error) = spawned {
+        log::warn!("[discord] failed to start reconnect thread: {}", error);
+        state.retrying = false;
+    }
+}
+
+/// Retente la connexion avec un délai croissant, puis rejoue la dernière présence.
+///
+/// S'arrête à la première connexion réussie ou quand `generation` est dépassée
+/// (client fermé ou réinitialisé).
+fn run_reconnect_loop(generation: u64) {
+    let mut attempt = 0u32;
+    loop {
+        std::thread::sleep(reconnect_delay(attempt));
+        attempt = attempt.saturating_add(1);
+        let Ok(mut state) = DISCORD_STATE.lock() else {
+            return;
+        };
+        if state.generation != generation {
+            return;
+        }
+        let Some(app_id) = state.app_id.clone() else {
+            state.retrying = false;
+            return;
+        };
+        let mut client = match connect_client(&app_id) {
+            Ok(client) => client,
+            Err(error) => {
+                log::debug!("[discord] reconnect attempt {} failed: {}", attempt, error);
+                continue;
+            }
+        };
+        if let Some(activity_data) = state.last_activity.as_ref() {
+            if let Err(error) = client.set_activity(build_activity(activity_data)) {
+                log::warn!("[discord] failed to replay activity: {}", error);
+            }
+        }
+        log::info!("[discord] reconnected after {} attempt(s)", attempt);
+        state.client = Some(client);
+        state.retrying = false;
+        return;
+    }
 }
 
 /// Initialise la connexion Discord Rich Presence.
+///
+/// Si Discord n'est pas encore lancé, la connexion est retentée en arrière-plan
+/// (voir `discord_rpc_status`).
 #[tauri::command]
 pub async fn init_discord_rpc(app_id: String) -> Result<(), String> {
-    let mut client_guard = DISCORD_CLIENT.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut client) = *client_guard {
+    let mut state = DISCORD_STATE.lock().map_err(|e| e.to_string())?;
+    if let Some(ref mut client) = state.client {
         let _ = client.close();
     }
+    state.client = None;
+    state.generation = state.generation.wrapping_add(1);
+    state.retrying = false;
 
     let mut client = DiscordIpcClient::new(&app_id).map_err(|e| e.to_string())?;
-    client.connect().map_err(|e| e.to_string())?;
-    *client_guard = Some(client);
+    state.app_id = Some(app_id);
+    match client.connect() {
+        Ok(()) => state.client = Some(client),
+        Err(error) => {
+            log::info!(
+                "[discord] Discord unavailable, retrying in background: {}",
+                error
+            );
+            schedule_reconnect(&mut state);
+        }
+    }
     Ok(())
 }
 
 /// Met à jour la présence Discord active.
+///
+/// Si le pipe IPC est rompu (Discord redémarré), la présence est conservée et rejouée
+/// après la reconnexion en arrière-plan.
 #[tauri::command]
-pub async fn update_discord_activity(activity_data: DiscordActivity) -> Result<(), String> {
-    let mut client_guard = DISCORD_CLIENT.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut client) = *client_guard {
-        let mut activity_builder = activity::Activity::new();
-
-        // Construction progressive des champs selon les données disponibles.
-        if let Some(ref details) = activity_data.details {
-            activity_builder = activity_builder.details(details);
-        }
-        if let Some(ref state) = activity_data.state {
-            activity_builder = activity_builder.state(state);
-        }
-        let start_time = activity_data.start_timestamp.unwrap_or_else(|| {
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs() as i64
-        });
-        activity_builder =
-            activity_builder.timestamps(activity::Timestamps::new().start(start_time));
-
-        let has_large_image = activity_data.large_image_key.is_some();
-        let has_small_image = activity_data.small_image_key.is_some();
-        if has_large_image || has_small_image {
-            let mut assets_builder = activity::Assets::new();
-            if let Some(ref key) = activity_data.large_image_key {
-                assets_builder = assets_builder.large_image(key);
-                if let Some(ref text) = activity_data.large_image_text {
-                    assets_builder = assets_builder.large_text(text);
-                }
-            }
-            if let Some(ref key) = activity_data.small_image_key {
-                assets_builder = assets_builder.small_image(key);
-                if let Some(ref text) = activity_data.small_image_text {
-                    assets_builder = assets_builder.small_text(text);
-                }
-            }
-            activity_builder = activity_builder.assets(assets_builder);
-        }
-
-        if let (Some(party_size), Some(party_max)) =
-            (activity_data.party_size, activity_data.party_max)
-        {
-            let party = activity::Party::new().size([party_size as i32, party_max as i32]);
-            activity_builder = activity_builder.party(party);
+pub async fn update_discord_activity(mut activity_data: DiscordActivity) -> Result<(), String> {
+    validate_discord_buttons(&activity_data.buttons)?;
+    // Fixé dès la demande pour que le temps écoulé survive à une reconnexion.
+    activity_data
+        .start_timestamp
+        .get_or_insert_with(current_unix_timestamp);
+    let mut state = DISCORD_STATE.lock().map_err(|e| e.to_string())?;
+    if state.app_id.is_none() {
+        return Err("Discord client not initialized. Call init_discord_rpc first.".to_string());
+    }
+    let result = match state.client.as_mut() {
+        Some(client) => client.set_activity(build_activity(&activity_data)),
+        None => Ok(()),
+    };
+    state.last_activity = Some(activity_data);
+    match result {
+        Err(error) if is_connection_error(error.as_ref()) => {
+            log::warn!("[discord] connection lost, reconnecting: {}", error);
+            state.client = None;
+            schedule_reconnect(&mut state);
+            Ok(())
         }
-
-        client
-            .set_activity(activity_builder)
-            .map_err(|e| e.to_string())?;
-        Ok(())
-    } else {
-        Err("Discord client not initialized. Call init_discord_rpc first.".to_string())
+        Err(error) => Err(error.to_string()),
+        Ok(()) => Ok(()),
     }
 }
 
 /// Efface la présence Discord en cours.
 #[tauri::command]
 pub async fn clear_discord_activity() -> Result<(), String> {
-    let mut client_guard = DISCORD_CLIENT.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut client) = *client_guard {
-        client.clear_activity().map_err(|e| e.to_string())?;
-        Ok(())
-    } else {
-        Err("Discord client not initialized.".to_string())
+    let mut state = DISCORD_STATE.lock().map_err(|e| e.to_string())?;
+    if state.app_id.is_none() {
+        return Err("Discord client not initialized.".to_string());
+    }
+    state.last_activity = None;
+    let result = match state.client.as_mut() {
+        Some(client) => client.clear_activity(),
+        None => Ok(()),
+    };
+    match result {
+        Err(error) if is_connection_error(error.as_ref()) => {
+            state.client = None;
+            schedule_reconnect(&mut state);
+            Ok(())
+        }
+        Err(error) => Err(error.to_string()),
+        Ok(()) => Ok(()),
     }
 }
 
-/// Ferme la connexion Discord RPC.
+/// Ferme la connexion Discord RPC et arrête les tentatives de reconnexion.
 #[tauri::command]
 pub async fn close_discord_rpc() -> Result<(), String> {
-    let mut client_guard = DISCORD_CLIENT.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut client) = *client_guard {
+    let mut state = DISCORD_STATE.lock().map_err(|e| e.to_string())?;
+    let client = state.client.take();
+    *state = DiscordRpcState {
+        generation: state.generation.wrapping_add(1),
+        ..DiscordRpcState::default()
+    };
+    if let Some(mut client) = client {
         client.close().map_err(|e| e.to_string())?;
-        *client_guard = None;
     }
     Ok(())
 }
+
+/// Retourne l'état de la connexion Discord RPC (`connected`, `retrying` ou `disabled`).
+#[tauri::command]
+pub fn discord_rpc_status() -> Result<DiscordRpcStatus, String> {
+    let state = DISCORD_STATE.lock().map_err(|e| e.to_string())?;
+    Ok(if state.client.is_some() {
+        DiscordRpcStatus::Connected
+    } else if state.app_id.is_some() {
+        DiscordRpcStatus::Retrying
+    } else {
+        DiscordRpcStatus::Disabled
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_connection_error, reconnect_delay, validate_discord_buttons, DiscordButton};
+    use std::time::Duration;
+
+    fn button(label: &str, url: &str) -> DiscordButton {
+        DiscordButton {
+            label: label.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn discord_buttons_follow_discord_limits() {
+        let website = button("Get QuranCaption", "https://qurancaption.com");
+        let channel = button("Watch my channel", "https://youtube.com/@channel");
+        assert!(validate_discord_buttons(&[]).is_ok());
+        assert!(validate_discord_buttons(&[website, channel]).is_ok());
+
+        let too_many = vec![
+            button("One", "https://a.com"),
+            button("Two", "https://b.com"),
+            button("Three", "https://c.com"),
+        ];
+        assert!(validate_discord_buttons(&too_many).is_err());
+        assert!(validate_discord_buttons(&[button("  ", "https://a.com")]).is_err());
+        assert!(validate_discord_buttons(&[button(&"x".repeat(33), "https://a.com")]).is_err());
+        assert!(validate_discord_buttons(&[button("Site", "qurancaption.com")]).is_err());
+        assert!(validate_discord_buttons(&[button("Site", "ftp://a.com")]).is_err());
+    }
+
+    #[test]
+    fn reconnect_backs_off_exponentially_up_to_the_cap() {
+        assert_eq!(reconnect_delay(0), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(1), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(4), Duration::from_secs(32));
+        assert_eq!(reconnect_delay(8), Duration::from_secs(300));
+        assert_eq!(reconnect_delay(u32::MAX), Duration::from_secs(300));
+
+        let pipe_closed: Box<dyn std::error::Error> =
+            Box::new(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(is_connection_error(pipe_closed.as_ref()));
+        let invalid_payload: Box<dyn std::error::Error> = "invalid activity".into();
+        assert!(!is_connection_error(invalid_payload.as_ref()));
+    }
+}
@@ -7,7 +7,9 @@ use std::thread;
 
 use crate::binaries;
 use crate::path_utils;
-use crate::utils::process::configure_command_no_window;
+use crate::utils::process::{
+    configure_command_no_window, track_process, untrack_process, wait_tracked_process,
+};
 use tauri::Emitter;
 
 /// Emet un evenement de progression du telechargement YouTube vers le frontend.
@@ -69,6 +71,86 @@ fn parse_ytdlp_progress_percent(line: &str) -> Option<f64> {
     percent_str.parse::<f64>().ok()
 }
 
+/// Construit les arguments yt-dlp pour un type de telechargement.
+///
+/// Les champs `title`/`uploader` injectes dans le modele de sortie viennent du site distant :
+/// `--restrict-filenames` les reduit a de l'ASCII sans espace ni caractere special et
+/// `--windows-filenames` ecarte en plus les noms reserves de Windows (`CON`, `NUL`...).
+///
+/// @param download_type `audio`, `video` ou `video_no_audio`.
+/// @param output_pattern Modele de chemin de sortie passe a `-o`.
+/// @param ffmpeg_dir Dossier contenant ffmpeg, si connu.
+/// @param url URL a telecharger.
+/// @returns La liste d'arguments, ou une erreur si le type est inconnu.
+fn build_ytdlp_args(
+    download_type: &str,
+    output_pattern: &str,
+    ffmpeg_dir: Option<&str>,
+    url: &str,
+) -> Result<Vec<String>, String> {
+    let mut args: Vec<&str> = vec![
+        "--restrict-filenames",
+        "--windows-filenames",
+        "--trim-filenames",
+        "120",
+        "--js-runtimes",
+        "node",
+        "--js-runtimes",
+        "bun",
+        "--js-runtimes",
+        "deno",
+        "--no-colors",
+    ];
+    if let Some(dir) = ffmpeg_dir {
+        args.push("--ffmpeg-location");
+        args.push(dir);
+    }
+
+    match download_type {
+        "audio" => args.extend_from_slice(&[
+            "--extract-audio",
+            "--audio-format",
+            "mp3",
+            "--audio-quality",
+            "0",
+            "--postprocessor-args",
+            "ffmpeg:-b:a 320k -ar 44100",
+            "--newline",
+            "-o",
+            output_pattern,
+        ]),
+        "video_no_audio" => args.extend_from_slice(&[
+            "--format",
+            "bestvideo[height<=1080][ext=mp4]/bestvideo[height<=1080]",
+            "--remux-video",
+            "mp4",
+            "--newline",
+            "-o",
+            output_pattern,
+        ]),
+        "video" => args.extend_from_slice(&[
+            "--format",
+            "bv*+ba/b",
+            "--merge-output-format",
+            "mp4",
+            "--newline",
+            "-o",
+            output_pattern,
+        ]),
+        _ => return Err("Invalid type: must be 'audio', 'video' or 'video_no_audio'".to_string()),
+    }
+
+    let lowered_url = url.to_ascii_lowercase();
+    let has_playlist = lowered_url.contains("list=");
+    let has_explicit_video = lowered_url.contains("v=") || lowered_url.contains("youtu.be/");
+    if has_playlist && has_explicit_video {
+        args.push("--no-playlist");
+    }
+    args.push(url);
+
+    Ok(args.into_iter().map(str::to_string).collect())
+}
+
 fn find_downloaded_file_by_suffix(
     download_path: &Path,
     extension: &str,
@@ -138,25 +220,6 @@ pub async fn download_from_youtube(
         .filter(|p| !p.as_os_str().is_empty())
         .map(|p| p.to_string_lossy().to_string());
 
-    // Construction des arguments selon le type de téléchargement demandé.
-    let mut args: Vec<&str> = vec![
-        "--restrict-filenames",
-        "--trim-filenames",
-        "120",
-        "--js-runtimes",
-        "node",
-        "--js-runtimes",
-        "bun",
-        "--js-runtimes",
-        "deno",
-        "--no-colors",
-    ];
-    let ffmpeg_dir_str;
-    if let Some(dir) = ffmpeg_dir {
-        ffmpeg_dir_str = dir;
-        args.push("--ffmpeg-location");
-        args.push(&ffmpeg_dir_str);
-    }
     let download_request_id = download_request_id.unwrap_or_else(|| {
         format!(
             "req-{}",
@@ -166,52 +229,14 @@ pub async fn download_from_youtube(
                 .unwrap_or(0)
         )
     });
+    // L'identifiant est injecté dans le nom de fichier: on le rend créable sur tout OS.
+    let file_suffix = path_utils::sanitize_filename(&download_request_id);
     let output_pattern = format!(
         "{}/%(title)s (%(uploader)s){}.%(ext)s",
-        download_path_str, download_request_id
+        download_path_str, file_suffix
     );
 
-    match _type.as_str() {
-        "audio" => args.extend_from_slice(&[
-            "--extract-audio",
-            "--audio-format",
-            "mp3",
-            "--audio-quality",
-            "0",
-            "--postprocessor-args",
-            "ffmpeg:-b:a 320k -ar 44100",
-            "--newline",
-            "-o",
-            &output_pattern,
-        ]),
-        "video_no_audio" => args.extend_from_slice(&[
-            "--format",
-            "bestvideo[height<=1080][ext=mp4]/bestvideo[height<=1080]",
-            "--remux-video",
-            "mp4",
-            "--newline",
-            "-o",
-            &output_pattern,
-        ]),
-        "video" => args.extend_from_slice(&[
-            "--format",
-            "bv*+ba/b",
-            "--merge-output-format",
-            "mp4",
-            "--newline",
-            "-o",
-            &output_pattern,
-        ]),
-        _ => return Err("Invalid type: must be 'audio', 'video' or 'video_no_audio'".to_string()),
-    }
-
-    let lowered_url = url.to_ascii_lowercase();
-    let has_playlist = lowered_url.contains("list=");
-    let has_explicit_video = lowered_url.contains("v=") || lowered_url.contains("youtu.be/");
-    if has_playlist && has_explicit_video {
-        args.push("--no-playlist");
-    }
-    args.push(&url);
+    let args = build_ytdlp_args(&_type, &output_pattern, ffmpeg_dir.as_deref(), &url)?;
 
     let mut cmd = Command::new(&yt_dlp_path);
     cmd.args(&args);
@@ -265,13 +290,20 @@ pub async fn download_from_youtube(
         }
     });
 
-    let status = child
-        .wait()
-        .map_err(|e| format!("Unable to wait for yt-dlp: {}", e))?;
+    // yt-dlp lance ffmpeg : l'annulation via `kill_tracked_process` arrête les deux.
+    let process = track_process(&download_request_id, child);
+    let status = wait_tracked_process(&process);
+    untrack_process(&download_request_id);
 
     let _ = stdout_handle.join();
     let _ = stderr_handle.join();
 
+    let Some(status) = status.map_err(|e| format!("Unable to wait for yt-dlp: {}", e))? else {
+        let error = "YouTube download was cancelled".to_string();
+        emit_youtube_download_error(&app_handle, &download_request_id, &error);
+        return Err(error);
+    };
+
     if status.success() {
         emit_youtube_download_progress(&app_handle, &download_request_id, 100.0, "finished");
 
@@ -280,11 +312,11 @@ pub async fn download_from_youtube(
             .map(|buffer| buffer.clone())
             .unwrap_or_default();
         if !output_str.trim().is_empty() {
-            println!("yt-dlp output: {}", output_str);
+            log::info!("yt-dlp output: {}", output_str);
         }
 
         let extension = if _type == "audio" { "mp3" } else { "mp4" };
-        match find_downloaded_file_by_suffix(&download_path_buf, extension, &download_request_id) {
+        match find_downloaded_file_by_suffix(&download_path_buf, extension, &file_suffix) {
             Ok(path) => {
                 if _type == "video" {
                     // Je commente cette ligne car au final ça sert à rien
@@ -308,3 +340,40 @@ pub async fn download_from_youtube(
         Err(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::build_ytdlp_args;
+
+    #[test]
+    fn ytdlp_args_sanitize_remote_fields_in_output_names() {
+        let pattern = "/downloads/%(title)s (%(uploader)s)req-1.%(ext)s";
+        let args = build_ytdlp_args(
+            "audio",
+            pattern,
+            Some("/opt/ffmpeg"),
+            "https://www.youtube.com/watch?v=abc&list=xyz",
+        )
+        .unwrap();
+
+        assert!(args.iter().any(|arg| arg == "--restrict-filenames"));
+        assert!(args.iter().any(|arg| arg == "--windows-filenames"));
+        let output_index = args.iter().position(|arg| arg == "-o").unwrap();
+        assert_eq!(args[output_index + 1], pattern);
+        let ffmpeg_index = args
+            .iter()
+            .position(|arg| arg == "--ffmpeg-location")
+            .unwrap();
+        assert_eq!(args[ffmpeg_index + 1], "/opt/ffmpeg");
+        assert!(args.iter().any(|arg| arg == "--no-playlist"));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("https://www.youtube.com/watch?v=abc&list=xyz")
+        );
+    }
+
+    #[test]
+    fn ytdlp_args_reject_unknown_type() {
+        assert!(build_ytdlp_args("image", "out.%(ext)s", None, "https://example.com").is_err());
+    }
+}
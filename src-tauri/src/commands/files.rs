@@ -1,13 +1,64 @@
 use std::fs;
 use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
 use std::time::Duration;
 
-use reqwest::header::{ACCEPT, ACCEPT_ENCODING, RANGE, USER_AGENT};
+use reqwest::header::{
+    ACCEPT, ACCEPT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE, USER_AGENT,
+};
 use tokio::io::AsyncWriteExt;
 
 use crate::path_utils;
+use crate::utils::file_head;
+use crate::utils::temp_file::find_orphaned_temp_files;
 use tauri::Emitter;
 
+/// Rapport de nettoyage des fichiers temporaires orphelins.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempCleanupReport {
+    /// Fichiers trouvés (supprimés sauf en mode `dry_run`).
+    pub files: Vec<String>,
+    /// Nombre de fichiers effectivement supprimés.
+    pub deleted_count: usize,
+    /// Octets libérés par la suppression.
+    pub freed_bytes: u64,
+}
+
+/// Informations sur un chemin, sans erreur s'il n'existe pas.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathInfo {
+    /// `true` si le chemin existe.
+    pub exists: bool,
+    /// `true` si le chemin est un fichier.
+    pub is_file: bool,
+    /// `true` si le chemin est un dossier.
+    pub is_dir: bool,
+    /// Taille en octets (fichiers uniquement).
+    pub size: Option<u64>,
+    /// Date de dernière modification (ms depuis l'epoch Unix).
+    pub modified_ms: Option<u64>,
+}
+
+/// Résultat de la validation d'un chemin de sortie saisi par l'utilisateur.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputPathValidation {
+    /// Chemin normalisé (dossier parent canonicalisé s'il existe).
+    pub path: String,
+    /// `true` si un fichier peut être écrit à cet emplacement.
+    pub valid: bool,
+    /// `true` si le dossier parent existe.
+    pub parent_exists: bool,
+    /// `true` si un fichier de test a pu être créé dans le dossier parent.
+    pub writable: bool,
+    /// `true` si un fichier existe déjà à ce chemin (il serait écrasé).
+    pub exists: bool,
+    /// Raison de l'invalidité.
+    pub error: Option<String>,
+}
+
 /// Calcule un pourcentage de copie borné entre 0 et 100.
 ///
 /// @param copied Nombre d'octets déjà copiés.
@@ -20,33 +71,131 @@ fn copy_progress_percent(copied: u64, total: u64) -> u8 {
     ((copied.saturating_mul(100) / total).min(100)) as u8
 }
 
+/// Copie un fichier par blocs et notifie chaque point de pourcentage franchi.
+///
+/// @param source Chemin du fichier source.
+/// @param destination Chemin du fichier de destination (écrasé s'il existe).
+/// @param on_progress Callback appelé avec le pourcentage courant.
+/// @returns Nombre d'octets copiés.
+fn copy_file_in_chunks(
+    source: &Path,
+    destination: &Path,
+    mut on_progress: impl FnMut(u8),
+) -> Result<u64, String> {
+    let total = fs::metadata(source)
+        .map_err(|error| error.to_string())?
+        .len();
+    let input = fs::File::open(source).map_err(|error| error.to_string())?;
+    let output = fs::File::create(destination).map_err(|error| error.to_string())?;
+    let mut reader = BufReader::new(input);
+    let mut writer = BufWriter::new(output);
+    let mut buffer = vec![0_u8; 256 * 1024];
+    let mut copied = 0_u64;
+    let mut last_progress = 0_u8;
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|error| error.to_string())?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|error| error.to_string())?;
+        copied += read as u64;
+        let progress = copy_progress_percent(copied, total);
+        if progress >= last_progress.saturating_add(1) {
+            last_progress = progress;
+            on_progress(progress);
+        }
+    }
+    writer.flush().map_err(|error| error.to_string())?;
+    Ok(copied)
+}
+
+/// Extensions des fichiers de téléchargement encore en cours (navigateurs, yt-dlp).
+const PARTIAL_DOWNLOAD_EXTENSIONS: [&str; 4] = ["crdownload", "part", "tmp", "download"];
+
 /// Recherche dans le dossier téléchargements un fichier créé après `start_time`.
+///
+/// Parmi les fichiers récents (et de l'extension attendue si fournie), le plus récent
+/// dont le nom contient `asset_name` est préféré; à défaut, le plus récent tout court.
+///
+/// @param start_time Horodatage (ms) de début du téléchargement.
+/// @param asset_name Nom attendu de l'asset (comparaison insensible à la casse).
+/// @param expected_extension Extension attendue, avec ou sans point (ex: `mp4`).
+/// @returns Chemin du fichier téléchargé.
 #[tauri::command]
-pub fn get_new_file_path(start_time: u64, asset_name: &str) -> Result<String, String> {
+pub fn get_new_file_path(
+    start_time: u64,
+    asset_name: &str,
+    expected_extension: Option<String>,
+) -> Result<String, String> {
     let download_path = dirs::download_dir()
         .ok_or_else(|| "Unable to determine download directory".to_string())?
         .to_string_lossy()
         .to_string();
+    let expected_extension = expected_extension
+        .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+        .filter(|extension| !extension.is_empty());
+    let asset_name_trimmed = asset_name.trim().to_lowercase();
 
     let entries = fs::read_dir(&download_path)
         .map_err(|e| format!("Unable to read download directory: {}", e))?;
+    let mut candidates: Vec<(u64, std::path::PathBuf)> = Vec::new();
     for entry in entries.flatten() {
-        if let Ok(metadata) = entry.metadata() {
-            if let Ok(created) = metadata.created() {
-                let created_time = created
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map_err(|_| "Time went backwards")?
-                    .as_millis() as u64;
-                if created_time > start_time {
-                    let file_path = entry.path();
-                    let file_path_str = file_path.to_string_lossy().to_string();
-                    let _asset_name_trimmed = asset_name.trim();
-                    return Ok(file_path_str);
-                }
-            }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let Ok(created) = metadata.created() else {
+            continue;
+        };
+        let created_time = created
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| "Time went backwards")?
+            .as_millis() as u64;
+        if created_time <= start_time {
+            continue;
+        }
+
+        let file_path = entry.path();
+        let extension = file_path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if PARTIAL_DOWNLOAD_EXTENSIONS.contains(&extension.as_str()) {
+            continue;
+        }
+        if expected_extension
+            .as_ref()
+            .is_some_and(|expected| expected != &extension)
+        {
+            continue;
         }
+        candidates.push((created_time, file_path));
     }
-    Err("Downloaded file not found".to_string())
+
+    // Du plus récent au plus ancien.
+    candidates.sort_by_key(|(created_time, _)| std::cmp::Reverse(*created_time));
+    let name_match = candidates.iter().find(|(_, path)| {
+        !asset_name_trimmed.is_empty()
+            && path
+                .file_name()
+                .map(|name| {
+                    name.to_string_lossy()
+                        .to_lowercase()
+                        .contains(&asset_name_trimmed)
+                })
+                .unwrap_or(false)
+    });
+    name_match
+        .or_else(|| candidates.first())
+        .map(|(_, path)| path.to_string_lossy().to_string())
+        .ok_or_else(|| "Downloaded file not found".to_string())
 }
 
 /// Écrit un fichier binaire en créant son dossier parent si nécessaire.
@@ -59,6 +208,117 @@ pub fn save_binary_file(path: String, content: Vec<u8>) -> Result<(), String> {
     fs::write(&path_buf, content).map_err(|e| format!("Failed to write file: {}", e))
 }
 
+/// Lit les premiers octets d'un fichier (magic bytes) sans le charger entièrement.
+///
+/// @param path Chemin du fichier à inspecter.
+/// @param n Nombre d'octets souhaités (plafonné à 1 Mo).
+/// @returns Les `n` premiers octets, ou moins si le fichier est plus court.
+#[tauri::command]
+pub fn read_file_head(path: String, n: usize) -> Result<Vec<u8>, String> {
+    let path_buf = path_utils::normalize_existing_path(&path);
+    if !path_buf.is_file() {
+        return Err(format!("File not found: {}", path));
+    }
+    file_head::read_file_head(&path_buf, n.min(file_head::MAX_FILE_HEAD_BYTES))
+        .map_err(|e| format!("Failed to read file: {}", e))
+}
+
+/// Décrit un chemin (existence, type, taille, date de modification).
+fn describe_path(path: &Path) -> PathInfo {
+    let Ok(metadata) = fs::metadata(path) else {
+        return PathInfo {
+            exists: false,
+            is_file: false,
+            is_dir: false,
+            size: None,
+            modified_ms: None,
+        };
+    };
+    PathInfo {
+        exists: true,
+        is_file: metadata.is_file(),
+        is_dir: metadata.is_dir(),
+        size: metadata.is_file().then_some(metadata.len()),
+        modified_ms: metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_millis() as u64),
+    }
+}
+
+/// Indique si un chemin existe et s'il s'agit d'un fichier ou d'un dossier.
+///
+/// Un chemin absent n'est pas une erreur : `exists` vaut alors `false`.
+///
+/// @param path Chemin à inspecter (normalisé comme les autres commandes fichiers).
+/// @returns Existence, type, taille et date de modification.
+#[tauri::command]
+pub fn path_info(path: String) -> PathInfo {
+    describe_path(&path_utils::normalize_existing_path(&path))
+}
+
+/// Vérifie qu'un fichier peut être créé dans un dossier en y écrivant un fichier de test.
+fn can_create_file_in(dir: &Path) -> bool {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let probe = dir.join(format!(
+        ".qurancaption-write-test-{}-{}",
+        std::process::id(),
+        stamp
+    ));
+    let created = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .is_ok();
+    if created {
+        let _ = fs::remove_file(&probe);
+    }
+    created
+}
+
+/// Valide un chemin de sortie déjà normalisé (dossier parent existant et accessible en écriture).
+fn validate_output_location(path: &Path) -> OutputPathValidation {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let parent_exists = parent.is_dir();
+    let writable = parent_exists && can_create_file_in(parent);
+    let error = if path.as_os_str().is_empty() || path.file_name().is_none() {
+        Some("Output path is empty or has no file name".to_string())
+    } else if path.is_dir() {
+        Some(format!("Output path is a directory: {}", path.display()))
+    } else if !parent_exists {
+        Some(format!("Directory does not exist: {}", parent.display()))
+    } else if !writable {
+        Some(format!("Directory is not writable: {}", parent.display()))
+    } else {
+        None
+    };
+    OutputPathValidation {
+        path: path.to_string_lossy().to_string(),
+        valid: error.is_none(),
+        parent_exists,
+        writable,
+        exists: path.is_file(),
+        error,
+    }
+}
+
+/// Indique si un chemin de sortie saisi par l'utilisateur est un emplacement valide et
+/// accessible en écriture, avant tout export.
+///
+/// @param path Chemin du fichier à écrire (normalisé comme les autres commandes fichiers).
+/// @returns Chemin normalisé, état du dossier parent et raison d'une éventuelle invalidité.
+#[tauri::command]
+pub fn validate_output_path(path: String) -> OutputPathValidation {
+    validate_output_location(&path_utils::normalize_output_path(&path))
+}
+
 /// Copie un fichier sans charger son contenu en mémoire JS.
 #[tauri::command]
 pub fn copy_file(source: String, destination: String) -> Result<(), String> {
@@ -103,17 +363,6 @@ pub fn copy_file_with_progress(
     let _ = fs::remove_file(&temp_path);
 
     let result = (|| -> Result<(), String> {
-        let total = fs::metadata(&source)
-            .map_err(|error| error.to_string())?
-            .len();
-        let input = fs::File::open(&source).map_err(|error| error.to_string())?;
-        let output = fs::File::create(&temp_path).map_err(|error| error.to_string())?;
-        let mut reader = BufReader::new(input);
-        let mut writer = BufWriter::new(output);
-        let mut buffer = vec![0_u8; 256 * 1024];
-        let mut copied = 0_u64;
-        let mut last_progress = 0_u8;
-
         let _ = app_handle.emit(
             "batch-file-copy-progress",
             serde_json::json!({
@@ -122,31 +371,16 @@ pub fn copy_file_with_progress(
                 "status": "copying"
             }),
         );
-        loop {
-            let read = reader
-                .read(&mut buffer)
-                .map_err(|error| error.to_string())?;
-            if read == 0 {
-                break;
-            }
-            writer
-                .write_all(&buffer[..read])
-                .map_err(|error| error.to_string())?;
-            copied += read as u64;
-            let progress = copy_progress_percent(copied, total);
-            if progress >= last_progress.saturating_add(1) {
-                last_progress = progress;
-                let _ = app_handle.emit(
-                    "batch-file-copy-progress",
-                    serde_json::json!({
-                        "copyRequestId": copy_request_id,
-                        "progress": progress,
-                        "status": "copying"
-                    }),
-                );
-            }
-        }
-        writer.flush().map_err(|error| error.to_string())?;
+        copy_file_in_chunks(&source, &temp_path, |progress| {
+            let _ = app_handle.emit(
+                "batch-file-copy-progress",
+                serde_json::json!({
+                    "copyRequestId": copy_request_id,
+                    "progress": progress,
+                    "status": "copying"
+                }),
+            );
+        })?;
         fs::rename(&temp_path, &destination).map_err(|error| error.to_string())?;
         Ok(())
     })();
@@ -177,38 +411,163 @@ pub fn save_file(location: String, content: String) -> Result<(), String> {
     fs::write(&path_buf, content).map_err(|e| format!("Failed to write file: {}", e))
 }
 
-/// Télécharge un fichier HTTP puis l'écrit de manière asynchrone sur disque.
-#[tauri::command]
-pub async fn download_file(url: String, path: String) -> Result<(), String> {
-    let path_buf = path_utils::normalize_output_path(&path);
+/// Construit le client HTTP utilisé pour les téléchargements de fichiers.
+pub(crate) fn build_download_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(15 * 60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Normalise une destination de téléchargement et crée son dossier parent.
+///
+/// Le nom de fichier est conservé tel quel : l'appelant relit le fichier à ce chemin.
+fn prepare_download_destination(path: &str) -> Result<std::path::PathBuf, String> {
+    let path_buf = path_utils::normalize_output_path(path);
     if let Some(parent) = path_buf.parent() {
         fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
     }
+    Ok(path_buf)
+}
+
+/// Indique si une réponse est un 206 reprenant exactement à l'octet `offset`.
+fn is_matching_partial_response(response: &reqwest::Response, offset: u64) -> bool {
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return false;
+    }
+    response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_content_range_start)
+        .is_some_and(|start| start == offset)
+}
+
+/// Extrait la taille totale d'un en-tête `Content-Range: bytes <start>-<end>/<total>`.
+fn parse_content_range_total(value: &str) -> Option<u64> {
+    let (_, total) = value.trim().strip_prefix("bytes")?.rsplit_once('/')?;
+    total.trim().parse().ok()
+}
+
+/// Taille complète du fichier servi par `response` (reprise à l'octet `offset` si 206).
+fn expected_file_size(response: &reqwest::Response, offset: u64) -> Option<u64> {
+    if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        return response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_content_range_total)
+            .or_else(|| response.content_length().map(|length| offset + length));
+    }
+    response.content_length()
+}
+
+/// Extrait l'octet de début d'un en-tête `Content-Range: bytes <start>-<end>/<total>`.
+fn parse_content_range_start(value: &str) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes")?.trim_start();
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Valeur `If-Range` identifiant la version téléchargée : ETag fort, sinon Last-Modified.
+///
+/// Un ETag faible (`W/...`) est refusé par `If-Range`, d'où le repli sur la date.
+fn response_validator(etag: Option<&str>, last_modified: Option<&str>) -> Option<String> {
+    etag.map(str::trim)
+        .filter(|etag| !etag.is_empty() && !etag.starts_with("W/"))
+        .or_else(|| last_modified.map(str::trim).filter(|date| !date.is_empty()))
+        .map(str::to_string)
+}
+
+/// Fichier mémorisant le validateur (`If-Range`) d'un `.part`.
+fn part_validator_path(temp_path: &Path) -> std::path::PathBuf {
+    let mut validator_os = temp_path.as_os_str().to_os_string();
+    validator_os.push(".validator");
+    std::path::PathBuf::from(validator_os)
+}
 
+/// Télécharge `url` vers `path_buf` via un fichier `.part`, avec reprise (Range) et retries.
+///
+/// Un `.part` existant (téléchargement interrompu lors d'une session précédente) est repris
+/// avec `If-Range` et l'ETag/Last-Modified mémorisé : si la ressource a changé, le serveur
+/// répond 200 et le fichier est retéléchargé en entier. Un `.part` sans validateur n'est
+/// pas repris. Le `.part` est conservé en cas d'échec.
+///
+/// @param client Client HTTP partagé.
+/// @param url URL à télécharger.
+/// @param path_buf Destination finale.
+/// @param on_chunk Callback appelé avec la taille de chaque bloc écrit (négatif si un bloc
+/// déjà compté est invalidé par un redémarrage complet).
+/// @returns Nombre d'octets du fichier final.
+pub(crate) async fn download_with_resume(
+    client: &reqwest::Client,
+    url: &str,
+    path_buf: &Path,
+    on_chunk: impl FnMut(i64),
+) -> Result<u64, String> {
+    download_with_resume_sized(client, url, path_buf, |_| {}, on_chunk).await
+}
+
+/// Comme `download_with_resume`, en signalant la taille complète du fichier à `on_size`
+/// dès qu'une réponse l'annonce (`Content-Length`/`Content-Range`, éventuellement plusieurs
+/// fois si le téléchargement repart de zéro).
+pub(crate) async fn download_with_resume_sized(
+    client: &reqwest::Client,
+    url: &str,
+    path_buf: &Path,
+    mut on_size: impl FnMut(u64),
+    mut on_chunk: impl FnMut(i64),
+) -> Result<u64, String> {
     let mut temp_os = path_buf.as_os_str().to_os_string();
     temp_os.push(".part");
     let temp_path = std::path::PathBuf::from(temp_os);
-    let _ = tokio::fs::remove_file(&temp_path).await;
+    let validator_path = part_validator_path(&temp_path);
 
-    let client = reqwest::Client::builder()
-        .connect_timeout(Duration::from_secs(15))
-        .timeout(Duration::from_secs(15 * 60))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    // Un `.part` laissé par une session précédente (app fermée, crash) n'est repris que si
+    // la version téléchargée est connue ; sinon il serait raccordé à un autre contenu.
+    let mut validator = tokio::fs::read_to_string(&validator_path)
+        .await
+        .ok()
+        .map(|raw| raw.trim().to_string())
+        .filter(|raw| !raw.is_empty());
+    let mut downloaded = tokio::fs::metadata(&temp_path)
+        .await
+        .ok()
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    if downloaded > 0 && validator.is_none() {
+        log::info!(
+            "[download] Restarting {}: no ETag/Last-Modified recorded for the partial file",
+            path_buf.display()
+        );
+        downloaded = 0;
+    }
+    if downloaded > 0 {
+        log::info!(
+            "[download] Resuming {} from {} bytes",
+            path_buf.display(),
+            downloaded
+        );
+        on_chunk(downloaded as i64);
+    }
 
     let max_retries = 3usize;
-    let mut downloaded = 0u64;
     let mut last_error = String::new();
 
     for attempt in 1..=max_retries {
         let mut request = client
-            .get(&url)
+            .get(url)
             .header(USER_AGENT, "QuranCaption/3")
             .header(ACCEPT, "*/*")
             .header(ACCEPT_ENCODING, "identity");
 
         if downloaded > 0 {
             request = request.header(RANGE, format!("bytes={}-", downloaded));
+            if let Some(validator) = validator.as_deref() {
+                request = request.header(IF_RANGE, validator);
+            }
         }
 
         let response = match request.send().await {
@@ -222,6 +581,19 @@ pub async fn download_file(url: String, path: String) -> Result<(), String> {
             }
         };
 
+        if downloaded > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // `.part` obsolète ou plus grand que la ressource distante : on repart de zéro.
+            on_chunk(-(downloaded as i64));
+            downloaded = 0;
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            let _ = tokio::fs::remove_file(&validator_path).await;
+            last_error = format!(
+                "Partial download rejected by server (attempt {}/{})",
+                attempt, max_retries
+            );
+            continue;
+        }
+
         if !response.status().is_success() {
             last_error = format!(
                 "HTTP error (attempt {}/{}): {}",
@@ -232,9 +604,33 @@ pub async fn download_file(url: String, path: String) -> Result<(), String> {
             continue;
         }
 
-        if downloaded > 0 && response.status() == reqwest::StatusCode::OK {
+        // Seule une réponse 206 dont le Content-Range commence à `downloaded` permet la
+        // reprise ; sinon (serveur sans support Range, ou 200 car `If-Range` ne correspond
+        // plus) le fichier est retéléchargé en entier.
+        if downloaded > 0 && !is_matching_partial_response(&response, downloaded) {
+            on_chunk(-(downloaded as i64));
             downloaded = 0;
         }
+        if let Some(size) = expected_file_size(&response, downloaded) {
+            on_size(size);
+        }
+        if downloaded == 0 {
+            let header = |name| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+            };
+            validator = response_validator(header(ETAG), header(LAST_MODIFIED));
+            match validator.as_deref() {
+                Some(validator) => {
+                    let _ = tokio::fs::write(&validator_path, validator).await;
+                }
+                None => {
+                    let _ = tokio::fs::remove_file(&validator_path).await;
+                }
+            }
+        }
 
         let mut file = if downloaded == 0 {
             tokio::fs::OpenOptions::new()
@@ -262,6 +658,7 @@ pub async fn download_file(url: String, path: String) -> Result<(), String> {
                         .await
                         .map_err(|e| format!("Failed to write file: {}", e))?;
                     downloaded += chunk.len() as u64;
+                    on_chunk(chunk.len() as i64);
                 }
                 Ok(None) => {
                     file.flush()
@@ -281,14 +678,15 @@ pub async fn download_file(url: String, path: String) -> Result<(), String> {
         }
 
         if request_completed {
-            tokio::fs::rename(&temp_path, &path_buf)
+            tokio::fs::rename(&temp_path, path_buf)
                 .await
                 .map_err(|e| format!("Failed to finalize file: {}", e))?;
-            return Ok(());
+            let _ = tokio::fs::remove_file(&validator_path).await;
+            return Ok(downloaded);
         }
     }
 
-    let _ = tokio::fs::remove_file(&temp_path).await;
+    // Le `.part` est conservé pour permettre une reprise lors d'un prochain appel.
     if last_error.is_empty() {
         Err("Download failed after retries".to_string())
     } else {
@@ -296,6 +694,245 @@ pub async fn download_file(url: String, path: String) -> Result<(), String> {
     }
 }
 
+/// Télécharge un fichier HTTP puis l'écrit de manière asynchrone sur disque.
+#[tauri::command]
+pub async fn download_file(url: String, path: String) -> Result<(), String> {
+    let path_buf = prepare_download_destination(&path)?;
+    let client = build_download_client()?;
+    download_with_resume(&client, &url, &path_buf, |_| {}).await?;
+    Ok(())
+}
+
+/// Calcule le SHA-256 (hexadécimal minuscule) d'un fichier.
+pub(crate) fn file_sha256(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to hash file: {}", e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Vérifie le SHA-256 d'un fichier hors du runtime async.
+async fn verify_sha256(path: std::path::PathBuf, expected: String) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        file_sha256(&path).map(|actual| actual.eq_ignore_ascii_case(expected.trim()))
+    })
+    .await
+    .map_err(|e| format!("Hash task failed: {}", e))?
+}
+
+/// Téléchargement demandé à `download_files`.
+#[derive(serde::Deserialize)]
+pub struct DownloadJob {
+    /// URL source.
+    pub url: String,
+    /// Chemin de destination.
+    pub path: String,
+    /// Empreinte SHA-256 attendue (hexadécimal), optionnelle.
+    pub sha256: Option<String>,
+}
+
+/// Résultat d'un téléchargement de `download_files`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobResult {
+    /// URL source.
+    pub url: String,
+    /// Chemin final (nom assaini).
+    pub path: String,
+    /// `true` si le fichier est présent et valide.
+    pub success: bool,
+    /// `true` si un fichier valide existait déjà (aucun téléchargement).
+    pub skipped: bool,
+    /// Erreur éventuelle.
+    pub error: Option<String>,
+}
+
+/// Progression agrégée d'un lot de téléchargements.
+struct BatchDownloadProgress {
+    downloaded_bytes: u64,
+    /// Somme des tailles annoncées par les serveurs.
+    total_bytes: u64,
+    /// Fichiers dont la taille est connue ou qui sont terminés sans l'avoir annoncée.
+    sized_files: usize,
+    completed_files: usize,
+    last_emit: std::time::Instant,
+}
+
+/// Émet la progression agrégée d'un lot de téléchargements.
+fn emit_download_files_progress(
+    app_handle: &tauri::AppHandle,
+    batch_id: Option<&str>,
+    progress: &BatchDownloadProgress,
+    total_files: usize,
+) {
+    let _ = app_handle.emit(
+        "download-files-progress",
+        serde_json::json!({
+            "batchId": batch_id,
+            "downloadedBytes": progress.downloaded_bytes,
+            // Connu seulement quand chaque fichier a annoncé sa taille (ou est terminé).
+            "totalBytes": (progress.sized_files == total_files).then_some(progress.total_bytes),
+            "completedFiles": progress.completed_files,
+            "totalFiles": total_files,
+        }),
+    );
+}
+
+/// Exécute un téléchargement du lot (vérification SHA-256 comprise).
+async fn run_download_job(
+    client: &reqwest::Client,
+    job: DownloadJob,
+    on_size: impl FnMut(u64),
+    on_chunk: impl FnMut(i64),
+) -> DownloadJobResult {
+    let mut result = DownloadJobResult {
+        url: job.url.clone(),
+        path: job.path.clone(),
+        success: false,
+        skipped: false,
+        error: None,
+    };
+    let path_buf = match prepare_download_destination(&job.path) {
+        Ok(path_buf) => path_buf,
+        Err(error) => {
+            result.error = Some(error);
+            return result;
+        }
+    };
+    result.path = path_buf.to_string_lossy().to_string();
+    let expected_sha256 = job
+        .sha256
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty());
+
+    // Un fichier déjà présent avec la bonne empreinte n'est pas retéléchargé.
+    if let Some(expected) = expected_sha256.as_ref() {
+        if path_buf.is_file() && verify_sha256(path_buf.clone(), expected.clone()).await == Ok(true)
+        {
+            result.success = true;
+            result.skipped = true;
+            return result;
+        }
+    }
+
+    if let Err(error) =
+        download_with_resume_sized(client, &job.url, &path_buf, on_size, on_chunk).await
+    {
+        result.error = Some(error);
+        return result;
+    }
+
+    if let Some(expected) = expected_sha256 {
+        match verify_sha256(path_buf.clone(), expected).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = fs::remove_file(&path_buf);
+                result.error = Some("SHA-256 mismatch after download".to_string());
+                return result;
+            }
+            Err(error) => {
+                result.error = Some(error);
+                return result;
+            }
+        }
+    }
+    result.success = true;
+    result
+}
+
+/// Télécharge plusieurs fichiers en parallèle (concurrence bornée).
+///
+/// Chaque fichier réutilise la logique de reprise/retry de `download_file` et peut être
+/// vérifié par SHA-256. La progression agrégée est émise sur `download-files-progress`
+/// (`downloadedBytes`, `totalBytes` si toutes les tailles sont connues, `completedFiles`).
+///
+/// @param jobs Fichiers à télécharger (`url`, `path`, `sha256` optionnel).
+/// @param max_concurrency Nombre maximal de téléchargements simultanés (défaut 3).
+/// @param batch_id Identifiant de corrélation des événements de progression.
+/// @returns Résultat de chaque téléchargement, dans l'ordre des jobs.
+#[tauri::command]
+pub async fn download_files(
+    jobs: Vec<DownloadJob>,
+    max_concurrency: Option<usize>,
+    batch_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<DownloadJobResult>, String> {
+    use futures_util::StreamExt;
+
+    let total_files = jobs.len();
+    let max_concurrency = max_concurrency.unwrap_or(3).clamp(1, 8);
+    let client = build_download_client()?;
+    let progress = std::sync::Mutex::new(BatchDownloadProgress {
+        downloaded_bytes: 0,
+        total_bytes: 0,
+        sized_files: 0,
+        completed_files: 0,
+        last_emit: std::time::Instant::now(),
+    });
+    let batch_id = batch_id.as_deref();
+
+    let mut results: Vec<(usize, DownloadJobResult)> =
+        futures_util::stream::iter(jobs.into_iter().enumerate())
+            .map(|(index, job)| {
+                let client = &client;
+                let progress = &progress;
+                let app_handle = &app_handle;
+                async move {
+                    // Taille annoncée pour ce fichier (remplacée si le serveur en annonce une autre).
+                    let reported_size = std::sync::Mutex::new(None::<u64>);
+                    let on_size = |size: u64| {
+                        let Ok(mut state) = progress.lock() else {
+                            return;
+                        };
+                        let previous = reported_size
+                            .lock()
+                            .ok()
+                            .and_then(|mut reported| reported.replace(size));
+                        match previous {
+                            Some(previous) => {
+                                state.total_bytes = state.total_bytes - previous + size;
+                            }
+                            None => {
+                                state.total_bytes += size;
+                                state.sized_files += 1;
+                            }
+                        }
+                    };
+                    let on_chunk = |delta: i64| {
+                        let Ok(mut state) = progress.lock() else {
+                            return;
+                        };
+                        state.downloaded_bytes =
+                            state.downloaded_bytes.saturating_add_signed(delta);
+                        if state.last_emit.elapsed() >= Duration::from_millis(250) {
+                            state.last_emit = std::time::Instant::now();
+                            emit_download_files_progress(app_handle, batch_id, &state, total_files);
+                        }
+                    };
+                    let result = run_download_job(client, job, on_size, on_chunk).await;
+                    if let Ok(mut state) = progress.lock() {
+                        state.completed_files += 1;
+                        if reported_size
+                            .lock()
+                            .is_ok_and(|reported| reported.is_none())
+                        {
+                            state.sized_files += 1;
+                        }
+                        emit_download_files_progress(app_handle, batch_id, &state, total_files);
+                    }
+                    (index, result)
+                }
+            })
+            .buffer_unordered(max_concurrency)
+            .collect()
+            .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
 /// Supprime un fichier existant.
 #[tauri::command]
 pub fn delete_file(path: String) -> Result<(), String> {
@@ -354,22 +991,97 @@ pub async fn send_http_text(url: String) -> Result<String, String> {
         .map_err(|e| format!("Failed to read HTTP response body: {}", e))
 }
 
+/// Émet la progression d'un déplacement de fichier vers le frontend.
+///
+/// @param app_handle Gestionnaire Tauri utilisé pour publier l'événement.
+/// @param move_request_id Identifiant de corrélation du déplacement.
+/// @param progress Pourcentage de progression entre 0 et 100.
+/// @param status État textuel du déplacement.
+fn emit_move_progress(
+    app_handle: &tauri::AppHandle,
+    move_request_id: &str,
+    progress: u8,
+    status: &str,
+) {
+    let _ = app_handle.emit(
+        "move-progress",
+        serde_json::json!({
+            "moveRequestId": move_request_id,
+            "progress": progress,
+            "status": status
+        }),
+    );
+}
+
 /// Déplace un fichier avec fallback copy+delete sur erreur cross-device.
+///
+/// Le fallback copie par blocs via un fichier `.part` et publie `move-progress`,
+/// afin qu'un déplacement volumineux vers un autre disque ne paraisse pas figé.
+///
+/// @param source Chemin du fichier à déplacer.
+/// @param destination Chemin final du fichier.
+/// @param move_request_id Identifiant optionnel pour relayer la progression.
+/// @param app_handle Gestionnaire Tauri utilisé pour publier la progression.
 #[tauri::command]
-pub fn move_file(source: String, destination: String) -> Result<(), String> {
+pub async fn move_file(
+    source: String,
+    destination: String,
+    move_request_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        move_file_blocking(source, destination, move_request_id, app_handle)
+    })
+    .await
+    .map_err(|e| format!("Unable to join move task: {}", e))?
+}
+
+/// Exécute le déplacement bloquant hors du thread principal.
+fn move_file_blocking(
+    source: String,
+    destination: String,
+    move_request_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
     let source_path = path_utils::normalize_existing_path(&source);
     let dest_path = path_utils::normalize_output_path(&destination);
+    let move_request_id = move_request_id.unwrap_or_else(|| {
+        format!(
+            "move-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|duration| duration.as_millis())
+                .unwrap_or(0)
+        )
+    });
 
     if dest_path.exists() {
         std::fs::remove_file(&dest_path).map_err(|e| e.to_string())?;
     }
 
     match std::fs::rename(&source_path, &dest_path) {
-        Ok(()) => Ok(()),
+        Ok(()) => {
+            emit_move_progress(&app_handle, &move_request_id, 100, "finished");
+            Ok(())
+        }
         Err(e) => {
             if e.raw_os_error() == Some(17) || e.raw_os_error() == Some(18) {
-                std::fs::copy(&source_path, &dest_path).map_err(|e| e.to_string())?;
+                let mut temp_name = dest_path.as_os_str().to_os_string();
+                temp_name.push(".part");
+                let temp_path = std::path::PathBuf::from(temp_name);
+
+                emit_move_progress(&app_handle, &move_request_id, 0, "copying");
+                let copy_result = copy_file_in_chunks(&source_path, &temp_path, |progress| {
+                    emit_move_progress(&app_handle, &move_request_id, progress, "copying");
+                })
+                .and_then(|_| std::fs::rename(&temp_path, &dest_path).map_err(|e| e.to_string()));
+                if let Err(error) = copy_result {
+                    let _ = std::fs::remove_file(&temp_path);
+                    return Err(format!("Failed to move file: {}", error));
+                }
+
                 std::fs::remove_file(&source_path).map_err(|e| e.to_string())?;
+                emit_move_progress(&app_handle, &move_request_id, 100, "finished");
                 Ok(())
             } else {
                 Err(e.to_string())
@@ -378,9 +1090,105 @@ pub fn move_file(source: String, destination: String) -> Result<(), String> {
     }
 }
 
+/// Liste puis supprime les fichiers temporaires laissés par un crash de l'application.
+///
+/// @param max_age_hours Âge minimal (en heures) d'un fichier pour être considéré orphelin, 24 par défaut.
+/// @param dry_run Si vrai, liste les fichiers sans les supprimer.
+/// @returns Rapport listant les fichiers trouvés, le nombre supprimé et les octets libérés.
+#[tauri::command]
+pub fn cleanup_temp_files(
+    max_age_hours: Option<u64>,
+    dry_run: Option<bool>,
+) -> Result<TempCleanupReport, String> {
+    let min_age = Duration::from_secs(max_age_hours.unwrap_or(24) * 3600);
+    let dry_run = dry_run.unwrap_or(false);
+    let mut report = TempCleanupReport {
+        files: Vec::new(),
+        deleted_count: 0,
+        freed_bytes: 0,
+    };
+
+    for (path, size) in find_orphaned_temp_files(min_age) {
+        report.files.push(path.to_string_lossy().to_string());
+        if dry_run {
+            continue;
+        }
+        if fs::remove_file(&path).is_ok() {
+            report.deleted_count += 1;
+            report.freed_bytes += size;
+        }
+    }
+
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::copy_progress_percent;
+    use super::{
+        copy_progress_percent, describe_path, parse_content_range_start, parse_content_range_total,
+        response_validator, validate_output_location,
+    };
+
+    #[test]
+    fn path_info_describes_files_dirs_and_missing_paths() {
+        let dir = std::env::temp_dir().join(format!(
+            "qurancaption-path-info-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("audio.mp3");
+        std::fs::write(&file, [0u8; 12]).unwrap();
+
+        let file_info = describe_path(&file);
+        assert!(file_info.exists && file_info.is_file && !file_info.is_dir);
+        assert_eq!(file_info.size, Some(12));
+        assert!(file_info.modified_ms.is_some());
+
+        let dir_info = describe_path(&dir);
+        assert!(dir_info.is_dir && dir_info.size.is_none());
+
+        let missing = describe_path(&dir.join("missing.mp3"));
+        assert!(!missing.exists && !missing.is_file && !missing.is_dir);
+        assert_eq!(missing.modified_ms, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn output_location_requires_an_existing_writable_parent() {
+        let dir = std::env::temp_dir().join(format!(
+            "qurancaption-output-path-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let valid = validate_output_location(&dir.join("video.mp4"));
+        assert!(valid.valid && valid.parent_exists && valid.writable && !valid.exists);
+        assert_eq!(valid.error, None);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        std::fs::write(dir.join("video.mp4"), b"old").unwrap();
+        let existing = validate_output_location(&dir.join("video.mp4"));
+        assert!(existing.valid && existing.exists);
+
+        let missing = validate_output_location(&dir.join("missing").join("video.mp4"));
+        assert!(!missing.valid && !missing.parent_exists && !missing.writable);
+        assert!(missing
+            .error
+            .unwrap()
+            .starts_with("Directory does not exist"));
+
+        let directory = validate_output_location(&dir);
+        assert!(!directory.valid);
+        assert!(directory
+            .error
+            .unwrap()
+            .starts_with("Output path is a directory"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
     #[test]
     fn copy_progress_is_bounded() {
@@ -389,4 +1197,30 @@ mod tests {
         assert_eq!(copy_progress_percent(20, 10), 100);
         assert_eq!(copy_progress_percent(0, 0), 100);
     }
+
+    #[test]
+    fn content_range_start_is_parsed() {
+        assert_eq!(
+            parse_content_range_start("bytes 1024-2047/4096"),
+            Some(1024)
+        );
+        assert_eq!(parse_content_range_start("bytes 0-99/*"), Some(0));
+        assert_eq!(parse_content_range_start("bytes */4096"), None);
+        assert_eq!(parse_content_range_start("items 1-2/3"), None);
+        assert_eq!(
+            parse_content_range_total("bytes 1024-2047/4096"),
+            Some(4096)
+        );
+        assert_eq!(parse_content_range_total("bytes 0-99/*"), None);
+
+        assert_eq!(
+            response_validator(Some("\"abc\""), Some("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some("\"abc\"".to_string())
+        );
+        assert_eq!(
+            response_validator(Some("W/\"abc\""), Some("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string())
+        );
+        assert_eq!(response_validator(None, None), None);
+    }
 }
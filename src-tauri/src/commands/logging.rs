@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+use tauri::plugin::TauriPlugin;
+use tauri::{Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+/// Nom du fichier journal du backend (sans extension) dans le dossier de logs de l'application.
+const LOG_FILE_NAME: &str = "qurancaption";
+/// Taille au-delà de laquelle le journal est archivé (octets).
+const LOG_MAX_FILE_SIZE: u128 = 5 * 1024 * 1024;
+/// Nombre de journaux archivés conservés.
+const LOG_KEEP_FILES: usize = 5;
+/// Fichier des données de l'application qui mémorise le niveau choisi par l'utilisateur.
+const LOG_LEVEL_FILE: &str = "log_level";
+
+/// Convertit un niveau reçu du frontend (`off`, `error`, `warn`, `info`, `debug`, `trace`).
+fn parse_log_level(raw: &str) -> Result<LevelFilter, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "off" => Ok(LevelFilter::Off),
+        "error" => Ok(LevelFilter::Error),
+        "warn" | "warning" => Ok(LevelFilter::Warn),
+        "info" => Ok(LevelFilter::Info),
+        "debug" => Ok(LevelFilter::Debug),
+        "trace" => Ok(LevelFilter::Trace),
+        other => Err(format!("Unknown log level: {}", other)),
+    }
+}
+
+/// Niveau par défaut : journalisation active en debug, opt-in en release.
+fn default_log_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Info
+    } else {
+        LevelFilter::Off
+    }
+}
+
+/// Chemin du fichier qui mémorise le niveau de log.
+fn log_level_file(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(LOG_LEVEL_FILE))
+}
+
+/// Construit le plugin de log : console et fichier tournant dans le dossier de logs.
+///
+/// Le plugin laisse passer tous les niveaux ; le filtrage effectif se fait via
+/// `log::set_max_level`, modifiable à chaud par `set_log_level`.
+pub(crate) fn build_log_plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::default()
+        .clear_targets()
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::LogDir {
+                file_name: Some(LOG_FILE_NAME.to_string()),
+            }),
+        ])
+        .level(LevelFilter::Trace)
+        .max_file_size(LOG_MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(LOG_KEEP_FILES))
+        .build()
+}
+
+/// Applique le niveau mémorisé (ou le niveau par défaut) une fois le plugin initialisé.
+pub(crate) fn apply_saved_log_level(app_handle: &tauri::AppHandle) {
+    let level = log_level_file(app_handle)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| parse_log_level(&raw).ok())
+        .unwrap_or_else(default_log_level);
+    log::set_max_level(level);
+}
+
+/// Change le niveau de journalisation du backend et le mémorise pour les prochains lancements.
+///
+/// `off` désactive l'écriture du fichier journal.
+#[tauri::command]
+pub fn set_log_level(app_handle: tauri::AppHandle, level: String) -> Result<(), String> {
+    let level = parse_log_level(&level)?;
+    let path = log_level_file(&app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, level.as_str().to_ascii_lowercase())
+        .map_err(|e| format!("Failed to save log level: {}", e))?;
+    log::set_max_level(level);
+    log::info!("[logging] log level set to {}", level);
+    Ok(())
+}
+
+/// Retourne le chemin du fichier journal courant du backend, à joindre aux rapports de bug.
+#[tauri::command]
+pub fn get_log_file_path(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let log_dir = app_handle.path().app_log_dir().map_err(|e| e.to_string())?;
+    Ok(log_dir
+        .join(format!("{}.log", LOG_FILE_NAME))
+        .to_string_lossy()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_log_level;
+    use log::LevelFilter;
+
+    #[test]
+    fn log_levels_are_parsed_case_insensitively() {
+        assert_eq!(parse_log_level(" Debug "), Ok(LevelFilter::Debug));
+        assert_eq!(parse_log_level("warning"), Ok(LevelFilter::Warn));
+        assert_eq!(parse_log_level("off"), Ok(LevelFilter::Off));
+        assert_eq!(
+            parse_log_level(&LevelFilter::Trace.as_str().to_ascii_lowercase()),
+            Ok(LevelFilter::Trace)
+        );
+        assert!(parse_log_level("verbose").is_err());
+    }
+}
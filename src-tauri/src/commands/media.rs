@@ -1,22 +1,27 @@
 use std::collections::HashSet;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-use std::thread;
+use std::process::Command;
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use font_kit::family_name::FamilyName;
 use font_kit::file_type::FileType;
 use font_kit::font::Font;
 use font_kit::handle::Handle;
-use font_kit::properties::Style;
+use font_kit::properties::{Properties, Style};
 use font_kit::source::SystemSource;
+use rayon::prelude::*;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
 use crate::binaries;
 use crate::path_utils;
-use crate::utils::process::configure_command_no_window;
+use crate::utils::ffmpeg;
+use crate::utils::process::{
+    self, configure_command_no_window, output_with_timeout, PROBE_TIMEOUT,
+};
+use crate::utils::temp_file::TempFileGuard;
 
 use super::diagnostics::{format_ffprobe_exec_failed, map_ffprobe_resolve_error};
 
@@ -35,6 +40,12 @@ pub struct SystemFontSource {
     pub font_style: String,
 }
 
+/// Familles de polices système énumérées, mises en cache pour la session.
+static SYSTEM_FONTS_CACHE: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Polices chargées depuis un fichier via `register_custom_font` pour la session.
+static CUSTOM_FONT_SOURCES: Mutex<Vec<SystemFontSource>> = Mutex::new(Vec::new());
+
 /// Retourne la durée d'un média en millisecondes via ffprobe.
 #[tauri::command]
 pub fn get_duration(file_path: &str) -> Result<i64, String> {
@@ -59,7 +70,7 @@ pub fn get_duration(file_path: &str) -> Result<i64, String> {
         file_path.to_string_lossy().as_ref(),
     ]);
     configure_command_no_window(&mut cmd);
-    let output = cmd.output();
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT);
 
     match output {
         Ok(result) => {
@@ -83,9 +94,8 @@ pub fn get_duration(file_path: &str) -> Result<i64, String> {
     }
 }
 
-/// Retourne la liste des polices système disponibles (noms de familles uniques).
-#[tauri::command]
-pub fn get_system_fonts() -> Result<Vec<String>, String> {
+/// Énumère les familles de polices système (sans cache).
+fn enumerate_system_fonts() -> Result<Vec<String>, String> {
     let source = SystemSource::new();
     // all_families() is the most portable API and avoids loading every single font file.
     if let Ok(mut families) = source.all_families() {
@@ -95,21 +105,556 @@ pub fn get_system_fonts() -> Result<Vec<String>, String> {
     }
 
     // Fallback path: enumerate handles and ignore fonts that fail to load.
+    // A handle carries no metadata, so the family name needs a full load: spread the loads
+    // over the rayon pool since this is the slow part with large font libraries.
     let fonts = source.all_fonts().map_err(|e| e.to_string())?;
-    let mut font_names = Vec::new();
-    let mut seen_names = HashSet::new();
-
-    for font in fonts {
-        if let Ok(handle) = font.load() {
-            let family = handle.family_name();
-            if seen_names.insert(family.clone()) {
-                font_names.push(family);
+    let mut font_names: Vec<String> = fonts
+        .par_iter()
+        .filter_map(|font| font.load().ok().map(|handle| handle.family_name()))
+        .collect();
+
+    font_names.sort();
+    font_names.dedup();
+    Ok(font_names)
+}
+
+/// Retourne la liste des polices système disponibles (noms de familles uniques).
+///
+/// L'énumération est lente sur les machines avec beaucoup de polices : le résultat est
+/// mis en cache pour la session (voir `refresh_system_fonts`).
+#[tauri::command]
+pub fn get_system_fonts() -> Result<Vec<String>, String> {
+    if let Some(families) = SYSTEM_FONTS_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.clone())
+    {
+        return Ok(families);
+    }
+    refresh_system_fonts()
+}
+
+/// Force une nouvelle énumération des polices système et met à jour le cache.
+#[tauri::command]
+pub fn refresh_system_fonts() -> Result<Vec<String>, String> {
+    let families = enumerate_system_fonts()?;
+    if let Ok(mut cache) = SYSTEM_FONTS_CACHE.lock() {
+        *cache = Some(families.clone());
+    }
+    if let Ok(mut cache) = FONT_CATEGORIES_CACHE.lock() {
+        *cache = None;
+    }
+    Ok(families)
+}
+
+/// Échantillon de lettres arabes (bloc U+0600–U+06FF) qu'une police doit couvrir.
+const ARABIC_SAMPLE_CHARS: [char; 12] = [
+    '\u{0627}', '\u{0628}', '\u{062A}', '\u{062D}', '\u{0633}', '\u{0639}', '\u{0644}', '\u{0645}',
+    '\u{0646}', '\u{0647}', '\u{0648}', '\u{064A}',
+];
+
+/// Indique si une police possède un vrai glyphe (hors `.notdef`) pour un caractère.
+fn font_has_glyph(font: &Font, c: char) -> bool {
+    font.glyph_for_char(c).is_some_and(|glyph_id| glyph_id != 0)
+}
+
+/// Indique si une police possède un glyphe pour chaque lettre arabe de l'échantillon.
+fn font_supports_arabic(font: &Font) -> bool {
+    ARABIC_SAMPLE_CHARS.iter().all(|&c| font_has_glyph(font, c))
+}
+
+/// Retourne les familles de polices système capables d'afficher de l'arabe.
+///
+/// Chaque famille est retenue si au moins une de ses variantes couvre l'échantillon
+/// `ARABIC_SAMPLE_CHARS` ; les polices impossibles à charger sont ignorées.
+#[tauri::command]
+pub async fn get_arabic_fonts() -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let families = get_system_fonts()?;
+        // Une source par thread rayon : les sources système ne sont pas partageables.
+        Ok(families
+            .into_par_iter()
+            .map_init(SystemSource::new, |source, family| {
+                let supported = source
+                    .select_family_by_name(&family)
+                    .map(|handle| {
+                        handle.fonts().iter().any(|font_handle| {
+                            font_handle
+                                .load()
+                                .map(|font| font_supports_arabic(&font))
+                                .unwrap_or(false)
+                        })
+                    })
+                    .unwrap_or(false);
+                supported.then_some(family)
+            })
+            .flatten()
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Arabic font detection task failed: {}", e))?
+}
+
+/// Échantillon de lettres latines qu'une police doit couvrir pour être classée `latin`.
+const LATIN_SAMPLE_CHARS: [char; 10] = ['A', 'E', 'M', 'Q', 'Z', 'a', 'e', 'm', 'q', 'z'];
+
+/// Polices système classées par écriture couverte.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontsByCategory {
+    /// Polices couvrant l'arabe mais pas le latin.
+    pub arabic: Vec<String>,
+    /// Polices couvrant le latin mais pas l'arabe.
+    pub latin: Vec<String>,
+    /// Polices couvrant l'arabe et le latin.
+    pub both: Vec<String>,
+    /// Polices ne couvrant aucun des deux échantillons (symboles, dingbats, autres écritures).
+    pub symbol: Vec<String>,
+}
+
+/// Classement mis en cache pour la session (vidé par `refresh_system_fonts`).
+static FONT_CATEGORIES_CACHE: Mutex<Option<FontsByCategory>> = Mutex::new(None);
+
+/// Détermine si une famille couvre l'arabe et/ou le latin (au moins une variante chacun).
+fn family_script_coverage(source: &SystemSource, family: &str) -> (bool, bool) {
+    let Ok(handle) = source.select_family_by_name(family) else {
+        return (false, false);
+    };
+    let (mut arabic, mut latin) = (false, false);
+    for font in handle.fonts().iter().filter_map(|font| font.load().ok()) {
+        arabic = arabic || font_supports_arabic(&font);
+        latin = latin || LATIN_SAMPLE_CHARS.iter().all(|&c| font_has_glyph(&font, c));
+        if arabic && latin {
+            break;
+        }
+    }
+    (arabic, latin)
+}
+
+/// Retourne les polices système regroupées par écriture (`arabic`, `latin`, `both`, `symbol`).
+///
+/// Le classement charge chaque police pour échantillonner ses glyphes : il est donc séparé de
+/// `get_system_fonts` et mis en cache pour la session.
+#[tauri::command]
+pub async fn get_fonts_by_category() -> Result<FontsByCategory, String> {
+    if let Some(categories) = FONT_CATEGORIES_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.clone())
+    {
+        return Ok(categories);
+    }
+
+    let categories = tauri::async_runtime::spawn_blocking(|| {
+        let families = get_system_fonts()?;
+        let coverage: Vec<(String, bool, bool)> = families
+            .into_par_iter()
+            .map_init(SystemSource::new, |source, family| {
+                let (arabic, latin) = family_script_coverage(source, &family);
+                (family, arabic, latin)
+            })
+            .collect();
+
+        let mut categories = FontsByCategory::default();
+        for (family, arabic, latin) in coverage {
+            match (arabic, latin) {
+                (true, true) => categories.both.push(family),
+                (true, false) => categories.arabic.push(family),
+                (false, true) => categories.latin.push(family),
+                (false, false) => categories.symbol.push(family),
             }
         }
+        Ok::<_, String>(categories)
+    })
+    .await
+    .map_err(|e| format!("Font categorization task failed: {}", e))??;
+
+    if let Ok(mut cache) = FONT_CATEGORIES_CACHE.lock() {
+        *cache = Some(categories.clone());
     }
+    Ok(categories)
+}
 
-    font_names.sort();
-    Ok(font_names)
+/// Métriques verticales d'une police, en unités de police et en pixels.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontMetrics {
+    /// Famille de la police résolue.
+    pub family: String,
+    /// Unités par em de la police.
+    pub units_per_em: u32,
+    /// Ascendante (unités de police, positive).
+    pub ascent: f32,
+    /// Descendante (unités de police, négative).
+    pub descent: f32,
+    /// Interligne additionnel recommandé (unités de police).
+    pub line_gap: f32,
+    /// Hauteur des capitales (unités de police).
+    pub cap_height: f32,
+    /// Hauteur d'x (unités de police).
+    pub x_height: f32,
+    /// Taille demandée en pixels.
+    pub size_px: f32,
+    /// Ascendante à `size_px`.
+    pub ascent_px: f32,
+    /// Descendante à `size_px` (négative).
+    pub descent_px: f32,
+    /// Hauteur de ligne à `size_px` (ascendante - descendante + interligne).
+    pub line_height_px: f32,
+    /// Hauteur des capitales à `size_px`.
+    pub cap_height_px: f32,
+}
+
+/// Résout une police depuis un chemin de fichier ou un nom de famille.
+///
+/// Les polices enregistrées via `register_custom_font` sont prioritaires sur les
+/// polices système de même famille.
+fn resolve_font_handle(family_or_path: &str) -> Result<Handle, String> {
+    let family_or_path = family_or_path.trim();
+    if family_or_path.is_empty() {
+        return Err("Font family or path is required".to_string());
+    }
+
+    let path_buf = path_utils::normalize_existing_path(family_or_path);
+    if path_buf.is_file() {
+        return Ok(Handle::from_path(path_buf, 0));
+    }
+
+    let custom_source = CUSTOM_FONT_SOURCES.lock().ok().and_then(|custom_fonts| {
+        custom_fonts
+            .iter()
+            .find(|source| source.family.eq_ignore_ascii_case(family_or_path))
+            .cloned()
+    });
+    if let Some(source) = custom_source {
+        return Ok(Handle::from_path(
+            PathBuf::from(&source.path),
+            source.font_index,
+        ));
+    }
+
+    SystemSource::new()
+        .select_best_match(
+            &[FamilyName::Title(family_or_path.to_string())],
+            &Properties::new(),
+        )
+        .map_err(|_| format!("Font not found: {}", family_or_path))
+}
+
+/// Charge une police depuis un chemin de fichier ou un nom de famille.
+fn load_font_by_family_or_path(family_or_path: &str) -> Result<Font, String> {
+    resolve_font_handle(family_or_path)?
+        .load()
+        .map_err(|e| format!("Failed to load font '{}': {:?}", family_or_path.trim(), e))
+}
+
+/// Retourne les métriques verticales d'une police pour positionner précisément les captions.
+///
+/// @param family_or_path Nom de famille (système ou enregistrée) ou chemin d'un fichier.
+/// @param size_px Taille de rendu en pixels pour les valeurs `*_px`.
+#[tauri::command]
+pub fn get_font_metrics(family_or_path: String, size_px: f32) -> Result<FontMetrics, String> {
+    if !size_px.is_finite() || size_px <= 0.0 {
+        return Err(format!("Invalid font size: {}", size_px));
+    }
+    let font = load_font_by_family_or_path(&family_or_path)?;
+    let metrics = font.metrics();
+    let units_per_em = metrics.units_per_em.max(1);
+    let scale = size_px / units_per_em as f32;
+
+    Ok(FontMetrics {
+        family: font.family_name(),
+        units_per_em,
+        ascent: metrics.ascent,
+        descent: metrics.descent,
+        line_gap: metrics.line_gap,
+        cap_height: metrics.cap_height,
+        x_height: metrics.x_height,
+        size_px,
+        ascent_px: metrics.ascent * scale,
+        descent_px: metrics.descent * scale,
+        line_height_px: (metrics.ascent - metrics.descent + metrics.line_gap) * scale,
+        cap_height_px: metrics.cap_height * scale,
+    })
+}
+
+/// Caractère d'un texte qu'une police ne sait pas afficher.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedCodepoint {
+    /// Point de code Unicode.
+    pub codepoint: u32,
+    /// Notation `U+XXXX` pour l'affichage.
+    pub unicode: String,
+    /// Le caractère lui-même.
+    pub character: String,
+}
+
+/// Indique si un caractère s'affiche sans glyphe (espaces, contrôles, marques bidi/jointure).
+fn is_glyphless_char(c: char) -> bool {
+    c.is_whitespace()
+        || c.is_control()
+        || matches!(
+            c,
+            '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}'
+        )
+}
+
+/// Vérifie qu'une police couvre chaque caractère d'un texte (harakat, ponctuation, traduction).
+///
+/// @param family_or_path Nom de famille (système ou enregistrée) ou chemin d'un fichier.
+/// @param text Texte de la caption à vérifier.
+/// @returns Caractères sans glyphe, sans doublon, dans l'ordre d'apparition (vide si tout est couvert).
+#[tauri::command]
+pub fn font_covers_text(
+    family_or_path: String,
+    text: String,
+) -> Result<Vec<UnsupportedCodepoint>, String> {
+    let font = load_font_by_family_or_path(&family_or_path)?;
+    let mut seen = HashSet::new();
+    Ok(text
+        .chars()
+        .filter(|&c| !is_glyphless_char(c) && seen.insert(c) && !font_has_glyph(&font, c))
+        .map(|c| UnsupportedCodepoint {
+            codepoint: c as u32,
+            unicode: format!("U+{:04X}", c as u32),
+            character: c.to_string(),
+        })
+        .collect())
+}
+
+/// Axe de variation d'une police variable (table OpenType `fvar`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FontVariationAxis {
+    /// Tag de l'axe (`wght`, `wdth`, `slnt`, `ital`, `opsz` ou tag propre à la police).
+    pub tag: String,
+    /// Nom lisible des axes enregistrés (`Weight`, `Width`, ...).
+    pub name: Option<String>,
+    /// Valeur minimale de l'axe.
+    pub min_value: f32,
+    /// Valeur par défaut de l'axe.
+    pub default_value: f32,
+    /// Valeur maximale de l'axe.
+    pub max_value: f32,
+    /// Axe que la police demande de ne pas proposer dans l'interface.
+    pub hidden: bool,
+}
+
+/// Taille minimale d'un enregistrement d'axe dans `fvar`.
+const FVAR_AXIS_RECORD_SIZE: usize = 20;
+/// Drapeau `HIDDEN_AXIS` d'un enregistrement d'axe.
+const FVAR_HIDDEN_AXIS_FLAG: u16 = 0x0001;
+
+/// Nom lisible d'un axe enregistré par la spécification OpenType.
+fn registered_axis_name(tag: &str) -> Option<&'static str> {
+    match tag {
+        "wght" => Some("Weight"),
+        "wdth" => Some("Width"),
+        "slnt" => Some("Slant"),
+        "ital" => Some("Italic"),
+        "opsz" => Some("Optical size"),
+        _ => None,
+    }
+}
+
+/// Décode les axes d'une table `fvar` (liste vide si la table est tronquée ou inconnue).
+fn parse_fvar_axes(fvar: &[u8]) -> Vec<FontVariationAxis> {
+    let read_u16 = |offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(
+            fvar.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+    let read_fixed = |offset: usize| -> Option<f32> {
+        let raw = i32::from_be_bytes(fvar.get(offset..offset + 4)?.try_into().ok()?);
+        Some(raw as f32 / 65536.0)
+    };
+    let (Some(1), Some(axes_offset), Some(axis_count), Some(axis_size)) =
+        (read_u16(0), read_u16(4), read_u16(8), read_u16(10))
+    else {
+        return Vec::new();
+    };
+    let axis_size = axis_size as usize;
+    if axis_size < FVAR_AXIS_RECORD_SIZE {
+        return Vec::new();
+    }
+
+    (0..axis_count as usize)
+        .map_while(|index| {
+            let record = axes_offset as usize + index * axis_size;
+            let tag = String::from_utf8_lossy(fvar.get(record..record + 4)?)
+                .trim_end()
+                .to_string();
+            Some(FontVariationAxis {
+                name: registered_axis_name(&tag).map(str::to_string),
+                min_value: read_fixed(record + 4)?,
+                default_value: read_fixed(record + 8)?,
+                max_value: read_fixed(record + 12)?,
+                hidden: read_u16(record + 16)? & FVAR_HIDDEN_AXIS_FLAG != 0,
+                tag,
+            })
+        })
+        .collect()
+}
+
+/// Liste les axes de variation d'une police variable (ex: slider de graisse).
+///
+/// @param path Chemin du fichier de police (un nom de famille est aussi accepté).
+/// @returns Axes dans l'ordre de la police ; vide pour une police non variable.
+#[tauri::command]
+pub fn get_font_variations(path: String) -> Result<Vec<FontVariationAxis>, String> {
+    let font = load_font_by_family_or_path(&path)?;
+    Ok(font
+        .load_font_table(u32::from_be_bytes(*b"fvar"))
+        .map(|fvar| parse_fvar_axes(&fvar))
+        .unwrap_or_default())
+}
+
+/// Marge (px) autour du texte dans les aperçus de police.
+const FONT_PREVIEW_PADDING_PX: f32 = 16.0;
+
+/// Échappe un chemin pour une option de filtre ffmpeg (`fontfile`, `textfile`).
+fn escape_filter_option_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .replace(':', "\\:")
+        .replace('\'', "\\'")
+}
+
+/// Estime la largeur (px) d'un texte à partir des avances de glyphes (sans shaping).
+fn estimate_text_width_px(font: &Font, text: &str, size_px: f32) -> f32 {
+    let units_per_em = font.metrics().units_per_em.max(1) as f32;
+    let advance_units: f32 = text
+        .chars()
+        .filter_map(|c| font.glyph_for_char(c))
+        .filter_map(|glyph_id| font.advance(glyph_id).ok())
+        .map(|advance| advance.x())
+        .sum();
+    advance_units * size_px / units_per_em
+}
+
+/// Rend un texte d'exemple dans une police en PNG (fond transparent) pour le sélecteur.
+///
+/// Le rendu passe par le filtre `drawtext` de ffmpeg avec `text_shaping=1` pour que
+/// l'arabe soit correctement lié et affiché de droite à gauche.
+///
+/// @param family_or_path Nom de famille (système ou enregistrée) ou chemin d'un fichier.
+/// @param text Texte d'exemple (ex: "بسم الله").
+/// @param size Taille du texte en pixels.
+/// @param color Couleur ffmpeg (`white`, `#RRGGBB`, `#RRGGBBAA`).
+/// @param output_path Chemin du PNG généré.
+/// @returns Chemin du PNG écrit.
+#[tauri::command]
+pub async fn render_font_preview(
+    family_or_path: String,
+    text: String,
+    size: u32,
+    color: Option<String>,
+    output_path: String,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if text.trim().is_empty() {
+            return Err("Preview text is empty".to_string());
+        }
+        if !(4..=512).contains(&size) {
+            return Err(format!("Invalid preview size: {}", size));
+        }
+        let color = color.unwrap_or_else(|| "white".to_string());
+        if color.is_empty()
+            || !color
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '#' | '@' | '.'))
+        {
+            return Err(format!("Invalid preview color: {}", color));
+        }
+
+        let handle = resolve_font_handle(&family_or_path)?;
+        let font = handle
+            .load()
+            .map_err(|e| format!("Failed to load font '{}': {:?}", family_or_path.trim(), e))?;
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        let mut _font_guard: Option<TempFileGuard> = None;
+        let font_path = match handle {
+            Handle::Path { path, .. } => path,
+            Handle::Memory { bytes, .. } => {
+                let temp_font =
+                    std::env::temp_dir().join(format!("qurancaption-font-preview-{}.ttf", stamp));
+                fs::write(&temp_font, bytes.as_slice())
+                    .map_err(|e| format!("Failed to write temporary font: {}", e))?;
+                _font_guard = Some(TempFileGuard(temp_font.clone()));
+                temp_font
+            }
+        };
+        let text_path = std::env::temp_dir().join(format!("qurancaption-font-preview-{}.txt", stamp));
+        fs::write(&text_path, text.trim())
+            .map_err(|e| format!("Failed to write preview text: {}", e))?;
+        let _text_guard = TempFileGuard(text_path.clone());
+
+        let metrics = font.metrics();
+        let scale = size as f32 / metrics.units_per_em.max(1) as f32;
+        let line_height = (metrics.ascent - metrics.descent + metrics.line_gap) * scale;
+        // Marge de 20 % : les formes contextuelles arabes sont souvent plus larges qu'isolées.
+        let width = (estimate_text_width_px(&font, text.trim(), size as f32) * 1.2
+            + FONT_PREVIEW_PADDING_PX * 2.0)
+            .ceil()
+            .max(size as f32) as u32;
+        let height = (line_height.max(size as f32) + FONT_PREVIEW_PADDING_PX * 2.0).ceil() as u32;
+
+        let output = path_utils::normalize_output_path(&output_path);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        let ffmpeg_path =
+            binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
+        let input = format!("color=c=black@0.0:s={}x{}", width, height);
+        let mut last_error = String::new();
+        // Les builds ffmpeg sans libfribidi n'exposent pas `text_shaping` : on réessaie sans.
+        for text_shaping in [true, false] {
+            let filter = format!(
+                "format=rgba,drawtext=fontfile='{}':textfile='{}':fontsize={}:fontcolor={}{}:x=(w-text_w)/2:y=(h-text_h)/2",
+                escape_filter_option_path(&font_path),
+                escape_filter_option_path(&text_path),
+                size,
+                color,
+                if text_shaping { ":text_shaping=1" } else { "" }
+            );
+            let mut cmd = Command::new(&ffmpeg_path);
+            cmd.args([
+                "-y",
+                "-hide_banner",
+                "-loglevel",
+                "error",
+                "-f",
+                "lavfi",
+                "-i",
+                &input,
+                "-vf",
+                &filter,
+                "-frames:v",
+                "1",
+                output.to_string_lossy().as_ref(),
+            ]);
+            configure_command_no_window(&mut cmd);
+            // Borné : un chargement de police lent ou un ffmpeg bloqué ne doit pas figer le sélecteur.
+            let result = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+                .map_err(|e| format!("Unable to execute ffmpeg for font preview: {}", e))?;
+            if result.status.success() {
+                return Ok(output.to_string_lossy().to_string());
+            }
+            last_error = String::from_utf8_lossy(&result.stderr).trim().to_string();
+            if !last_error.contains("text_shaping") {
+                break;
+            }
+        }
+        Err(format!("Font preview rendering failed: {}", last_error))
+    })
+    .await
+    .map_err(|e| format!("Font preview task failed: {}", e))?
 }
 
 /// Resolves selected system font families to concrete font files.
@@ -149,6 +694,17 @@ pub fn get_system_font_sources(
         );
     }
 
+    if let Ok(custom_fonts) = CUSTOM_FONT_SOURCES.lock() {
+        for source in custom_fonts
+            .iter()
+            .filter(|source| requested.contains(&source.family))
+        {
+            if seen_sources.insert(font_source_key(source)) {
+                sources.push(source.clone());
+            }
+        }
+    }
+
     sources.sort_by(|a, b| {
         a.family
             .cmp(&b.family)
@@ -233,6 +789,20 @@ fn add_font_source_if_requested(
         return;
     };
 
+    let source = font_source_from_font(path, font_index, requested_family, &font);
+    if !seen_sources.insert(font_source_key(&source)) {
+        return;
+    }
+    sources.push(source);
+}
+
+/// Construit la description `SystemFontSource` d'une police chargée.
+fn font_source_from_font(
+    path: &Path,
+    font_index: u32,
+    family: &str,
+    font: &Font,
+) -> SystemFontSource {
     let properties = font.properties();
     let full_name = font.full_name();
     let postscript_name = font.postscript_name();
@@ -245,28 +815,156 @@ fn add_font_source_if_requested(
     let font_weight = properties.weight.0.round().clamp(1.0, 1000.0) as u16;
     let font_weight_range =
         font_weight_range_for_source(path, &full_name, postscript_name.as_deref());
-    let path_string = path.to_string_lossy().to_string();
-    let key = format!(
-        "{}:{}:{}:{}:{:?}:{}",
-        requested_family, path_string, font_index, font_weight, font_weight_range, font_style
-    );
 
-    if !seen_sources.insert(key) {
-        return;
-    }
-
-    sources.push(SystemFontSource {
-        family: requested_family.to_string(),
-        source_family,
+    SystemFontSource {
+        family: family.to_string(),
+        source_family: font.family_name(),
         full_name,
         postscript_name,
-        path: path_string,
+        path: path.to_string_lossy().to_string(),
         font_index,
         format: font_format_for_path(path),
         font_weight,
         font_weight_range,
         font_style,
-    });
+    }
+}
+
+/// Clé de déduplication d'une source de police.
+fn font_source_key(source: &SystemFontSource) -> String {
+    format!(
+        "{}:{}:{}:{}:{:?}:{}",
+        source.family,
+        source.path,
+        source.font_index,
+        source.font_weight,
+        source.font_weight_range,
+        source.font_style
+    )
+}
+
+/// Valide un fichier de police (ttf/otf/ttc/otc) et décrit chacune de ses faces via font-kit.
+///
+/// @param path Chemin brut reçu du frontend (pour les messages d'erreur).
+/// @returns Chemin normalisé du fichier et ses faces chargeables.
+fn load_font_file_faces(path: &str) -> Result<(PathBuf, Vec<SystemFontSource>), String> {
+    let path_buf = path_utils::normalize_existing_path(path);
+    if !path_buf.is_file() {
+        return Err(format!("Font file not found: {}", path));
+    }
+    let extension = path_buf
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(extension.as_str(), "ttf" | "otf" | "ttc" | "otc") {
+        return Err(format!(
+            "Unsupported font file '{}'. Expected a .ttf or .otf file.",
+            path
+        ));
+    }
+
+    let mut file =
+        fs::File::open(&path_buf).map_err(|e| format!("Failed to open font file: {}", e))?;
+    let font_count = match Font::analyze_file(&mut file) {
+        Ok(FileType::Single) => 1,
+        Ok(FileType::Collection(font_count)) => font_count,
+        Err(e) => return Err(format!("Invalid font file '{}': {:?}", path, e)),
+    };
+
+    let faces: Vec<SystemFontSource> = (0..font_count)
+        .filter_map(|font_index| {
+            let font = Handle::from_path(path_buf.clone(), font_index)
+                .load()
+                .ok()?;
+            Some(font_source_from_font(
+                &path_buf,
+                font_index,
+                &font.family_name(),
+                &font,
+            ))
+        })
+        .collect();
+    if faces.is_empty() {
+        return Err(format!("Invalid font file '{}': no loadable face", path));
+    }
+    Ok((path_buf, faces))
+}
+
+/// Enregistre un fichier de police (ttf/otf) non installé pour l'utiliser dans les captions.
+///
+/// Chaque face du fichier est validée via font-kit puis mise en cache pour la session :
+/// `get_system_font_sources` la retourne ensuite comme une police système.
+///
+/// @param path Chemin du fichier de police.
+/// @returns Les faces enregistrées (famille, style, graisse, ...).
+#[tauri::command]
+pub fn register_custom_font(path: String) -> Result<Vec<SystemFontSource>, String> {
+    let (path_buf, registered) = load_font_file_faces(&path)?;
+
+    let mut custom_fonts = CUSTOM_FONT_SOURCES
+        .lock()
+        .map_err(|_| "Custom font registry is poisoned".to_string())?;
+    let registered_path = path_buf.to_string_lossy().to_string();
+    custom_fonts.retain(|source| source.path != registered_path);
+    custom_fonts.extend(registered.iter().cloned());
+    Ok(registered)
+}
+
+/// Dossier du projet où sont copiées les polices utilisées par ses captions.
+const PROJECT_FONTS_DIR: &str = "fonts";
+
+/// Copie une police dans le dossier `fonts/` d'un projet pour qu'il reste portable.
+///
+/// Le fichier est validé comme police avant la copie. Un fichier identique déjà présent
+/// est réutilisé ; un fichier différent de même nom reçoit un suffixe (`-1`, `-2`, ...).
+///
+/// @param path Chemin du fichier de police à importer.
+/// @param project_dir Dossier racine du projet.
+/// @returns Chemin de la police relatif au projet (ex: `fonts/Amiri-Regular.ttf`).
+#[tauri::command]
+pub fn import_font_to_project(path: String, project_dir: String) -> Result<String, String> {
+    let (source_path, _) = load_font_file_faces(&path)?;
+    let project_path = path_utils::normalize_existing_path(&project_dir);
+    if !project_path.is_dir() {
+        return Err(format!("Project folder not found: {}", project_dir));
+    }
+    let fonts_dir = project_path.join(PROJECT_FONTS_DIR);
+    fs::create_dir_all(&fonts_dir)
+        .map_err(|e| format!("Failed to create project fonts folder: {}", e))?;
+
+    let file_name = source_path
+        .file_name()
+        .ok_or_else(|| format!("Invalid font file path: {}", path))?;
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let source_bytes =
+        fs::read(&source_path).map_err(|e| format!("Failed to read font file: {}", e))?;
+
+    let mut suffix = 0;
+    let destination = loop {
+        let candidate_name = if suffix == 0 {
+            file_name.to_string_lossy().to_string()
+        } else {
+            format!("{}-{}.{}", stem, suffix, extension)
+        };
+        let candidate = fonts_dir.join(&candidate_name);
+        if !candidate.exists() {
+            fs::write(&candidate, &source_bytes)
+                .map_err(|e| format!("Failed to copy font into project: {}", e))?;
+            break candidate_name;
+        }
+        if fs::read(&candidate).is_ok_and(|existing| existing == source_bytes) {
+            break candidate_name;
+        }
+        suffix += 1;
+    };
+    Ok(format!("{}/{}", PROJECT_FONTS_DIR, destination))
 }
 
 fn font_weight_range_for_source(
@@ -510,7 +1208,7 @@ pub fn get_video_dimensions(file_path: &str) -> Result<serde_json::Value, String
         &file_path_str,
     ]);
     configure_command_no_window(&mut cmd);
-    let output = cmd.output();
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT);
 
     match output {
         Ok(result) => {
@@ -537,6 +1235,115 @@ pub fn get_video_dimensions(file_path: &str) -> Result<serde_json::Value, String
     }
 }
 
+/// Nombre maximal d'erreurs de décodage rapportées par `probe_decodable`.
+const MAX_REPORTED_DECODE_ERRORS: usize = 20;
+
+/// Résultat de `probe_decodable`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodeProbe {
+    /// `true` si ffmpeg a décodé tout le fichier sans erreur.
+    pub decodable: bool,
+    /// Erreurs rapportées par ffmpeg (doublons consécutifs retirés, liste tronquée).
+    pub errors: Vec<String>,
+}
+
+/// Regroupe les erreurs de décodage de la sortie `-v error` de ffmpeg.
+///
+/// Un fichier corrompu répète souvent la même erreur à chaque trame : les doublons
+/// consécutifs sont retirés et la liste est limitée à `MAX_REPORTED_DECODE_ERRORS`.
+fn collect_decode_errors(stderr: &str) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+    let mut total = 0usize;
+    let mut previous: Option<&str> = None;
+    for line in stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if previous == Some(line) {
+            continue;
+        }
+        previous = Some(line);
+        total += 1;
+        if errors.len() < MAX_REPORTED_DECODE_ERRORS {
+            errors.push(line.to_string());
+        }
+    }
+    if total > errors.len() {
+        errors.push(format!("... and {} more errors", total - errors.len()));
+    }
+    errors
+}
+
+/// Arguments ffmpeg de la sonde de décodage.
+///
+/// Le mode rapide (`-err_detect explode` + `-xerror`) s'arrête à la première erreur au
+/// lieu de décoder tout le fichier pour les recenser.
+fn build_decode_probe_args(source_path: &str, fast: bool) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-v".to_string(), "error".to_string()];
+    if fast {
+        args.extend(["-err_detect", "explode", "-xerror"].map(str::to_string));
+    }
+    args.extend(["-i", source_path, "-f", "null", "-"].map(str::to_string));
+    args
+}
+
+/// Décode entièrement un fichier média (`-f null`) pour détecter une source corrompue.
+///
+/// À appeler avant l'ajout au projet : un fichier illisible est signalé à l'import
+/// plutôt que par un échec de l'export. La progression est publiée sur `ffmpeg-progress`.
+///
+/// @param fast - S'arrête à la première erreur (`-err_detect explode`)
+/// @param job_id - Identifiant de corrélation et d'annulation (`cancel_ffmpeg_job`)
+#[tauri::command]
+pub async fn probe_decodable(
+    file_path: String,
+    fast: Option<bool>,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<DecodeProbe, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source_path = path_utils::normalize_existing_path(&file_path);
+        if !source_path.exists() {
+            return Err(format!("Source file not found: {}", file_path));
+        }
+        let source_path = source_path.to_string_lossy().to_string();
+        let total_duration_ms = get_duration(&source_path)
+            .ok()
+            .filter(|duration| *duration > 0)
+            .map(|duration| duration as u64)
+            .unwrap_or(0);
+        // Toujours suivie pour rester annulable, même sans identifiant fourni.
+        let job_id = job_id.unwrap_or_else(|| {
+            format!(
+                "probe-decodable-{}",
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|duration| duration.as_millis())
+                    .unwrap_or(0)
+            )
+        });
+        let output = ffmpeg::run_with_progress_output(
+            &build_decode_probe_args(&source_path, fast.unwrap_or(false)),
+            total_duration_ms,
+            ffmpeg::FFMPEG_PROGRESS_EVENT,
+            &app_handle,
+            Some(&job_id),
+        )?;
+
+        let mut errors = collect_decode_errors(&output.stderr);
+        if !output.status.success() && errors.is_empty() {
+            errors.push(format!("ffmpeg exited with {}", output.status));
+        }
+        Ok(DecodeProbe {
+            decodable: output.status.success() && errors.is_empty(),
+            errors,
+        })
+    })
+    .await
+    .map_err(|e| format!("Unable to join decode probe task: {}", e))?
+}
+
 /// Detects whether the primary media stream uses a near-constant bitrate.
 ///
 /// For video containers, this checks audio stream `a:0` first (subtitle sync issue is audio-driven),
@@ -571,7 +1378,7 @@ pub fn is_constant_bitrate(file_path: String) -> Result<bool, String> {
         ]);
         configure_command_no_window(&mut cmd);
 
-        let output = cmd.output().map_err(|e| {
+        let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT).map_err(|e| {
             format_ffprobe_exec_failed(&format!("Unable to execute ffprobe: {}", e))
         })?;
         if !output.status.success() {
@@ -632,104 +1439,286 @@ pub fn is_constant_bitrate(file_path: String) -> Result<bool, String> {
 }
 
 /// Coupe une portion audio sans ré-encodage (copie de flux).
+///
+/// La progression est publiée sur l'événement `ffmpeg-progress` (voir `utils::ffmpeg`).
+///
+/// @param job_id - Identifiant optionnel de corrélation et d'annulation
 #[tauri::command]
 pub fn cut_audio(
     source_path: String,
     start_ms: u64,
     end_ms: u64,
     output_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
 ) -> Result<(), String> {
     if !std::path::Path::new(&source_path).exists() {
         return Err(format!("Source file not found: {}", source_path));
     }
 
-    let ffmpeg_path =
-        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
     let start_secs = start_ms as f64 / 1000.0;
     let duration_secs = (end_ms as f64 - start_ms as f64) / 1000.0;
     if duration_secs <= 0.0 {
         return Err("Duration must be positive".to_string());
     }
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
-        "-ss",
-        &start_secs.to_string(),
-        "-t",
-        &duration_secs.to_string(),
-        "-i",
-        &source_path,
-        "-c",
-        "copy",
-        "-y",
-        &output_path,
-    ]);
-    configure_command_no_window(&mut cmd);
-    match cmd.output() {
-        Ok(result) if result.status.success() => Ok(()),
-        Ok(result) => Err(format!(
-            "ffmpeg error: {}",
-            String::from_utf8_lossy(&result.stderr)
-        )),
-        Err(e) => Err(format!("Unable to execute ffmpeg: {}", e)),
-    }
+    let args = vec![
+        "-ss".to_string(),
+        start_secs.to_string(),
+        "-t".to_string(),
+        duration_secs.to_string(),
+        "-i".to_string(),
+        source_path,
+        "-c".to_string(),
+        "copy".to_string(),
+        "-y".to_string(),
+        output_path,
+    ];
+    ffmpeg::run_with_progress(
+        &args,
+        end_ms - start_ms,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    )
 }
 
 /// Coupe une portion vidéo sans ré-encodage (copie de flux).
+///
+/// La progression est publiée sur l'événement `ffmpeg-progress` (voir `utils::ffmpeg`).
+///
+/// @param job_id - Identifiant optionnel de corrélation et d'annulation
 #[tauri::command]
 pub fn cut_video(
     source_path: String,
     start_ms: u64,
     end_ms: u64,
     output_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
 ) -> Result<(), String> {
     if !std::path::Path::new(&source_path).exists() {
         return Err(format!("Source file not found: {}", source_path));
     }
 
-    let ffmpeg_path =
-        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
     let start_secs = start_ms as f64 / 1000.0;
     let duration_secs = (end_ms as f64 - start_ms as f64) / 1000.0;
     if duration_secs <= 0.0 {
         return Err("Duration must be positive".to_string());
     }
 
-    let mut cmd = Command::new(&ffmpeg_path);
+    let args = vec![
+        "-ss".to_string(),
+        start_secs.to_string(),
+        "-t".to_string(),
+        duration_secs.to_string(),
+        "-i".to_string(),
+        source_path,
+        "-map".to_string(),
+        "0".to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-y".to_string(),
+        output_path,
+    ];
+    ffmpeg::run_with_progress(
+        &args,
+        end_ms - start_ms,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    )
+}
+
+/// Événement signalant qu'une concaténation audio doit ré-encoder ses entrées.
+const CONCAT_AUDIO_STATUS_EVENT: &str = "concat-audio-status";
+/// Fréquence d'échantillonnage commune en cas de ré-encodage.
+const CONCAT_REENCODE_SAMPLE_RATE: u32 = 44_100;
+/// Fréquence imposée par l'encodeur Opus, qui refuse 44,1 kHz.
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// Format du premier flux audio d'un fichier, tel que rapporté par ffprobe.
+#[derive(Clone, Debug, PartialEq)]
+struct AudioStreamFormat {
+    codec_name: String,
+    sample_rate: u32,
+    channels: u32,
+    /// Disposition des canaux (`stereo`, `5.1(side)`...), vide si ffprobe l'ignore.
+    channel_layout: String,
+}
+
+/// Sonde codec, fréquence, nombre et disposition des canaux du flux `a:0`.
+///
+/// @returns `None` si ffprobe est indisponible ou échoue
+fn probe_audio_stream_format(file_path_str: &str) -> Option<AudioStreamFormat> {
+    let ffprobe_path = binaries::resolve_binary("ffprobe")?;
+    let mut cmd = Command::new(&ffprobe_path);
     cmd.args([
-        "-ss",
-        &start_secs.to_string(),
-        "-t",
-        &duration_secs.to_string(),
-        "-i",
-        &source_path,
-        "-map",
-        "0",
-        "-c",
-        "copy",
-        "-y",
-        &output_path,
+        "-v",
+        "error",
+        "-select_streams",
+        "a:0",
+        "-show_entries",
+        "stream=codec_name,sample_rate,channels,channel_layout",
+        "-of",
+        "default=noprint_wrappers=1",
+        file_path_str,
     ]);
     configure_command_no_window(&mut cmd);
-    match cmd.output() {
-        Ok(result) if result.status.success() => Ok(()),
-        Ok(result) => Err(format!(
-            "ffmpeg error: {}",
-            String::from_utf8_lossy(&result.stderr)
-        )),
-        Err(e) => Err(format!("Unable to execute ffmpeg: {}", e)),
+    let out = output_with_timeout(&mut cmd, PROBE_TIMEOUT).ok()?;
+    if !out.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let mut format = AudioStreamFormat {
+        codec_name: String::new(),
+        sample_rate: 0,
+        channels: 0,
+        channel_layout: String::new(),
+    };
+    for line in stdout.lines() {
+        if let Some(v) = line.strip_prefix("codec_name=") {
+            format.codec_name = v.trim().to_string();
+        } else if let Some(v) = line.strip_prefix("sample_rate=") {
+            format.sample_rate = v.trim().parse().unwrap_or(0);
+        } else if let Some(v) = line.strip_prefix("channels=") {
+            format.channels = v.trim().parse().unwrap_or(0);
+        } else if let Some(v) = line.strip_prefix("channel_layout=") {
+            let layout = v.trim();
+            if layout != "unknown" {
+                format.channel_layout = layout.to_string();
+            }
+        }
     }
+    (!format.codec_name.is_empty()).then_some(format)
+}
+
+/// Indique si les entrées peuvent être concaténées par copie de flux.
+///
+/// Une entrée non sondée compte comme différente : le ré-encodage est le repli sûr.
+fn audio_formats_are_uniform(formats: &[Option<AudioStreamFormat>]) -> bool {
+    match formats.first() {
+        Some(Some(first)) => formats.iter().all(|format| format.as_ref() == Some(first)),
+        _ => false,
+    }
+}
+
+/// Encodeur audio d'une sortie ré-encodée, choisi selon l'extension du fichier.
+///
+/// Pour une extension inconnue, `-c:a` est omis : ffmpeg prend l'encodeur par défaut du
+/// conteneur plutôt qu'un AAC qu'il pourrait refuser.
+fn audio_codec_args_for_output(output_path: &str) -> &'static [&'static str] {
+    let extension = Path::new(output_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "wav" => &["-c:a", "pcm_s16le"],
+        "mp3" => &["-c:a", "libmp3lame", "-b:a", "192k"],
+        "flac" => &["-c:a", "flac"],
+        "ogg" | "opus" => &["-c:a", "libopus", "-b:a", "192k"],
+        "m4a" | "aac" | "mp4" | "mov" => &["-c:a", "aac", "-b:a", "192k"],
+        _ => &[],
+    }
+}
+
+/// Arguments ffmpeg d'une concaténation ré-encodée via le filtre `concat`.
+///
+/// Chaque entrée est rééchantillonnée en 44,1 kHz stéréo (48 kHz pour Opus ; encodeur selon
+/// `audio_codec_args_for_output`).
+fn build_concat_reencode_args(source_paths: &[String], output_path: &str) -> Vec<String> {
+    let codec_args = audio_codec_args_for_output(output_path);
+    let sample_rate = if codec_args.contains(&"libopus") {
+        OPUS_SAMPLE_RATE
+    } else {
+        CONCAT_REENCODE_SAMPLE_RATE
+    };
+    let mut args = Vec::new();
+    let mut filter = String::new();
+    for (index, path) in source_paths.iter().enumerate() {
+        args.push("-i".to_string());
+        args.push(path.clone());
+        filter.push_str(&format!(
+            "[{}:a:0]aresample={},aformat=channel_layouts=stereo[a{}];",
+            index, sample_rate, index
+        ));
+    }
+    for index in 0..source_paths.len() {
+        filter.push_str(&format!("[a{}]", index));
+    }
+    filter.push_str(&format!("concat=n={}:v=0:a=1[out]", source_paths.len()));
+
+    args.extend(
+        ["-filter_complex", filter.as_str(), "-map", "[out]"]
+            .iter()
+            .chain(codec_args)
+            .map(|arg| arg.to_string()),
+    );
+    args.extend([
+        "-ar".to_string(),
+        sample_rate.to_string(),
+        "-ac".to_string(),
+        "2".to_string(),
+        "-y".to_string(),
+        output_path.to_string(),
+    ]);
+    args
 }
 
 /// Concatène plusieurs fichiers audio à l'aide du demuxer concat de ffmpeg.
+///
+/// Si les entrées n'ont pas toutes le même codec, la même fréquence et le même nombre de
+/// canaux, la copie de flux produirait un fichier corrompu : elles sont alors ré-encodées
+/// via le filtre `concat`, ce qui est signalé sur l'événement `concat-audio-status`.
+///
+/// La progression est publiée sur l'événement `ffmpeg-progress` (voir `utils::ffmpeg`).
+///
+/// @param job_id - Identifiant optionnel de corrélation et d'annulation
 #[tauri::command]
-pub fn concat_audio(source_paths: Vec<String>, output_path: String) -> Result<(), String> {
+pub fn concat_audio(
+    source_paths: Vec<String>,
+    output_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     if source_paths.is_empty() {
         return Err("No source files provided".to_string());
     }
 
-    let ffmpeg_path =
-        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
+    let total_duration_ms = source_paths
+        .iter()
+        .map(|path| get_duration(path).unwrap_or(0).max(0) as u64)
+        .sum();
+
+    let formats: Vec<Option<AudioStreamFormat>> = source_paths
+        .iter()
+        .map(|path| probe_audio_stream_format(path))
+        .collect();
+    if !audio_formats_are_uniform(&formats) {
+        log::info!(
+            "[concat_audio] inputs differ, re-encoding {} files: {:?}",
+            source_paths.len(),
+            formats
+        );
+        let _ = app_handle.emit(
+            CONCAT_AUDIO_STATUS_EVENT,
+            serde_json::json!({
+                "jobId": job_id,
+                "status": "reencoding",
+                "message": "Audio files have different formats, re-encoding to a common format",
+            }),
+        );
+        let args = build_concat_reencode_args(&source_paths, &output_path);
+        return ffmpeg::run_with_progress(
+            &args,
+            total_duration_ms,
+            ffmpeg::FFMPEG_PROGRESS_EVENT,
+            &app_handle,
+            job_id.as_deref(),
+        );
+    }
+
     let temp_dir = std::env::temp_dir();
     let list_file_path = temp_dir.join(format!(
         "concat_audio_{}.txt",
@@ -741,39 +1730,331 @@ pub fn concat_audio(source_paths: Vec<String>, output_path: String) -> Result<()
 
     let mut list_content = String::new();
     for path in &source_paths {
-        let escaped_path = path.replace("'", "'\\''");
-        list_content.push_str(&format!("file '{}'\n", escaped_path));
+        list_content.push_str(&format!(
+            "file '{}'\n",
+            path_utils::escape_ffconcat_path(path)
+        ));
     }
     fs::write(&list_file_path, list_content)
         .map_err(|e| format!("Failed to write concat list: {}", e))?;
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
-        "-f",
-        "concat",
-        "-safe",
-        "0",
-        "-i",
-        &list_file_path.to_string_lossy(),
-        "-c",
-        "copy",
-        "-y",
-        &output_path,
-    ]);
-    configure_command_no_window(&mut cmd);
-    let output = cmd.output();
+    let args = vec![
+        "-f".to_string(),
+        "concat".to_string(),
+        "-safe".to_string(),
+        "0".to_string(),
+        "-i".to_string(),
+        list_file_path.to_string_lossy().to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-y".to_string(),
+        output_path,
+    ];
+    let result = ffmpeg::run_with_progress(
+        &args,
+        total_duration_ms,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    );
     let _ = fs::remove_file(&list_file_path);
+    result
+}
 
-    match output {
-        Ok(result) if result.status.success() => Ok(()),
-        Ok(result) => Err(format!(
-            "ffmpeg error: {}",
-            String::from_utf8_lossy(&result.stderr)
-        )),
-        Err(e) => Err(format!("Unable to execute ffmpeg: {}", e)),
+/// Poids des canaux dans le downmix stéréo d'un flux surround. Le centre (la récitation)
+/// pèse autant que les enceintes avant ; le LFE est ignoré. Chaque sortie somme à 1 pour
+/// éviter l'écrêtage.
+const DOWNMIX_5_1_WEIGHTS: (f64, f64, f64) = (0.4, 0.4, 0.2);
+/// Poids (avant, centre, latérales, arrière) d'un flux 7.1.
+const DOWNMIX_7_1_WEIGHTS: (f64, f64, f64, f64) = (0.35, 0.35, 0.15, 0.15);
+
+/// Résultat de `downmix_to_stereo`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDownmix {
+    /// Disposition des canaux de la source (`5.1`, `stereo`...), vide si inconnue.
+    pub source_layout: String,
+    /// Nombre de canaux de la source.
+    pub source_channels: u32,
+    /// Disposition du fichier produit (`stereo`).
+    pub layout: String,
+}
+
+/// Filtre `pan` d'un downmix stéréo qui conserve le canal central.
+///
+/// @returns `None` pour une source mono/stéréo ou une disposition non reconnue : le
+/// rééchantillonnage standard (`-ac 2`) suffit alors
+fn stereo_downmix_filter(channels: u32, channel_layout: &str) -> Option<String> {
+    match (channels, channel_layout) {
+        (6, "5.1") => {
+            let (front, center, surround) = DOWNMIX_5_1_WEIGHTS;
+            Some(format!(
+                "pan=stereo|FL={front}*FL+{center}*FC+{surround}*BL|FR={front}*FR+{center}*FC+{surround}*BR",
+                front = front,
+                center = center,
+                surround = surround
+            ))
+        }
+        (6, "5.1(side)") => {
+            let (front, center, surround) = DOWNMIX_5_1_WEIGHTS;
+            Some(format!(
+                "pan=stereo|FL={front}*FL+{center}*FC+{surround}*SL|FR={front}*FR+{center}*FC+{surround}*SR",
+                front = front,
+                center = center,
+                surround = surround
+            ))
+        }
+        (8, "7.1") => {
+            let (front, center, side, back) = DOWNMIX_7_1_WEIGHTS;
+            Some(format!(
+                "pan=stereo|FL={front}*FL+{center}*FC+{side}*SL+{back}*BL|FR={front}*FR+{center}*FC+{side}*SR+{back}*BR",
+                front = front,
+                center = center,
+                side = side,
+                back = back
+            ))
+        }
+        // Disposition inconnue : les canaux n'ont pas de nom, on les désigne par leur
+        // index dans l'ordre habituel (FL FR FC LFE BL BR, puis SL SR en 7.1).
+        (6, "") => {
+            let (front, center, surround) = DOWNMIX_5_1_WEIGHTS;
+            Some(format!(
+                "pan=stereo|c0={front}*c0+{center}*c2+{surround}*c4|c1={front}*c1+{center}*c2+{surround}*c5",
+                front = front,
+                center = center,
+                surround = surround
+            ))
+        }
+        (8, "") => {
+            let (front, center, side, back) = DOWNMIX_7_1_WEIGHTS;
+            Some(format!(
+                "pan=stereo|c0={front}*c0+{center}*c2+{side}*c6+{back}*c4|c1={front}*c1+{center}*c2+{side}*c7+{back}*c5",
+                front = front,
+                center = center,
+                side = side,
+                back = back
+            ))
+        }
+        _ => None,
     }
 }
 
+/// Arguments ffmpeg du downmix stéréo d'un fichier audio.
+fn build_downmix_args(
+    source_path: &str,
+    output_path: &str,
+    format: &AudioStreamFormat,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["-i", source_path, "-map", "0:a:0"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    if let Some(filter) = stereo_downmix_filter(format.channels, &format.channel_layout) {
+        args.push("-af".to_string());
+        args.push(filter);
+    }
+    args.extend(
+        audio_codec_args_for_output(output_path)
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    args.extend([
+        "-ac".to_string(),
+        "2".to_string(),
+        "-y".to_string(),
+        output_path.to_string(),
+    ]);
+    args
+}
+
+/// Convertit un audio en stéréo en conservant le canal central d'une source surround.
+///
+/// Le downmix par défaut de ffmpeg atténue fortement le centre d'une source 5.1/7.1, là où
+/// se trouve la récitation. La disposition des canaux est sondée pour appliquer une matrice
+/// `pan` adaptée ; les autres sources sont simplement rééchantillonnées en stéréo.
+///
+/// La progression est publiée sur l'événement `ffmpeg-progress` (voir `utils::ffmpeg`).
+///
+/// @param job_id - Identifiant optionnel de corrélation et d'annulation
+/// @returns La disposition de la source et celle du fichier produit
+#[tauri::command]
+pub fn downmix_to_stereo(
+    source_path: String,
+    output_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<AudioDownmix, String> {
+    let normalized_path = path_utils::normalize_existing_path(&source_path);
+    if !normalized_path.exists() {
+        return Err(format!("Source file not found: {}", source_path));
+    }
+    let normalized_path = normalized_path.to_string_lossy().to_string();
+    let format = probe_audio_stream_format(&normalized_path)
+        .ok_or_else(|| format!("Failed to read the audio stream of {}", source_path))?;
+    let args = build_downmix_args(&normalized_path, &output_path, &format);
+    ffmpeg::run_with_progress(
+        &args,
+        get_duration(&normalized_path).unwrap_or(0).max(0) as u64,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    )?;
+    Ok(AudioDownmix {
+        source_layout: format.channel_layout,
+        source_channels: format.channels,
+        layout: "stereo".to_string(),
+    })
+}
+
+/// Morceau produit par `split_audio_by_duration`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioChunk {
+    /// Chemin du fichier du morceau.
+    pub path: String,
+    /// Début du morceau dans le fichier source (ms), à ajouter aux timestamps du morceau.
+    pub start_ms: u64,
+    /// Fin du morceau dans le fichier source (ms).
+    pub end_ms: u64,
+}
+
+/// Lit la liste CSV (`fichier,début,fin` en secondes) écrite par le muxer `segment`.
+fn parse_segment_list(content: &str, output_dir: &Path) -> Result<Vec<AudioChunk>, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.rsplitn(3, ',');
+            let (Some(end), Some(start), Some(file_name)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(format!("Invalid segment list line: {}", line));
+            };
+            let seconds_to_ms = |value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map(|seconds| (seconds.max(0.0) * 1000.0).round() as u64)
+                    .map_err(|_| format!("Invalid segment list line: {}", line))
+            };
+            Ok(AudioChunk {
+                path: output_dir
+                    .join(file_name.trim().trim_matches('"'))
+                    .to_string_lossy()
+                    .to_string(),
+                start_ms: seconds_to_ms(start)?,
+                end_ms: seconds_to_ms(end)?,
+            })
+        })
+        .collect()
+}
+
+/// Découpe un audio long en morceaux de durée égale, sans ré-encodage (muxer `segment`).
+///
+/// Les coupes tombent sur des limites de paquets : la durée réelle de chaque morceau peut
+/// légèrement différer de `chunk_ms`, d'où les décalages retournés. La progression est
+/// publiée sur l'événement `ffmpeg-progress` (voir `utils::ffmpeg`).
+///
+/// @param chunk_ms - Durée visée de chaque morceau
+/// @param output_dir - Dossier des morceaux (créé au besoin)
+/// @param job_id - Identifiant optionnel de corrélation et d'annulation
+/// @returns Les morceaux dans l'ordre, avec leur décalage dans le fichier source
+#[tauri::command]
+pub fn split_audio_by_duration(
+    file_path: String,
+    chunk_ms: u64,
+    output_dir: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<Vec<AudioChunk>, String> {
+    let source_path = path_utils::normalize_existing_path(&file_path);
+    if !source_path.exists() {
+        return Err(format!("Source file not found: {}", file_path));
+    }
+    if chunk_ms == 0 {
+        return Err("Chunk duration must be positive".to_string());
+    }
+    let output_dir = PathBuf::from(output_dir);
+    fs::create_dir_all(&output_dir).map_err(|e| {
+        format!(
+            "Failed to create output directory '{}': {}",
+            output_dir.to_string_lossy(),
+            e
+        )
+    })?;
+
+    let extension = source_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| "mka".to_string());
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_millis();
+    let list_path = std::env::temp_dir().join(format!("split_audio_{}.csv", stamp));
+    let _list_guard = TempFileGuard(list_path.clone());
+
+    let args = vec![
+        "-i".to_string(),
+        source_path.to_string_lossy().to_string(),
+        "-map".to_string(),
+        "0:a:0".to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-f".to_string(),
+        "segment".to_string(),
+        "-segment_time".to_string(),
+        (chunk_ms as f64 / 1000.0).to_string(),
+        "-reset_timestamps".to_string(),
+        "1".to_string(),
+        "-segment_list".to_string(),
+        list_path.to_string_lossy().to_string(),
+        "-segment_list_type".to_string(),
+        "csv".to_string(),
+        "-y".to_string(),
+        output_dir
+            .join(format!("chunk_{}_%03d.{}", stamp, extension))
+            .to_string_lossy()
+            .to_string(),
+    ];
+    let total_duration_ms = get_duration(&file_path).unwrap_or(0).max(0) as u64;
+    ffmpeg::run_with_progress(
+        &args,
+        total_duration_ms,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    )?;
+
+    let list_content = fs::read_to_string(&list_path)
+        .map_err(|e| format!("Failed to read segment list: {}", e))?;
+    let chunks = parse_segment_list(&list_content, &output_dir)?;
+    if chunks.is_empty() {
+        return Err("ffmpeg produced no audio chunks".to_string());
+    }
+    Ok(chunks)
+}
+
+/// Annule une commande ffmpeg lancée avec un `job_id` (cut, concat, conversion CBR...).
+///
+/// @param job_id - Identifiant transmis à la commande à annuler
+/// @returns `true` si une commande en cours a été arrêtée
+#[tauri::command]
+pub fn cancel_ffmpeg_job(job_id: String) -> Result<bool, String> {
+    ffmpeg::cancel_ffmpeg_job(&job_id)
+}
+
+/// Tue un processus long enregistré sous `id` (export, job ffmpeg, téléchargement YouTube...)
+/// ainsi que ses processus descendants.
+///
+/// @param id - Identifiant de l'opération (`export_id`, `download_request_id`, `ffmpeg:<job_id>`,
+///   `segmentation:<moteur>` ou `segmentation:worker`)
+/// @returns `true` si un processus en cours a été arrêté
+#[tauri::command]
+pub fn kill_tracked_process(id: String) -> Result<bool, String> {
+    process::kill_tracked_process(&id)
+}
+
 /// Emet la progression d'une conversion CBR vers le frontend.
 ///
 /// @param app_handle Gestionnaire Tauri utilise pour publier l'evenement.
@@ -802,31 +2083,6 @@ fn emit_cbr_conversion_progress(
     );
 }
 
-/// Extrait le temps courant depuis la sortie `-progress` de ffmpeg.
-///
-/// @param line Ligne brute emise par ffmpeg.
-/// @returns Le temps courant en secondes, ou `None` si la ligne ne contient pas de temps.
-fn parse_ffmpeg_progress_time_s(line: &str) -> Option<f64> {
-    if let Some(value) = line.strip_prefix("out_time_ms=") {
-        return value.trim().parse::<f64>().ok().map(|ms| ms / 1_000_000.0);
-    }
-    if let Some(value) = line.strip_prefix("out_time_us=") {
-        return value.trim().parse::<f64>().ok().map(|us| us / 1_000_000.0);
-    }
-    if let Some(value) = line.strip_prefix("out_time=") {
-        let parts: Vec<&str> = value.trim().split(':').collect();
-        if parts.len() != 3 {
-            return None;
-        }
-
-        let hours = parts[0].parse::<f64>().ok()?;
-        let minutes = parts[1].parse::<f64>().ok()?;
-        let seconds = parts[2].parse::<f64>().ok()?;
-        return Some(hours * 3600.0 + minutes * 60.0 + seconds);
-    }
-    None
-}
-
 /// Lance une conversion CBR asynchrone sans bloquer le thread principal.
 ///
 /// @param file_path Chemin du fichier a convertir.
@@ -863,8 +2119,6 @@ fn convert_audio_to_cbr_blocking(
         return Err(format!("File not found: {}", file_path_str));
     }
 
-    let ffmpeg_path =
-        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
     let extension = file_path
         .extension()
         .and_then(|ext| ext.to_str())
@@ -898,17 +2152,12 @@ fn convert_audio_to_cbr_blocking(
     );
 
     // Paramètres ffmpeg distincts pour flux audio pur vs conteneur vidéo.
-    let mut cmd = Command::new(&ffmpeg_path);
     let is_audio_only = matches!(
         extension.to_lowercase().as_str(),
         "mp3" | "wav" | "flac" | "aac" | "ogg" | "m4a"
     );
-    if is_audio_only {
-        cmd.args([
-            "-nostdin",
-            "-hide_banner",
-            "-i",
-            &file_path_str,
+    let encoding_args: &[&str] = if is_audio_only {
+        &[
             "-codec:a",
             "libmp3lame",
             "-b:a",
@@ -919,112 +2168,56 @@ fn convert_audio_to_cbr_blocking(
             "2",
             "-f",
             "mp3",
-            "-progress",
-            "pipe:1",
-            "-y",
-            temp_path.to_string_lossy().as_ref(),
-        ]);
+        ]
     } else {
-        cmd.args([
-            "-nostdin",
-            "-hide_banner",
-            "-i",
-            &file_path_str,
-            "-b:v",
-            "1200k",
-            "-minrate",
-            "1200k",
-            "-maxrate",
-            "1200k",
-            "-bufsize",
-            "1200k",
-            "-b:a",
-            "64k",
-            "-vcodec",
-            "libx264",
-            "-acodec",
-            "aac",
-            "-strict",
-            "-2",
-            "-ac",
-            "2",
-            "-ar",
+        &[
+            "-b:v", "1200k", "-minrate", "1200k", "-maxrate", "1200k", "-bufsize", "1200k", "-b:a",
+            "64k", "-vcodec", "libx264", "-acodec", "aac", "-strict", "-2", "-ac", "2", "-ar",
             "44100",
-            "-progress",
-            "pipe:1",
-            "-y",
-            temp_path.to_string_lossy().as_ref(),
-        ]);
-    }
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-    configure_command_no_window(&mut cmd);
-
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Unable to execute ffmpeg: {}", e))?;
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| "Failed to capture ffmpeg progress".to_string())?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| "Failed to capture ffmpeg stderr".to_string())?;
-    let stderr_handle = thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        reader
-            .lines()
-            .map_while(Result::ok)
-            .collect::<Vec<String>>()
-            .join("\n")
-    });
-
-    let reader = BufReader::new(stdout);
-    for line in reader.lines().map_while(Result::ok) {
-        if let Some(current_time_s) = parse_ffmpeg_progress_time_s(&line) {
-            let progress = if total_duration_s > 0.0 {
-                (current_time_s / total_duration_s * 100.0).clamp(0.0, 99.5)
-            } else {
-                0.0
-            };
+        ]
+    };
+    let mut args: Vec<String> = vec!["-hide_banner".into(), "-i".into(), file_path_str.clone()];
+    args.extend(encoding_args.iter().map(|arg| arg.to_string()));
+    args.push("-y".into());
+    args.push(temp_path.to_string_lossy().to_string());
+
+    let total_duration_ms = (total_duration_s * 1000.0) as u64;
+    let result = ffmpeg::run_with_progress_callback(
+        &args,
+        total_duration_ms,
+        Some(&conversion_request_id),
+        |progress| {
             emit_cbr_conversion_progress(
                 &app_handle,
                 &conversion_request_id,
-                progress,
-                current_time_s,
+                progress.percent.min(99.5),
+                progress.current_time_ms as f64 / 1000.0,
                 total_duration_s,
                 "converting",
             );
-        }
-    }
-
-    let status = child
-        .wait()
-        .map_err(|e| format!("Unable to wait for ffmpeg: {}", e))?;
-    let stderr = stderr_handle.join().unwrap_or_default();
+        },
+    );
 
-    if status.success() {
-        if let Err(e) = std::fs::remove_file(&file_path) {
-            let _ = std::fs::remove_file(&temp_path);
-            return Err(format!("Failed to remove original file: {}", e));
-        }
-        if let Err(e) = std::fs::rename(&temp_path, &file_path) {
-            return Err(format!("Failed to replace original file: {}", e));
-        }
-        emit_cbr_conversion_progress(
-            &app_handle,
-            &conversion_request_id,
-            100.0,
-            total_duration_s,
-            total_duration_s,
-            "finished",
-        );
-        Ok(())
-    } else {
+    if let Err(error) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    if let Err(e) = std::fs::remove_file(&file_path) {
         let _ = std::fs::remove_file(&temp_path);
-        Err(format!("ffmpeg error: {}", stderr))
+        return Err(format!("Failed to remove original file: {}", e));
+    }
+    if let Err(e) = std::fs::rename(&temp_path, &file_path) {
+        return Err(format!("Failed to replace original file: {}", e));
     }
+    emit_cbr_conversion_progress(
+        &app_handle,
+        &conversion_request_id,
+        100.0,
+        total_duration_s,
+        total_duration_s,
+        "finished",
+    );
+    Ok(())
 }
 
 /// Estime l'écart (en millisecondes) entre la durée du flux audio (basée sur
@@ -1066,8 +2259,7 @@ pub fn audio_timestamp_stretch_ms(file_path: String) -> Result<i64, String> {
         &file_path_str,
     ]);
     configure_command_no_window(&mut stream_cmd);
-    let stream_out = stream_cmd
-        .output()
+    let stream_out = output_with_timeout(&mut stream_cmd, PROBE_TIMEOUT)
         .map_err(|e| format_ffprobe_exec_failed(&format!("Unable to execute ffprobe: {}", e)))?;
     if !stream_out.status.success() {
         return Err(format_ffprobe_exec_failed(&String::from_utf8_lossy(
@@ -1146,7 +2338,7 @@ fn probe_audio_for_retime(file_path_str: &str) -> (String, String, u32, u32, u32
         file_path_str,
     ]);
     configure_command_no_window(&mut cmd);
-    let out = match cmd.output() {
+    let out = match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
         Ok(o) if o.status.success() => o,
         _ => return empty,
     };
@@ -1193,15 +2385,17 @@ fn probe_audio_for_retime(file_path_str: &str) -> (String, String, u32, u32, u32
 /// Volontairement distinct de `convert_audio_to_cbr` (qui ne touche pas aux
 /// timestamps). Remplacement sur place (temp -> rename), comme `convert_audio_to_cbr`.
 #[tauri::command]
-pub fn normalize_audio_timestamps(file_path: String) -> Result<(), String> {
+pub fn normalize_audio_timestamps(
+    file_path: String,
+    job_id: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     let file_path = path_utils::normalize_existing_path(&file_path);
     let file_path_str = file_path.to_string_lossy().to_string();
     if !file_path.exists() {
         return Err(format!("File not found: {}", file_path_str));
     }
 
-    let ffmpeg_path =
-        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
     let extension = file_path
         .extension()
         .and_then(|ext| ext.to_str())
@@ -1296,38 +2490,199 @@ pub fn normalize_audio_timestamps(file_path: String) -> Result<(), String> {
     args.push("-y".into());
     args.push(temp_str);
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args(&args);
-    configure_command_no_window(&mut cmd);
+    let total_duration_ms = get_duration(&file_path_str).unwrap_or(0).max(0) as u64;
+    if let Err(error) = ffmpeg::run_with_progress(
+        &args,
+        total_duration_ms,
+        ffmpeg::FFMPEG_PROGRESS_EVENT,
+        &app_handle,
+        job_id.as_deref(),
+    ) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(error);
+    }
 
-    match cmd.output() {
-        Ok(result) => {
-            if result.status.success() {
-                // Échange sûr : déplacer l'original de côté (backup) AVANT de le
-                // remplacer, afin de ne jamais laisser le fichier source manquant
-                // (cette opération tourne automatiquement pendant la segmentation).
-                let _ = std::fs::remove_file(&backup_path); // nettoie un résidu éventuel
-                if let Err(e) = std::fs::rename(&file_path, &backup_path) {
-                    let _ = std::fs::remove_file(&temp_path);
-                    return Err(format!("Failed to back up original file: {}", e));
-                }
-                if let Err(e) = std::fs::rename(&temp_path, &file_path) {
-                    // Restaure l'original depuis le backup.
-                    let _ = std::fs::rename(&backup_path, &file_path);
-                    let _ = std::fs::remove_file(&temp_path);
-                    return Err(format!("Failed to replace original file: {}", e));
-                }
-                let _ = std::fs::remove_file(&backup_path);
-                Ok(())
-            } else {
-                let _ = std::fs::remove_file(&temp_path);
-                let stderr = String::from_utf8_lossy(&result.stderr);
-                Err(format!("ffmpeg error: {}", stderr))
-            }
-        }
-        Err(e) => {
-            let _ = std::fs::remove_file(&temp_path);
-            Err(format!("Unable to execute ffmpeg: {}", e))
+    // Échange sûr : déplacer l'original de côté (backup) AVANT de le
+    // remplacer, afin de ne jamais laisser le fichier source manquant
+    // (cette opération tourne automatiquement pendant la segmentation).
+    let _ = std::fs::remove_file(&backup_path); // nettoie un résidu éventuel
+    if let Err(e) = std::fs::rename(&file_path, &backup_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!("Failed to back up original file: {}", e));
+    }
+    if let Err(e) = std::fs::rename(&temp_path, &file_path) {
+        // Restaure l'original depuis le backup.
+        let _ = std::fs::rename(&backup_path, &file_path);
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!("Failed to replace original file: {}", e));
+    }
+    let _ = std::fs::remove_file(&backup_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        audio_formats_are_uniform, build_concat_reencode_args, build_decode_probe_args,
+        build_downmix_args, collect_decode_errors, parse_segment_list, AudioChunk,
+        AudioStreamFormat, MAX_REPORTED_DECODE_ERRORS,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn segment_list_offsets_are_converted_to_ms() {
+        let chunks = parse_segment_list(
+            "chunk_1_000.mp3,0.000000,600.012000\nchunk_1_001.mp3,600.012000,1200.024\n",
+            Path::new("out"),
+        )
+        .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                AudioChunk {
+                    path: Path::new("out")
+                        .join("chunk_1_000.mp3")
+                        .to_string_lossy()
+                        .to_string(),
+                    start_ms: 0,
+                    end_ms: 600_012,
+                },
+                AudioChunk {
+                    path: Path::new("out")
+                        .join("chunk_1_001.mp3")
+                        .to_string_lossy()
+                        .to_string(),
+                    start_ms: 600_012,
+                    end_ms: 1_200_024,
+                },
+            ]
+        );
+        assert!(parse_segment_list("chunk.mp3,abc", Path::new("out")).is_err());
+    }
+
+    #[test]
+    fn mixed_concat_inputs_are_reencoded_through_the_concat_filter() {
+        let mp3 = AudioStreamFormat {
+            codec_name: "mp3".to_string(),
+            sample_rate: 44_100,
+            channels: 2,
+            channel_layout: "stereo".to_string(),
+        };
+        let m4a = AudioStreamFormat {
+            codec_name: "aac".to_string(),
+            sample_rate: 48_000,
+            channels: 2,
+            channel_layout: "stereo".to_string(),
+        };
+        assert!(audio_formats_are_uniform(&[
+            Some(mp3.clone()),
+            Some(mp3.clone())
+        ]));
+        assert!(!audio_formats_are_uniform(&[Some(mp3.clone()), Some(m4a)]));
+        assert!(!audio_formats_are_uniform(&[Some(mp3), None]));
+        assert!(!audio_formats_are_uniform(&[]));
+
+        let args =
+            build_concat_reencode_args(&["a.mp3".to_string(), "b.m4a".to_string()], "out.wav");
+        assert_eq!(args[..4], ["-i", "a.mp3", "-i", "b.m4a"]);
+        let filter_index = args
+            .iter()
+            .position(|arg| arg == "-filter_complex")
+            .unwrap();
+        assert_eq!(
+            args[filter_index + 1],
+            "[0:a:0]aresample=44100,aformat=channel_layouts=stereo[a0];\
+             [1:a:0]aresample=44100,aformat=channel_layouts=stereo[a1];\
+             [a0][a1]concat=n=2:v=0:a=1[out]"
+        );
+        assert!(args.windows(2).any(|pair| pair == ["-c:a", "pcm_s16le"]));
+        assert_eq!(args.last().map(String::as_str), Some("out.wav"));
+
+        let args = build_concat_reencode_args(&["a.mp3".to_string()], "out.m4a");
+        assert!(args.windows(2).any(|pair| pair == ["-c:a", "aac"]));
+        let args = build_concat_reencode_args(&["a.mp3".to_string()], "out.flac");
+        assert!(args.windows(2).any(|pair| pair == ["-c:a", "flac"]));
+        for output in ["out.ogg", "out.opus"] {
+            let args = build_concat_reencode_args(&["a.mp3".to_string()], output);
+            assert!(args.windows(2).any(|pair| pair == ["-c:a", "libopus"]));
+            assert!(args.windows(2).any(|pair| pair == ["-ar", "48000"]));
         }
+        let args = build_concat_reencode_args(&["a.mp3".to_string()], "out.mka");
+        assert!(!args.iter().any(|arg| arg == "-c:a"));
+    }
+
+    #[test]
+    fn surround_downmix_keeps_the_center_channel() {
+        let mut format = AudioStreamFormat {
+            codec_name: "ac3".to_string(),
+            sample_rate: 48_000,
+            channels: 6,
+            channel_layout: "5.1(side)".to_string(),
+        };
+        let args = build_downmix_args("in.mkv", "out.wav", &format);
+        let filter_index = args.iter().position(|arg| arg == "-af").unwrap();
+        assert_eq!(
+            args[filter_index + 1],
+            "pan=stereo|FL=0.4*FL+0.4*FC+0.2*SL|FR=0.4*FR+0.4*FC+0.2*SR"
+        );
+        assert!(args.windows(2).any(|pair| pair == ["-c:a", "pcm_s16le"]));
+        assert!(args.windows(2).any(|pair| pair == ["-ac", "2"]));
+
+        format.channel_layout = "5.1".to_string();
+        let args = build_downmix_args("in.mkv", "out.m4a", &format);
+        assert!(args
+            .iter()
+            .any(|arg| arg == "pan=stereo|FL=0.4*FL+0.4*FC+0.2*BL|FR=0.4*FR+0.4*FC+0.2*BR"));
+
+        // Disposition inconnue : canaux désignés par index, pas par nom.
+        format.channel_layout = String::new();
+        let args = build_downmix_args("in.wav", "out.wav", &format);
+        assert!(args
+            .iter()
+            .any(|arg| arg == "pan=stereo|c0=0.4*c0+0.4*c2+0.2*c4|c1=0.4*c1+0.4*c2+0.2*c5"));
+        format.channels = 8;
+        let args = build_downmix_args("in.wav", "out.wav", &format);
+        let filter_index = args.iter().position(|arg| arg == "-af").unwrap();
+        assert!(args[filter_index + 1].starts_with("pan=stereo|c0="));
+        assert!(!args[filter_index + 1].contains("FL"));
+
+        format.channels = 1;
+        format.channel_layout = "mono".to_string();
+        let args = build_downmix_args("in.mp3", "out.mp3", &format);
+        assert!(!args.iter().any(|arg| arg == "-af"));
+        assert!(args.windows(2).any(|pair| pair == ["-ac", "2"]));
+    }
+
+    #[test]
+    fn decode_errors_are_deduplicated_and_truncated() {
+        assert!(collect_decode_errors("\n").is_empty());
+        let stderr = "[h264 @ 0x1] error while decoding MB 12 4\n\
+                      [h264 @ 0x1] error while decoding MB 12 4\n\
+                      [aac @ 0x2] Invalid data found when processing input\n";
+        assert_eq!(
+            collect_decode_errors(stderr),
+            vec![
+                "[h264 @ 0x1] error while decoding MB 12 4",
+                "[aac @ 0x2] Invalid data found when processing input",
+            ]
+        );
+
+        let many: String = (0..25).map(|i| format!("error {}\n", i)).collect();
+        let errors = collect_decode_errors(&many);
+        assert_eq!(errors.len(), MAX_REPORTED_DECODE_ERRORS + 1);
+        assert_eq!(errors.last().unwrap(), "... and 5 more errors");
+    }
+
+    #[test]
+    fn fast_decode_probe_stops_at_first_error() {
+        let full = build_decode_probe_args("in.mp4", false);
+        assert_eq!(full, ["-v", "error", "-i", "in.mp4", "-f", "null", "-"]);
+        let fast = build_decode_probe_args("in.mp4", true);
+        let input_index = fast.iter().position(|arg| arg == "-i").unwrap();
+        let explode_index = fast
+            .windows(2)
+            .position(|pair| pair == ["-err_detect", "explode"]);
+        assert!(explode_index.is_some_and(|index| index < input_index));
+        assert!(fast.iter().any(|arg| arg == "-xerror"));
     }
 }
@@ -10,8 +10,12 @@ pub mod discord;
 pub mod downloads;
 /// Commandes de gestion de fichiers.
 pub mod files;
+/// Commandes du journal fichier du backend.
+pub mod logging;
 /// Commandes multimédia et utilitaires ffmpeg/ffprobe.
 pub mod media;
+/// Commandes de validation et de réparation des fichiers projet.
+pub mod projects;
 /// Commandes de capture d'écran.
 pub mod screenshot;
 /// Commandes de segmentation cloud/local.
@@ -0,0 +1,707 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::path_utils;
+
+/// Nom appliqué à un projet dont le nom est absent ou vide.
+const FALLBACK_PROJECT_NAME: &str = "Untitled project";
+
+/// Profondeur maximale parcourue sous chaque dossier de recherche de `relink_assets`.
+const RELINK_MAX_DEPTH: usize = 6;
+
+/// Problème détecté lors de la validation d'un fichier projet.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectProblem {
+    /// Chemin JSON du champ concerné (ex: `content.timeline.tracks[2].clips`).
+    pub field: String,
+    /// Description lisible du problème.
+    pub message: String,
+    /// `true` si le problème a été corrigé automatiquement.
+    pub repaired: bool,
+}
+
+/// Résultat de `load_and_validate_project`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectValidationReport {
+    /// `true` si le projet peut être chargé (aucun problème bloquant).
+    pub valid: bool,
+    /// JSON du projet, éventuellement réparé. Absent si le projet est invalide.
+    pub project: Option<Value>,
+    /// Liste des problèmes trouvés, réparés ou non.
+    pub problems: Vec<ProjectProblem>,
+}
+
+/// Asset dont le chemin a été réécrit par `relink_assets`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkedAsset {
+    /// Ancien chemin (introuvable).
+    pub old_path: String,
+    /// Nouveau chemin trouvé dans les dossiers de recherche.
+    pub new_path: String,
+}
+
+/// Asset toujours introuvable après `relink_assets`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingAsset {
+    /// Chemin enregistré dans le projet.
+    pub path: String,
+    /// Fichiers homonymes de tailles différentes (relink ambigu), vide si aucun trouvé.
+    pub candidates: Vec<String>,
+}
+
+/// Résultat de `relink_assets`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkReport {
+    /// JSON du projet avec les chemins mis à jour.
+    pub project: Value,
+    /// Assets relinkés.
+    pub relinked: Vec<RelinkedAsset>,
+    /// Assets toujours manquants.
+    pub missing: Vec<MissingAsset>,
+}
+
+/// Fichier à écrire dans une sauvegarde transactionnelle.
+#[derive(serde::Deserialize)]
+pub struct ProjectFileWrite {
+    /// Chemin de destination.
+    pub path: String,
+    /// Contenu texte complet du fichier.
+    pub content: String,
+}
+
+/// Fichier en cours d'écriture par `save_project_transaction`.
+struct StagedFile {
+    destination: PathBuf,
+    temp_path: PathBuf,
+    backup_path: Option<PathBuf>,
+    committed: bool,
+}
+
+/// Enregistre un problème bloquant.
+fn push_error(problems: &mut Vec<ProjectProblem>, field: &str, message: impl Into<String>) {
+    problems.push(ProjectProblem {
+        field: field.to_string(),
+        message: message.into(),
+        repaired: false,
+    });
+}
+
+/// Enregistre un problème corrigé automatiquement.
+fn push_repair(problems: &mut Vec<ProjectProblem>, field: &str, message: impl Into<String>) {
+    problems.push(ProjectProblem {
+        field: field.to_string(),
+        message: message.into(),
+        repaired: true,
+    });
+}
+
+/// Retourne l'objet enfant `key`, ou enregistre une erreur s'il manque ou n'est pas un objet.
+fn require_object<'a>(
+    parent: &'a mut Map<String, Value>,
+    key: &str,
+    field: &str,
+    problems: &mut Vec<ProjectProblem>,
+) -> Option<&'a mut Map<String, Value>> {
+    match parent.get_mut(key) {
+        Some(Value::Object(object)) => Some(object),
+        Some(Value::Null) | None => {
+            push_error(
+                problems,
+                field,
+                format!("Missing required object '{}'", field),
+            );
+            None
+        }
+        Some(other) => {
+            push_error(
+                problems,
+                field,
+                format!(
+                    "'{}' must be an object, found {}",
+                    field,
+                    json_type_name(other)
+                ),
+            );
+            None
+        }
+    }
+}
+
+/// Nom lisible du type d'une valeur JSON.
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Vérifie `detail` et répare son nom si besoin.
+fn validate_detail(root: &mut Map<String, Value>, problems: &mut Vec<ProjectProblem>) {
+    let Some(detail) = require_object(root, "detail", "detail", problems) else {
+        return;
+    };
+
+    match detail.get("name") {
+        Some(Value::String(name)) if !name.trim().is_empty() => {}
+        Some(Value::String(_)) | Some(Value::Null) | None => {
+            detail.insert(
+                "name".to_string(),
+                Value::String(FALLBACK_PROJECT_NAME.to_string()),
+            );
+            push_repair(
+                problems,
+                "detail.name",
+                format!(
+                    "Missing project name, renamed to '{}'",
+                    FALLBACK_PROJECT_NAME
+                ),
+            );
+        }
+        Some(other) => push_error(
+            problems,
+            "detail.name",
+            format!(
+                "'detail.name' must be a string, found {}",
+                json_type_name(other)
+            ),
+        ),
+    }
+}
+
+/// Vérifie `content.assets`: tableau d'objets avec un `filePath` texte.
+fn validate_assets(content: &mut Map<String, Value>, problems: &mut Vec<ProjectProblem>) {
+    let assets = match content.get_mut("assets") {
+        Some(Value::Array(assets)) => assets,
+        Some(Value::Null) | None => {
+            content.insert("assets".to_string(), Value::Array(Vec::new()));
+            push_repair(
+                problems,
+                "content.assets",
+                "Missing assets list, reset to empty",
+            );
+            return;
+        }
+        Some(other) => {
+            push_error(
+                problems,
+                "content.assets",
+                format!(
+                    "'content.assets' must be an array, found {}",
+                    json_type_name(other)
+                ),
+            );
+            return;
+        }
+    };
+
+    let before = assets.len();
+    assets.retain(|asset| !asset.is_null());
+    if assets.len() != before {
+        push_repair(
+            problems,
+            "content.assets",
+            format!("Removed {} empty asset entries", before - assets.len()),
+        );
+    }
+
+    for (index, asset) in assets.iter().enumerate() {
+        let field = format!("content.assets[{}].filePath", index);
+        match asset.get("filePath") {
+            Some(Value::String(_)) => {}
+            Some(other) => push_error(
+                problems,
+                &field,
+                format!(
+                    "'{}' must be a string, found {}",
+                    field,
+                    json_type_name(other)
+                ),
+            ),
+            None if asset.is_object() => push_error(
+                problems,
+                &field,
+                format!("Missing required field '{}'", field),
+            ),
+            None => push_error(
+                problems,
+                &format!("content.assets[{}]", index),
+                format!("Asset must be an object, found {}", json_type_name(asset)),
+            ),
+        }
+    }
+}
+
+/// Vérifie `content.timeline.tracks`: tableau d'objets dont `clips` est un tableau.
+fn validate_tracks(content: &mut Map<String, Value>, problems: &mut Vec<ProjectProblem>) {
+    let Some(timeline) = require_object(content, "timeline", "content.timeline", problems) else {
+        return;
+    };
+    let tracks = match timeline.get_mut("tracks") {
+        Some(Value::Array(tracks)) => tracks,
+        Some(Value::Null) | None => {
+            push_error(
+                problems,
+                "content.timeline.tracks",
+                "Missing required field 'content.timeline.tracks'",
+            );
+            return;
+        }
+        Some(other) => {
+            push_error(
+                problems,
+                "content.timeline.tracks",
+                format!(
+                    "'content.timeline.tracks' must be an array, found {}",
+                    json_type_name(other)
+                ),
+            );
+            return;
+        }
+    };
+
+    for (index, track) in tracks.iter_mut().enumerate() {
+        let field = format!("content.timeline.tracks[{}]", index);
+        let Some(track) = track.as_object_mut() else {
+            push_error(
+                problems,
+                &field,
+                format!("Track must be an object, found {}", json_type_name(track)),
+            );
+            continue;
+        };
+
+        let clips_field = format!("{}.clips", field);
+        match track.get_mut("clips") {
+            Some(Value::Array(clips)) => {
+                let before = clips.len();
+                clips.retain(|clip| !clip.is_null());
+                if clips.len() != before {
+                    push_repair(
+                        problems,
+                        &clips_field,
+                        format!("Removed {} empty clip entries", before - clips.len()),
+                    );
+                }
+            }
+            Some(Value::Null) | None => {
+                track.insert("clips".to_string(), Value::Array(Vec::new()));
+                push_repair(problems, &clips_field, "Missing clips list, reset to empty");
+            }
+            Some(other) => push_error(
+                problems,
+                &clips_field,
+                format!(
+                    "'{}' must be an array, found {}",
+                    clips_field,
+                    json_type_name(other)
+                ),
+            ),
+        }
+    }
+}
+
+/// Valide (et répare si possible) la structure d'un projet déjà parsé.
+///
+/// @param project - JSON du projet, modifié en place pour les réparations sûres
+/// @returns Liste des problèmes trouvés
+pub(crate) fn validate_project_value(project: &mut Value) -> Vec<ProjectProblem> {
+    let mut problems = Vec::new();
+    let Some(root) = project.as_object_mut() else {
+        push_error(
+            &mut problems,
+            "$",
+            format!(
+                "Project must be an object, found {}",
+                json_type_name(project)
+            ),
+        );
+        return problems;
+    };
+
+    validate_detail(root, &mut problems);
+    if let Some(content) = require_object(root, "content", "content", &mut problems) {
+        validate_assets(content, &mut problems);
+        validate_tracks(content, &mut problems);
+    }
+    problems
+}
+
+/// Charge un fichier projet, valide ses champs requis et répare les problèmes évidents.
+///
+/// @param path - Chemin du fichier JSON du projet
+/// @returns Rapport avec le projet (réparé) ou la liste des problèmes bloquants
+#[tauri::command]
+pub fn load_and_validate_project(path: String) -> Result<ProjectValidationReport, String> {
+    let project_path = path_utils::normalize_existing_path(&path);
+    let raw = fs::read_to_string(&project_path)
+        .map_err(|e| format!("Failed to read project '{}': {}", path, e))?;
+    let mut project: Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("Project '{}' is not valid JSON: {}", path, e))?;
+
+    let problems = validate_project_value(&mut project);
+    let valid = problems.iter().all(|problem| problem.repaired);
+    Ok(ProjectValidationReport {
+        valid,
+        project: valid.then_some(project),
+        problems,
+    })
+}
+
+/// Clé d'index insensible à la casse pour un nom de fichier.
+fn file_name_key(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+}
+
+/// Indexe par nom les fichiers des dossiers de recherche (récursif, profondeur bornée).
+fn index_search_dirs(search_dirs: &[String]) -> HashMap<String, Vec<(PathBuf, u64)>> {
+    let mut index: HashMap<String, Vec<(PathBuf, u64)>> = HashMap::new();
+    let mut pending: Vec<(PathBuf, usize)> = search_dirs
+        .iter()
+        .map(|dir| (path_utils::normalize_existing_path(dir), 0))
+        .collect();
+
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if depth < RELINK_MAX_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            } else if let Some(key) = file_name_key(&path) {
+                let candidates = index.entry(key).or_default();
+                if !candidates.iter().any(|(known, _)| known == &path) {
+                    candidates.push((path, metadata.len()));
+                }
+            }
+        }
+    }
+    index
+}
+
+/// Cherche un remplaçant unique pour un asset manquant.
+///
+/// Plusieurs homonymes sont acceptés s'ils ont tous la même taille (copies du même
+/// fichier); sinon le relink est ambigu et les candidats sont renvoyés en erreur.
+fn find_relink_candidate(
+    index: &HashMap<String, Vec<(PathBuf, u64)>>,
+    missing_path: &Path,
+) -> Result<PathBuf, Vec<String>> {
+    let candidates = file_name_key(missing_path)
+        .and_then(|key| index.get(&key))
+        .map(|candidates| candidates.as_slice())
+        .unwrap_or_default();
+    match candidates {
+        [] => Err(Vec::new()),
+        [(first, first_size), rest @ ..] if rest.iter().all(|(_, size)| size == first_size) => {
+            Ok(first.clone())
+        }
+        _ => Err(candidates
+            .iter()
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect()),
+    }
+}
+
+/// Relinke les assets manquants d'un projet en cherchant leurs fichiers dans `search_dirs`.
+///
+/// @param project_json - JSON complet du projet
+/// @param search_dirs - Dossiers à parcourir (récursivement) à la recherche des médias déplacés
+/// @returns Le projet mis à jour et le rapport relinkés / toujours manquants
+#[tauri::command]
+pub async fn relink_assets(
+    project_json: Value,
+    search_dirs: Vec<String>,
+) -> Result<RelinkReport, String> {
+    tauri::async_runtime::spawn_blocking(move || relink_assets_blocking(project_json, search_dirs))
+        .await
+        .map_err(|e| format!("Relink task failed: {}", e))?
+}
+
+/// Implémentation bloquante de `relink_assets`.
+fn relink_assets_blocking(
+    mut project: Value,
+    search_dirs: Vec<String>,
+) -> Result<RelinkReport, String> {
+    let assets = project
+        .pointer_mut("/content/assets")
+        .and_then(|assets| assets.as_array_mut())
+        .ok_or_else(|| "Project has no 'content.assets' array".to_string())?;
+
+    let mut index: Option<HashMap<String, Vec<(PathBuf, u64)>>> = None;
+    let mut relinked = Vec::new();
+    let mut missing = Vec::new();
+    for asset in assets.iter_mut().filter_map(|asset| asset.as_object_mut()) {
+        let Some(old_path) = asset.get("filePath").and_then(|path| path.as_str()) else {
+            continue;
+        };
+        if old_path.is_empty() || path_utils::normalize_existing_path(old_path).exists() {
+            continue;
+        }
+        let old_path = old_path.to_string();
+
+        // L'index n'est construit qu'au premier asset manquant.
+        let index = index.get_or_insert_with(|| index_search_dirs(&search_dirs));
+        match find_relink_candidate(index, Path::new(&old_path)) {
+            Ok(new_path) => {
+                let new_path = new_path.to_string_lossy().to_string();
+                asset.insert("filePath".to_string(), Value::String(new_path.clone()));
+                asset.insert("exists".to_string(), Value::Bool(true));
+                relinked.push(RelinkedAsset { old_path, new_path });
+            }
+            Err(candidates) => missing.push(MissingAsset {
+                path: old_path,
+                candidates,
+            }),
+        }
+    }
+
+    Ok(RelinkReport {
+        project,
+        relinked,
+        missing,
+    })
+}
+
+/// Construit un chemin frère de `path` avec le suffixe donné (`projet.json.<suffix>`).
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!(".{}", suffix));
+    path.with_file_name(file_name)
+}
+
+/// Annule une transaction: restaure les sauvegardes et supprime les fichiers temporaires.
+fn rollback_staged_files(staged: &[StagedFile]) {
+    for file in staged.iter().rev() {
+        if file.committed {
+            match &file.backup_path {
+                Some(backup) => {
+                    let _ = fs::rename(backup, &file.destination);
+                }
+                None => {
+                    let _ = fs::remove_file(&file.destination);
+                }
+            }
+        } else {
+            if let Some(backup) = &file.backup_path {
+                let _ = fs::rename(backup, &file.destination);
+            }
+            let _ = fs::remove_file(&file.temp_path);
+        }
+    }
+}
+
+/// Écrit et synchronise un fichier temporaire.
+fn write_synced(path: &Path, content: &str) -> Result<(), String> {
+    let mut file = fs::File::create(path)
+        .map_err(|e| format!("Failed to create '{}': {}", path.to_string_lossy(), e))?;
+    std::io::Write::write_all(&mut file, content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Écrit plusieurs fichiers (projet + manifeste d'assets, ...) de façon quasi atomique.
+///
+/// Tous les contenus sont d'abord écrits dans des fichiers temporaires voisins, puis
+/// renommés ensemble. En cas d'échec, les fichiers déjà remplacés sont restaurés et
+/// les temporaires supprimés, de sorte que l'ensemble reste cohérent.
+///
+/// @param files - Liste des fichiers à écrire (`path`, `content`)
+#[tauri::command]
+pub fn save_project_transaction(files: Vec<ProjectFileWrite>) -> Result<(), String> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_millis();
+    let temp_suffix = format!("{}.tmp", stamp);
+    let backup_suffix = format!("{}.bak", stamp);
+
+    let mut destinations: Vec<PathBuf> = Vec::with_capacity(files.len());
+    for file in &files {
+        let destination = path_utils::normalize_output_path(&file.path);
+        if destinations.contains(&destination) {
+            return Err(format!("Duplicate path in save transaction: {}", file.path));
+        }
+        destinations.push(destination);
+    }
+
+    // Phase 1: écriture de tous les temporaires.
+    let mut staged: Vec<StagedFile> = Vec::with_capacity(files.len());
+    for (file, destination) in files.iter().zip(destinations) {
+        if let Some(parent) = destination.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                rollback_staged_files(&staged);
+                return Err(format!("Failed to create directory: {}", e));
+            }
+        }
+        let temp_path = sibling_path(&destination, &temp_suffix);
+        if let Err(error) = write_synced(&temp_path, &file.content) {
+            let _ = fs::remove_file(&temp_path);
+            rollback_staged_files(&staged);
+            return Err(error);
+        }
+        staged.push(StagedFile {
+            destination,
+            temp_path,
+            backup_path: None,
+            committed: false,
+        });
+    }
+
+    // Phase 2: sauvegarde des originaux puis renommage des temporaires.
+    for index in 0..staged.len() {
+        let file = &mut staged[index];
+        if file.destination.exists() {
+            let backup = sibling_path(&file.destination, &backup_suffix);
+            if let Err(e) = fs::rename(&file.destination, &backup) {
+                let error = format!(
+                    "Failed to back up '{}': {}",
+                    file.destination.to_string_lossy(),
+                    e
+                );
+                rollback_staged_files(&staged);
+                return Err(error);
+            }
+            file.backup_path = Some(backup);
+        }
+        if let Err(e) = fs::rename(&file.temp_path, &file.destination) {
+            let error = format!(
+                "Failed to replace '{}': {}",
+                file.destination.to_string_lossy(),
+                e
+            );
+            rollback_staged_files(&staged);
+            return Err(error);
+        }
+        file.committed = true;
+    }
+
+    for file in &staged {
+        if let Some(backup) = &file.backup_path {
+            let _ = fs::remove_file(backup);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        find_relink_candidate, save_project_transaction, validate_project_value, ProjectFileWrite,
+    };
+
+    #[test]
+    fn save_transaction_keeps_originals_when_a_write_fails() {
+        let dir = std::env::temp_dir().join(format!("qc-save-tx-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let project = dir.join("project.json");
+        std::fs::write(&project, "old").unwrap();
+        // Un fichier à la place d'un dossier parent fait échouer la seconde écriture.
+        std::fs::write(dir.join("blocked"), "").unwrap();
+
+        let result = save_project_transaction(vec![
+            ProjectFileWrite {
+                path: project.to_string_lossy().to_string(),
+                content: "new".to_string(),
+            },
+            ProjectFileWrite {
+                path: dir
+                    .join("blocked/manifest.json")
+                    .to_string_lossy()
+                    .to_string(),
+                content: "{}".to_string(),
+            },
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&project).unwrap(), "old");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn relink_candidate_requires_unambiguous_match() {
+        let mut index = HashMap::new();
+        index.insert(
+            "intro.mp3".to_string(),
+            vec![
+                (PathBuf::from("a/intro.mp3"), 10),
+                (PathBuf::from("b/Intro.mp3"), 10),
+            ],
+        );
+        index.insert(
+            "outro.mp3".to_string(),
+            vec![
+                (PathBuf::from("a/outro.mp3"), 10),
+                (PathBuf::from("b/outro.mp3"), 20),
+            ],
+        );
+
+        assert_eq!(
+            find_relink_candidate(&index, Path::new("old/INTRO.mp3")),
+            Ok(PathBuf::from("a/intro.mp3"))
+        );
+        assert_eq!(
+            find_relink_candidate(&index, Path::new("old/outro.mp3"))
+                .unwrap_err()
+                .len(),
+            2
+        );
+        assert!(find_relink_candidate(&index, Path::new("old/none.mp3"))
+            .unwrap_err()
+            .is_empty());
+    }
+
+    #[test]
+    fn repairs_missing_name_and_null_entries() {
+        let mut project = serde_json::json!({
+            "detail": { "name": null },
+            "content": {
+                "assets": [null, { "filePath": "a.mp3" }],
+                "timeline": { "tracks": [{ "clips": null }] }
+            }
+        });
+        let problems = validate_project_value(&mut project);
+
+        assert!(problems.iter().all(|problem| problem.repaired));
+        assert_eq!(project["detail"]["name"], "Untitled project");
+        assert_eq!(project["content"]["assets"].as_array().unwrap().len(), 1);
+        assert!(project["content"]["timeline"]["tracks"][0]["clips"].is_array());
+    }
+
+    #[test]
+    fn reports_missing_required_objects() {
+        let mut project = serde_json::json!({ "detail": null, "content": { "assets": [] } });
+        let problems = validate_project_value(&mut project);
+        let fields: Vec<&str> = problems.iter().map(|p| p.field.as_str()).collect();
+
+        assert!(fields.contains(&"detail"));
+        assert!(fields.contains(&"content.timeline"));
+        assert!(problems.iter().all(|problem| !problem.repaired));
+    }
+}
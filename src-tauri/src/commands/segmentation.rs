@@ -1,27 +1,54 @@
 use crate::segmentation;
-use crate::segmentation::types::{HifzAudioSegment, SegmentationAudioClip};
+use crate::segmentation::types::{
+    AudioPreprocessOptions, HifzAudioSegment, SegmentationAudioClip, SegmentationBatchItem,
+    SegmentationChunkResult,
+};
 
-/// Lance une segmentation Quran cloud via l'API Multi-Aligner.
+/// Lance une segmentation Quran cloud via l'API Multi-Aligner (repli local optionnel).
 #[tauri::command]
 pub async fn segment_quran_audio(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
+    upload_bitrate_kbps: Option<u32>,
+    upload_sample_rate: Option<u32>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    hf_token: Option<String>,
+    fallback_to_local: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
-    segmentation::segment_quran_audio(
+    segmentation::segment_quran_audio_with_fallback(
         app_handle,
         audio_path,
         audio_clips,
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         model_name,
         device,
+        upload_bitrate_kbps,
+        upload_sample_rate,
+        surah_hint,
+        ayah_range_hint,
+        hf_token,
+        fallback_to_local.unwrap_or(false),
+        keep_temp_audio,
+        preprocess,
     )
     .await
 }
@@ -68,6 +95,14 @@ pub async fn get_segmentation_mfa_timestamps_direct(
     .await
 }
 
+/// Teste la connectivite de la Space cloud (joignable, latence, demarrage a froid probable).
+#[tauri::command]
+pub async fn ping_segmentation_cloud(
+    base_url: Option<String>,
+) -> Result<segmentation::CloudPingResult, String> {
+    segmentation::ping_segmentation_cloud(base_url).await
+}
+
 /// Liste les récitations Preload disponibles (catalogue + chapitres) côté cloud.
 #[tauri::command]
 pub async fn preload_recitations() -> Result<serde_json::Value, String> {
@@ -109,22 +144,176 @@ pub async fn preload_audio(
 }
 
 /// VÃ©rifie la disponibilitÃ© des moteurs de segmentation locale.
+///
+/// Le resultat est mis en cache ; `force` refait le check complet.
 #[tauri::command]
 pub async fn check_local_segmentation_ready(
     app_handle: tauri::AppHandle,
     hf_token: Option<String>,
+    force: Option<bool>,
 ) -> Result<serde_json::Value, String> {
-    segmentation::check_local_segmentation_ready(app_handle, hf_token).await
+    segmentation::check_local_segmentation_ready(app_handle, hf_token, force).await
 }
 
 /// Installe les dÃ©pendances Python d'un moteur local (`legacy` ou `multi`).
+///
+/// `offline_wheels_dir` installe sans reseau depuis un dossier de wheels prepare a l'avance.
+/// `pip_index_url`, `extra_index_url` et `torch_index_url` remplacent les index par defaut
+/// (miroirs PyPI / PyTorch, URLs https uniquement). `proxy_url` force un proxy http(s) ;
+/// sans valeur, les variables `HTTPS_PROXY`/`HTTP_PROXY` du systeme sont reprises.
+/// `data_mirror_url` est l'URL de base d'un miroir des fichiers data Multi-Aligner, essayee
+/// avant les sources par defaut (fichiers toujours verifies par SHA-256).
+/// Les versions des paquets sont figees par moteur ; `latest` installe les dernieres
+/// versions pour tester une mise a jour. L'installation est refusee si l'espace disque libre
+/// est insuffisant, sauf avec `ignore_disk_check`.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn install_local_segmentation_deps(
     app_handle: tauri::AppHandle,
     engine: String,
     hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: Option<bool>,
+    ignore_disk_check: Option<bool>,
 ) -> Result<String, String> {
-    segmentation::install_local_segmentation_deps(app_handle, engine, hf_token).await
+    segmentation::install_local_segmentation_deps(
+        app_handle,
+        engine,
+        hf_token,
+        offline_wheels_dir,
+        pip_index_url,
+        extra_index_url,
+        torch_index_url,
+        proxy_url,
+        data_mirror_url,
+        latest,
+        ignore_disk_check,
+    )
+    .await
+}
+
+/// Installe plusieurs moteurs locaux a la suite (cache pip partage, echec isole par moteur).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn install_local_segmentation_engines(
+    app_handle: tauri::AppHandle,
+    engines: Vec<String>,
+    hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: Option<bool>,
+    ignore_disk_check: Option<bool>,
+) -> Result<Vec<segmentation::LocalEngineInstallResult>, String> {
+    segmentation::install_local_segmentation_engines(
+        app_handle,
+        engines,
+        hf_token,
+        offline_wheels_dir,
+        pip_index_url,
+        extra_index_url,
+        torch_index_url,
+        proxy_url,
+        data_mirror_url,
+        latest,
+        ignore_disk_check,
+    )
+    .await
+}
+
+/// Telecharge le Python autonome utilise comme base des venvs locaux.
+#[tauri::command]
+pub async fn install_managed_python(app_handle: tauri::AppHandle) -> Result<String, String> {
+    segmentation::install_managed_python(app_handle).await
+}
+
+/// Liste les paquets et fichiers a reunir pour installer un moteur local hors ligne.
+#[tauri::command]
+pub fn get_local_install_manifest(
+    app_handle: tauri::AppHandle,
+    engine: String,
+) -> Result<segmentation::OfflineInstallManifest, String> {
+    segmentation::get_local_install_manifest(app_handle, engine)
+}
+
+/// Annule l'installation en cours des dependances d'un moteur local.
+#[tauri::command]
+pub fn cancel_local_install(engine: String) -> Result<bool, String> {
+    segmentation::cancel_local_install(engine)
+}
+
+/// Retourne l'espace disque occupe par les donnees de l'application (venvs, caches, journaux).
+#[tauri::command]
+pub async fn get_app_storage_report(
+    app_handle: tauri::AppHandle,
+) -> Result<segmentation::AppStorageReport, String> {
+    segmentation::get_app_storage_report(app_handle).await
+}
+
+/// Vide les categories de stockage demandees (les venvs seulement si `venvs` est liste).
+#[tauri::command]
+pub async fn clear_app_cache(
+    app_handle: tauri::AppHandle,
+    categories: Vec<String>,
+) -> Result<segmentation::AppCacheClearReport, String> {
+    segmentation::clear_app_cache(app_handle, categories).await
+}
+
+/// Retourne l'espace disque occupe par les moteurs locaux (venvs, data, cache de modeles).
+#[tauri::command]
+pub async fn get_local_engine_storage_info(
+    app_handle: tauri::AppHandle,
+    fast: Option<bool>,
+) -> Result<segmentation::LocalEngineStorageInfo, String> {
+    segmentation::get_local_engine_storage_info(app_handle, fast.unwrap_or(false)).await
+}
+
+/// Verifie chaque fichier data Multi-Aligner et retourne son etat (valide, erreur, taille).
+#[tauri::command]
+pub async fn verify_multi_aligner_data(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<segmentation::MultiAlignerDataFileStatus>, String> {
+    segmentation::verify_multi_aligner_data(app_handle).await
+}
+
+/// Retourne les versions de Python et des paquets cles installes dans le venv d'un moteur local.
+#[tauri::command]
+pub async fn get_local_engine_versions(
+    app_handle: tauri::AppHandle,
+    engine: String,
+) -> Result<segmentation::LocalEngineVersions, String> {
+    segmentation::get_local_engine_versions(app_handle, engine).await
+}
+
+/// Reprend les venvs laisses dans le dossier de donnees d'une ancienne version de l'application.
+#[tauri::command]
+pub async fn migrate_local_envs(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<segmentation::VenvMigrationReport>, String> {
+    segmentation::migrate_local_envs(app_handle).await
+}
+
+/// Supprime l'environnement Python d'un moteur local et retourne l'espace libere.
+#[tauri::command]
+pub async fn uninstall_local_segmentation_deps(
+    app_handle: tauri::AppHandle,
+    engine: String,
+    remove_data_files: Option<bool>,
+) -> Result<segmentation::LocalDepsUninstallReport, String> {
+    segmentation::uninstall_local_segmentation_deps(
+        app_handle,
+        engine,
+        remove_data_files.unwrap_or(false),
+    )
+    .await
 }
 
 /// Lance la segmentation locale en mode legacy Whisper.
@@ -133,19 +322,49 @@ pub async fn segment_quran_audio_local(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     whisper_model: Option<String>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
     segmentation::segment_quran_audio_local(
         app_handle,
         audio_path,
         audio_clips,
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         whisper_model,
+        surah_hint,
+        ayah_range_hint,
+        include_wbw_timestamps,
+        cpu_threads,
+        background,
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+        ignore_memory_check,
+        keep_temp_audio,
+        preprocess,
     )
     .await
 }
@@ -156,23 +375,53 @@ pub async fn segment_quran_audio_local_multi(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
     hf_token: Option<String>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
     segmentation::segment_quran_audio_local_multi(
         app_handle,
         audio_path,
         audio_clips,
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         model_name,
         device,
         hf_token,
+        surah_hint,
+        ayah_range_hint,
+        include_wbw_timestamps,
+        cpu_threads,
+        background,
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+        ignore_memory_check,
+        keep_temp_audio,
+        preprocess,
     )
     .await
 }
@@ -183,23 +432,47 @@ pub async fn segment_quran_audio_local_muaalem(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
     include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
     segmentation::segment_quran_audio_local_muaalem(
         app_handle,
         audio_path,
         audio_clips,
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         model_name,
         device,
         include_wbw_timestamps,
+        cpu_threads,
+        background,
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+        ignore_memory_check,
+        keep_temp_audio,
+        preprocess,
     )
     .await
 }
@@ -210,6 +483,10 @@ pub async fn segment_quran_audio_local_surah_splitter(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
@@ -217,11 +494,23 @@ pub async fn segment_quran_audio_local_surah_splitter(
     device: Option<String>,
     surah: Option<u32>,
     include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
     segmentation::segment_quran_audio_local_surah_splitter(
         app_handle,
         audio_path,
         audio_clips,
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
@@ -229,10 +518,164 @@ pub async fn segment_quran_audio_local_surah_splitter(
         device,
         surah,
         include_wbw_timestamps,
+        cpu_threads,
+        background,
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+        ignore_memory_check,
+        keep_temp_audio,
+        preprocess,
     )
     .await
 }
 
+/// Segmente plusieurs fichiers audio a la suite et retourne les resultats par identifiant.
+#[tauri::command]
+pub async fn segment_quran_audio_batch(
+    app_handle: tauri::AppHandle,
+    batch_id: String,
+    items: Vec<SegmentationBatchItem>,
+    engine: String,
+    hf_token: Option<String>,
+) -> Result<segmentation::SegmentationBatchReport, String> {
+    segmentation::segment_quran_audio_batch(app_handle, batch_id, items, engine, hf_token).await
+}
+
+/// Demande l'arret d'un lot de segmentation apres l'element en cours.
+#[tauri::command]
+pub fn cancel_segmentation_batch(batch_id: String) -> Result<(), String> {
+    segmentation::cancel_segmentation_batch(batch_id)
+}
+
+/// Detecte les GPUs/CPU disponibles pour le selecteur de device (resultat mis en cache).
+#[tauri::command]
+pub async fn detect_compute_devices(
+    app_handle: tauri::AppHandle,
+    refresh: Option<bool>,
+    probe_torch: Option<bool>,
+) -> Result<segmentation::ComputeDevices, String> {
+    segmentation::detect_compute_devices(
+        app_handle,
+        refresh.unwrap_or(false),
+        probe_torch.unwrap_or(false),
+    )
+    .await
+}
+
+/// Liste les modeles de segmentation presents dans le cache Hugging Face.
+#[tauri::command]
+pub async fn get_model_cache_info() -> Result<segmentation::ModelCacheInfo, String> {
+    segmentation::get_model_cache_info().await
+}
+
+/// Supprime des modeles du cache Hugging Face et retourne l'espace libere.
+#[tauri::command]
+pub async fn clear_model_cache(
+    repo_ids: Option<Vec<String>>,
+    all: Option<bool>,
+) -> Result<segmentation::ModelCacheClearReport, String> {
+    segmentation::clear_model_cache(repo_ids.unwrap_or_default(), all.unwrap_or(false)).await
+}
+
+/// Definit et memorise le dossier de cache Hugging Face des moteurs locaux (cache par defaut si vide).
+#[tauri::command]
+pub fn set_hf_cache_dir(
+    app_handle: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    segmentation::set_hf_cache_dir(&app_handle, path)
+}
+
+/// Fusionne les resultats de segmentation des morceaux d'un audio long (voir `split_audio_by_duration`).
+#[tauri::command]
+pub fn merge_segmentation_results(
+    results: Vec<SegmentationChunkResult>,
+) -> Result<serde_json::Value, String> {
+    segmentation::merge_segmentation_results(results)
+}
+
+/// Exporte un resultat de segmentation en fichier SRT ; retourne le nombre de cues ecrits.
+#[tauri::command]
+pub fn segmentation_to_srt(
+    payload: serde_json::Value,
+    output_path: String,
+    text_field: Option<String>,
+    include_refs: Option<bool>,
+) -> Result<usize, String> {
+    segmentation::segmentation_to_srt(payload, output_path, text_field, include_refs)
+}
+
+/// Estime la duree d'une segmentation locale (facteurs de reference affines par les executions passees).
+#[tauri::command]
+pub fn estimate_local_segmentation(
+    app_handle: tauri::AppHandle,
+    audio_duration_s: f64,
+    engine: String,
+    device: Option<String>,
+) -> Result<segmentation::LocalSegmentationEstimate, String> {
+    segmentation::estimate_local_segmentation(app_handle, audio_duration_s, engine, device)
+}
+
+/// Demarre la surveillance periodique des moteurs locaux (evenement `engine-health-changed`).
+#[tauri::command]
+pub fn start_engine_health_monitor(
+    app_handle: tauri::AppHandle,
+    interval_minutes: u64,
+) -> Result<(), String> {
+    segmentation::start_engine_health_monitor(app_handle, interval_minutes)
+}
+
+/// Arrete la surveillance des moteurs locaux ; retourne `true` si elle etait active.
+#[tauri::command]
+pub fn stop_engine_health_monitor() -> Result<bool, String> {
+    segmentation::stop_engine_health_monitor()
+}
+
+/// Active/desactive le mode mock de segmentation (commande de developpement).
+#[tauri::command]
+pub fn set_segmentation_mock(enabled: bool, payload_path: Option<String>) -> Result<(), String> {
+    segmentation::set_segmentation_mock(enabled, payload_path)
+}
+
+/// Retourne les chemins des derniers journaux de segmentation locale (plus recent d'abord).
+#[tauri::command]
+pub async fn get_segmentation_logs(
+    app_handle: tauri::AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    segmentation::get_segmentation_logs(app_handle, limit).await
+}
+
+/// Retourne le dernier journal d'installation des dependances locales (chemin et contenu).
+#[tauri::command]
+pub async fn get_latest_install_log(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<segmentation::InstallLogContent>, String> {
+    segmentation::get_latest_install_log(app_handle).await
+}
+
+/// Active/desactive le worker Python resident des moteurs locaux (modeles gardes en memoire).
+#[tauri::command]
+pub async fn set_segmentation_worker_mode(
+    enabled: bool,
+    idle_timeout_minutes: Option<u64>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        segmentation::set_segmentation_worker_mode(enabled, idle_timeout_minutes)
+    })
+    .await
+    .map_err(|e| format!("Worker mode task failed: {}", e))?
+}
+
+/// Arrete le worker Python resident (y compris pendant une requete) ; retourne `true` s'il tournait.
+#[tauri::command]
+pub async fn shutdown_segmentation_worker() -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(segmentation::shutdown_segmentation_worker)
+        .await
+        .map_err(|e| format!("Worker shutdown task failed: {}", e))?
+}
+
 /// Genere une nouvelle piste audio Hifz en repetant chaque segment fourni.
 #[tauri::command]
 pub async fn generate_hifz_audio(
@@ -63,9 +63,11 @@ pub fn adjust_auto_batch_limit_for_memory(current_limit: usize) -> usize {
 
     if used_percent >= constants::AUTO_MEMORY_SOFT_LIMIT_PERCENT {
         let next = (current_limit / 2).max(constants::FILTERGRAPH_BATCH_MIN);
-        println!(
+        log::info!(
             "[memory][auto-batch] RAM avant batch {:.1}%, limite {} -> {}",
-            used_percent, current_limit, next
+            used_percent,
+            current_limit,
+            next
         );
         next
     } else {
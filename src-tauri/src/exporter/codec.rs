@@ -123,7 +123,7 @@ pub fn test_nvenc_availability(ffmpeg_path: Option<&str>) -> bool {
         }
     }
 
-    println!("[nvenc_test] Test de disponibilité NVENC...");
+    log::info!("[nvenc_test] Test de disponibilité NVENC...");
 
     // Test avec une frame noire 128x128 (résolution minimale NVENC)
     let mut cmd = Command::new(exe);
@@ -157,7 +157,7 @@ pub fn test_nvenc_availability(ffmpeg_path: Option<&str>) -> bool {
             let stderr = String::from_utf8_lossy(&output.stderr);
 
             if success {
-                println!("[nvenc_test] ✓ NVENC disponible et fonctionnel");
+                log::info!("[nvenc_test] ✓ NVENC disponible et fonctionnel");
                 true
             } else {
                 let stderr_lower = stderr.to_lowercase();
@@ -168,22 +168,22 @@ pub fn test_nvenc_availability(ffmpeg_path: Option<&str>) -> bool {
                     || stderr_lower.contains("cuda")
                     || stderr_lower.contains("driver")
                 {
-                    println!(
+                    log::info!(
                         "[nvenc_test] ✗ NVENC non disponible (pas de GPU NVIDIA ou drivers manquants)"
                     );
                     false
                 } else if stderr_lower.contains("frame dimension") {
                     // Problème de dimensions : retenter avec une résolution plus grande
-                    println!("[nvenc_test] Retry avec résolution plus grande...");
+                    log::info!("[nvenc_test] Retry avec résolution plus grande...");
                     test_nvenc_with_larger_resolution(ffmpeg_path)
                 } else {
-                    println!("[nvenc_test] ✗ NVENC erreur: {}", stderr.trim());
+                    log::info!("[nvenc_test] ✗ NVENC erreur: {}", stderr.trim());
                     false
                 }
             }
         }
         Err(e) => {
-            println!("[nvenc_test] ✗ Erreur lors du test NVENC: {}", e);
+            log::info!("[nvenc_test] ✗ Erreur lors du test NVENC: {}", e);
             false
         }
     };
@@ -229,10 +229,10 @@ fn test_nvenc_with_larger_resolution(ffmpeg_path: Option<&str>) -> bool {
         Ok(output) => {
             let success = output.status.success();
             if success {
-                println!("[nvenc_test] ✓ NVENC disponible avec résolution 256x256");
+                log::info!("[nvenc_test] ✓ NVENC disponible avec résolution 256x256");
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                println!(
+                log::info!(
                     "[nvenc_test] ✗ NVENC toujours non disponible: {}",
                     stderr.trim()
                 );
@@ -240,7 +240,7 @@ fn test_nvenc_with_larger_resolution(ffmpeg_path: Option<&str>) -> bool {
             success
         }
         Err(e) => {
-            println!("[nvenc_test] ✗ Erreur test résolution plus grande: {}", e);
+            log::info!("[nvenc_test] ✗ Erreur test résolution plus grande: {}", e);
             false
         }
     }
@@ -279,13 +279,17 @@ pub fn choose_best_codec(
         high_resolution && !matches!(performance_profile, ExportPerformanceProfile::Fastest);
 
     if force_cpu_high_quality && !hw.iter().any(|encoder| encoder == "h264_videotoolbox") {
-        println!(
+        log::info!(
             "[codec] Export haute résolution détecté ({}x{}), forçage libx264 haute qualité",
-            width, height
+            width,
+            height
         );
-        println!(
+        log::info!(
             "[codec] usage={:?} profile={:?} resolution={}x{} selected=libx264",
-            usage, performance_profile, width, height
+            usage,
+            performance_profile,
+            width,
+            height
         );
 
         let codec = "libx264".to_string();
@@ -314,9 +318,13 @@ pub fn choose_best_codec(
         if hw[0] == "h264_nvenc" {
             if test_nvenc_availability(ffmpeg_exe.as_deref()) {
                 let codec = hw[0].clone();
-                println!(
+                log::info!(
                     "[codec] usage={:?} profile={:?} resolution={}x{} selected={}",
-                    usage, performance_profile, width, height, codec
+                    usage,
+                    performance_profile,
+                    width,
+                    height,
+                    codec
                 );
                 let mut params = vec!["-pix_fmt".to_string(), "yuv420p".to_string()];
                 // Le débit NVENC par défaut est insuffisant pour les fonds animés en Balanced.
@@ -332,15 +340,19 @@ pub fn choose_best_codec(
                 extra.insert("preset".to_string(), Some("fast".to_string()));
                 return (codec, params, extra);
             } else {
-                println!("[codec] NVENC détecté mais non fonctionnel, fallback vers libx264");
+                log::info!("[codec] NVENC détecté mais non fonctionnel, fallback vers libx264");
             }
         }
         // VideoToolbox (macOS)
         else if hw[0] == "h264_videotoolbox" {
             let codec = hw[0].clone();
-            println!(
+            log::info!(
                 "[codec] usage={:?} profile={:?} resolution={}x{} selected={}",
-                usage, performance_profile, width, height, codec
+                usage,
+                performance_profile,
+                width,
+                height,
+                codec
             );
             let (bitrate, maxrate, bufsize) = if high_resolution {
                 match usage {
@@ -372,9 +384,13 @@ pub fn choose_best_codec(
         // Autres encodeurs hardware (QSV, AMF)
         else {
             let codec = hw[0].clone();
-            println!(
+            log::info!(
                 "[codec] usage={:?} profile={:?} resolution={}x{} selected={}",
-                usage, performance_profile, width, height, codec
+                usage,
+                performance_profile,
+                width,
+                height,
+                codec
             );
             let params = vec!["-pix_fmt".to_string(), "yuv420p".to_string()];
             let mut extra = HashMap::new();
@@ -385,9 +401,13 @@ pub fn choose_best_codec(
 
     // Fallback : libx264 logiciel
     let codec = "libx264".to_string();
-    println!(
+    log::info!(
         "[codec] usage={:?} profile={:?} resolution={}x{} selected={}",
-        usage, performance_profile, width, height, codec
+        usage,
+        performance_profile,
+        width,
+        height,
+        codec
     );
     let mut extra = HashMap::new();
     let params = {
@@ -425,7 +445,7 @@ pub fn choose_h265_codec(
     for codec in ["hevc_videotoolbox", "hevc_nvenc", "hevc_qsv", "hevc_amf"] {
         if hw.iter().any(|encoder| encoder == codec) {
             if codec == "hevc_nvenc" && !test_nvenc_availability(ffmpeg_exe.as_deref()) {
-                println!(
+                log::info!(
                     "[codec] HEVC NVENC détecté mais non fonctionnel, fallback H.265 logiciel"
                 );
                 continue;
@@ -460,9 +480,10 @@ pub fn choose_h265_codec(
                     "hvc1".to_string(),
                 ]
             };
-            println!(
+            log::info!(
                 "[codec] h265 profile={:?} selected={}",
-                performance_profile, codec
+                performance_profile,
+                codec
             );
             return (codec.to_string(), params, extra);
         }
@@ -470,7 +491,7 @@ pub fn choose_h265_codec(
 
     let mut extra = HashMap::new();
     extra.insert("preset".to_string(), Some("medium".to_string()));
-    println!(
+    log::info!(
         "[codec] h265 profile={:?} selected=libx265",
         performance_profile
     );
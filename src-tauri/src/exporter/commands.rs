@@ -1,4 +1,5 @@
 use crate::path_utils;
+use crate::utils::process;
 
 use rayon::prelude::*;
 use std::fs::{self, File};
@@ -16,7 +17,7 @@ use super::ffmpeg_runner;
 use super::ffmpeg_utils;
 use super::preprocess;
 use super::types::{
-    CodecUsage, ExportPerformanceProfile, ExportVideoCodec, FfmpegProgressContext,
+    CodecUsage, ExportPerformanceProfile, ExportVideoCodec, FfconcatEntry, FfmpegProgressContext,
     VideoClipTransitionMode, VideoInput,
 };
 
@@ -81,14 +82,15 @@ pub async fn export_video(
     ffmpeg_runner::clear_export_cancelled(&export_id);
 
     // ---- Logs de démarrage ----
-    println!("[start_export] export_id={}", export_id);
-    println!("[start_export] imgs_folder={}", imgs_folder);
-    println!("[start_export] final_file_path={}", final_file_path);
-    println!(
+    log::info!("[start_export] export_id={}", export_id);
+    log::info!("[start_export] imgs_folder={}", imgs_folder);
+    log::info!("[start_export] final_file_path={}", final_file_path);
+    log::info!(
         "[start_export] fps={}, fade_duration(ms)={}",
-        fps, fade_duration
+        fps,
+        fade_duration
     );
-    println!(
+    log::info!(
         "[start_export] export_fade: video(in={}, out={}) audio(in={}, out={}) duration(ms)={}",
         video_fade_in_enabled.unwrap_or(false),
         video_fade_out_enabled.unwrap_or(false),
@@ -96,45 +98,45 @@ pub async fn export_video(
         audio_fade_out_enabled.unwrap_or(false),
         export_fade_duration_ms.unwrap_or(0)
     );
-    println!(
+    log::info!(
         "[start_export] export_without_background={}",
         export_without_background.unwrap_or(false)
     );
-    println!(
+    log::info!(
         "[start_export] transparent_export_format={}",
         transparent_export_format
             .as_deref()
             .unwrap_or("mov_prores_4444")
     );
-    println!(
+    log::info!(
         "[env] CPU cores: {:?}",
         std::thread::available_parallelism().map(|n| n.get())
     );
-    println!("[perf] profile={:?}", performance_profile);
-    println!(
+    log::info!("[perf] profile={:?}", performance_profile);
+    log::info!(
         "[perf] thread_cap={:?}",
         codec::compute_ffmpeg_thread_cap(performance_profile)
     );
-    println!(
+    log::info!(
         "[timeline] blank timings fournis={}",
         blank_timings.as_ref().map_or(0, Vec::len)
     );
 
     if let Some(ref audios) = audios {
-        println!("[audio] {} fichier(s) audio fourni(s)", audios.len());
+        log::info!("[audio] {} fichier(s) audio fourni(s)", audios.len());
     } else {
-        println!("[audio] aucun fichier audio fourni");
+        log::info!("[audio] aucun fichier audio fourni");
     }
 
     if let Some(ref videos) = videos {
-        println!("[video] {} fichier(s) vidéo fourni(s)", videos.len());
+        log::info!("[video] {} fichier(s) vidéo fourni(s)", videos.len());
     } else {
-        println!("[video] aucune vidéo de fond fournie");
+        log::info!("[video] aucune vidéo de fond fournie");
     }
 
     // ---- Scan des PNG ----
     let folder = path_utils::normalize_existing_path(&imgs_folder);
-    println!(
+    log::info!(
         "[scan] Parcours du dossier: {:?}",
         folder.canonicalize().unwrap_or_else(|_| folder.clone())
     );
@@ -164,7 +166,7 @@ pub async fn export_video(
         .map(|p| p.canonicalize().unwrap_or(p))
         .collect();
 
-    println!("[scan] {} image(s) trouvée(s)", files.len());
+    log::info!("[scan] {} image(s) trouvée(s)", files.len());
 
     if files.is_empty() {
         return Err("Aucune image .png trouvée dans imgs_folder".to_string());
@@ -202,15 +204,15 @@ pub async fn export_video(
         .collect();
 
     let ts_preview: Vec<i32> = ts.iter().take(10).cloned().collect();
-    println!(
+    log::info!(
         "[timeline] Premiers timestamps: {:?}{}",
         ts_preview,
         if ts.len() > 10 { " ..." } else { "" }
     );
-    println!("[timeline] Nombre d'images: {}", ts.len());
+    log::info!("[timeline] Nombre d'images: {}", ts.len());
 
     // ---- Taille cible (dimensions de 0.png) ----
-    println!("[image] Ouverture de la première image pour taille cible...");
+    log::info!("[image] Ouverture de la première image pour taille cible...");
     let target_size = {
         let img_data = fs::read(&files[0]).map_err(|e| format!("Erreur lecture image: {}", e))?;
         let img = image::load_from_memory(&img_data)
@@ -219,18 +221,19 @@ pub async fn export_video(
         ((img.width() as i32 / 2) * 2, (img.height() as i32 / 2) * 2)
     };
 
-    println!("[image] Taille cible: {}x{}", target_size.0, target_size.1);
+    log::info!("[image] Taille cible: {}x{}", target_size.0, target_size.1);
 
     // ---- Durée totale ----
     let fade_ms = fade_duration;
     let tail_ms = fade_ms.max(1000);
     let total_duration_ms = duration.unwrap_or_else(|| ts[ts.len() - 1] + tail_ms);
     let duration_s = total_duration_ms as f64 / 1000.0;
-    println!(
+    log::info!(
         "[timeline] Durée totale: {} ms ({:.3} s)",
-        total_duration_ms, duration_s
+        total_duration_ms,
+        duration_s
     );
-    println!(
+    log::info!(
         "[perf] Préparation terminée en {:.0} ms",
         t0.elapsed().as_millis()
     );
@@ -238,7 +241,7 @@ pub async fn export_video(
     // ---- Préparation du dossier de sortie ----
     let out_path = path_utils::normalize_output_path(&final_file_path);
     if let Some(parent) = out_path.parent() {
-        println!("[fs] Création du dossier de sortie si besoin: {:?}", parent);
+        log::info!("[fs] Création du dossier de sortie si besoin: {:?}", parent);
         fs::create_dir_all(parent).map_err(|e| format!("Erreur création dossier: {}", e))?;
     }
 
@@ -250,7 +253,7 @@ pub async fn export_video(
     for raw_audio_path in audios.unwrap_or_default() {
         let normalized = path_utils::normalize_existing_path(&raw_audio_path);
         if normalized.as_os_str().is_empty() || !normalized.exists() {
-            println!(
+            log::info!(
                 "[audio][warn] Fichier audio introuvable, export sans ce fichier: {}",
                 raw_audio_path
             );
@@ -260,9 +263,9 @@ pub async fn export_video(
         audios_vec.push(normalized.to_string_lossy().to_string());
     }
     if audios_vec.is_empty() {
-        println!("[audio] Aucun fichier audio valide, export sans audio");
+        log::info!("[audio] Aucun fichier audio valide, export sans audio");
     } else {
-        println!(
+        log::info!(
             "[audio] {} fichier(s) audio valide(s) après vérification",
             audios_vec.len()
         );
@@ -326,8 +329,8 @@ pub async fn export_video(
     let export_time_s = t0.elapsed().as_secs_f64();
     *constants::LAST_EXPORT_TIME_S.lock().unwrap() = Some(export_time_s);
     ffmpeg_runner::clear_export_cancelled(&export_id);
-    println!("[done] Export terminé en {:.2}s", export_time_s);
-    println!("[metric] export_time_seconds={:.3}", export_time_s);
+    log::info!("[done] Export terminé en {:.2}s", export_time_s);
+    log::info!("[metric] export_time_seconds={:.3}", export_time_s);
 
     let output_file_name = out_path
         .file_name()
@@ -794,16 +797,13 @@ fn format_seconds_ticks(ticks: u128, timebase: u128) -> String {
     format!("{}.{:06}", whole, micros)
 }
 
-/// Echappe un chemin pour le demuxer concat de FFmpeg.
-fn escape_concat_path(path: &Path) -> String {
-    path.to_string_lossy()
-        .replace('\\', "/")
-        .replace('\'', "\\'")
-}
-
 /// Ecrit une entree de fichier dans le plan concat.
 fn write_concat_file<W: Write>(writer: &mut W, path: &Path, timebase: u128) -> ExportResult<()> {
-    writeln!(writer, "file '{}'", escape_concat_path(path))?;
+    writeln!(
+        writer,
+        "file '{}'",
+        path_utils::escape_ffconcat_path(&path.to_string_lossy())
+    )?;
     writeln!(writer, "option framerate {}", timebase)?;
     Ok(())
 }
@@ -1185,7 +1185,7 @@ fn run_fast_export(
         .filter(|p| {
             let exists = Path::new(p).exists();
             if !exists {
-                println!("[fast_export] fichier audio introuvable, ignoré: {}", p);
+                log::info!("[fast_export] fichier audio introuvable, ignoré: {}", p);
             }
             exists
         })
@@ -1203,8 +1203,8 @@ fn run_fast_export(
         Some("Initializing..."),
         None,
     );
-    println!("[fast_export] Initialisation: generation du plan overlay TGA...");
-    println!(
+    log::info!("[fast_export] Initialisation: generation du plan overlay TGA...");
+    log::info!(
         "[fast_export] fade timeline effectif={}ms",
         fade_duration_ms.max(0)
     );
@@ -1225,7 +1225,7 @@ fn run_fast_export(
     ) {
         Ok(plan) => plan,
         Err(error) if is_no_space_left_error(error.as_ref()) => {
-            println!(
+            log::info!(
                 "[fast_export][warn] plan overlay TGA impossible par manque d'espace, retry PNG: {}",
                 error
             );
@@ -1245,7 +1245,7 @@ fn run_fast_export(
         }
         Err(error) => return Err(error),
     };
-    println!(
+    log::info!(
         "[fast_export] Frames source={} fades={} taille_source={}x{} opaque={} compose_noir={}",
         overlay_plan.source_frame_count,
         overlay_plan.generated_fade_frames,
@@ -1304,7 +1304,7 @@ fn run_fast_export(
         if !bg.is_normalized {
             let seek_s = (start_time_ms as f64 / 1000.0).max(0.0);
             cmd.extend_from_slice(&["-ss".to_string(), format!("{:.6}", seek_s)]);
-            println!("[background] input fast seek: {}s for {}", seek_s, bg.path);
+            log::info!("[background] input fast seek: {}s for {}", seek_s, bg.path);
         }
         cmd.extend_from_slice(&["-i".to_string(), bg.path.clone()]);
         current_idx += 1;
@@ -1330,7 +1330,7 @@ fn run_fast_export(
         && (!have_audio
             || (audio_paths.len() == 1 && !audio_fade_in_enabled && !audio_fade_out_enabled));
     if direct_visible_export {
-        println!(
+        log::info!(
             "[fast_export] chemin direct eligible: export_visible=true, fond_video=false, frames_opacifiees={}, audio_simple={}",
             overlay_plan.composited_to_black,
             !have_audio || audio_paths.len() == 1
@@ -1370,7 +1370,7 @@ fn run_fast_export(
                 audio_fade_out_enabled
             ));
         }
-        println!("[fast_export] chemin direct ignore: {}", reasons.join(", "));
+        log::info!("[fast_export] chemin direct ignore: {}", reasons.join(", "));
     }
 
     let needs_black_background = !direct_visible_export
@@ -1408,7 +1408,7 @@ fn run_fast_export(
 
     if direct_visible_export {
         let direct_duration_s = overlay_plan.duration_ticks as f64 / overlay_plan.timebase as f64;
-        println!(
+        log::info!(
             "[fast_export] voie directe visible sans filtre overlay (duree_concat={:.3}s, duree_ui={:.3}s)",
             direct_duration_s, duration_s
         );
@@ -1431,7 +1431,7 @@ fn run_fast_export(
         if have_audio {
             cmd.extend_from_slice(&["-map".to_string(), format!("{}:a", audio_start_idx)]);
             if (audio_gain - 1.0).abs() > 1e-6 {
-                println!("[fast_export] audio direct: volume={:.3}", audio_gain);
+                log::info!("[fast_export] audio direct: volume={:.3}", audio_gain);
                 cmd.extend_from_slice(&[
                     "-af".to_string(),
                     format!("volume={:.6}", audio_gain),
@@ -1441,10 +1441,10 @@ fn run_fast_export(
                     "320k".to_string(),
                 ]);
             } else if can_stream_copy_simple_audio(&audio_paths[0], out_path) {
-                println!("[fast_export] audio direct: copie sans reencodage");
+                log::info!("[fast_export] audio direct: copie sans reencodage");
                 cmd.extend_from_slice(&["-c:a".to_string(), "copy".to_string()]);
             } else {
-                println!("[fast_export] audio direct: fallback reencodage aac");
+                log::info!("[fast_export] audio direct: fallback reencodage aac");
                 cmd.extend_from_slice(&[
                     "-c:a".to_string(),
                     "aac".to_string(),
@@ -1466,7 +1466,7 @@ fn run_fast_export(
             cmd.extend_from_slice(&["-movflags".to_string(), "+faststart".to_string()]);
         }
         cmd.push(out_path.to_string());
-        println!("[fast_export] commande directe complete: {}", cmd.join(" "));
+        log::info!("[fast_export] commande directe complete: {}", cmd.join(" "));
         run_final_export_command(export_id, &cmd, direct_duration_s, &app_handle)?;
 
         if !Path::new(out_path).exists() {
@@ -1533,7 +1533,7 @@ fn run_fast_export(
                         bg_start_idx + i,
                         i
                     ));
-                    println!(
+                    log::info!(
                         "[background] normalized=true redundant_scale_skipped=true idx={}",
                         i
                     );
@@ -1546,7 +1546,7 @@ fn run_fast_export(
                         fps,
                         i
                     ));
-                    println!("[background] normalized=false idx={}", i);
+                    log::info!("[background] normalized=false idx={}", i);
                 }
                 labels.push(label);
                 durations.push(bg.duration_s);
@@ -1571,14 +1571,14 @@ fn run_fast_export(
             if let Some(bg) = single_bg {
                 if bg.is_normalized {
                     // Background déjà à la bonne résolution, FPS et SAR
-                    println!("[background] normalized=true redundant_scale_skipped=true");
+                    log::info!("[background] normalized=true redundant_scale_skipped=true");
                     filter_lines.push(format!(
                         "[{}]trim=start=0:end={:.6},setpts=PTS-STARTPTS[bgtrim]",
                         bg_label, bg_trim_end
                     ));
                 } else {
                     // Background non normalisé (direct single pass ou fallback)
-                    println!("[background] normalized=false (full filter chain)");
+                    log::info!("[background] normalized=false (full filter chain)");
                     filter_lines.push(format!(
                         "[{}]setpts=PTS-STARTPTS,{},fps={},setsar=1,trim=end={:.6}[bgtrim]",
                         bg_label, background_fit_filter, fps, bg_trim_end
@@ -1684,7 +1684,7 @@ fn run_fast_export(
     let filter_complex = filter_lines.join(";");
     let fg_path = temp_dir.path.join("fast-export.ffgraph");
     fs::write(&fg_path, filter_complex)?;
-    println!("[fast_export] filter_complex_script -> {:?}", fg_path);
+    log::info!("[fast_export] filter_complex_script -> {:?}", fg_path);
 
     cmd.extend_from_slice(&[
         "-filter_complex_script".to_string(),
@@ -1898,58 +1898,53 @@ fn build_background_transition_chain(
 /// Annule un export en cours.
 ///
 /// Marque l'export comme annulé (vérifié par `ensure_export_not_cancelled`)
-/// et tue le processus FFmpeg associé s'il est encore actif.
+/// et tue le processus FFmpeg associé (et ses descendants) s'il est encore actif.
 #[tauri::command]
 pub fn cancel_export(export_id: String) -> Result<String, String> {
-    println!(
+    log::info!(
         "[cancel_export] Demande d'annulation pour export_id: {}",
         export_id
     );
     ffmpeg_runner::mark_export_cancelled(&export_id);
 
-    let mut active_exports = constants::ACTIVE_EXPORTS
-        .lock()
-        .map_err(|_| "Failed to lock active exports")?;
-
-    if let Some(process_ref) = active_exports.remove(&export_id) {
-        if let Ok(mut process_guard) = process_ref.lock() {
-            if let Some(mut child) = process_guard.take() {
-                match child.kill() {
-                    Ok(_) => {
-                        println!(
-                            "[cancel_export] Processus FFmpeg tué avec succès pour export_id: {}",
-                            export_id
-                        );
-                        let _ = child.wait(); // Nettoyer le processus zombie
-                        Ok(format!("Export {} annulé avec succès", export_id))
-                    }
-                    Err(e) => {
-                        println!(
-                            "[cancel_export] Erreur lors de l'arrêt du processus: {:?}",
-                            e
-                        );
-                        Err(format!("Erreur lors de l'annulation: {}", e))
-                    }
-                }
-            } else {
-                println!(
-                    "[cancel_export] Aucun processus actif trouvé pour export_id: {}",
-                    export_id
-                );
-                Err(format!("Aucun processus actif pour l'export {}", export_id))
-            }
-        } else {
-            Err("Failed to lock process".to_string())
+    match process::kill_tracked_process(&export_id) {
+        Ok(true) => {
+            log::info!(
+                "[cancel_export] Processus FFmpeg tué avec succès pour export_id: {}",
+                export_id
+            );
+            Ok(format!("Export {} annulé avec succès", export_id))
+        }
+        Ok(false) => {
+            log::info!(
+                "[cancel_export] Aucun processus actif trouvé pour export_id: {}",
+                export_id
+            );
+            Ok(format!("Annulation demandée pour l'export {}", export_id))
+        }
+        Err(e) => {
+            log::info!("[cancel_export] Erreur lors de l'arrêt du processus: {}", e);
+            Err(format!("Erreur lors de l'annulation: {}", e))
         }
-    } else {
-        println!(
-            "[cancel_export] Export_id non trouvé dans les exports actifs: {}",
-            export_id
-        );
-        Ok(format!("Annulation demandée pour l'export {}", export_id))
     }
 }
 
+// ---------------------------------------------------------------------------
+// Commande Tauri : write_ffconcat
+// ---------------------------------------------------------------------------
+
+/// Écrit un fichier ffconcat v1 (`file`/`inpoint`/`outpoint`) à partir de segments.
+///
+/// Le fichier peut être passé tel quel à FFmpeg (`-f concat -safe 0 -i ...`) pour
+/// assembler un montage sans pré-découper les sources.
+///
+/// @returns Chemin du fichier ffconcat écrit
+#[tauri::command]
+pub fn write_ffconcat(entries: Vec<FfconcatEntry>, output_path: String) -> Result<String, String> {
+    concat::write_ffconcat_file(entries, &output_path)
+        .map(|path| path.to_string_lossy().to_string())
+}
+
 // ---------------------------------------------------------------------------
 // Commande Tauri : concat_videos
 // ---------------------------------------------------------------------------
@@ -1987,12 +1982,12 @@ pub async fn concat_videos(
     let output_path_buf = path_utils::normalize_output_path(&output_path);
     let output_path_str = output_path_buf.to_string_lossy().to_string();
 
-    println!(
+    log::info!(
         "[concat_videos] Début de la concaténation de {} vidéos",
         normalized_video_paths.len()
     );
-    println!("[concat_videos] Fichier de sortie: {}", output_path_str);
-    println!(
+    log::info!("[concat_videos] Fichier de sortie: {}", output_path_str);
+    log::info!(
         "[concat_videos] export_fade: video(in={}, out={}) audio(in={}, out={}) duration(ms)={}",
         video_fade_in_enabled.unwrap_or(false),
         video_fade_out_enabled.unwrap_or(false),
@@ -2000,11 +1995,11 @@ pub async fn concat_videos(
         audio_fade_out_enabled.unwrap_or(false),
         export_fade_duration_ms.unwrap_or(0)
     );
-    println!(
+    log::info!(
         "[concat_videos] export_without_background={}",
         export_without_background.unwrap_or(false)
     );
-    println!(
+    log::info!(
         "[concat_videos] transparent_export_format={}",
         transparent_export_format
             .as_deref()
@@ -2035,7 +2030,7 @@ pub async fn concat_videos(
 
     // Cas trivial : une seule vidéo sans fades → copie simple
     if normalized_video_paths.len() == 1 && !apply_any_fade {
-        println!("[concat_videos] Une seule vidéo, copie vers le fichier final");
+        log::info!("[concat_videos] Une seule vidéo, copie vers le fichier final");
         std::fs::copy(&normalized_video_paths[0], &output_path_str)
             .map_err(|e| format!("Erreur lors de la copie: {}", e))?;
         return Ok(output_path_str);
@@ -2062,7 +2057,7 @@ pub async fn concat_videos(
     let all_have_audio = !audio_presence.is_empty() && audio_presence.iter().all(|&has| has);
     let any_have_audio = audio_presence.iter().any(|&has| has);
     if any_have_audio && !all_have_audio {
-        println!(
+        log::info!(
             "[concat_videos][warn] Certains segments n'ont pas d'audio; l'audio final sera désactivé"
         );
     }
@@ -2274,7 +2269,7 @@ pub async fn concat_videos(
     }
     cmd.push(output_path_str.clone());
 
-    println!("[concat_videos] Exécution de FFmpeg...");
+    log::info!("[concat_videos] Exécution de FFmpeg...");
 
     let progress_context = FfmpegProgressContext {
         base_time_s: 0.0,
@@ -2298,7 +2293,7 @@ pub async fn concat_videos(
         return Err("Le fichier de sortie n'a pas été créé".to_string());
     }
 
-    println!(
+    log::info!(
         "[concat_videos] ✅ Concaténation réussie: {}",
         output_path_str
     );
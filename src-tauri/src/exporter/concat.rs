@@ -8,7 +8,7 @@ use super::batching;
 use super::codec;
 use super::ffmpeg_runner;
 use super::ffmpeg_utils;
-use super::types::{ExportPerformanceProfile, FfmpegProgressContext};
+use super::types::{ExportPerformanceProfile, FfconcatEntry, FfmpegProgressContext};
 
 // ---------------------------------------------------------------------------
 // Fichier de concaténation FFmpeg
@@ -43,6 +43,81 @@ pub fn write_video_concat_file(
     Ok(concat_path)
 }
 
+/// Formate une durée en millisecondes pour une directive ffconcat (`12.345`).
+fn format_ffconcat_time(ms: i64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// Construit le contenu d'un fichier ffconcat v1 avec directives `inpoint`/`outpoint`.
+///
+/// Les bornes doivent être positives ou nulles et `outpoint_ms` strictement supérieur à
+/// `inpoint_ms`.
+pub fn build_ffconcat_content(entries: &[FfconcatEntry]) -> Result<String, String> {
+    if entries.is_empty() {
+        return Err("No entries provided for the ffconcat file".to_string());
+    }
+    let mut content = String::from("ffconcat version 1.0\n");
+    for (index, entry) in entries.iter().enumerate() {
+        let inpoint_ms = entry.inpoint_ms.filter(|ms| *ms > 0);
+        if entry.inpoint_ms.is_some_and(|ms| ms < 0) {
+            return Err(format!("Entry {}: inpoint_ms must be >= 0", index));
+        }
+        if let Some(outpoint_ms) = entry.outpoint_ms {
+            if outpoint_ms <= inpoint_ms.unwrap_or(0) {
+                return Err(format!(
+                    "Entry {}: outpoint_ms must be greater than inpoint_ms",
+                    index
+                ));
+            }
+        }
+        content.push_str(&format!(
+            "file '{}'\n",
+            path_utils::escape_ffconcat_path(&entry.path)
+        ));
+        if let Some(inpoint_ms) = inpoint_ms {
+            content.push_str(&format!("inpoint {}\n", format_ffconcat_time(inpoint_ms)));
+        }
+        if let Some(outpoint_ms) = entry.outpoint_ms {
+            content.push_str(&format!("outpoint {}\n", format_ffconcat_time(outpoint_ms)));
+        }
+    }
+    Ok(content)
+}
+
+/// Écrit un fichier ffconcat à partir de segments de timeline (chemins normalisés).
+///
+/// FFmpeg lit directement les portions demandées : aucun fichier intermédiaire découpé.
+pub fn write_ffconcat_file(
+    entries: Vec<FfconcatEntry>,
+    output_path: &str,
+) -> Result<PathBuf, String> {
+    let entries: Vec<FfconcatEntry> = entries
+        .into_iter()
+        .map(|entry| FfconcatEntry {
+            path: path_utils::normalize_existing_path(&entry.path)
+                .to_string_lossy()
+                .to_string(),
+            ..entry
+        })
+        .collect();
+    if let Some(missing) = entries
+        .iter()
+        .find(|entry| !Path::new(&entry.path).exists())
+    {
+        return Err(format!("Media file not found: {}", missing.path));
+    }
+    let content = build_ffconcat_content(&entries)?;
+
+    let output_path = path_utils::normalize_output_path(output_path);
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ffconcat directory: {}", e))?;
+    }
+    fs::write(&output_path, content)
+        .map_err(|e| format!("Failed to write ffconcat file: {}", e))?;
+    Ok(output_path)
+}
+
 // ---------------------------------------------------------------------------
 // Concaténation sans ré-encodage (stream copy)
 // ---------------------------------------------------------------------------
@@ -106,7 +181,7 @@ pub fn concat_videos_with_stream_copy(
 
     cmd.push(output_path_buf.to_string_lossy().to_string());
 
-    println!(
+    log::info!(
         "[batching] concat stream-copy: {} fichier(s) -> {}",
         video_paths.len(),
         output_path
@@ -394,7 +469,7 @@ pub fn concat_internal_batch_videos(
         .max(0.0)
         .min(total_duration_s.max(0.0));
     let expected_video_s: f64 = batch_durations_s.iter().sum();
-    println!(
+    log::info!(
         "[batching] concat interne: {} batch(s), duree calculee={:.6}s, duree finale={:.6}s",
         batch_paths.len(),
         expected_video_s,
@@ -690,3 +765,49 @@ pub fn concat_internal_batch_videos(
     fs::remove_file(video_concat_path).ok();
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{build_ffconcat_content, FfconcatEntry};
+
+    fn entry(path: &str, inpoint_ms: Option<i64>, outpoint_ms: Option<i64>) -> FfconcatEntry {
+        FfconcatEntry {
+            path: path.to_string(),
+            inpoint_ms,
+            outpoint_ms,
+        }
+    }
+
+    #[test]
+    fn ffconcat_content_has_inpoint_and_outpoint_directives() {
+        let content = build_ffconcat_content(&[
+            entry("/media/Al-Fatiha.mp4", Some(1_500), Some(62_250)),
+            entry("/media/l'intro.mp4", None, Some(4_000)),
+            entry("/media/outro.mp4", Some(0), None),
+        ])
+        .unwrap();
+        assert_eq!(
+            content.lines().collect::<Vec<_>>(),
+            vec![
+                "ffconcat version 1.0",
+                "file '/media/Al-Fatiha.mp4'",
+                "inpoint 1.500",
+                "outpoint 62.250",
+                r"file '/media/l'\''intro.mp4'",
+                "outpoint 4.000",
+                "file '/media/outro.mp4'",
+            ]
+        );
+    }
+
+    #[test]
+    fn ffconcat_content_rejects_invalid_bounds() {
+        assert!(build_ffconcat_content(&[]).is_err());
+        assert_eq!(
+            build_ffconcat_content(&[entry("a.mp4", Some(-1), None)]).unwrap_err(),
+            "Entry 0: inpoint_ms must be >= 0"
+        );
+        assert!(build_ffconcat_content(&[entry("a.mp4", Some(0), None)]).is_ok());
+        assert!(build_ffconcat_content(&[entry("a.mp4", Some(2_000), Some(2_000))]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex};
 
 // ---------------------------------------------------------------------------
 // Durée du dernier export terminé (en secondes)
@@ -13,12 +13,6 @@ pub static LAST_EXPORT_TIME_S: Mutex<Option<f64>> = Mutex::new(None);
 // Exports actifs et annulation
 // ---------------------------------------------------------------------------
 
-/// Contient les processus FFmpeg actifs, indexés par `export_id`.
-/// Permet d'annuler un export en cours en tuant le processus associé.
-pub static ACTIVE_EXPORTS: LazyLock<
-    Mutex<HashMap<String, Arc<Mutex<Option<std::process::Child>>>>>,
-> = LazyLock::new(|| Mutex::new(HashMap::new()));
-
 /// Ensemble des `export_id` dont l'annulation a été demandée.
 /// Les fonctions d'export vérifient cet ensemble régulièrement pour s'arrêter proprement.
 pub static CANCELLED_EXPORTS: LazyLock<Mutex<HashSet<String>>> =
@@ -1,3 +1,5 @@
+use crate::utils::process;
+
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
@@ -165,13 +167,13 @@ pub fn run_ffmpeg_command(
     ensure_export_not_cancelled(export_id)?;
 
     // Affichage de la commande (tronquée si trop longue)
-    println!("[ffmpeg] Commande:");
+    log::info!("[ffmpeg] Commande:");
     let preview = if cmd.len() > 14 {
         format!("{} ...", cmd[..14].join(" "))
     } else {
         cmd.join(" ")
     };
-    println!("  {}", preview);
+    log::info!("  {}", preview);
 
     // Construction et lancement du processus
     let mut command = Command::new(&cmd[0]);
@@ -181,13 +183,7 @@ pub fn run_ffmpeg_command(
     configure_command_no_window(&mut command);
 
     let child = command.spawn()?;
-    let process_ref = Arc::new(Mutex::new(Some(child)));
-    {
-        let mut active_exports = constants::ACTIVE_EXPORTS
-            .lock()
-            .map_err(|_| "Failed to lock active exports")?;
-        active_exports.insert(export_id.to_string(), process_ref.clone());
-    }
+    let process_ref = process::track_process(export_id, child);
 
     // État mémoire partagé (utilisé même sans watcher pour éviter des branches)
     let memory_state = memory_monitor
@@ -225,7 +221,7 @@ pub fn run_ffmpeg_command(
     for line in reader.lines() {
         if let Ok(line) = line {
             if !is_ffmpeg_progress_line(&line) {
-                println!("[ffmpeg] {}", line);
+                log::info!("[ffmpeg] {}", line);
             }
 
             stderr_content.push_str(&line);
@@ -243,7 +239,7 @@ pub fn run_ffmpeg_command(
                         0.0
                     };
 
-                    println!(
+                    log::info!(
                         "[progress] {}% ({:.1}s / {:.1}s)",
                         progress.round(),
                         current_time_s,
@@ -288,12 +284,7 @@ pub fn run_ffmpeg_command(
     };
 
     // Nettoyage des ressources
-    {
-        let mut active_exports = constants::ACTIVE_EXPORTS
-            .lock()
-            .map_err(|_| "Failed to lock active exports")?;
-        active_exports.remove(export_id);
-    }
+    process::untrack_process(export_id);
     if let Some(handle) = memory_monitor_handle {
         let _ = handle.join();
     }
@@ -359,14 +350,14 @@ pub fn run_ffmpeg_command(
 
         if let Some(parent) = log_write_path.parent() {
             if let Err(mkdir_err) = fs::create_dir_all(parent) {
-                eprintln!("Failed to create log directory {:?}: {}", parent, mkdir_err);
+                log::warn!("Failed to create log directory {:?}: {}", parent, mkdir_err);
             }
         }
 
         if let Err(log_err) = std::fs::write(&log_write_path, &log_content) {
-            eprintln!("Failed to write log file {:?}: {}", log_write_path, log_err);
+            log::warn!("Failed to write log file {:?}: {}", log_write_path, log_err);
         } else {
-            println!("FFmpeg error details saved to: {}", log_write_path_display);
+            log::info!("FFmpeg error details saved to: {}", log_write_path_display);
         }
 
         let error_msg = format!(
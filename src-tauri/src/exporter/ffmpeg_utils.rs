@@ -1,4 +1,5 @@
 use crate::binaries;
+use crate::utils::process::{output_with_timeout, PROBE_TIMEOUT};
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -21,12 +22,12 @@ pub fn resolve_ffmpeg_binary() -> Option<String> {
     }
 
     // En dernier recours, utiliser ffmpeg du PATH système
-    println!("[ffmpeg] Tentative d'utilisation de ffmpeg du système (PATH)");
+    log::info!("[ffmpeg] Tentative d'utilisation de ffmpeg du système (PATH)");
     let mut cmd = std::process::Command::new("ffmpeg");
     cmd.arg("-version");
     configure_command_no_window(&mut cmd);
     if cmd.output().is_ok() {
-        println!("[ffmpeg] ✓ FFmpeg trouvé dans le PATH système");
+        log::info!("[ffmpeg] ✓ FFmpeg trouvé dans le PATH système");
         return Some("ffmpeg".to_string());
     }
 
@@ -41,12 +42,12 @@ pub fn resolve_ffprobe_binary() -> String {
         return path;
     }
 
-    println!("[ffprobe] Tentative d'utilisation de ffprobe du système (PATH)");
+    log::info!("[ffprobe] Tentative d'utilisation de ffprobe du système (PATH)");
     let mut cmd = std::process::Command::new("ffprobe");
     cmd.arg("-version");
     configure_command_no_window(&mut cmd);
     if cmd.output().is_ok() {
-        println!("[ffprobe] ✓ FFprobe trouvé dans le PATH système");
+        log::info!("[ffprobe] ✓ FFprobe trouvé dans le PATH système");
         return "ffprobe".to_string();
     }
 
@@ -175,7 +176,7 @@ pub fn ffprobe_duration_sec(path: &str) -> f64 {
 
     configure_command_no_window(&mut cmd);
 
-    let output = match cmd.output() {
+    let output = match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
         Ok(output) => output,
         Err(_) => return 0.0,
     };
@@ -213,7 +214,7 @@ pub fn video_has_audio(path: &str) -> bool {
     ]);
     configure_command_no_window(&mut cmd);
 
-    let output = cmd.output();
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT);
 
     match output {
         Ok(out) => !out.stdout.is_empty(),
@@ -52,12 +52,12 @@ fn probe_xfade_backend_available(ffmpeg_exe: &str, backend: XfadeHardwareBackend
 
     if let Ok(cache) = cache.lock() {
         if let Some(available) = cache.get(ffmpeg_exe) {
-            println!("[{}][xfade] disponibilite cachee: {}", name, available);
+            log::info!("[{}][xfade] disponibilite cachee: {}", name, available);
             return *available;
         }
     }
 
-    println!("[{}][xfade] detection avec {}", name, ffmpeg_exe);
+    log::info!("[{}][xfade] detection avec {}", name, ffmpeg_exe);
 
     let mut filters_cmd = Command::new(ffmpeg_exe);
     filters_cmd.args(["-hide_banner", "-filters"]);
@@ -68,7 +68,7 @@ fn probe_xfade_backend_available(ffmpeg_exe: &str, backend: XfadeHardwareBackend
         .unwrap_or(false);
 
     if !has_filter {
-        println!("[{}][xfade] indisponible: filtre {} absent", name, filter);
+        log::info!("[{}][xfade] indisponible: filtre {} absent", name, filter);
         if let Ok(mut cache) = cache.lock() {
             cache.insert(ffmpeg_exe.to_string(), false);
         }
@@ -114,12 +114,12 @@ fn probe_xfade_backend_available(ffmpeg_exe: &str, backend: XfadeHardwareBackend
 
     let available = match test_cmd.output() {
         Ok(output) if output.status.success() => {
-            println!("[{}][xfade] disponible: test chaine RGBA reussi", name);
+            log::info!("[{}][xfade] disponible: test chaine RGBA reussi", name);
             true
         }
         Ok(output) => {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            println!(
+            log::info!(
                 "[{}][xfade] indisponible: test chaine RGBA echoue: {}",
                 name,
                 stderr.trim()
@@ -127,9 +127,10 @@ fn probe_xfade_backend_available(ffmpeg_exe: &str, backend: XfadeHardwareBackend
             false
         }
         Err(error) => {
-            println!(
+            log::info!(
                 "[{}][xfade] indisponible: impossible de lancer le test: {}",
-                name, error
+                name,
+                error
             );
             false
         }
@@ -157,7 +158,7 @@ fn choose_xfade_backend(ffmpeg_exe: &str) -> XfadeHardwareBackend {
         return XfadeHardwareBackend::OpenCl;
     }
 
-    println!("[gpu][xfade] aucun backend GPU utilisable, fallback CPU");
+    log::info!("[gpu][xfade] aucun backend GPU utilisable, fallback CPU");
     XfadeHardwareBackend::Cpu
 }
 
@@ -315,9 +316,10 @@ pub fn build_and_run_ffmpeg_filter_complex(
                 if is_interrupted_error(error.as_ref()) {
                     return Err(error);
                 }
-                println!(
+                log::info!(
                     "[gpu][xfade][warn] rendu {:?} echoue, retry CPU: {}",
-                    xfade_backend, error
+                    xfade_backend,
+                    error
                 );
                 fs::remove_file(out_path).ok();
                 return render_ffmpeg_filter_complex_single(
@@ -356,11 +358,13 @@ pub fn build_and_run_ffmpeg_filter_complex(
         return render_result;
     }
 
-    println!(
+    log::info!(
         "[batching] {} image(s), mode {:?}, limite initiale {}, rendu interne en batchs",
-        n, batch_mode, batch_limit
+        n,
+        batch_mode,
+        batch_limit
     );
-    println!(
+    log::info!(
         "[batching] {} timing(s) blank disponibles pour le rendu",
         blank_timestamps.len()
     );
@@ -462,7 +466,7 @@ pub fn build_and_run_ffmpeg_filter_complex(
         );
         let batch_output = batch_output_path.to_string_lossy().to_string();
 
-        println!(
+        log::info!(
             "[batching] batch {}: images {}..{} adjusted_start={}ms source_start={}ms intended_start={}ms encoded_start={:.6}s start_blank={} end_blank={} start_fade={}ms end_fade={}ms tail={}ms graph_duration={}ms intended_end={}ms encoded_duration={:.6}s output={}",
             batch_index,
             batch_start_idx,
@@ -536,9 +540,11 @@ pub fn build_and_run_ffmpeg_filter_complex(
                     && !memory_error
                     && !is_interrupted_error(error.as_ref())
                 {
-                    println!(
+                    log::info!(
                         "[gpu][xfade][warn] batch {} {:?} echoue, retry CPU: {}",
-                        batch_index, xfade_backend, error
+                        batch_index,
+                        xfade_backend,
+                        error
                     );
                     fs::remove_file(&batch_output).ok();
                     render_ffmpeg_filter_complex_single(
@@ -597,9 +603,10 @@ pub fn build_and_run_ffmpeg_filter_complex(
 
                     auto_batch_limit =
                         (effective_batch_limit / 2).max(constants::FILTERGRAPH_BATCH_MIN);
-                    println!(
+                    log::info!(
                         "[memory][auto-batch] retry batch {} with limit {} after RAM limit",
-                        batch_index, auto_batch_limit
+                        batch_index,
+                        auto_batch_limit
                     );
                     continue;
                 }
@@ -617,14 +624,19 @@ pub fn build_and_run_ffmpeg_filter_complex(
             let next_limit =
                 batching::next_auto_batch_limit_after_success(effective_batch_limit, peak_percent);
             if next_limit != effective_batch_limit {
-                println!(
+                log::info!(
                     "[memory][auto-batch] batch {} peak {:.1}%, next limit {} -> {}",
-                    batch_index, peak_percent, effective_batch_limit, next_limit
+                    batch_index,
+                    peak_percent,
+                    effective_batch_limit,
+                    next_limit
                 );
             } else {
-                println!(
+                log::info!(
                     "[memory][auto-batch] batch {} peak {:.1}%, keeping limit {}",
-                    batch_index, peak_percent, effective_batch_limit
+                    batch_index,
+                    peak_percent,
+                    effective_batch_limit
                 );
             }
             auto_batch_limit = next_limit;
@@ -664,7 +676,7 @@ pub fn build_and_run_ffmpeg_filter_complex(
         app_handle,
     )?;
 
-    println!(
+    log::info!(
         "[batching] Keeping {} internal batch file(s) for debugging",
         batch_paths.len()
     );
@@ -834,7 +846,7 @@ pub fn render_ffmpeg_filter_complex_single(
             XfadeHardwareBackend::OpenCl => ("opencl", "opencl=qc_xfade_ocl:0.0", "qc_xfade_ocl"),
             XfadeHardwareBackend::Cpu => unreachable!(),
         };
-        println!(
+        log::info!(
             "[{}][xfade] active pour ce batch: {} transition(s), format RGBA",
             backend_name,
             n - 1,
@@ -846,9 +858,10 @@ pub fn render_ffmpeg_filter_complex_single(
             device.to_string(),
         ]);
     } else if n > 1 {
-        println!(
+        log::info!(
             "[gpu][xfade] fallback CPU pour ce batch: backend={:?}, fade_s={:.6}",
-            xfade_backend, fade_s
+            xfade_backend,
+            fade_s
         );
     }
 
@@ -865,7 +878,7 @@ pub fn render_ffmpeg_filter_complex_single(
             image_path.clone(),
         ]);
     }
-    println!("[ffmpeg] {} entree(s) PNG loopee(s) pour le batch", n);
+    log::info!("[ffmpeg] {} entree(s) PNG loopee(s) pour le batch", n);
 
     if let Some(thread_cap) = codec::compute_ffmpeg_thread_cap(performance_profile) {
         cmd.extend_from_slice(&["-threads".to_string(), thread_cap.to_string()]);
@@ -1124,7 +1137,7 @@ pub fn render_ffmpeg_filter_complex_single(
     let fg_path = tmp_dir.join(format!("filter-{}.ffgraph", &fg_hash[..8]));
 
     fs::write(&fg_path, &filter_complex)?;
-    println!("[ffmpeg] filter_complex_script -> {:?}", fg_path);
+    log::info!("[ffmpeg] filter_complex_script -> {:?}", fg_path);
 
     let fg_name = fg_path.to_string_lossy().to_string();
 
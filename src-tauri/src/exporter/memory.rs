@@ -81,9 +81,10 @@ pub fn spawn_memory_monitor(
 
             if let Ok(mut process_guard) = process_ref.lock() {
                 if let Some(child) = process_guard.as_mut() {
-                    println!(
+                    log::info!(
                         "[memory][auto-batch] RAM {:.1}% >= {:.1}%, stopping ffmpeg for retry",
-                        used_percent, config.max_used_percent
+                        used_percent,
+                        config.max_used_percent
                     );
                     let _ = child.kill();
                 } else {
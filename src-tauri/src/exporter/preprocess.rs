@@ -171,7 +171,7 @@ pub fn ffmpeg_preprocess_video(
     cmd.extend(params);
     cmd.push(tmp_output);
 
-    println!(
+    log::info!(
         "[preproc] ffmpeg cadrage du fond -> {}",
         Path::new(dst)
             .file_name()
@@ -322,9 +322,10 @@ pub fn create_video_from_image(
 
     cmd.push(tmp_output);
 
-    println!(
+    log::info!(
         "[preproc][IMG] Création vidéo depuis image: {} -> {}",
-        image_path, output_path
+        image_path,
+        output_path
     );
     let progress_context = Some(FfmpegProgressContext {
         base_time_s: 0.0,
@@ -467,7 +468,7 @@ pub fn preprocess_background_videos(
         let must_regenerate = !ffmpeg_utils::is_cached_video_valid(&dst, expected_duration_s);
         if must_regenerate {
             if dst.exists() {
-                println!(
+                log::info!(
                     "[preproc][cache] Fichier invalide détecté, régénération: {}",
                     dst.display()
                 );
@@ -491,10 +492,10 @@ pub fn preprocess_background_videos(
                 app_handle,
             ) {
                 Ok(_) => {
-                    println!("[background] path=preprocessed-generated");
+                    log::info!("[background] path=preprocessed-generated");
                 }
                 Err(e) => {
-                    println!(
+                    log::info!(
                         "[preproc][ERREUR] Impossible de créer la vidéo à partir de l'image: {:?}",
                         e
                     );
@@ -502,7 +503,7 @@ pub fn preprocess_background_videos(
                 }
             }
         } else {
-            println!("[background] path=preprocessed-cache");
+            log::info!("[background] path=preprocessed-cache");
         }
 
         out_paths.push(PreparedBackgroundVideo {
@@ -542,7 +543,7 @@ pub fn preprocess_background_videos(
         let vid_path = &input.path;
         // Ignorer les fichiers vidéo qui n'existent pas (projet ouvert sur une autre machine, etc.)
         if !Path::new(vid_path).exists() {
-            println!("[background] fichier introuvable, ignoré: {}", vid_path);
+            log::info!("[background] fichier introuvable, ignoré: {}", vid_path);
             emit_bg_progress(idx + 1);
             continue;
         }
@@ -649,9 +650,10 @@ pub fn preprocess_background_videos(
             let needed_s = (take_ms as f64 / 1000.0).max(0.001);
             // N'utiliser la voie directe que si la source couvre toute la durée nécessaire
             if available_s + 0.1 >= needed_s {
-                println!(
+                log::info!(
                     "[background] path=direct-single-pass src={} duration={:.3}s",
-                    vid_path, src_duration_s
+                    vid_path,
+                    src_duration_s
                 );
                 out_paths.push(PreparedBackgroundVideo {
                     path: vid_path.to_string(),
@@ -666,14 +668,14 @@ pub fn preprocess_background_videos(
 
         if must_regenerate {
             if dst.exists() {
-                println!(
+                log::info!(
                     "[preproc][cache] Fichier invalide détecté, régénération: {}",
                     dst.display()
                 );
                 fs::remove_file(&dst).ok();
             }
             if prefer_hw && !should_prefer_hw {
-                println!("[preproc] boucle macOS: encodage logiciel du fond");
+                log::info!("[preproc] boucle macOS: encodage logiciel du fond");
             }
 
             match ffmpeg_preprocess_video(
@@ -696,19 +698,19 @@ pub fn preprocess_background_videos(
                 app_handle,
             ) {
                 Ok(_) => {
-                    println!("[background] path=preprocessed-generated");
+                    log::info!("[background] path=preprocessed-generated");
                 }
                 Err(e) => {
-                    println!("[preproc][ERREUR] {:?}", e);
+                    log::info!("[preproc][ERREUR] {:?}", e);
                     if is_loop {
-                        println!("[background] fallback noir: preprocessing loop impossible");
+                        log::info!("[background] fallback noir: preprocessing loop impossible");
                         cum_start = cum_end;
                         emit_bg_progress(idx + 1);
                         continue;
                     }
                     // En cas d'échec, utiliser la vidéo originale comme fallback
                     let fallback_duration_s = (take_ms as f64 / 1000.0).max(0.001);
-                    println!("[background] path=fallback-original normalized=false");
+                    log::info!("[background] path=fallback-original normalized=false");
                     out_paths.push(PreparedBackgroundVideo {
                         path: vid_path.clone(),
                         is_normalized: false,
@@ -725,7 +727,7 @@ pub fn preprocess_background_videos(
                 duration_s: expected_duration_s,
             });
         } else {
-            println!("[background] path=preprocessed-cache");
+            log::info!("[background] path=preprocessed-cache");
             out_paths.push(PreparedBackgroundVideo {
                 path: dst.to_string_lossy().to_string(),
                 is_normalized: true,
@@ -122,6 +122,17 @@ pub struct VideoInput {
     pub loop_until_audio_end: Option<bool>,
 }
 
+/// Entrée d'un fichier ffconcat : média source et bornes de lecture optionnelles.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct FfconcatEntry {
+    /// Chemin vers le fichier média.
+    pub path: String,
+    /// Début de lecture dans le fichier (ms), directive `inpoint`.
+    pub inpoint_ms: Option<i64>,
+    /// Fin de lecture dans le fichier (ms), directive `outpoint`.
+    pub outpoint_ms: Option<i64>,
+}
+
 /// Vidéo de fond prétraitée, prête pour l'overlay final.
 #[derive(Debug, Clone)]
 pub struct PreparedBackgroundVideo {
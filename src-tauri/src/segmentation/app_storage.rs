@@ -0,0 +1,387 @@
+//! Espace disque des données de l'application et nettoyage sélectif des caches.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Manager;
+
+use super::data_files::{required_multi_aligner_data_files, resolve_multi_aligner_data_dir};
+use super::debug_audio::debug_audio_root;
+use super::local::with_engine_idle;
+use super::managed_python::managed_python_root;
+use super::model_cache::{
+    clear_model_cache, hf_hub_cache_dirs, quran_model_repo_dirs, resolve_hf_hub_cache_dir,
+};
+use super::python_env::{get_local_venv_root, get_pip_cache_dir};
+use super::storage::SizeScan;
+use super::types::LocalSegmentationEngine;
+use super::uninstall::{remove_listed_data_files, uninstall_local_segmentation_deps};
+
+/// Budget de parcours disque du rapport complet.
+const APP_STORAGE_SCAN_BUDGET: Duration = Duration::from_secs(30);
+
+/// Catégorie d'espace disque mesurée et nettoyable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AppStorageCategory {
+    /// Environnements Python des moteurs locaux (`python_envs`).
+    Venvs,
+    /// Python autonome téléchargé (`python_runtime`).
+    PythonRuntime,
+    /// Cache pip partagé des installations (`pip_cache`).
+    PipCache,
+    /// Journaux de segmentation et d'export (`logs`).
+    Logs,
+    /// Audio pré-traité conservé par `keep_temp_audio` (`debug_audio`).
+    DebugAudio,
+    /// Fichiers data Multi-Aligner.
+    MultiAlignerData,
+    /// Modèles Quran du cache Hugging Face.
+    ModelCache,
+}
+
+impl AppStorageCategory {
+    const ALL: [Self; 7] = [
+        Self::Venvs,
+        Self::PythonRuntime,
+        Self::PipCache,
+        Self::Logs,
+        Self::DebugAudio,
+        Self::MultiAlignerData,
+        Self::ModelCache,
+    ];
+
+    fn from_raw(raw: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_key() == raw.trim())
+            .ok_or_else(|| {
+                format!(
+                    "Unknown storage category '{}'. Expected one of: {}",
+                    raw,
+                    Self::ALL.map(|category| category.as_key()).join(", ")
+                )
+            })
+    }
+
+    fn as_key(&self) -> &'static str {
+        match self {
+            Self::Venvs => "venvs",
+            Self::PythonRuntime => "pythonRuntime",
+            Self::PipCache => "pipCache",
+            Self::Logs => "logs",
+            Self::DebugAudio => "debugAudio",
+            Self::MultiAlignerData => "multiAlignerData",
+            Self::ModelCache => "modelCache",
+        }
+    }
+}
+
+/// Taille d'une catégorie du rapport de stockage.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppStorageEntry {
+    /// Clé de la catégorie (`venvs`, `pythonRuntime`, `pipCache`, `logs`, `debugAudio`, `multiAlignerData`, `modelCache`).
+    pub category: String,
+    /// Dossier mesuré, s'il a pu être résolu.
+    pub path: Option<String>,
+    /// Autres dossiers inclus dans la taille (cache de modèles par défaut après un
+    /// changement de dossier).
+    pub extra_paths: Vec<String>,
+    /// Taille en octets.
+    pub size_bytes: u64,
+    /// `true` si le parcours a été interrompu (taille sous-estimée).
+    pub approximate: bool,
+}
+
+/// Espace disque occupé par l'application.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppStorageReport {
+    /// Dossier de données de l'application.
+    pub app_data_dir: String,
+    /// Tailles par catégorie.
+    pub entries: Vec<AppStorageEntry>,
+    /// Reste du dossier de données (projets, réglages, ...), jamais nettoyé.
+    pub other_bytes: u64,
+    /// Somme des tailles ci-dessus.
+    pub total_bytes: u64,
+    /// `true` si au moins une taille est sous-estimée.
+    pub approximate: bool,
+}
+
+/// Résultat de `clear_app_cache`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppCacheClearReport {
+    /// Catégories nettoyées.
+    pub cleared: Vec<String>,
+    /// Octets libérés.
+    pub freed_bytes: u64,
+}
+
+/// Dossier de données de l'application.
+fn app_data_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+/// Dossier `logs` des données de l'application.
+fn app_logs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app_handle)?.join("logs"))
+}
+
+/// Mesure une catégorie ; les fichiers data et le cache de modèles ne sont pas des dossiers entiers.
+fn measure_category(
+    app_handle: &tauri::AppHandle,
+    category: AppStorageCategory,
+    scan: &mut SizeScan,
+) -> AppStorageEntry {
+    let mut entry = AppStorageEntry {
+        category: category.as_key().to_string(),
+        path: None,
+        extra_paths: Vec::new(),
+        size_bytes: 0,
+        approximate: false,
+    };
+    let path = match category {
+        AppStorageCategory::Venvs => get_local_venv_root(app_handle).ok(),
+        AppStorageCategory::PythonRuntime => managed_python_root(app_handle).ok(),
+        AppStorageCategory::PipCache => get_pip_cache_dir(app_handle).ok(),
+        AppStorageCategory::Logs => app_logs_dir(app_handle).ok(),
+        AppStorageCategory::DebugAudio => debug_audio_root(app_handle).ok(),
+        AppStorageCategory::MultiAlignerData => resolve_multi_aligner_data_dir(app_handle).ok(),
+        AppStorageCategory::ModelCache => resolve_hf_hub_cache_dir(),
+    };
+    let Some(path) = path else {
+        return entry;
+    };
+    match category {
+        AppStorageCategory::MultiAlignerData => {
+            entry.size_bytes = required_multi_aligner_data_files()
+                .iter()
+                .filter_map(|data_file| fs::metadata(path.join(data_file.name)).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+        }
+        AppStorageCategory::ModelCache => {
+            // `clear_model_cache` vide aussi le cache par défaut : il est compté ici.
+            for cache_dir in hf_hub_cache_dirs() {
+                for (_, repo_dir) in quran_model_repo_dirs(&cache_dir) {
+                    let (size_bytes, approximate) = scan.measure(&repo_dir.join("blobs"));
+                    entry.size_bytes += size_bytes;
+                    entry.approximate |= approximate;
+                }
+                if cache_dir != path {
+                    entry
+                        .extra_paths
+                        .push(cache_dir.to_string_lossy().to_string());
+                }
+            }
+        }
+        _ => {
+            (entry.size_bytes, entry.approximate) = scan.measure(&path);
+        }
+    }
+    entry.path = Some(path.to_string_lossy().to_string());
+    entry
+}
+
+/// Taille des entrées du dossier de données hors dossiers déjà mesurés.
+fn measure_other_entries(app_data_dir: &Path, excluded: &[PathBuf], scan: &mut SizeScan) -> u64 {
+    let Ok(entries) = fs::read_dir(app_data_dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| !excluded.contains(&entry.path()))
+        .filter_map(|entry| {
+            let metadata = fs::symlink_metadata(entry.path()).ok()?;
+            if metadata.is_dir() {
+                Some(scan.measure(&entry.path()).0)
+            } else {
+                metadata.is_file().then_some(metadata.len())
+            }
+        })
+        .sum()
+}
+
+/// Retourne la taille des environnements Python, fichiers data, caches, journaux et audio de
+/// débogage.
+pub async fn get_app_storage_report(
+    app_handle: tauri::AppHandle,
+) -> Result<AppStorageReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_data_dir = app_data_dir(&app_handle)?;
+        let mut scan = SizeScan::new(APP_STORAGE_SCAN_BUDGET);
+        let entries: Vec<AppStorageEntry> = AppStorageCategory::ALL
+            .into_iter()
+            .map(|category| measure_category(&app_handle, category, &mut scan))
+            .collect();
+
+        let excluded: Vec<PathBuf> = entries
+            .iter()
+            .filter_map(|entry| entry.path.as_ref().map(PathBuf::from))
+            .collect();
+        let other_bytes = measure_other_entries(&app_data_dir, &excluded, &mut scan);
+        let total_bytes = entries.iter().map(|entry| entry.size_bytes).sum::<u64>() + other_bytes;
+        Ok(AppStorageReport {
+            app_data_dir: app_data_dir.to_string_lossy().to_string(),
+            entries,
+            other_bytes,
+            total_bytes,
+            approximate: scan.truncated,
+        })
+    })
+    .await
+    .map_err(|e| format!("App storage scan task failed: {}", e))?
+}
+
+/// Supprime un dossier entier et retourne sa taille (0 s'il n'existe pas).
+fn remove_dir_with_size(path: &Path, label: &str) -> Result<u64, String> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let (size_bytes, _) = SizeScan::new(APP_STORAGE_SCAN_BUDGET).measure(path);
+    fs::remove_dir_all(path).map_err(|e| {
+        format!(
+            "Failed to clear {} '{}': {}",
+            label,
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(size_bytes)
+}
+
+/// Vide les catégories demandées ; seules celles listées explicitement sont touchées.
+///
+/// Les venvs sont supprimés moteur par moteur (refusé si une segmentation tourne). Le Python
+/// autonome ne peut être supprimé qu'avec les venvs qui s'appuient dessus.
+///
+/// @param categories - Catégories à vider (`venvs`, `pythonRuntime`, `pipCache`, `logs`, `debugAudio`, `multiAlignerData`, `modelCache`)
+pub async fn clear_app_cache(
+    app_handle: tauri::AppHandle,
+    categories: Vec<String>,
+) -> Result<AppCacheClearReport, String> {
+    let mut selected: Vec<AppStorageCategory> = Vec::new();
+    for raw in &categories {
+        let category = AppStorageCategory::from_raw(raw)?;
+        if !selected.contains(&category) {
+            selected.push(category);
+        }
+    }
+    if selected.is_empty() {
+        return Err("No storage category selected for cleanup".to_string());
+    }
+    if selected.contains(&AppStorageCategory::PythonRuntime)
+        && !selected.contains(&AppStorageCategory::Venvs)
+        && get_local_venv_root(&app_handle)
+            .ok()
+            .and_then(|root| fs::read_dir(root).ok())
+            .is_some_and(|mut entries| entries.next().is_some())
+    {
+        return Err(
+            "The managed Python runtime is used by local environments; clear 'venvs' too"
+                .to_string(),
+        );
+    }
+
+    let mut report = AppCacheClearReport::default();
+    // Venvs d'abord : le Python autonome ne doit disparaître qu'après eux.
+    for category in AppStorageCategory::ALL
+        .into_iter()
+        .filter(|category| selected.contains(category))
+    {
+        let freed_bytes = match category {
+            AppStorageCategory::Venvs => {
+                let mut freed_bytes = 0u64;
+                for engine in LocalSegmentationEngine::ALL {
+                    freed_bytes += uninstall_local_segmentation_deps(
+                        app_handle.clone(),
+                        engine.as_key().to_string(),
+                        false,
+                    )
+                    .await?
+                    .freed_bytes;
+                }
+                freed_bytes
+            }
+            AppStorageCategory::PythonRuntime => {
+                remove_dir_with_size(&managed_python_root(&app_handle)?, "managed Python")?
+            }
+            AppStorageCategory::PipCache => {
+                remove_dir_with_size(&get_pip_cache_dir(&app_handle)?, "pip cache")?
+            }
+            AppStorageCategory::Logs => remove_dir_with_size(&app_logs_dir(&app_handle)?, "logs")?,
+            AppStorageCategory::DebugAudio => {
+                remove_dir_with_size(&debug_audio_root(&app_handle)?, "debug audio")?
+            }
+            AppStorageCategory::MultiAlignerData => {
+                let data_dir = resolve_multi_aligner_data_dir(&app_handle)?;
+                with_engine_idle(LocalSegmentationEngine::MultiAligner, || {
+                    let file_names: Vec<&str> = required_multi_aligner_data_files()
+                        .iter()
+                        .map(|data_file| data_file.name)
+                        .collect();
+                    remove_listed_data_files(&data_dir, &file_names)
+                        .map(|(_, freed_bytes)| freed_bytes)
+                })?
+            }
+            AppStorageCategory::ModelCache => {
+                clear_model_cache(Vec::new(), true).await?.freed_bytes
+            }
+        };
+        report.freed_bytes += freed_bytes;
+        report.cleared.push(category.as_key().to_string());
+    }
+
+    log::info!(
+        "[storage][clear] categories={:?} freed_bytes={}",
+        report.cleared,
+        report.freed_bytes
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure_other_entries, AppStorageCategory, SizeScan};
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn categories_round_trip_and_reject_unknown_keys() {
+        for category in AppStorageCategory::ALL {
+            assert_eq!(
+                AppStorageCategory::from_raw(category.as_key()),
+                Ok(category)
+            );
+        }
+        assert!(AppStorageCategory::from_raw("projects")
+            .unwrap_err()
+            .contains("venvs, pythonRuntime, pipCache"));
+    }
+
+    #[test]
+    fn other_entries_exclude_measured_directories() {
+        let root = std::env::temp_dir().join(format!(
+            "qurancaption-app-storage-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("python_envs")).unwrap();
+        fs::create_dir_all(root.join("projects")).unwrap();
+        fs::write(root.join("python_envs").join("torch.so"), vec![0u8; 100]).unwrap();
+        fs::write(root.join("projects").join("a.json"), vec![0u8; 30]).unwrap();
+        fs::write(root.join("settings.json"), vec![0u8; 5]).unwrap();
+
+        let mut scan = SizeScan::new(Duration::from_secs(30));
+        assert_eq!(
+            measure_other_entries(&root, &[root.join("python_envs")], &mut scan),
+            35
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use tauri::{Emitter, Listener};
+
+use super::fallback::segment_quran_audio_with_fallback;
+use super::local::{
+    segment_quran_audio_local, segment_quran_audio_local_muaalem, segment_quran_audio_local_multi,
+    segment_quran_audio_local_surah_splitter,
+};
+use super::types::{LocalSegmentationEngine, SegmentationBatchItem};
+
+/// Lots dont l'annulation a été demandée (vérifié entre deux éléments).
+static CANCELLED_BATCHES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Moteur utilisé pour tous les éléments d'un lot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BatchEngine {
+    Cloud,
+    Local(LocalSegmentationEngine),
+}
+
+impl BatchEngine {
+    /// Accepte `cloud` ou une clé de moteur local (`legacy`, `multi`, `muaalem`, ...).
+    fn from_raw(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        if raw.eq_ignore_ascii_case("cloud") {
+            return Ok(Self::Cloud);
+        }
+        LocalSegmentationEngine::from_raw(raw).map(Self::Local)
+    }
+}
+
+/// Résultat d'un élément du lot : segmentation réussie ou erreur.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationBatchItemResult {
+    /// Résultat de segmentation (même format que les commandes unitaires).
+    pub result: Option<serde_json::Value>,
+    /// Erreur rencontrée pour cet élément.
+    pub error: Option<String>,
+}
+
+/// Bilan d'un lot de segmentation.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationBatchReport {
+    /// Résultats par identifiant d'élément traité.
+    pub results: HashMap<String, SegmentationBatchItemResult>,
+    /// Éléments non traités suite à une annulation, dans l'ordre du lot.
+    pub skipped: Vec<String>,
+    /// `true` si le lot a été annulé avant la fin.
+    pub cancelled: bool,
+}
+
+/// Vérifie qu'un lot est non vide et que ses identifiants sont renseignés et uniques.
+fn validate_batch_items(items: &[SegmentationBatchItem]) -> Result<(), String> {
+    if items.is_empty() {
+        return Err("Segmentation batch is empty".to_string());
+    }
+    let mut ids = HashSet::new();
+    for item in items {
+        if item.id.trim().is_empty() {
+            return Err("Every batch item needs a non-empty id".to_string());
+        }
+        if !ids.insert(item.id.as_str()) {
+            return Err(format!("Duplicate batch item id '{}'", item.id));
+        }
+    }
+    Ok(())
+}
+
+/// Indique si l'annulation du lot a été demandée.
+fn is_batch_cancelled(batch_id: &str) -> bool {
+    CANCELLED_BATCHES
+        .lock()
+        .map(|cancelled| cancelled.iter().any(|id| id == batch_id))
+        .unwrap_or(false)
+}
+
+/// Retire le marqueur d'annulation d'un lot.
+fn clear_batch_cancelled(batch_id: &str) {
+    if let Ok(mut cancelled) = CANCELLED_BATCHES.lock() {
+        cancelled.retain(|id| id != batch_id);
+    }
+}
+
+/// Lot en cours : retire son marqueur d'annulation à la fin, même si le lot est interrompu
+/// (erreur, future abandonnée).
+struct BatchRunGuard<'a>(&'a str);
+
+impl<'a> BatchRunGuard<'a> {
+    fn start(batch_id: &'a str) -> Self {
+        clear_batch_cancelled(batch_id);
+        Self(batch_id)
+    }
+}
+
+impl Drop for BatchRunGuard<'_> {
+    fn drop(&mut self) {
+        clear_batch_cancelled(self.0);
+    }
+}
+
+/// Demande l'arrêt d'un lot ; l'élément en cours se termine normalement.
+pub fn cancel_segmentation_batch(batch_id: String) -> Result<(), String> {
+    if let Ok(mut cancelled) = CANCELLED_BATCHES.lock() {
+        if !cancelled.contains(&batch_id) {
+            cancelled.push(batch_id);
+        }
+    }
+    Ok(())
+}
+
+/// Émet un événement `segmentation-batch` pour un élément.
+fn emit_batch_item_event(
+    app_handle: &tauri::AppHandle,
+    batch_id: &str,
+    item_id: &str,
+    index: usize,
+    total: usize,
+    status: &str,
+    error: Option<&str>,
+) {
+    let _ = app_handle.emit(
+        "segmentation-batch",
+        serde_json::json!({
+            "batchId": batch_id,
+            "itemId": item_id,
+            "index": index,
+            "total": total,
+            "status": status,
+            "error": error,
+        }),
+    );
+}
+
+/// Segmente un élément du lot avec le moteur choisi.
+async fn run_batch_item(
+    app_handle: tauri::AppHandle,
+    engine: BatchEngine,
+    item: SegmentationBatchItem,
+    hf_token: Option<String>,
+    ignore_memory_check: bool,
+) -> Result<serde_json::Value, String> {
+    let params = item.params;
+    let audio_path = Some(item.audio_path);
+    let ignore_memory_check = Some(ignore_memory_check);
+    match engine {
+        BatchEngine::Cloud => {
+            segment_quran_audio_with_fallback(
+                app_handle,
+                audio_path,
+                None,
+                params.range_start_ms,
+                params.range_end_ms,
+                params.min_confidence,
+                params.mark_low_confidence,
+                params.min_silence_ms,
+                params.min_speech_ms,
+                params.pad_ms,
+                params.model_name,
+                params.device,
+                None,
+                None,
+                params.surah_hint,
+                params.ayah_range_hint,
+                hf_token,
+                false,
+                None,
+                params.preprocess,
+            )
+            .await
+        }
+        BatchEngine::Local(LocalSegmentationEngine::LegacyWhisper) => {
+            segment_quran_audio_local(
+                app_handle,
+                audio_path,
+                None,
+                params.range_start_ms,
+                params.range_end_ms,
+                params.min_confidence,
+                params.mark_low_confidence,
+                params.min_silence_ms,
+                params.min_speech_ms,
+                params.pad_ms,
+                params.model_name,
+                params.surah_hint,
+                params.ayah_range_hint,
+                params.include_wbw_timestamps,
+                params.cpu_threads,
+                params.background,
+                params.timeout_minutes,
+                params.stall_warning_minutes,
+                params.stall_timeout_minutes,
+                ignore_memory_check,
+                None,
+                params.preprocess,
+            )
+            .await
+        }
+        BatchEngine::Local(LocalSegmentationEngine::MultiAligner) => {
+            segment_quran_audio_local_multi(
+                app_handle,
+                audio_path,
+                None,
+                params.range_start_ms,
+                params.range_end_ms,
+                params.min_confidence,
+                params.mark_low_confidence,
+                params.min_silence_ms,
+                params.min_speech_ms,
+                params.pad_ms,
+                params.model_name,
+                params.device,
+                hf_token,
+                params.surah_hint,
+                params.ayah_range_hint,
+                params.include_wbw_timestamps,
+                params.cpu_threads,
+                params.background,
+                params.timeout_minutes,
+                params.stall_warning_minutes,
+                params.stall_timeout_minutes,
+                ignore_memory_check,
+                None,
+                params.preprocess,
+            )
+            .await
+        }
+        BatchEngine::Local(LocalSegmentationEngine::MuaalemLocal) => {
+            segment_quran_audio_local_muaalem(
+                app_handle,
+                audio_path,
+                None,
+                params.range_start_ms,
+                params.range_end_ms,
+                params.min_confidence,
+                params.mark_low_confidence,
+                params.min_silence_ms,
+                params.min_speech_ms,
+                params.pad_ms,
+                params.model_name,
+                params.device,
+                params.include_wbw_timestamps,
+                params.cpu_threads,
+                params.background,
+                params.timeout_minutes,
+                params.stall_warning_minutes,
+                params.stall_timeout_minutes,
+                ignore_memory_check,
+                None,
+                params.preprocess,
+            )
+            .await
+        }
+        BatchEngine::Local(LocalSegmentationEngine::SurahSplitter) => {
+            segment_quran_audio_local_surah_splitter(
+                app_handle,
+                audio_path,
+                None,
+                params.range_start_ms,
+                params.range_end_ms,
+                params.min_confidence,
+                params.mark_low_confidence,
+                params.min_silence_ms,
+                params.min_speech_ms,
+                params.pad_ms,
+                params.model_name,
+                params.device,
+                params.surah_hint,
+                params.include_wbw_timestamps,
+                params.cpu_threads,
+                params.background,
+                params.timeout_minutes,
+                params.stall_warning_minutes,
+                params.stall_timeout_minutes,
+                ignore_memory_check,
+                None,
+                params.preprocess,
+            )
+            .await
+        }
+    }
+}
+
+/// Segmente plusieurs fichiers audio à la suite avec le même moteur.
+///
+/// Chaque élément émet `segmentation-batch` (`started`, `finished` ou `failed`) et ses
+/// événements `segmentation-status` sont relayés sur `segmentation-batch-progress` avec
+/// l'identifiant de l'élément. Sans worker résident (`set_segmentation_worker_mode`),
+/// chaque élément relance Python ; la vérification mémoire n'est faite qu'avant le
+/// premier succès. Une erreur n'arrête pas
+/// le lot ; une annulation l'arrête après l'élément en cours.
+///
+/// @param batch_id - Identifiant du lot (pour `cancel_segmentation_batch`)
+/// @param items - Fichiers à segmenter, traités dans l'ordre
+/// @param engine - `cloud` ou clé d'un moteur local
+/// @param hf_token - Token HF (Multi-Aligner local, Space cloud)
+pub async fn segment_quran_audio_batch(
+    app_handle: tauri::AppHandle,
+    batch_id: String,
+    items: Vec<SegmentationBatchItem>,
+    engine: String,
+    hf_token: Option<String>,
+) -> Result<SegmentationBatchReport, String> {
+    let engine = BatchEngine::from_raw(&engine)?;
+    validate_batch_items(&items)?;
+    let _run_guard = BatchRunGuard::start(&batch_id);
+
+    let total = items.len();
+    let mut report = SegmentationBatchReport {
+        results: HashMap::with_capacity(total),
+        skipped: Vec::new(),
+        cancelled: false,
+    };
+    let mut memory_checked = false;
+
+    for (index, item) in items.into_iter().enumerate() {
+        if report.cancelled || is_batch_cancelled(&batch_id) {
+            report.cancelled = true;
+            report.skipped.push(item.id);
+            continue;
+        }
+
+        let item_id = item.id.clone();
+        emit_batch_item_event(
+            &app_handle,
+            &batch_id,
+            &item_id,
+            index,
+            total,
+            "started",
+            None,
+        );
+
+        let relay_handle = app_handle.clone();
+        let relay_batch_id = batch_id.clone();
+        let relay_item_id = item_id.clone();
+        let listener_id = app_handle.listen("segmentation-status", move |event| {
+            let status = serde_json::from_str::<serde_json::Value>(event.payload())
+                .unwrap_or(serde_json::Value::Null);
+            let _ = relay_handle.emit(
+                "segmentation-batch-progress",
+                serde_json::json!({
+                    "batchId": relay_batch_id,
+                    "itemId": relay_item_id,
+                    "index": index,
+                    "total": total,
+                    "status": status,
+                }),
+            );
+        });
+        let outcome = run_batch_item(
+            app_handle.clone(),
+            engine,
+            item,
+            hf_token.clone(),
+            memory_checked,
+        )
+        .await;
+        app_handle.unlisten(listener_id);
+
+        let item_result = match outcome {
+            Ok(result) => {
+                memory_checked = true;
+                emit_batch_item_event(
+                    &app_handle,
+                    &batch_id,
+                    &item_id,
+                    index,
+                    total,
+                    "finished",
+                    None,
+                );
+                SegmentationBatchItemResult {
+                    result: Some(result),
+                    error: None,
+                }
+            }
+            Err(error) => {
+                log::info!(
+                    "[segmentation][batch] item {} ({}/{}) failed: {}",
+                    item_id,
+                    index + 1,
+                    total,
+                    error
+                );
+                emit_batch_item_event(
+                    &app_handle,
+                    &batch_id,
+                    &item_id,
+                    index,
+                    total,
+                    "failed",
+                    Some(error.as_str()),
+                );
+                SegmentationBatchItemResult {
+                    result: None,
+                    error: Some(error),
+                }
+            }
+        };
+        report.results.insert(item_id, item_result);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cancel_segmentation_batch, is_batch_cancelled, validate_batch_items, BatchEngine,
+        BatchRunGuard,
+    };
+    use crate::segmentation::types::{LocalSegmentationEngine, SegmentationBatchItem};
+
+    fn item(id: &str) -> SegmentationBatchItem {
+        SegmentationBatchItem {
+            id: id.to_string(),
+            audio_path: format!("{}.mp3", id),
+            params: Default::default(),
+        }
+    }
+
+    #[test]
+    fn run_guard_clears_the_cancel_marker_when_dropped() {
+        cancel_segmentation_batch("stale".to_string()).unwrap();
+        {
+            let _guard = BatchRunGuard::start("stale");
+            assert!(!is_batch_cancelled("stale"));
+            cancel_segmentation_batch("stale".to_string()).unwrap();
+            assert!(is_batch_cancelled("stale"));
+        }
+        assert!(!is_batch_cancelled("stale"));
+    }
+
+    #[test]
+    fn batch_items_and_engine_are_validated() {
+        assert!(validate_batch_items(&[]).is_err());
+        assert!(validate_batch_items(&[item("ep1"), item(" ")]).is_err());
+        assert!(
+            validate_batch_items(&[item("ep1"), item("ep2"), item("ep1")])
+                .unwrap_err()
+                .contains("'ep1'")
+        );
+        assert!(validate_batch_items(&[item("ep1"), item("ep2")]).is_ok());
+
+        assert_eq!(BatchEngine::from_raw("Cloud"), Ok(BatchEngine::Cloud));
+        assert_eq!(
+            BatchEngine::from_raw("muaalem"),
+            Ok(BatchEngine::Local(LocalSegmentationEngine::MuaalemLocal))
+        );
+        assert!(BatchEngine::from_raw("whisperx").is_err());
+    }
+}
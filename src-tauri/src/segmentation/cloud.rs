@@ -1,7 +1,8 @@
 use std::cmp::min;
+use std::collections::VecDeque;
 use std::fs;
 use std::process::Command;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use bytes::Bytes;
 use futures_util::{stream, StreamExt};
@@ -9,20 +10,26 @@ use reqwest::multipart::{Form, Part};
 use tauri::Emitter;
 
 use crate::binaries;
+use crate::exporter::ffmpeg_utils::ffprobe_duration_sec;
 use crate::path_utils;
 use crate::utils::process::configure_command_no_window;
 use crate::utils::temp_file::TempFileGuard;
 
 use super::audio_merge::merge_audio_clips_for_segmentation;
+use super::debug_audio::{attach_debug_audio_path, keep_preprocessed_audio};
+use super::mock::mock_segmentation_result;
+use super::preprocess::AudioPreprocess;
+use super::result::{offset_segmentation_payload, validate_segmentation_payload};
 use super::types::{
-    SegmentationAudioClip, QURAN_MULTI_ALIGNER_BASE_URL, QURAN_MULTI_ALIGNER_ESTIMATE_CALL_URL,
-    QURAN_MULTI_ALIGNER_MFA_DIRECT_CALL_URL, QURAN_MULTI_ALIGNER_MFA_SESSION_CALL_URL,
-    QURAN_MULTI_ALIGNER_PRELOAD_AUDIO_CALL_URL,
+    normalize_compute_device, AudioPreprocessOptions, ConfidenceFilter, SegmentationAudioClip,
+    SegmentationHint, SegmentationRange, QURAN_MULTI_ALIGNER_BASE_URL,
+    QURAN_MULTI_ALIGNER_ESTIMATE_CALL_URL, QURAN_MULTI_ALIGNER_MFA_DIRECT_CALL_URL,
+    QURAN_MULTI_ALIGNER_MFA_SESSION_CALL_URL, QURAN_MULTI_ALIGNER_PRELOAD_AUDIO_CALL_URL,
     QURAN_MULTI_ALIGNER_PRELOAD_AUDIO_RECITATIONS_CALL_URL,
     QURAN_MULTI_ALIGNER_PRELOAD_RECITATIONS_CALL_URL,
     QURAN_MULTI_ALIGNER_PRELOAD_SEGMENTS_CALL_URL, QURAN_MULTI_ALIGNER_PROCESS_CALL_URL,
+    QURAN_MULTI_ALIGNER_SPACE_RUNTIME_URL, QURAN_MULTI_ALIGNER_SPACE_URL,
     QURAN_MULTI_ALIGNER_SPLIT_SEGMENTS_CALL_URL, QURAN_MULTI_ALIGNER_UPLOAD_URL,
-    QURAN_SEGMENTATION_MOCK_PAYLOAD, QURAN_SEGMENTATION_USE_MOCK,
 };
 
 /// Émet un état de progression de segmentation vers le frontend.
@@ -32,14 +39,310 @@ fn emit_cloud_status(
     message: String,
     progress: Option<f64>,
 ) {
-    let payload = serde_json::json!({
+    emit_cloud_status_with_details(app_handle, step, message, progress, serde_json::json!({}));
+}
+
+/// Émet un état de progression enrichi de champs additionnels (taille, débit, ...).
+fn emit_cloud_status_with_details(
+    app_handle: &tauri::AppHandle,
+    step: &str,
+    message: String,
+    progress: Option<f64>,
+    details: serde_json::Value,
+) {
+    let mut payload = serde_json::json!({
         "step": step,
         "message": message,
         "progress": progress,
     });
+    if let (Some(target), serde_json::Value::Object(extra)) = (payload.as_object_mut(), details) {
+        target.extend(extra);
+    }
     let _ = app_handle.emit("segmentation-status", payload);
 }
 
+/// Fenêtre glissante utilisée pour mesurer le débit d'upload.
+const UPLOAD_THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Mesure le débit d'upload sur une fenêtre glissante, lissé pour stabiliser l'ETA.
+#[derive(Default)]
+struct UploadThroughputTracker {
+    samples: VecDeque<(Instant, u64)>,
+    smoothed_bytes_per_second: Option<f64>,
+}
+
+impl UploadThroughputTracker {
+    /// Enregistre le nombre total d'octets envoyés à l'instant `now`.
+    fn record(&mut self, sent_bytes: u64, now: Instant) {
+        self.samples.push_back((now, sent_bytes));
+        while self.samples.len() > 2
+            && now.duration_since(self.samples[0].0) > UPLOAD_THROUGHPUT_WINDOW
+        {
+            self.samples.pop_front();
+        }
+
+        let (Some((first_time, first_bytes)), Some((last_time, last_bytes))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return;
+        };
+        let elapsed_s = last_time.duration_since(*first_time).as_secs_f64();
+        // Trop peu de recul au démarrage: pas de débit plutôt qu'une division par ~0.
+        if elapsed_s < 0.5 || last_bytes <= first_bytes {
+            return;
+        }
+
+        let instant_rate = (last_bytes - first_bytes) as f64 / elapsed_s;
+        self.smoothed_bytes_per_second = Some(match self.smoothed_bytes_per_second {
+            Some(previous) => previous * 0.7 + instant_rate * 0.3,
+            None => instant_rate,
+        });
+    }
+
+    /// Retourne le débit lissé en octets/seconde s'il est déjà mesurable.
+    fn bytes_per_second(&self) -> Option<f64> {
+        self.smoothed_bytes_per_second.filter(|rate| *rate > 0.0)
+    }
+}
+
+/// Formate une durée restante en texte court (`45s`, `4 min`).
+fn format_eta(eta_seconds: f64) -> String {
+    if eta_seconds < 60.0 {
+        format!("about {}s", eta_seconds.ceil() as u64)
+    } else {
+        format!("about {} min", (eta_seconds / 60.0).round().max(1.0) as u64)
+    }
+}
+
+/// Suffixe stable d'une erreur cloud sans réponse HTTP (connexion, délai, lecture du flux).
+pub(crate) const CLOUD_NETWORK_ERROR_TAG: &str = "(network error)";
+
+/// Ajoute un statut HTTP à un message d'erreur sous la forme stable `(HTTP 503)`.
+fn with_http_status(message: &str, status: u16) -> String {
+    format!("{} (HTTP {})", message, status)
+}
+
+/// Formate l'erreur d'une requête cloud avec son statut HTTP, ou `(network error)` si
+/// aucune réponse n'a été reçue, pour que le repli local décide sur ce statut.
+fn http_error(context: &str, error: reqwest::Error) -> String {
+    match error.status() {
+        Some(status) => format!("{}: {}", with_http_status(context, status.as_u16()), error),
+        None => format!("{} {}: {}", context, CLOUD_NETWORK_ERROR_TAG, error),
+    }
+}
+
+/// Statut HTTP porté par une erreur cloud formatée par `http_error`.
+pub(crate) fn cloud_error_http_status(error: &str) -> Option<u16> {
+    let (_, rest) = error.split_once("(HTTP ")?;
+    let (status, _) = rest.split_once(')')?;
+    status.trim().parse().ok()
+}
+
+/// Délai maximal d'attente du réveil de la Space Hugging Face.
+const SPACE_WAKEUP_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Intervalle entre deux vérifications de l'état de la Space.
+const SPACE_WAKEUP_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Lit le stage d'exécution de la Space (`RUNNING`, `SLEEPING`, `BUILDING`, ...).
+async fn fetch_space_runtime_stage(client: &reqwest::Client) -> Result<String, String> {
+    let runtime: serde_json::Value = client
+        .get(QURAN_MULTI_ALIGNER_SPACE_RUNTIME_URL)
+        .timeout(Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| http_error("Space runtime request failed", e))?
+        .error_for_status()
+        .map_err(|e| http_error("Space runtime request error", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Space runtime: {}", e))?;
+    runtime
+        .get("stage")
+        .and_then(|stage| stage.as_str())
+        .map(|stage| stage.to_uppercase())
+        .ok_or_else(|| "Space runtime response has no stage".to_string())
+}
+
+/// Vérifie que la Space cloud est démarrée et attend son réveil si elle dormait.
+///
+/// Si l'état n'est pas lisible (API indisponible), on continue sans bloquer:
+/// l'upload échouera de lui-même si la Space est réellement injoignable.
+async fn ensure_cloud_space_awake(
+    app_handle: &tauri::AppHandle,
+    client: &reqwest::Client,
+) -> Result<(), String> {
+    let started_at = Instant::now();
+    let mut wakeup_requested = false;
+    loop {
+        let stage = match fetch_space_runtime_stage(client).await {
+            Ok(stage) => stage,
+            Err(error) => {
+                log::info!(
+                    "[segmentation] Unable to check cloud Space state: {}",
+                    error
+                );
+                return Ok(());
+            }
+        };
+
+        match stage.as_str() {
+            "RUNNING" => {
+                if wakeup_requested {
+                    emit_cloud_status(
+                        app_handle,
+                        "cloud_wakeup",
+                        "Cloud server is awake.".to_string(),
+                        Some(100.0),
+                    );
+                }
+                return Ok(());
+            }
+            "SLEEPING" | "BUILDING" | "APP_STARTING" | "RUNNING_BUILDING" | "STARTING" => {}
+            // Une Space arrêtée ou en erreur répond 503 : même statut pour le repli local.
+            "RUNTIME_ERROR" | "BUILD_ERROR" | "CONFIG_ERROR" => {
+                return Err(with_http_status(
+                    &format!(
+                        "Cloud segmentation server is unavailable ({}). Please try again later or switch to local segmentation.",
+                        stage
+                    ),
+                    503,
+                ));
+            }
+            "PAUSED" | "STOPPED" => {
+                return Err(with_http_status(
+                    &format!(
+                        "Cloud segmentation server is paused ({}). Please switch to local segmentation.",
+                        stage
+                    ),
+                    503,
+                ));
+            }
+            _ => {
+                log::info!("[segmentation] Unknown cloud Space stage '{}'", stage);
+                return Ok(());
+            }
+        }
+
+        if !wakeup_requested {
+            // Une simple requête sur la racine déclenche le réveil d'une Space endormie.
+            let _ = client
+                .get(QURAN_MULTI_ALIGNER_SPACE_URL)
+                .timeout(Duration::from_secs(15))
+                .send()
+                .await;
+            wakeup_requested = true;
+        }
+
+        let elapsed = started_at.elapsed();
+        if elapsed >= SPACE_WAKEUP_TIMEOUT {
+            return Err(with_http_status(
+                &format!(
+                    "Cloud server did not wake up after {} minutes (stage: {}). Please try again later or switch to local segmentation.",
+                    SPACE_WAKEUP_TIMEOUT.as_secs() / 60,
+                    stage
+                ),
+                503,
+            ));
+        }
+        emit_cloud_status_with_details(
+            app_handle,
+            "cloud_wakeup",
+            "Cloud server is waking up, this can take 2-3 minutes...".to_string(),
+            None,
+            serde_json::json!({
+                "stage": stage,
+                "elapsedSeconds": elapsed.as_secs(),
+            }),
+        );
+        tokio::time::sleep(SPACE_WAKEUP_POLL_INTERVAL).await;
+    }
+}
+
+/// Latence au-delà de laquelle une réponse de la Space suggère un démarrage à froid.
+const CLOUD_COLD_START_LATENCY: Duration = Duration::from_secs(5);
+/// Délai maximal d'un test de connectivité cloud.
+const CLOUD_PING_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Résultat d'un test de connectivité vers l'API Gradio cloud.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudPingResult {
+    /// `true` si le serveur a répondu sans erreur 5xx.
+    pub reachable: bool,
+    /// Code HTTP reçu, `None` si aucune réponse.
+    pub status_code: Option<u16>,
+    /// Durée de la requête (ms).
+    pub latency_ms: u64,
+    /// `true` si la Space semble endormie ou en cours de réveil.
+    pub cold_start_likely: bool,
+    /// Erreur réseau éventuelle.
+    pub error: Option<String>,
+}
+
+/// Déduit un démarrage à froid probable : réponse lente, passerelle HF en attente ou délai dépassé.
+fn is_cold_start_likely(status_code: Option<u16>, latency: Duration, timed_out: bool) -> bool {
+    timed_out
+        || matches!(status_code, Some(502..=504))
+        || (status_code.is_some() && latency >= CLOUD_COLD_START_LATENCY)
+}
+
+/// Teste rapidement la connectivité de l'API Gradio cloud avant un long upload.
+///
+/// @param base_url - Racine Gradio à tester (par défaut celle de Quran Multi-Aligner)
+pub async fn ping_segmentation_cloud(base_url: Option<String>) -> Result<CloudPingResult, String> {
+    let base_url = base_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| QURAN_MULTI_ALIGNER_BASE_URL.to_string());
+    let parsed = reqwest::Url::parse(&base_url)
+        .map_err(|e| format!("Invalid cloud base URL '{}': {}", base_url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Invalid cloud base URL '{}': only http(s) URLs are supported",
+            base_url
+        ));
+    }
+    let client = reqwest::Client::builder()
+        .connect_timeout(CLOUD_PING_TIMEOUT)
+        .timeout(CLOUD_PING_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let started_at = Instant::now();
+    let response = client.get(parsed).send().await;
+    let latency = started_at.elapsed();
+    let result = match response {
+        Ok(response) => {
+            let status_code = response.status().as_u16();
+            CloudPingResult {
+                reachable: !response.status().is_server_error(),
+                status_code: Some(status_code),
+                latency_ms: latency.as_millis() as u64,
+                cold_start_likely: is_cold_start_likely(Some(status_code), latency, false),
+                error: None,
+            }
+        }
+        Err(error) => CloudPingResult {
+            reachable: false,
+            status_code: None,
+            latency_ms: latency.as_millis() as u64,
+            cold_start_likely: is_cold_start_likely(None, latency, error.is_timeout()),
+            error: Some(error.to_string()),
+        },
+    };
+    log::info!(
+        "[segmentation] cloud ping {} -> reachable={} status={:?} latency={}ms",
+        base_url,
+        result.reachable,
+        result.status_code,
+        result.latency_ms
+    );
+    Ok(result)
+}
+
+/// Callback recevant les événements SSE intermédiaires (nom d'événement, payload).
+type SseIntermediateCallback = Box<dyn FnMut(&str, &serde_json::Value) + Send>;
+
 /// Maintient l'état d'analyse d'un flux SSE Gradio et extrait le payload final.
 #[derive(Default)]
 struct SseAccumulator {
@@ -47,9 +350,78 @@ struct SseAccumulator {
     current_data: String,
     latest_payload: Option<serde_json::Value>,
     complete_payload: Option<serde_json::Value>,
+    on_intermediate: Option<SseIntermediateCallback>,
+}
+
+/// Progression intermédiaire extraite d'un événement `generating` de l'aligneur.
+#[derive(Debug, PartialEq)]
+struct CloudStreamProgress {
+    /// Pourcentage 0-100, si l'aligneur en fournit un.
+    progress: Option<f64>,
+    /// Message d'étape éventuel (`stage`, `message`, ...).
+    message: Option<String>,
+}
+
+/// Lit un champ numérique parmi plusieurs noms possibles.
+fn read_number_field(payload: &serde_json::Value, keys: &[&str]) -> Option<f64> {
+    keys.iter()
+        .find_map(|key| payload.get(*key).and_then(|value| value.as_f64()))
+        .filter(|value| value.is_finite())
+}
+
+/// Traduit un payload intermédiaire de l'aligneur en progression exploitable.
+///
+/// Sont reconnus: `percent` (0-100), `progress` (fraction 0-1 ou pourcentage),
+/// `chunk_index` (0-based) + `total_chunks`, et un message `message`/`stage`/`status`.
+fn extract_stream_progress(payload: &serde_json::Value) -> Option<CloudStreamProgress> {
+    // Gradio encapsule les sorties dans un tableau.
+    let payload = match payload {
+        serde_json::Value::Array(values) => values.first()?,
+        other => other,
+    };
+    if !payload.is_object() {
+        return None;
+    }
+
+    let progress = read_number_field(payload, &["percent", "progress_percent"])
+        .or_else(|| {
+            read_number_field(payload, &["progress"]).map(|value| {
+                if value <= 1.0 {
+                    value * 100.0
+                } else {
+                    value
+                }
+            })
+        })
+        .or_else(|| {
+            let index = read_number_field(payload, &["chunk_index", "current_chunk"])?;
+            let total = read_number_field(payload, &["total_chunks", "num_chunks"])?;
+            (total > 0.0).then(|| (index + 1.0) / total * 100.0)
+        })
+        .map(|value| value.clamp(0.0, 100.0));
+    let message = ["message", "stage", "status"]
+        .iter()
+        .find_map(|key| payload.get(*key).and_then(|value| value.as_str()))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    if progress.is_none() && message.is_none() {
+        return None;
+    }
+    Some(CloudStreamProgress { progress, message })
 }
 
 impl SseAccumulator {
+    /// Crée un accumulateur qui transmet les événements non terminaux à `callback`.
+    fn with_intermediate_callback(
+        callback: impl FnMut(&str, &serde_json::Value) + Send + 'static,
+    ) -> Self {
+        Self {
+            on_intermediate: Some(Box::new(callback)),
+            ..Self::default()
+        }
+    }
+
     /// Ingère une ligne SSE; renvoie `Some(payload)` dès qu'un événement `complete` est reçu.
     fn push_line(&mut self, line: &str) -> Result<Option<serde_json::Value>, String> {
         let line = line.trim_end_matches('\r');
@@ -103,6 +475,9 @@ impl SseAccumulator {
                 self.current_data.clear();
                 return Ok(Some(payload));
             }
+            if let Some(callback) = self.on_intermediate.as_mut() {
+                callback(&self.current_event, &payload);
+            }
         }
 
         self.current_event.clear();
@@ -117,10 +492,27 @@ impl SseAccumulator {
         }
         self.complete_payload
             .or(self.latest_payload)
-            .ok_or_else(|| "Process stream ended without a result".to_string())
+            // Flux coupé avant tout résultat : traité comme une coupure réseau.
+            .ok_or_else(|| {
+                format!(
+                    "Process stream ended without a result {}",
+                    CLOUD_NETWORK_ERROR_TAG
+                )
+            })
     }
 }
 
+/// Valide le device transmis au Space : `MPS` (choix local sur Mac) y équivaut à `GPU`.
+fn resolve_cloud_device(device: Option<String>) -> Result<String, String> {
+    normalize_compute_device(device, true).map(|device| {
+        if device == "MPS" {
+            "GPU".to_string()
+        } else {
+            device
+        }
+    })
+}
+
 /// Estime la durée de traitement de l'endpoint Multi-Aligner côté cloud.
 pub async fn estimate_duration(
     endpoint: String,
@@ -136,13 +528,7 @@ pub async fn estimate_duration(
         ));
     }
 
-    let selected_device = device.unwrap_or_else(|| "GPU".to_string()).to_uppercase();
-    if selected_device != "GPU" && selected_device != "CPU" {
-        return Err(format!(
-            "Invalid device '{}'. Expected 'GPU' or 'CPU'.",
-            selected_device
-        ));
-    }
+    let selected_device = resolve_cloud_device(device)?;
 
     if !audio_duration_s.is_finite() || audio_duration_s <= 0.0 {
         return Err("audio_duration_s must be a positive finite number.".to_string());
@@ -167,9 +553,9 @@ pub async fn estimate_duration(
         .json(&call_payload)
         .send()
         .await
-        .map_err(|e| format!("Estimate call failed: {}", e))?
+        .map_err(|e| http_error("Estimate call failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Estimate call error: {}", e))?;
+        .map_err(|e| http_error("Estimate call error", e))?;
     let call_json: serde_json::Value = call_response
         .json()
         .await
@@ -188,9 +574,9 @@ pub async fn estimate_duration(
         .get(&stream_url)
         .send()
         .await
-        .map_err(|e| format!("Estimate stream request failed: {}", e))?
+        .map_err(|e| http_error("Estimate stream request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Estimate stream request error: {}", e))?;
+        .map_err(|e| http_error("Estimate stream request error", e))?;
 
     let mut sse_parser = SseAccumulator::default();
     let mut buffered_bytes: Vec<u8> = Vec::new();
@@ -198,7 +584,7 @@ pub async fn estimate_duration(
     let mut stream = stream_response.bytes_stream();
 
     'stream_loop: while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Failed to read estimate stream: {}", e))?;
+        let chunk = chunk_result.map_err(|e| http_error("Failed to read estimate stream", e))?;
         if chunk.is_empty() {
             continue;
         }
@@ -265,9 +651,9 @@ async fn upload_audio_file(
         .multipart(upload_form)
         .send()
         .await
-        .map_err(|e| format!("Upload request failed: {}", e))?
+        .map_err(|e| http_error("Upload request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Upload request error: {}", e))?;
+        .map_err(|e| http_error("Upload request error", e))?;
 
     let uploaded_paths: Vec<String> = upload_response
         .json()
@@ -293,9 +679,9 @@ async fn call_gradio_endpoint(
         .json(&call_payload)
         .send()
         .await
-        .map_err(|e| format!("Endpoint call failed: {}", e))?
+        .map_err(|e| http_error("Endpoint call failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Endpoint call error: {}", e))?;
+        .map_err(|e| http_error("Endpoint call error", e))?;
     let call_json: serde_json::Value = call_response
         .json()
         .await
@@ -314,9 +700,9 @@ async fn call_gradio_endpoint(
         .get(&stream_url)
         .send()
         .await
-        .map_err(|e| format!("Endpoint stream request failed: {}", e))?
+        .map_err(|e| http_error("Endpoint stream request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Endpoint stream request error: {}", e))?;
+        .map_err(|e| http_error("Endpoint stream request error", e))?;
 
     let mut sse_parser = SseAccumulator::default();
     let mut buffered_bytes: Vec<u8> = Vec::new();
@@ -324,7 +710,7 @@ async fn call_gradio_endpoint(
     let mut stream = stream_response.bytes_stream();
 
     'stream_loop: while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Failed to read endpoint stream: {}", e))?;
+        let chunk = chunk_result.map_err(|e| http_error("Failed to read endpoint stream", e))?;
         if chunk.is_empty() {
             continue;
         }
@@ -637,15 +1023,28 @@ pub async fn segment_quran_audio(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
+    upload_bitrate_kbps: Option<u32>,
+    upload_sample_rate: Option<u32>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
-    if QURAN_SEGMENTATION_USE_MOCK {
-        return serde_json::from_str(QURAN_SEGMENTATION_MOCK_PAYLOAD)
-            .map_err(|e| format!("Mock segmentation JSON invalid: {}", e));
+    let segmentation_hint = SegmentationHint::from_raw(surah_hint, ayah_range_hint)?;
+    let range = SegmentationRange::from_raw(range_start_ms, range_end_ms)?;
+    let confidence_filter = ConfidenceFilter::from_raw(min_confidence, mark_low_confidence)?;
+    let preprocess = AudioPreprocess::cloud(preprocess, upload_bitrate_kbps, upload_sample_rate)?;
+    if let Some(mock_result) = mock_segmentation_result(&app_handle, "cloud") {
+        return mock_result;
     }
 
     emit_cloud_status(
@@ -660,21 +1059,24 @@ pub async fn segment_quran_audio(
         binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
     let mut _merged_guard: Option<TempFileGuard> = None;
     let audio_path = if let Some(clips) = audio_clips.as_ref().filter(|c| !c.is_empty()) {
-        println!(
+        log::info!(
             "[segmentation] Merging {} audio clip(s) for cloud segmentation",
             clips.len()
         );
         for (idx, clip) in clips.iter().enumerate() {
-            println!(
+            log::info!(
                 "[segmentation] clip[{}] path={} start_ms={} end_ms={}",
-                idx, clip.path, clip.start_ms, clip.end_ms
+                idx,
+                clip.path,
+                clip.start_ms,
+                clip.end_ms
             );
         }
         let needs_merge = clips.len() > 1 || clips[0].start_ms > 0;
         if needs_merge {
             let (merged_path, guard) = merge_audio_clips_for_segmentation(&ffmpeg_path, clips)?;
             _merged_guard = Some(guard);
-            println!(
+            log::info!(
                 "[segmentation] Using merged audio for cloud: {}",
                 merged_path.to_string_lossy()
             );
@@ -701,22 +1103,17 @@ pub async fn segment_quran_audio(
     let _temp_guard = TempFileGuard(temp_path.clone());
 
     let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
-        "-y",
-        "-hide_banner",
-        "-loglevel",
-        "error",
-        "-i",
-        &audio_path_str,
-        "-c:a",
-        "libopus",
-        "-b:a",
-        "64k",
-        "-vbr",
-        "on",
-        "-vn",
-        temp_path.to_string_lossy().as_ref(),
-    ]);
+    cmd.args(["-y", "-hide_banner", "-loglevel", "error"]);
+    if let Some(range) = range {
+        cmd.args(range.to_ffmpeg_input_args());
+    }
+    cmd.arg("-i").arg(&audio_path_str);
+    if let Some(range) = range {
+        cmd.args(range.to_ffmpeg_output_args());
+    }
+    // Qualité d'upload : 64k sans resample ni downmix par défaut (comportement historique).
+    cmd.args(preprocess.cloud_ffmpeg_args());
+    cmd.args(["-vn", temp_path.to_string_lossy().as_ref()]);
     configure_command_no_window(&mut cmd);
     let output = cmd
         .output()
@@ -725,13 +1122,17 @@ pub async fn segment_quran_audio(
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(format!("ffmpeg error: {}", stderr));
     }
-    emit_cloud_status(
-        &app_handle,
-        "cloud_prepare",
-        "Audio prepared. Starting upload...".to_string(),
-        Some(0.0),
-    );
+    let debug_audio_path = if keep_temp_audio.unwrap_or(false) {
+        keep_preprocessed_audio(&app_handle, &temp_path)
+    } else {
+        None
+    };
 
+    let audio_duration_s =
+        Some(ffprobe_duration_sec(&temp_path.to_string_lossy())).filter(|duration| *duration > 0.0);
+    if range.is_some() && audio_duration_s.is_none() {
+        return Err("Selected range is empty or beyond the end of the audio".to_string());
+    }
     let audio_bytes =
         fs::read(&temp_path).map_err(|e| format!("Failed to read OGG audio: {}", e))?;
     let total_bytes = audio_bytes.len() as u64;
@@ -739,47 +1140,78 @@ pub async fn segment_quran_audio(
         return Err("Cloud upload payload is empty after preprocessing".to_string());
     }
     let total_mb = total_bytes as f64 / (1024.0 * 1024.0);
-    emit_cloud_status(
+    emit_cloud_status_with_details(
         &app_handle,
-        "cloud_upload",
-        format!("Uploading {:.1} MB to cloud...", total_mb),
+        "cloud_prepare",
+        format!(
+            "Audio prepared ({:.1} MB at {} kbps). Starting upload...",
+            total_mb, preprocess.bitrate_kbps
+        ),
         Some(0.0),
+        serde_json::json!({
+            "payloadBytes": total_bytes,
+            "uploadBitrateKbps": preprocess.bitrate_kbps,
+            "uploadSampleRate": preprocess.sample_rate,
+            "uploadMono": preprocess.mono,
+        }),
     );
 
     let client = reqwest::Client::builder()
         .connect_timeout(Duration::from_secs(20))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    ensure_cloud_space_awake(&app_handle, &client).await?;
+
+    emit_cloud_status(
+        &app_handle,
+        "cloud_upload",
+        format!("Uploading {:.1} MB to cloud...", total_mb),
+        Some(0.0),
+    );
     let bytes = Bytes::from(audio_bytes);
     let upload_chunk_size: usize = 256 * 1024;
     let upload_app_handle = app_handle.clone();
-    let upload_stream = stream::unfold((bytes, 0usize, 0u64), move |state| {
+    let upload_state = (bytes, 0usize, 0u64, UploadThroughputTracker::default());
+    let upload_stream = stream::unfold(upload_state, move |state| {
         let app_handle = upload_app_handle.clone();
         async move {
-            let (bytes, offset, last_percent) = state;
+            let (bytes, offset, last_percent, mut throughput) = state;
             if offset >= bytes.len() {
                 return None;
             }
 
+            // Le chunk précédent a été consommé par reqwest: `offset` octets sont partis.
+            throughput.record(offset as u64, Instant::now());
             let end = min(offset + upload_chunk_size, bytes.len());
             let chunk = bytes.slice(offset..end);
             let percent = ((end as f64 / bytes.len() as f64) * 100.0).min(100.0);
             let rounded_percent = percent.floor() as u64;
             if rounded_percent > last_percent {
-                emit_cloud_status(
+                let bytes_per_second = throughput.bytes_per_second();
+                let eta_seconds = bytes_per_second
+                    .map(|rate| (bytes.len() - offset) as f64 / rate)
+                    .filter(|eta| eta.is_finite());
+                let eta_suffix = eta_seconds
+                    .map(|eta| format!(" ({} remaining)", format_eta(eta)))
+                    .unwrap_or_default();
+                emit_cloud_status_with_details(
                     &app_handle,
                     "cloud_upload",
                     format!(
-                        "Uploading {:.1} MB to cloud... {}%",
-                        total_mb, rounded_percent
+                        "Uploading {:.1} MB to cloud... {}%{}",
+                        total_mb, rounded_percent, eta_suffix
                     ),
                     Some(percent),
+                    serde_json::json!({
+                        "bytes_per_second": bytes_per_second,
+                        "eta_seconds": eta_seconds.map(|eta| eta.round()),
+                    }),
                 );
             }
 
             Some((
                 Ok::<Bytes, std::io::Error>(chunk),
-                (bytes, end, rounded_percent.max(last_percent)),
+                (bytes, end, rounded_percent.max(last_percent), throughput),
             ))
         }
     });
@@ -795,9 +1227,9 @@ pub async fn segment_quran_audio(
         .multipart(upload_form)
         .send()
         .await
-        .map_err(|e| format!("Upload request failed: {}", e))?
+        .map_err(|e| http_error("Upload request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Upload request error: {}", e))?;
+        .map_err(|e| http_error("Upload request error", e))?;
     emit_cloud_status(
         &app_handle,
         "cloud_upload",
@@ -821,13 +1253,7 @@ pub async fn segment_quran_audio(
         ));
     }
 
-    let selected_device = device.unwrap_or_else(|| "GPU".to_string()).to_uppercase();
-    if selected_device != "GPU" && selected_device != "CPU" {
-        return Err(format!(
-            "Invalid device '{}'. Expected 'GPU' or 'CPU'.",
-            selected_device
-        ));
-    }
+    let selected_device = resolve_cloud_device(device)?;
 
     let file_payload = serde_json::json!({
         "path": uploaded_path,
@@ -835,25 +1261,30 @@ pub async fn segment_quran_audio(
         "mime_type": "audio/ogg",
         "meta": { "_type": "gradio.FileData" }
     });
-    let call_payload = serde_json::json!({
-        "data": [
-            file_payload,
-            min_silence_ms.unwrap_or(200),
-            min_speech_ms.unwrap_or(1000),
-            pad_ms.unwrap_or(100),
-            selected_model,
-            selected_device
-        ]
-    });
+    let mut call_data = vec![
+        file_payload,
+        serde_json::json!(min_silence_ms.unwrap_or(200)),
+        serde_json::json!(min_speech_ms.unwrap_or(1000)),
+        serde_json::json!(pad_ms.unwrap_or(100)),
+        serde_json::json!(selected_model),
+        serde_json::json!(selected_device),
+    ];
+    // Les indices ne sont ajoutés que s'ils sont fournis pour garder l'appel historique inchangé.
+    if let Some(hint) = segmentation_hint {
+        call_data.push(serde_json::json!(hint.surah));
+        call_data.push(serde_json::json!(hint.ayah_range.map(|(from, _)| from)));
+        call_data.push(serde_json::json!(hint.ayah_range.map(|(_, to)| to)));
+    }
+    let call_payload = serde_json::json!({ "data": call_data });
 
     let call_response = client
         .post(QURAN_MULTI_ALIGNER_PROCESS_CALL_URL)
         .json(&call_payload)
         .send()
         .await
-        .map_err(|e| format!("Process call failed: {}", e))?
+        .map_err(|e| http_error("Process call failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Process call error: {}", e))?;
+        .map_err(|e| http_error("Process call error", e))?;
     emit_cloud_status(
         &app_handle,
         "cloud_process",
@@ -878,17 +1309,35 @@ pub async fn segment_quran_audio(
         .get(&stream_url)
         .send()
         .await
-        .map_err(|e| format!("Process stream request failed: {}", e))?
+        .map_err(|e| http_error("Process stream request failed", e))?
         .error_for_status()
-        .map_err(|e| format!("Process stream request error: {}", e))?;
-
-    let mut sse_parser = SseAccumulator::default();
+        .map_err(|e| http_error("Process stream request error", e))?;
+
+    let progress_app_handle = app_handle.clone();
+    let mut sse_parser =
+        SseAccumulator::with_intermediate_callback(move |_event, payload: &serde_json::Value| {
+            let Some(update) = extract_stream_progress(payload) else {
+                return;
+            };
+            let message = match (update.message, update.progress) {
+                (Some(message), Some(progress)) => format!("{} ({:.0}%)", message, progress),
+                (Some(message), None) => message,
+                (None, Some(progress)) => format!("Processing on cloud... {:.0}%", progress),
+                (None, None) => return,
+            };
+            emit_cloud_status(
+                &progress_app_handle,
+                "cloud_process",
+                message,
+                update.progress,
+            );
+        });
     let mut buffered_bytes: Vec<u8> = Vec::new();
     let mut completed_payload: Option<serde_json::Value> = None;
     let mut stream = stream_response.bytes_stream();
 
     'stream_loop: while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| format!("Failed to read process stream: {}", e))?;
+        let chunk = chunk_result.map_err(|e| http_error("Failed to read process stream", e))?;
         if chunk.is_empty() {
             continue;
         }
@@ -944,7 +1393,7 @@ pub async fn segment_quran_audio(
         .and_then(|value| value.as_str())
         .filter(|value| !value.trim().is_empty())
         .map(|value| value.to_string());
-    if let Some(audio_id) = audio_id {
+    let result = if let Some(audio_id) = audio_id {
         emit_cloud_status(
             &app_handle,
             "cloud_split",
@@ -972,8 +1421,98 @@ pub async fn segment_quran_audio(
             "One-verse recompute completed.".to_string(),
             Some(100.0),
         );
-        return Ok(split_payload);
+        split_payload
+    } else {
+        payload
+    };
+
+    let mut result = validate_segmentation_payload(result, audio_duration_s, confidence_filter);
+    if let Some(range) = range {
+        offset_segmentation_payload(&mut result, range);
+        range.attach_to_result(&mut result);
     }
+    if let Some(hint) = segmentation_hint {
+        hint.attach_to_result(&mut result);
+    }
+    attach_debug_audio_path(&mut result, debug_audio_path.as_deref());
+    Ok(result)
+}
 
-    Ok(payload)
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use std::time::Duration;
+
+    use super::{
+        extract_stream_progress, is_cold_start_likely, CloudStreamProgress, SseAccumulator,
+    };
+
+    /// Transcript SSE enregistré (heartbeat, progression par chunk, étape, résultat final).
+    const RECORDED_PROCESS_STREAM: &str = "event: heartbeat\r\ndata: null\r\n\r\n\
+event: generating\r\ndata: [{\"stage\": \"Running VAD\"}]\r\n\r\n\
+event: generating\r\ndata: [{\"chunk_index\": 0, \"total_chunks\": 4}]\r\n\r\n\
+event: generating\r\ndata: [{\"message\": \"Aligning\", \"progress\": 0.75}]\r\n\r\n\
+event: complete\r\ndata: [{\"segments\": [], \"audio_id\": \"abc\"}]\r\n\r\n";
+
+    #[test]
+    fn recorded_stream_produces_intermediate_statuses_then_result() {
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&statuses);
+        let mut parser = SseAccumulator::with_intermediate_callback(move |event, payload| {
+            assert_eq!(event, "generating");
+            if let Some(progress) = extract_stream_progress(payload) {
+                recorded.lock().unwrap().push(progress);
+            }
+        });
+
+        let mut completed = None;
+        for line in RECORDED_PROCESS_STREAM.split('\n') {
+            if let Some(payload) = parser.push_line(line).unwrap() {
+                completed = Some(payload);
+                break;
+            }
+        }
+
+        assert_eq!(
+            *statuses.lock().unwrap(),
+            vec![
+                CloudStreamProgress {
+                    progress: None,
+                    message: Some("Running VAD".to_string()),
+                },
+                CloudStreamProgress {
+                    progress: Some(25.0),
+                    message: None,
+                },
+                CloudStreamProgress {
+                    progress: Some(75.0),
+                    message: Some("Aligning".to_string()),
+                },
+            ]
+        );
+        assert_eq!(completed.unwrap()[0]["audio_id"], "abc");
+    }
+
+    #[test]
+    fn stream_error_event_is_reported() {
+        let mut parser = SseAccumulator::default();
+        parser.push_line("event: error").unwrap();
+        parser
+            .push_line("data: {\"error\": \"GPU quota exceeded\"}")
+            .unwrap();
+        let error = parser.push_line("").unwrap_err();
+        assert!(error.contains("GPU quota exceeded"));
+    }
+
+    #[test]
+    fn cold_start_is_inferred_from_status_latency_and_timeouts() {
+        let fast = Duration::from_millis(300);
+        let slow = Duration::from_secs(8);
+        assert!(!is_cold_start_likely(Some(200), fast, false));
+        assert!(is_cold_start_likely(Some(200), slow, false));
+        assert!(is_cold_start_likely(Some(503), fast, false));
+        assert!(is_cold_start_likely(None, slow, true));
+        assert!(!is_cold_start_likely(None, fast, false));
+    }
 }
@@ -1,37 +1,147 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::commands::files::file_sha256;
+use crate::utils::file_head::read_file_head;
 
 use super::python_env::resolve_python_resource_path;
 
 const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+/// Segment des URL pointant sur le tag des fichiers data.
+const DATA_FILES_TAG_SEGMENT: &str = "/multi-aligner-data-v1/";
+/// Segment des URL de la branche `main`, dernier recours si le tag ou sa release manquent.
+const DATA_FILES_BRANCH_SEGMENT: &str = "/main/";
+
+/// Fichier data Multi-Aligner obligatoire et son empreinte attendue.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MultiAlignerDataFile {
+    /// Nom du fichier dans le dossier `data`.
+    pub name: &'static str,
+    /// URL de téléchargement.
+    pub url: &'static str,
+    /// URL de repli, essayée si `url` échoue ou livre un fichier invalide.
+    ///
+    /// Asset de release GitHub : servi hors du stockage LFS, il reste joignable quand le
+    /// quota ou le serveur LFS bloque `url`.
+    pub mirror_url: Option<&'static str>,
+    /// Taille attendue en octets.
+    pub size_bytes: u64,
+    /// Empreinte SHA-256 attendue (hexadécimal).
+    pub sha256: &'static str,
+}
 
-const MULTI_ALIGNER_DATA_FILES: [(&str, &str); 6] = [
-    (
-        "phoneme_cache.pkl",
-        "https://media.githubusercontent.com/media/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/phoneme_cache.pkl",
-    ),
-    (
-        "phoneme_ngram_index_5.pkl",
-        "https://media.githubusercontent.com/media/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/phoneme_ngram_index_5.pkl",
-    ),
-    (
-        "qpc_hafs.json",
-        "https://media.githubusercontent.com/media/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/qpc_hafs.json",
-    ),
-    (
-        "surah_info.json",
-        "https://raw.githubusercontent.com/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/surah_info.json",
-    ),
-    (
-        "digital_khatt_v2_script.json",
-        "https://media.githubusercontent.com/media/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/digital_khatt_v2_script.json",
-    ),
-    (
-        "phoneme_sub_costs.json",
-        "https://raw.githubusercontent.com/zonetecde/QuranCaption/main/src-tauri/python/quran-multi-aligner/data/phoneme_sub_costs.json",
-    ),
+/// Fichiers data attendus.
+///
+/// Les URL visent le tag `multi-aligner-data-v1` et non `main` : le contenu d'une branche
+/// évolue alors que les tailles et empreintes ci-dessous sont figées. La release du même
+/// tag porte une copie de chaque fichier en asset (miroir). Mettre à jour un fichier
+/// implique un nouveau tag, sa release et la mise à jour conjointe des URL et des empreintes.
+/// L'URL équivalente sur `main` reste essayée en dernier (voir `download_urls`).
+const MULTI_ALIGNER_DATA_FILES: [MultiAlignerDataFile; 6] = [
+    MultiAlignerDataFile {
+        name: "phoneme_cache.pkl",
+        url: "https://media.githubusercontent.com/media/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/phoneme_cache.pkl",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/phoneme_cache.pkl"),
+        size_bytes: 7_964_064,
+        sha256: "027283ab3be8a239b99ba4b3ffeb869efddc3da6fce12e02473d3e335dbf3a04",
+    },
+    MultiAlignerDataFile {
+        name: "phoneme_ngram_index_5.pkl",
+        url: "https://media.githubusercontent.com/media/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/phoneme_ngram_index_5.pkl",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/phoneme_ngram_index_5.pkl"),
+        size_bytes: 6_205_946,
+        sha256: "b522664de41f590fc18fad385f023cb1a85829623cbcf035fe18152be52bc739",
+    },
+    MultiAlignerDataFile {
+        name: "qpc_hafs.json",
+        url: "https://media.githubusercontent.com/media/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/qpc_hafs.json",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/qpc_hafs.json"),
+        size_bytes: 11_596_756,
+        sha256: "9b2f91a19769275d0da57464002beacd8cec396b02b520aa14d17e3b135012a7",
+    },
+    MultiAlignerDataFile {
+        name: "surah_info.json",
+        url: "https://raw.githubusercontent.com/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/surah_info.json",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/surah_info.json"),
+        size_bytes: 399_552,
+        sha256: "3636ee85446bc804a06a27bb4dc9752f36b45f476f9c1ca2661634d3ca87b9d4",
+    },
+    MultiAlignerDataFile {
+        name: "digital_khatt_v2_script.json",
+        url: "https://media.githubusercontent.com/media/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/digital_khatt_v2_script.json",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/digital_khatt_v2_script.json"),
+        size_bytes: 14_832_957,
+        sha256: "fd396239908253a07dc530580fd978c515cbac1b7751b2680296580fb62b247c",
+    },
+    MultiAlignerDataFile {
+        name: "phoneme_sub_costs.json",
+        url: "https://raw.githubusercontent.com/zonetecde/QuranCaption/multi-aligner-data-v1/src-tauri/python/quran-multi-aligner/data/phoneme_sub_costs.json",
+        mirror_url: Some("https://github.com/zonetecde/QuranCaption/releases/download/multi-aligner-data-v1/phoneme_sub_costs.json"),
+        size_bytes: 1_242,
+        sha256: "6378eac4de275a8e4efce72b3083756ecf2db1eb380eb98dfda231389e586868",
+    },
 ];
 
+/// État d'un fichier data Multi-Aligner rapporté par `verify_multi_aligner_data`.
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiAlignerDataFileStatus {
+    /// Nom du fichier dans le dossier `data`.
+    pub name: String,
+    /// `true` si l'en-tête, la taille et le SHA-256 sont conformes.
+    pub valid: bool,
+    /// Cause de l'invalidité.
+    pub error: Option<String>,
+    /// Taille sur disque en octets (`None` si le fichier est absent).
+    pub size_bytes: Option<u64>,
+    /// Taille attendue en octets.
+    pub expected_size_bytes: u64,
+}
+
+/// Fichiers dont l'empreinte a été vérifiée, avec la taille et la date de modification
+/// constatées : ils ne sont pas re-hachés tant qu'ils ne changent pas.
+static VERIFIED_DATA_FILES: Mutex<Vec<(PathBuf, u64, SystemTime)>> = Mutex::new(Vec::new());
+
+impl MultiAlignerDataFile {
+    /// Sources de téléchargement, dans l'ordre d'essai : miroir choisi par l'utilisateur
+    /// (`<data_mirror_url>/<name>`), URL principale, miroir intégré puis la même URL sur
+    /// `main`.
+    ///
+    /// `main` sert tant que le tag et sa release ne sont pas publiés ; son contenu peut
+    /// diverger, mais le SHA-256 attendu rejette alors le fichier.
+    pub fn download_urls(&self, data_mirror_url: Option<&str>) -> Vec<String> {
+        let branch_url = self.url.contains(DATA_FILES_TAG_SEGMENT).then(|| {
+            self.url
+                .replacen(DATA_FILES_TAG_SEGMENT, DATA_FILES_BRANCH_SEGMENT, 1)
+        });
+        data_mirror_url
+            .map(|base| format!("{}/{}", base.trim_end_matches('/'), self.name))
+            .into_iter()
+            .chain(std::iter::once(self.url.to_string()))
+            .chain(self.mirror_url.map(str::to_string))
+            .chain(branch_url)
+            .collect()
+    }
+}
+
+/// Valide l'URL de base d'un miroir des fichiers data : vide ignorée, sinon `http(s)://`.
+///
+/// Le `http://` est accepté (miroir interne) : chaque fichier reste vérifié par son SHA-256.
+pub(crate) fn resolve_data_mirror_url(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(raw) = raw.map(str::trim).filter(|raw| !raw.is_empty()) else {
+        return Ok(None);
+    };
+    let url = reqwest::Url::parse(raw).map_err(|e| format!("Invalid data mirror URL: {}", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(
+            "Invalid data mirror URL: only http:// and https:// URLs are allowed".to_string(),
+        );
+    }
+    Ok(Some(raw.trim_end_matches('/').to_string()))
+}
+
 /// Résout le dossier `data` du code Python Multi-Aligner embarqué.
 pub(crate) fn resolve_multi_aligner_data_dir(
     app_handle: &tauri::AppHandle,
@@ -40,7 +150,7 @@ pub(crate) fn resolve_multi_aligner_data_dir(
 }
 
 /// Retourne la liste des fichiers data Multi-Aligner obligatoires.
-pub(crate) fn required_multi_aligner_data_files() -> &'static [(&'static str, &'static str)] {
+pub(crate) fn required_multi_aligner_data_files() -> &'static [MultiAlignerDataFile] {
     &MULTI_ALIGNER_DATA_FILES
 }
 
@@ -74,14 +184,14 @@ pub(crate) fn validate_pickle_data_file(path: &Path) -> Result<(), String> {
         return Err(format!("Missing data file: {}", path.to_string_lossy()));
     }
 
-    let head = fs::read(path).map_err(|e| {
+    let head = read_file_head(path, 128).map_err(|e| {
         format!(
             "Unable to read data file '{}': {}",
             path.to_string_lossy(),
             e
         )
     })?;
-    let head = &head[..head.len().min(128)];
+    let head = head.as_slice();
 
     if is_lfs_pointer(head) {
         return Err(format!(
@@ -140,8 +250,55 @@ fn validate_json_data_file(path: &Path) -> Result<(), String> {
     Ok(())
 }
 
-/// Vérifie qu'un fichier data multi-aligner est valide selon son extension.
-pub(crate) fn validate_multi_aligner_data_file(path: &Path) -> Result<(), String> {
+/// Vérifie la taille puis l'empreinte SHA-256 d'un fichier data (lecture complète).
+fn verify_data_file_digest(path: &Path, expected: &MultiAlignerDataFile) -> Result<(), String> {
+    let metadata = fs::metadata(path).map_err(|e| {
+        format!(
+            "Unable to read data file '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    if metadata.len() != expected.size_bytes {
+        return Err(format!(
+            "Data file '{}' is {} bytes instead of {} (incomplete download).",
+            path.to_string_lossy(),
+            metadata.len(),
+            expected.size_bytes
+        ));
+    }
+
+    let verified_key = metadata
+        .modified()
+        .ok()
+        .map(|modified| (path.to_path_buf(), metadata.len(), modified));
+    if let (Some(key), Ok(verified)) = (verified_key.as_ref(), VERIFIED_DATA_FILES.lock()) {
+        if verified.contains(key) {
+            return Ok(());
+        }
+    }
+
+    let actual = file_sha256(path)?;
+    if !actual.eq_ignore_ascii_case(expected.sha256) {
+        return Err(format!(
+            "Data file '{}' is corrupted (SHA-256 {} instead of {}).",
+            path.to_string_lossy(),
+            actual,
+            expected.sha256
+        ));
+    }
+    if let (Some(key), Ok(mut verified)) = (verified_key, VERIFIED_DATA_FILES.lock()) {
+        verified.retain(|(verified_path, _, _)| verified_path != &key.0);
+        verified.push(key);
+    }
+    Ok(())
+}
+
+/// Vérifie qu'un fichier data multi-aligner est valide (appel bloquant).
+///
+/// Les contrôles d'en-tête selon l'extension servent de filtre rapide avant la vérification
+/// de la taille et du SHA-256 attendus.
+pub(crate) fn verify_multi_aligner_data_file(path: &Path) -> Result<(), String> {
     let extension = path
         .extension()
         .and_then(|value| value.to_str())
@@ -149,15 +306,135 @@ pub(crate) fn validate_multi_aligner_data_file(path: &Path) -> Result<(), String
         .to_ascii_lowercase();
 
     if extension == "pkl" {
-        return validate_pickle_data_file(path);
+        validate_pickle_data_file(path)?;
+    } else if extension == "json" {
+        validate_json_data_file(path)?;
+    } else {
+        return Err(format!(
+            "Unsupported Multi-Aligner data file type for '{}'",
+            path.to_string_lossy()
+        ));
+    }
+
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    match MULTI_ALIGNER_DATA_FILES
+        .iter()
+        .find(|data_file| Some(data_file.name) == file_name)
+    {
+        Some(expected) => verify_data_file_digest(path, expected),
+        None => Ok(()),
     }
+}
+
+/// Vérifie qu'un fichier data multi-aligner est valide, hachage hors du runtime async.
+pub(crate) async fn validate_multi_aligner_data_file(path: PathBuf) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || verify_multi_aligner_data_file(&path))
+        .await
+        .map_err(|e| format!("Data file check task failed: {}", e))?
+}
 
-    if extension == "json" {
-        return validate_json_data_file(path);
+/// Vérifie tous les fichiers data Multi-Aligner, sans s'arrêter au premier invalide.
+///
+/// @returns État de chaque fichier, dans l'ordre de `required_multi_aligner_data_files`
+pub async fn verify_multi_aligner_data(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<MultiAlignerDataFileStatus>, String> {
+    let data_dir = resolve_multi_aligner_data_dir(&app_handle)?;
+    let mut statuses = Vec::with_capacity(MULTI_ALIGNER_DATA_FILES.len());
+    for data_file in required_multi_aligner_data_files() {
+        let path = data_dir.join(data_file.name);
+        let size_bytes = fs::metadata(&path).map(|metadata| metadata.len()).ok();
+        let error = validate_multi_aligner_data_file(path).await.err();
+        statuses.push(MultiAlignerDataFileStatus {
+            name: data_file.name.to_string(),
+            valid: error.is_none(),
+            error,
+            size_bytes,
+            expected_size_bytes: data_file.size_bytes,
+        });
     }
+    log::info!(
+        "[segmentation][data] {}/{} Multi-Aligner data file(s) valid",
+        statuses.iter().filter(|status| status.valid).count(),
+        statuses.len()
+    );
+    Ok(statuses)
+}
 
-    Err(format!(
-        "Unsupported Multi-Aligner data file type for '{}'",
-        path.to_string_lossy()
-    ))
+#[cfg(test)]
+mod tests {
+    use super::{
+        resolve_data_mirror_url, verify_data_file_digest, MultiAlignerDataFile,
+        MULTI_ALIGNER_DATA_FILES,
+    };
+    use std::fs;
+
+    #[test]
+    fn data_file_urls_are_pinned_to_the_digest_tag() {
+        for data_file in MULTI_ALIGNER_DATA_FILES {
+            let urls = data_file.download_urls(None);
+            let (branch_url, pinned_urls) = urls.split_last().unwrap();
+            for url in pinned_urls {
+                assert!(
+                    url.contains("/multi-aligner-data-v1/") && !url.contains("/main/"),
+                    "{} is not pinned: {}",
+                    data_file.name,
+                    url
+                );
+                assert!(url.ends_with(data_file.name));
+            }
+            // Dernier recours : l'URL principale sur `main`, tant que le tag n'est pas publié.
+            assert_eq!(
+                *branch_url,
+                data_file.url.replace("/multi-aligner-data-v1/", "/main/")
+            );
+            // Le miroir ne doit pas dépendre du même stockage LFS que l'URL principale.
+            let mirror = data_file.mirror_url.unwrap();
+            assert!(mirror.contains("/releases/download/"), "{}", mirror);
+        }
+    }
+
+    #[test]
+    fn data_file_size_and_digest_are_verified() {
+        let path = std::env::temp_dir().join(format!(
+            "qurancaption-data-file-test-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, b"abc").unwrap();
+        let expected = MultiAlignerDataFile {
+            name: "test.json",
+            url: "",
+            mirror_url: None,
+            size_bytes: 3,
+            sha256: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        };
+
+        let wrong_digest = MultiAlignerDataFile {
+            sha256: "00",
+            ..expected
+        };
+        assert!(verify_data_file_digest(&path, &wrong_digest)
+            .unwrap_err()
+            .contains("is corrupted"));
+        let truncated = MultiAlignerDataFile {
+            size_bytes: 200,
+            ..expected
+        };
+        assert!(verify_data_file_digest(&path, &truncated)
+            .unwrap_err()
+            .contains("3 bytes instead of 200"));
+        assert_eq!(verify_data_file_digest(&path, &expected), Ok(()));
+        assert_eq!(verify_data_file_digest(&path, &expected), Ok(()));
+
+        let mirror = resolve_data_mirror_url(Some(" https://mirror.example.org/qc/ ")).unwrap();
+        assert_eq!(mirror.as_deref(), Some("https://mirror.example.org/qc"));
+        assert_eq!(
+            expected.download_urls(mirror.as_deref()),
+            vec!["https://mirror.example.org/qc/test.json", ""]
+        );
+        assert_eq!(resolve_data_mirror_url(Some("  ")), Ok(None));
+        assert!(resolve_data_mirror_url(Some("ftp://mirror.example.org")).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,135 @@
+//! Conservation de l'audio pré-traité envoyé aux moteurs, pour diagnostiquer un alignement faux.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::Manager;
+
+/// Nombre maximal de fichiers audio de débogage conservés.
+const MAX_DEBUG_AUDIO_FILES: usize = 5;
+
+/// Dossier `debug_audio` des données de l'application (sans le créer).
+pub(super) fn debug_audio_root(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("debug_audio"))
+}
+
+/// Retourne le dossier `debug_audio` des données de l'application (créé au besoin).
+fn debug_audio_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = debug_audio_root(app_handle)?;
+    fs::create_dir_all(&dir).map_err(|e| {
+        format!(
+            "Failed to create debug audio directory '{}': {}",
+            dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(dir)
+}
+
+/// Supprime les fichiers les plus anciens au-delà de `max_files` (date de modification).
+fn prune_debug_audio(dir: &Path, max_files: usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, std::time::SystemTime)> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.modified().ok()?)))
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    for (path, _) in files.into_iter().skip(max_files) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Copie l'audio pré-traité dans `debug_audio` avant sa suppression par `TempFileGuard`.
+///
+/// Un échec est seulement journalisé : il ne doit pas faire échouer la segmentation.
+///
+/// @returns Chemin de la copie, `None` si la copie a échoué
+pub(super) fn keep_preprocessed_audio(
+    app_handle: &tauri::AppHandle,
+    temp_path: &Path,
+) -> Option<PathBuf> {
+    let kept = debug_audio_dir(app_handle).and_then(|dir| {
+        let file_name = temp_path
+            .file_name()
+            .ok_or_else(|| "Preprocessed audio has no file name".to_string())?;
+        let destination = dir.join(file_name);
+        fs::copy(temp_path, &destination)
+            .map_err(|e| format!("Failed to keep preprocessed audio: {}", e))?;
+        prune_debug_audio(&dir, MAX_DEBUG_AUDIO_FILES);
+        Ok(destination)
+    });
+    match kept {
+        Ok(path) => {
+            log::info!(
+                "[segmentation] kept preprocessed audio at {}",
+                path.to_string_lossy()
+            );
+            Some(path)
+        }
+        Err(error) => {
+            log::warn!("[segmentation] {}", error);
+            None
+        }
+    }
+}
+
+/// Ajoute `debugAudioPath` au résultat de segmentation.
+pub(super) fn attach_debug_audio_path(result: &mut serde_json::Value, path: Option<&Path>) {
+    if let (Some(object), Some(path)) = (result.as_object_mut(), path) {
+        object.insert(
+            "debugAudioPath".to_string(),
+            serde_json::json!(path.to_string_lossy().replace('\\', "/")),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prune_debug_audio;
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn only_the_newest_debug_audio_files_are_kept() {
+        let dir = std::env::temp_dir().join(format!(
+            "qurancaption-debug-audio-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        for index in 0..4u64 {
+            let path = dir.join(format!("qurancaption-seg-{}.ogg", index));
+            fs::write(&path, b"ogg").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(100 - index))
+                .unwrap();
+        }
+
+        prune_debug_audio(&dir, 2);
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "qurancaption-seg-2.ogg".to_string(),
+                "qurancaption-seg-3.ogg".to_string()
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
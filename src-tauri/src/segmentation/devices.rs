@@ -0,0 +1,401 @@
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::utils::process::{configure_command_no_window, output_with_timeout, PROBE_TIMEOUT};
+
+use super::python_env::{get_engine_venv_path, get_venv_python_exe};
+use super::types::{LocalSegmentationEngine, IS_APPLE_SILICON};
+
+/// Moteurs locaux dont le venv peut être sondé pour connaître les devices vus par torch.
+const TORCH_PROBE_ENGINES: [LocalSegmentationEngine; 4] = [
+    LocalSegmentationEngine::MultiAligner,
+    LocalSegmentationEngine::LegacyWhisper,
+    LocalSegmentationEngine::MuaalemLocal,
+    LocalSegmentationEngine::SurahSplitter,
+];
+
+/// Script Python minimal décrivant ce que torch détecte dans un venv.
+const TORCH_PROBE_SCRIPT: &str = r#"
+import json
+try:
+    import torch
+    cuda = bool(torch.cuda.is_available())
+    mps_backend = getattr(torch.backends, "mps", None)
+    mps = bool(mps_backend is not None and mps_backend.is_available())
+    print(json.dumps({
+        "torchVersion": torch.__version__,
+        "cudaAvailable": cuda,
+        "mpsAvailable": mps,
+        "deviceName": torch.cuda.get_device_name(0) if cuda else None,
+        "rocmVersion": getattr(torch.version, "hip", None),
+    }))
+except Exception as exc:
+    print(json.dumps({"error": str(exc)}))
+"#;
+
+/// GPU NVIDIA détecté via `nvidia-smi`.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvidiaGpuInfo {
+    /// Nom commercial du GPU.
+    pub name: String,
+    /// VRAM totale en Mo, si rapportée.
+    pub vram_mb: Option<u64>,
+    /// Version du driver NVIDIA, si rapportée.
+    pub driver_version: Option<String>,
+}
+
+/// GPU AMD détecté via `rocminfo` (Linux avec ROCm).
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmdGpuInfo {
+    /// Nom commercial du GPU.
+    pub name: String,
+    /// Cible LLVM du GPU (`gfx1030`, ...), utile pour `HSA_OVERRIDE_GFX_VERSION`.
+    pub gfx_target: Option<String>,
+}
+
+/// Résultat de la sonde torch exécutée dans le venv d'un moteur local.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorchDeviceProbe {
+    /// Clé du moteur dont le venv a été sondé.
+    pub engine: String,
+    /// Version de torch installée.
+    pub torch_version: Option<String>,
+    /// `true` si torch voit un GPU CUDA utilisable.
+    pub cuda_available: bool,
+    /// `true` si torch peut utiliser le backend Apple MPS.
+    pub mps_available: bool,
+    /// Nom du GPU CUDA vu par torch.
+    pub device_name: Option<String>,
+    /// Version ROCm de torch (build ROCm : le GPU AMD est exposé via l'API CUDA).
+    pub rocm_version: Option<String>,
+    /// Erreur rencontrée pendant la sonde (torch absent, crash, ...).
+    pub error: Option<String>,
+}
+
+/// Capacités de calcul de la machine pour le sélecteur de device de segmentation.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputeDevices {
+    /// GPUs NVIDIA détectés (vide si `nvidia-smi` est absent).
+    pub nvidia_gpus: Vec<NvidiaGpuInfo>,
+    /// GPUs AMD détectés via ROCm (Linux uniquement).
+    pub amd_gpus: Vec<AmdGpuInfo>,
+    /// `true` sur Apple Silicon (backend MPS disponible côté plateforme).
+    pub mps_available: bool,
+    /// Nombre de coeurs logiques du CPU.
+    pub cpu_cores: usize,
+    /// RAM totale en Mo.
+    pub total_ram_mb: u64,
+    /// Sondes torch par venv installé (`None` si la sonde n'a pas été demandée).
+    pub torch: Option<Vec<TorchDeviceProbe>>,
+    /// Device conseillé pour la segmentation (`GPU` ou `CPU`).
+    pub recommended_device: String,
+    /// Backend derrière `GPU` (`CUDA`, `ROCm` ou `MPS`), `None` sans GPU utilisable.
+    pub gpu_backend: Option<String>,
+}
+
+/// Résultat de détection mis en cache pour la session.
+static COMPUTE_DEVICES_CACHE: Mutex<Option<ComputeDevices>> = Mutex::new(None);
+
+/// Parse la sortie CSV de `nvidia-smi --query-gpu=name,memory.total,driver_version`.
+fn parse_nvidia_smi_output(output: &str) -> Vec<NvidiaGpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',').map(str::trim);
+            let name = fields.next().filter(|name| !name.is_empty())?.to_string();
+            let vram_mb = fields.next().and_then(|value| value.parse().ok());
+            let driver_version = fields
+                .next()
+                .filter(|value| !value.is_empty())
+                .map(str::to_string);
+            Some(NvidiaGpuInfo {
+                name,
+                vram_mb,
+                driver_version,
+            })
+        })
+        .collect()
+}
+
+/// Liste les GPUs NVIDIA via `nvidia-smi` (vide si l'outil est absent ou échoue).
+fn detect_nvidia_gpus() -> Vec<NvidiaGpuInfo> {
+    let mut cmd = Command::new("nvidia-smi");
+    cmd.args([
+        "--query-gpu=name,memory.total,driver_version",
+        "--format=csv,noheader,nounits",
+    ]);
+    configure_command_no_window(&mut cmd);
+    match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
+        Ok(output) if output.status.success() => {
+            parse_nvidia_smi_output(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Parse la sortie de `rocminfo` : un agent par bloc, seuls les agents `GPU` sont gardés.
+fn parse_rocminfo_output(output: &str) -> Vec<AmdGpuInfo> {
+    let mut gpus = Vec::new();
+    let mut field_values: Vec<(String, String)> = Vec::new();
+    let mut flush = |fields: &mut Vec<(String, String)>| {
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+                .filter(|value| !value.is_empty())
+        };
+        if field("Device Type").as_deref() == Some("GPU") {
+            let gfx_target = field("Name").filter(|name| name.starts_with("gfx"));
+            if let Some(name) = field("Marketing Name").or_else(|| gfx_target.clone()) {
+                gpus.push(AmdGpuInfo { name, gfx_target });
+            }
+        }
+        fields.clear();
+    };
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Agent ") && !trimmed.contains(':') {
+            flush(&mut field_values);
+            continue;
+        }
+        // Premier niveau d'indentation uniquement : les sous-sections (pools, ISA) sont ignorées.
+        if line.starts_with("  ") && !line.starts_with("   ") {
+            if let Some((key, value)) = trimmed.split_once(':') {
+                let key = key.trim().to_string();
+                if !field_values.iter().any(|(name, _)| *name == key) {
+                    field_values.push((key, value.trim().to_string()));
+                }
+            }
+        }
+    }
+    flush(&mut field_values);
+    gpus
+}
+
+/// Liste les GPUs AMD via `rocminfo` (vide hors Linux ou sans ROCm).
+///
+/// `rocminfo` est lancé une seule fois : l'installation standard `/opt/rocm` si elle
+/// existe, sinon celui du `PATH`.
+fn detect_amd_gpus() -> Vec<AmdGpuInfo> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    let rocm_bin = std::path::Path::new("/opt/rocm/bin/rocminfo");
+    let mut cmd = if rocm_bin.exists() {
+        Command::new(rocm_bin)
+    } else {
+        Command::new("rocminfo")
+    };
+    configure_command_no_window(&mut cmd);
+    match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
+        Ok(output) if output.status.success() => {
+            parse_rocminfo_output(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Indique si `nvidia-smi` signale au moins un GPU NVIDIA.
+pub(crate) fn has_nvidia_gpu() -> bool {
+    !detect_nvidia_gpus().is_empty()
+}
+
+/// Indique si un GPU AMD utilisable par un build ROCm de PyTorch est présent (Linux).
+pub(crate) fn has_rocm_gpu() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    !detect_amd_gpus().is_empty()
+        || (std::path::Path::new("/opt/rocm").is_dir() && std::path::Path::new("/dev/kfd").exists())
+}
+
+/// Retourne la VRAM libre (Mo) du GPU NVIDIA le mieux doté, `None` sans GPU NVIDIA.
+///
+/// Appel bloquant, borné par `PROBE_TIMEOUT` (pilote figé, `nvidia-smi` qui ne répond plus).
+pub(crate) fn detect_nvidia_free_vram_mb() -> Option<u64> {
+    let mut cmd = Command::new("nvidia-smi");
+    cmd.args(["--query-gpu=memory.free", "--format=csv,noheader,nounits"]);
+    configure_command_no_window(&mut cmd);
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse::<u64>().ok())
+        .max()
+}
+
+/// Exécute la sonde torch dans le venv de chaque moteur local installé.
+fn probe_torch_devices(app_handle: &tauri::AppHandle) -> Vec<TorchDeviceProbe> {
+    TORCH_PROBE_ENGINES
+        .iter()
+        .filter_map(|engine| {
+            let venv_dir = get_engine_venv_path(app_handle, *engine).ok()?;
+            let python_exe = get_venv_python_exe(&venv_dir);
+            if !python_exe.exists() {
+                return None;
+            }
+
+            let mut probe = TorchDeviceProbe {
+                engine: engine.as_key().to_string(),
+                torch_version: None,
+                cuda_available: false,
+                mps_available: false,
+                device_name: None,
+                rocm_version: None,
+                error: None,
+            };
+            let mut cmd = Command::new(&python_exe);
+            cmd.args(["-c", TORCH_PROBE_SCRIPT]);
+            configure_command_no_window(&mut cmd);
+            let payload = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+                .map_err(|e| format!("Failed to run torch probe: {}", e))
+                .and_then(|output| {
+                    serde_json::from_slice::<serde_json::Value>(&output.stdout)
+                        .map_err(|e| format!("Invalid torch probe output: {}", e))
+                });
+            match payload {
+                Ok(payload) => {
+                    let text = |key: &str| {
+                        payload
+                            .get(key)
+                            .and_then(|value| value.as_str())
+                            .map(str::to_string)
+                    };
+                    let flag = |key: &str| {
+                        payload
+                            .get(key)
+                            .and_then(|value| value.as_bool())
+                            .unwrap_or(false)
+                    };
+                    probe.torch_version = text("torchVersion");
+                    probe.cuda_available = flag("cudaAvailable");
+                    probe.mps_available = flag("mpsAvailable");
+                    probe.device_name = text("deviceName");
+                    probe.rocm_version = text("rocmVersion");
+                    probe.error = text("error");
+                }
+                Err(error) => probe.error = Some(error),
+            }
+            Some(probe)
+        })
+        .collect()
+}
+
+/// Détecte les capacités de calcul de la machine (sans cache).
+fn detect_compute_devices_uncached(
+    app_handle: &tauri::AppHandle,
+    probe_torch: bool,
+) -> ComputeDevices {
+    let nvidia_gpus = detect_nvidia_gpus();
+    let amd_gpus = detect_amd_gpus();
+    let mps_available = IS_APPLE_SILICON;
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|cores| cores.get())
+        .unwrap_or(1);
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let total_ram_mb = system.total_memory() / (1024 * 1024);
+    let torch = probe_torch.then(|| probe_torch_devices(app_handle));
+
+    // Quand torch a été sondé, son avis prime sur la détection matérielle brute.
+    let gpu_usable = match torch.as_ref().filter(|probes| !probes.is_empty()) {
+        Some(probes) => probes
+            .iter()
+            .any(|probe| probe.cuda_available || probe.mps_available),
+        None => !nvidia_gpus.is_empty() || !amd_gpus.is_empty() || mps_available,
+    };
+    // Sur Apple Silicon, `GPU` est exécuté via MPS (voir `resolve_compute_device`).
+    let gpu_backend = gpu_usable.then(|| {
+        let rocm = match torch.as_ref().filter(|probes| !probes.is_empty()) {
+            Some(probes) => probes.iter().any(|probe| probe.rocm_version.is_some()),
+            None => nvidia_gpus.is_empty() && !amd_gpus.is_empty(),
+        };
+        if mps_available {
+            "MPS"
+        } else if rocm {
+            "ROCm"
+        } else {
+            "CUDA"
+        }
+        .to_string()
+    });
+
+    ComputeDevices {
+        nvidia_gpus,
+        amd_gpus,
+        mps_available,
+        cpu_cores,
+        total_ram_mb,
+        torch,
+        recommended_device: if gpu_usable { "GPU" } else { "CPU" }.to_string(),
+        gpu_backend,
+    }
+}
+
+/// Détecte les GPUs/CPU disponibles pour la segmentation locale.
+///
+/// Le résultat est mis en cache pour la session ; `refresh` force une nouvelle détection.
+///
+/// @param refresh - Ignore le cache et relance la détection
+/// @param probe_torch - Sonde aussi torch dans les venvs des moteurs installés
+pub async fn detect_compute_devices(
+    app_handle: tauri::AppHandle,
+    refresh: bool,
+    probe_torch: bool,
+) -> Result<ComputeDevices, String> {
+    if !refresh {
+        let cached = COMPUTE_DEVICES_CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| cache.clone())
+            .filter(|devices| !probe_torch || devices.torch.is_some());
+        if let Some(devices) = cached {
+            return Ok(devices);
+        }
+    }
+
+    let devices = tauri::async_runtime::spawn_blocking(move || {
+        detect_compute_devices_uncached(&app_handle, probe_torch)
+    })
+    .await
+    .map_err(|e| format!("Compute device detection task failed: {}", e))?;
+
+    if let Ok(mut cache) = COMPUTE_DEVICES_CACHE.lock() {
+        *cache = Some(devices.clone());
+    }
+    Ok(devices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_nvidia_smi_output, parse_rocminfo_output};
+
+    #[test]
+    fn nvidia_smi_csv_is_parsed() {
+        let gpus = parse_nvidia_smi_output(
+            "NVIDIA GeForce RTX 3060, 12288, 551.86\nNVIDIA T4, [N/A], \n\n",
+        );
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 3060");
+        assert_eq!(gpus[0].vram_mb, Some(12288));
+        assert_eq!(gpus[0].driver_version.as_deref(), Some("551.86"));
+        assert_eq!(gpus[1].vram_mb, None);
+        assert_eq!(gpus[1].driver_version, None);
+    }
+
+    #[test]
+    fn rocminfo_gpu_agents_are_parsed() {
+        let output = "*******\nAgent 1\n*******\n  Name:                    AMD Ryzen 7 5800X\n  Marketing Name:          AMD Ryzen 7 5800X\n  Device Type:             CPU\n*******\nAgent 2\n*******\n  Name:                    gfx1030\n  Marketing Name:          AMD Radeon RX 6800\n  Device Type:             GPU\n  ISA Info:\n    ISA 1\n      Name:                    amdgcn-amd-amdhsa--gfx1030\n";
+        let gpus = parse_rocminfo_output(output);
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name, "AMD Radeon RX 6800");
+        assert_eq!(gpus[0].gfx_target.as_deref(), Some("gfx1030"));
+        assert!(parse_rocminfo_output("ROCk module is NOT loaded\n").is_empty());
+    }
+}
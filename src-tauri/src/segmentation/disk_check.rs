@@ -0,0 +1,143 @@
+//! Vérification de l'espace disque avant l'installation d'un moteur local.
+//!
+//! Une installation interrompue faute de place laisse un venv corrompu : mieux vaut refuser
+//! avant de commencer.
+
+use std::path::Path;
+
+use crate::commands::diagnostics::disk_space_for_path;
+
+use super::devices::{has_nvidia_gpu, has_rocm_gpu};
+use super::types::LocalSegmentationEngine;
+
+/// Espace disque estimé d'une installation (Mo).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct InstallDiskRequirement {
+    /// Venv, cache pip et fichiers data, sur le volume des données de l'application.
+    pub app_data_mb: u64,
+    /// Wheels décompressées et builds de pip, sur le volume temporaire.
+    pub temp_mb: u64,
+}
+
+/// Estime l'espace nécessaire à l'installation d'un moteur.
+///
+/// @param gpu_torch - PyTorch avec les bibliothèques CUDA/ROCm (bien plus lourd que le build CPU)
+pub(crate) fn estimate_install_disk_requirement(
+    engine: LocalSegmentationEngine,
+    gpu_torch: bool,
+) -> InstallDiskRequirement {
+    let torch_mb = if gpu_torch { 6_000 } else { 3_000 };
+    let requirements_mb = 1_000;
+    let data_mb = match engine {
+        LocalSegmentationEngine::MultiAligner => 500,
+        _ => 0,
+    };
+    InstallDiskRequirement {
+        app_data_mb: torch_mb + requirements_mb + data_mb,
+        temp_mb: if gpu_torch { 2_500 } else { 1_000 },
+    }
+}
+
+/// Indique si l'installer retiendra un PyTorch GPU (wheels Linux par défaut, CUDA Windows, ROCm).
+fn expects_gpu_torch() -> bool {
+    if cfg!(target_os = "macos") {
+        return false;
+    }
+    cfg!(target_os = "linux") || has_nvidia_gpu() || has_rocm_gpu()
+}
+
+/// Formate une taille disque en Go lisibles.
+fn format_gb(mb: u64) -> String {
+    format!("{:.1} GB", mb as f64 / 1024.0)
+}
+
+/// Compare le besoin à l'espace libre des volumes (`(point de montage, Mo libres)`).
+///
+/// Quand les données et le dossier temporaire partagent un volume, les besoins s'additionnent.
+/// Un volume non mesurable n'est pas vérifié.
+fn evaluate_disk_space(
+    engine: LocalSegmentationEngine,
+    requirement: InstallDiskRequirement,
+    app_data: Option<(String, u64)>,
+    temp: Option<(String, u64)>,
+) -> Result<(), String> {
+    let mut checks: Vec<(String, u64, u64)> = Vec::new();
+    if let Some((mount_point, free_mb)) = app_data {
+        checks.push((mount_point, free_mb, requirement.app_data_mb));
+    }
+    if let Some((mount_point, free_mb)) = temp {
+        match checks.iter_mut().find(|check| check.0 == mount_point) {
+            Some(check) => check.2 += requirement.temp_mb,
+            None => checks.push((mount_point, free_mb, requirement.temp_mb)),
+        }
+    }
+    for (mount_point, free_mb, needed_mb) in checks {
+        if free_mb < needed_mb {
+            return Err(format!(
+                "Not enough disk space to install {}: needs ~{} on {}, you have {} free.",
+                engine.as_label(),
+                format_gb(needed_mb),
+                mount_point,
+                format_gb(free_mb)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Vérifie l'espace libre avant l'installation d'un moteur.
+///
+/// @param install_dir - Dossier du venv (volume des données de l'application)
+/// @param ignore_disk_check - Désactive la vérification (installation forcée)
+pub(crate) fn check_install_disk_space(
+    engine: LocalSegmentationEngine,
+    install_dir: &Path,
+    ignore_disk_check: bool,
+) -> Result<(), String> {
+    if ignore_disk_check {
+        return Ok(());
+    }
+    let requirement = estimate_install_disk_requirement(engine, expects_gpu_torch());
+    let free_space = |path: &Path| {
+        disk_space_for_path(path)
+            .map(|disk| (disk.mount_point, disk.available_bytes / (1024 * 1024)))
+    };
+    let app_data = free_space(install_dir);
+    let temp = free_space(&std::env::temp_dir());
+    log::info!(
+        "[segmentation][install] disk check engine={} need={:?} app_data={:?} temp={:?}",
+        engine.as_key(),
+        requirement,
+        app_data,
+        temp
+    );
+    evaluate_disk_space(engine, requirement, app_data, temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_install_disk_requirement, evaluate_disk_space};
+    use crate::segmentation::types::LocalSegmentationEngine;
+
+    #[test]
+    fn install_is_refused_when_a_volume_is_too_full() {
+        let engine = LocalSegmentationEngine::MultiAligner;
+        let requirement = estimate_install_disk_requirement(engine, true);
+        assert_eq!(requirement.app_data_mb, 7_500);
+
+        let volume = |mount_point: &str, free_mb: u64| Some((mount_point.to_string(), free_mb));
+        assert!(evaluate_disk_space(
+            engine,
+            requirement,
+            volume("D:\\", 8_000),
+            volume("C:\\", 3_000)
+        )
+        .is_ok());
+        assert_eq!(
+            evaluate_disk_space(engine, requirement, volume("/", 8_000), volume("/", 8_000)),
+            Err("Not enough disk space to install Multi-Aligner: needs ~9.8 GB on /, you have 7.8 GB free.".to_string())
+        );
+        assert!(evaluate_disk_space(engine, requirement, None, volume("/tmp", 500)).is_err());
+        assert!(evaluate_disk_space(engine, requirement, None, None).is_ok());
+    }
+}
@@ -0,0 +1,226 @@
+use tauri::Emitter;
+
+use super::cloud::{cloud_error_http_status, segment_quran_audio, CLOUD_NETWORK_ERROR_TAG};
+use super::local::{segment_quran_audio_local, segment_quran_audio_local_multi};
+use super::status::check_local_segmentation_ready;
+use super::types::{AudioPreprocessOptions, LocalSegmentationEngine, SegmentationAudioClip};
+
+/// Statuts HTTP d'un cloud temporairement indisponible : surcharge, passerelle, Space arrêtée.
+const CLOUD_UNAVAILABLE_STATUSES: [u16; 3] = [429, 502, 503];
+
+/// Indique si une erreur cloud justifie un repli local : statut 429/502/503 ou requête
+/// restée sans réponse. Les autres statuts (400, 500, ...) viennent de la requête elle-même.
+pub(crate) fn is_cloud_unavailable_error(error: &str) -> bool {
+    match cloud_error_http_status(error) {
+        Some(status) => CLOUD_UNAVAILABLE_STATUSES.contains(&status),
+        None => error.contains(CLOUD_NETWORK_ERROR_TAG),
+    }
+}
+
+/// Marque le backend ayant réellement produit le résultat (`cloud`, `local-multi`, ...).
+fn tag_backend(mut result: serde_json::Value, backend: &str) -> serde_json::Value {
+    if let Some(object) = result.as_object_mut() {
+        object.insert(
+            "backend".to_string(),
+            serde_json::Value::String(backend.to_string()),
+        );
+    }
+    result
+}
+
+/// Choisit le moteur local utilisable pour un repli (Multi-Aligner en priorité).
+async fn select_fallback_engine(
+    app_handle: &tauri::AppHandle,
+    hf_token: Option<String>,
+) -> Option<LocalSegmentationEngine> {
+    let readiness = check_local_segmentation_ready(app_handle.clone(), hf_token, None)
+        .await
+        .ok()?;
+    let is_usable = |key: &str| {
+        readiness
+            .pointer(&format!("/engines/{}/usable", key))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    };
+    if is_usable("multi") {
+        Some(LocalSegmentationEngine::MultiAligner)
+    } else if is_usable("legacy") {
+        Some(LocalSegmentationEngine::LegacyWhisper)
+    } else {
+        None
+    }
+}
+
+/// Segmentation cloud avec repli automatique vers un moteur local si le cloud est indisponible.
+///
+/// Sans `fallback_to_local`, se comporte comme `segment_quran_audio`. Le résultat porte
+/// un champ `backend` indiquant le moteur qui l'a produit.
+pub async fn segment_quran_audio_with_fallback(
+    app_handle: tauri::AppHandle,
+    audio_path: Option<String>,
+    audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
+    min_silence_ms: Option<u32>,
+    min_speech_ms: Option<u32>,
+    pad_ms: Option<u32>,
+    model_name: Option<String>,
+    device: Option<String>,
+    upload_bitrate_kbps: Option<u32>,
+    upload_sample_rate: Option<u32>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    hf_token: Option<String>,
+    fallback_to_local: bool,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
+) -> Result<serde_json::Value, String> {
+    let cloud_error = match segment_quran_audio(
+        app_handle.clone(),
+        audio_path.clone(),
+        audio_clips.clone(),
+        range_start_ms,
+        range_end_ms,
+        min_confidence,
+        mark_low_confidence,
+        min_silence_ms,
+        min_speech_ms,
+        pad_ms,
+        model_name.clone(),
+        device.clone(),
+        upload_bitrate_kbps,
+        upload_sample_rate,
+        surah_hint,
+        ayah_range_hint,
+        keep_temp_audio,
+        preprocess,
+    )
+    .await
+    {
+        Ok(result) => return Ok(tag_backend(result, "cloud")),
+        Err(error) => error,
+    };
+    if !fallback_to_local || !is_cloud_unavailable_error(&cloud_error) {
+        return Err(cloud_error);
+    }
+
+    let Some(engine) = select_fallback_engine(&app_handle, hf_token.clone()).await else {
+        return Err(format!(
+            "{} (no local segmentation engine is installed for automatic fallback)",
+            cloud_error
+        ));
+    };
+    log::info!(
+        "[segmentation] Cloud failed ({}), falling back to local {}",
+        cloud_error,
+        engine.as_label()
+    );
+    let _ = app_handle.emit(
+        "segmentation-status",
+        serde_json::json!({
+            "step": "cloud_fallback",
+            "message": format!(
+                "Cloud segmentation is unavailable. Switching to local {}...",
+                engine.as_label()
+            ),
+            "progress": serde_json::Value::Null,
+            "cloudError": cloud_error,
+        }),
+    );
+
+    let result = match engine {
+        LocalSegmentationEngine::MultiAligner => {
+            segment_quran_audio_local_multi(
+                app_handle,
+                audio_path,
+                audio_clips,
+                range_start_ms,
+                range_end_ms,
+                min_confidence,
+                mark_low_confidence,
+                min_silence_ms,
+                min_speech_ms,
+                pad_ms,
+                model_name,
+                device,
+                hf_token,
+                surah_hint,
+                ayah_range_hint,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                keep_temp_audio,
+                preprocess,
+            )
+            .await?
+        }
+        _ => {
+            segment_quran_audio_local(
+                app_handle,
+                audio_path,
+                audio_clips,
+                range_start_ms,
+                range_end_ms,
+                min_confidence,
+                mark_low_confidence,
+                min_silence_ms,
+                min_speech_ms,
+                pad_ms,
+                None,
+                surah_hint,
+                ayah_range_hint,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                keep_temp_audio,
+                preprocess,
+            )
+            .await?
+        }
+    };
+    Ok(tag_backend(result, &format!("local-{}", engine.as_key())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_cloud_unavailable_error;
+
+    #[test]
+    fn only_network_and_server_errors_trigger_fallback() {
+        assert!(is_cloud_unavailable_error(
+            "Upload request failed (network error): error sending request"
+        ));
+        assert!(is_cloud_unavailable_error(
+            "Process call error (HTTP 503): HTTP status server error (503 Service Unavailable)"
+        ));
+        assert!(is_cloud_unavailable_error(
+            "Upload request error (HTTP 429): HTTP status client error (429 Too Many Requests)"
+        ));
+        assert!(is_cloud_unavailable_error(
+            "Cloud server did not wake up after 5 minutes (stage: BUILDING). Please try again later or switch to local segmentation. (HTTP 503)"
+        ));
+        assert!(!is_cloud_unavailable_error(
+            "Process call error (HTTP 500): HTTP status server error (500 Internal Server Error)"
+        ));
+        assert!(!is_cloud_unavailable_error(
+            "Cloud segmentation stream error: connection reset while matching"
+        ));
+        assert!(!is_cloud_unavailable_error(
+            "Invalid model_name 'Huge'. Expected 'Base' or 'Large'."
+        ));
+        assert!(!is_cloud_unavailable_error(
+            "Audio file not found: C:/missing.mp3"
+        ));
+        assert!(!is_cloud_unavailable_error("ffmpeg error: invalid data"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Surveillance périodique (opt-in) des moteurs locaux installés.
+//!
+//! Une mise à jour Windows ou un antivirus peut mettre en quarantaine une partie d'un venv ;
+//! sans surveillance, l'utilisateur ne le découvre qu'à l'échec d'une segmentation. Le
+//! moniteur refait un sous-ensemble léger du check de préparation (venv, imports, fichiers
+//! data) dans un thread dédié et émet `engine-health-changed` à chaque passage prêt/cassé.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::Emitter;
+
+use super::data_files::{
+    required_multi_aligner_data_files, resolve_multi_aligner_data_dir,
+    verify_multi_aligner_data_file,
+};
+use super::python_env::{
+    get_engine_venv_path, get_venv_python_exe, is_venv_install_incomplete, run_python_import_check,
+};
+use super::status::invalidate_local_readiness_cache;
+use super::types::LocalSegmentationEngine;
+
+/// Évènement émis quand un moteur passe de prêt à cassé (ou inversement).
+const ENGINE_HEALTH_EVENT: &str = "engine-health-changed";
+/// Intervalle maximal accepté entre deux vérifications (minutes).
+const MAX_INTERVAL_MINUTES: u64 = 24 * 60;
+
+/// Moniteur en cours : fermer `stop_tx` arrête son thread.
+struct EngineHealthMonitor {
+    stop_tx: mpsc::Sender<()>,
+}
+
+/// Moniteur actif (un seul à la fois).
+static HEALTH_MONITOR: Mutex<Option<EngineHealthMonitor>> = Mutex::new(None);
+
+/// État d'un moteur installé lors d'une vérification.
+#[derive(Clone, Debug, PartialEq)]
+struct EngineHealth {
+    ready: bool,
+    /// Cause de l'échec, absente si le moteur est prêt.
+    reason: Option<String>,
+}
+
+impl EngineHealth {
+    fn ready() -> Self {
+        Self {
+            ready: true,
+            reason: None,
+        }
+    }
+
+    fn broken(reason: String) -> Self {
+        Self {
+            ready: false,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Vérifie un moteur (venv, imports, fichiers data pour Multi-Aligner).
+///
+/// @returns `None` si le moteur n'est pas installé ou si son installation est en cours
+fn check_engine_health(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+) -> Option<EngineHealth> {
+    let venv_dir = get_engine_venv_path(app_handle, engine).ok()?;
+    if !venv_dir.exists() || is_venv_install_incomplete(&venv_dir) {
+        return None;
+    }
+    let python_exe = get_venv_python_exe(&venv_dir);
+    if !python_exe.exists() {
+        return Some(EngineHealth::broken(format!(
+            "{} Python executable is missing",
+            engine.as_label()
+        )));
+    }
+    let (imports_ok, missing_modules) =
+        run_python_import_check(&python_exe, engine.required_import_modules());
+    if !imports_ok {
+        return Some(EngineHealth::broken(if missing_modules.is_empty() {
+            format!("{} Python environment failed to start", engine.as_label())
+        } else {
+            format!(
+                "{} packages are incomplete (missing imports: {})",
+                engine.as_label(),
+                missing_modules.join(", ")
+            )
+        }));
+    }
+    if engine == LocalSegmentationEngine::MultiAligner {
+        let data_dir = resolve_multi_aligner_data_dir(app_handle).ok()?;
+        for data_file in required_multi_aligner_data_files() {
+            if let Err(error) = verify_multi_aligner_data_file(&data_dir.join(data_file.name)) {
+                return Some(EngineHealth::broken(format!(
+                    "Multi-Aligner data files are invalid: {}",
+                    error
+                )));
+            }
+        }
+    }
+    Some(EngineHealth::ready())
+}
+
+/// Moteurs dont l'état prêt/cassé a changé depuis la vérification précédente.
+///
+/// Un moteur absent de `previous` (première vérification, installation récente) sert de
+/// référence sans être signalé.
+fn health_changes(
+    previous: &[(LocalSegmentationEngine, EngineHealth)],
+    current: &[(LocalSegmentationEngine, EngineHealth)],
+) -> Vec<(LocalSegmentationEngine, EngineHealth)> {
+    current
+        .iter()
+        .filter(|(engine, health)| {
+            previous
+                .iter()
+                .any(|(known, previous)| known == engine && previous.ready != health.ready)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Boucle du thread de surveillance, jusqu'à la fermeture de `stop_rx`.
+fn run_health_monitor(
+    app_handle: tauri::AppHandle,
+    interval: Duration,
+    stop_rx: mpsc::Receiver<()>,
+) {
+    let mut states: Vec<(LocalSegmentationEngine, EngineHealth)> = Vec::new();
+    loop {
+        let current: Vec<(LocalSegmentationEngine, EngineHealth)> = LocalSegmentationEngine::ALL
+            .into_iter()
+            .filter_map(|engine| {
+                check_engine_health(&app_handle, engine).map(|health| (engine, health))
+            })
+            .collect();
+        let changes = health_changes(&states, &current);
+        if !changes.is_empty() {
+            // Le résultat en cache du check de préparation ne reflète plus l'état réel.
+            invalidate_local_readiness_cache();
+        }
+        for (engine, health) in changes {
+            log::warn!(
+                "[segmentation][health] engine={} ready={} reason={:?}",
+                engine.as_key(),
+                health.ready,
+                health.reason
+            );
+            let _ = app_handle.emit(
+                ENGINE_HEALTH_EVENT,
+                serde_json::json!({
+                    "engine": engine.as_key(),
+                    "ready": health.ready,
+                    "reason": health.reason,
+                }),
+            );
+        }
+        states = current;
+
+        match stop_rx.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            _ => break,
+        }
+    }
+    log::info!("[segmentation][health] monitor stopped");
+}
+
+/// Démarre la surveillance périodique des moteurs locaux (remplace un moniteur existant).
+///
+/// @param interval_minutes - Délai entre deux vérifications (1 à 1440 minutes)
+pub fn start_engine_health_monitor(
+    app_handle: tauri::AppHandle,
+    interval_minutes: u64,
+) -> Result<(), String> {
+    if !(1..=MAX_INTERVAL_MINUTES).contains(&interval_minutes) {
+        return Err(format!(
+            "interval_minutes must be between 1 and {}",
+            MAX_INTERVAL_MINUTES
+        ));
+    }
+    let mut monitor = HEALTH_MONITOR
+        .lock()
+        .map_err(|_| "Engine health monitor lock poisoned".to_string())?;
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let interval = Duration::from_secs(interval_minutes * 60);
+    std::thread::Builder::new()
+        .name("engine-health-monitor".to_string())
+        .spawn(move || run_health_monitor(app_handle, interval, stop_rx))
+        .map_err(|e| format!("Failed to start engine health monitor: {}", e))?;
+    // Remplacer l'ancien moniteur ferme son canal : son thread s'arrête de lui-même.
+    *monitor = Some(EngineHealthMonitor { stop_tx });
+    log::info!(
+        "[segmentation][health] monitor started (every {} min)",
+        interval_minutes
+    );
+    Ok(())
+}
+
+/// Arrête la surveillance des moteurs locaux.
+///
+/// Une vérification en cours se termine avant l'arrêt du thread.
+///
+/// @returns `true` si un moniteur tournait
+pub fn stop_engine_health_monitor() -> Result<bool, String> {
+    let monitor = HEALTH_MONITOR
+        .lock()
+        .map_err(|_| "Engine health monitor lock poisoned".to_string())?
+        .take();
+    match monitor {
+        Some(monitor) => {
+            let _ = monitor.stop_tx.send(());
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{health_changes, EngineHealth};
+    use crate::segmentation::types::LocalSegmentationEngine;
+
+    #[test]
+    fn only_ready_transitions_of_known_engines_are_reported() {
+        let multi = LocalSegmentationEngine::MultiAligner;
+        let legacy = LocalSegmentationEngine::LegacyWhisper;
+        let broken = EngineHealth::broken("Multi-Aligner Python executable is missing".into());
+        let previous = vec![(multi, EngineHealth::ready())];
+
+        assert!(health_changes(&[], &[(multi, broken.clone())]).is_empty());
+        assert_eq!(
+            health_changes(
+                &previous,
+                &[(multi, broken.clone()), (legacy, EngineHealth::ready())]
+            ),
+            vec![(multi, broken.clone())]
+        );
+        let other_reason = EngineHealth::broken("Multi-Aligner data files are invalid".into());
+        assert!(health_changes(&[(multi, broken)], &[(multi, other_reason)]).is_empty());
+        assert!(health_changes(&previous, &previous).is_empty());
+    }
+}
@@ -1,7 +1,6 @@
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::Command;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::Serialize;
@@ -9,6 +8,7 @@ use tauri::{AppHandle, Emitter};
 
 use crate::binaries;
 use crate::path_utils;
+use crate::utils::ffmpeg;
 use crate::utils::process::configure_command_no_window;
 use crate::utils::temp_file::TempFileGuard;
 
@@ -40,31 +40,6 @@ fn emit_hifz_progress(
     );
 }
 
-fn parse_progress_time_s(line: &str) -> Option<f64> {
-    if let Some(value) = line.strip_prefix("out_time_ms=") {
-        return value.trim().parse::<f64>().ok().map(|ms| ms / 1_000_000.0);
-    }
-    if let Some(value) = line.strip_prefix("out_time_us=") {
-        return value.trim().parse::<f64>().ok().map(|us| us / 1_000_000.0);
-    }
-    if let Some(value) = line.strip_prefix("out_time=") {
-        return parse_ffmpeg_time(value.trim());
-    }
-    None
-}
-
-fn parse_ffmpeg_time(value: &str) -> Option<f64> {
-    let parts: Vec<&str> = value.split(':').collect();
-    if parts.len() != 3 {
-        return None;
-    }
-
-    let hours = parts[0].parse::<f64>().ok()?;
-    let minutes = parts[1].parse::<f64>().ok()?;
-    let seconds = parts[2].parse::<f64>().ok()?;
-    Some(hours * 3600.0 + minutes * 60.0 + seconds)
-}
-
 fn build_hifz_filter_graph(segments: &[HifzAudioSegment]) -> Result<(String, i64), String> {
     let mut filter_lines: Vec<String> = Vec::new();
     let mut concat_inputs = String::new();
@@ -239,14 +214,11 @@ pub async fn generate_hifz_audio(
             .map_err(|e| format!("Failed to create Hifz output directory: {}", e))?;
     }
 
-    let mut cmd = Command::new(&ffmpeg_path);
-    cmd.args([
+    let args: Vec<String> = [
         "-y",
         "-hide_banner",
         "-loglevel",
         "error",
-        "-progress",
-        "pipe:2",
         "-i",
         source_audio_path.to_string_lossy().as_ref(),
         "-filter_complex_script",
@@ -262,9 +234,10 @@ pub async fn generate_hifz_audio(
         "-ac",
         "2",
         &output_path,
-    ]);
-    configure_command_no_window(&mut cmd);
-    cmd.stderr(Stdio::piped());
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
 
     emit_hifz_progress(
         &app_handle,
@@ -274,40 +247,16 @@ pub async fn generate_hifz_audio(
         "Starting Hifz audio generation...",
     );
 
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Unable to execute ffmpeg: {}", e))?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| "Failed to capture ffmpeg progress".to_string())?;
-    let reader = BufReader::new(stderr);
-    let mut stderr_content = String::new();
-
-    for line in reader.lines() {
-        let line = line.map_err(|e| format!("Failed to read ffmpeg progress: {}", e))?;
-        stderr_content.push_str(&line);
-        stderr_content.push('\n');
-
-        if let Some(current_time_s) = parse_progress_time_s(&line) {
-            let current_time_s = current_time_s.min(output_duration_s);
-            let progress = (current_time_s / output_duration_s * 100.0).clamp(0.0, 100.0);
-            emit_hifz_progress(
-                &app_handle,
-                progress,
-                current_time_s,
-                output_duration_s,
-                "Generating Hifz repetition audio...",
-            );
-        }
-    }
-
-    let status = child
-        .wait()
-        .map_err(|e| format!("Unable to wait for ffmpeg: {}", e))?;
-    if !status.success() {
-        return Err(format!("ffmpeg Hifz audio error: {}", stderr_content));
-    }
+    ffmpeg::run_with_progress_callback(&args, output_duration_ms.max(1) as u64, None, |progress| {
+        emit_hifz_progress(
+            &app_handle,
+            progress.percent,
+            progress.current_time_ms as f64 / 1000.0,
+            output_duration_s,
+            "Generating Hifz repetition audio...",
+        );
+    })
+    .map_err(|e| format!("Hifz audio generation failed: {}", e))?;
 
     emit_hifz_progress(
         &app_handle,
@@ -325,7 +274,7 @@ pub async fn generate_hifz_audio(
 
 #[cfg(test)]
 mod tests {
-    use super::{build_hifz_filter_graph, parse_ffmpeg_time, parse_progress_time_s};
+    use super::build_hifz_filter_graph;
     use crate::segmentation::types::HifzAudioSegment;
 
     #[test]
@@ -365,15 +314,4 @@ mod tests {
         assert!(graph.contains("concat=n=6:v=0:a=1[outa]"));
         assert_eq!(duration_ms, 2250);
     }
-
-    #[test]
-    fn parses_ffmpeg_progress_time_values() {
-        assert_eq!(parse_progress_time_s("out_time_ms=2500000"), Some(2.5));
-        assert_eq!(parse_progress_time_s("out_time_us=1500000"), Some(1.5));
-        assert_eq!(
-            parse_progress_time_s("out_time=00:01:02.500000"),
-            Some(62.5)
-        );
-        assert_eq!(parse_ffmpeg_time("invalid"), None);
-    }
 }
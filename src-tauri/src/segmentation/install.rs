@@ -1,59 +1,176 @@
 use std::fs;
-use std::process::Command;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use serde::Serialize;
 use tauri::Emitter;
 
-use crate::utils::process::configure_command_no_window;
+use crate::commands::files::{build_download_client, download_with_resume};
+use crate::utils::process::{
+    configure_command_no_window, kill_tracked_process, output_with_timeout, track_process,
+    untrack_process, wait_tracked_process, PROBE_TIMEOUT,
+};
+use crate::utils::temp_file::TempPathsGuard;
 
 use super::data_files::{
-    required_multi_aligner_data_files, resolve_multi_aligner_data_dir,
-    validate_multi_aligner_data_file,
+    required_multi_aligner_data_files, resolve_data_mirror_url, resolve_multi_aligner_data_dir,
+    validate_multi_aligner_data_file, MultiAlignerDataFile,
+};
+use super::devices::has_rocm_gpu;
+use super::disk_check::check_install_disk_space;
+use super::install_log::InstallLog;
+use super::install_progress::{
+    for_each_output_line, pip_supports_raw_progress_bar, InstallPhase, InstallProgress,
+};
+use super::managed_python::resolve_base_python;
+use super::model_cache::apply_hf_cache_env;
+use super::offline::{
+    check_offline_wheels_dir, offline_pip_args, parse_missing_pip_packages,
+    resolve_offline_wheels_dir, PIP_TOOLING_PACKAGES, TORCH_CPU_INDEX_URL, TORCH_PACKAGES,
 };
+use super::pip_index::{redact_index_url, PipIndexOptions};
+use super::proxy::{remember_install_proxy, ProxySettings};
 use super::python_env::{
-    apply_hf_token_env, create_venv_if_missing, get_venv_python_exe, resolve_python_resource_path,
-    resolve_system_python, MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR,
+    apply_hf_token_env, clear_venv_install_incomplete, create_venv_if_missing,
+    get_engine_venv_path, get_pip_cache_dir, get_venv_python_exe, mark_venv_install_incomplete,
+    resolve_python_resource_path, MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR,
 };
 use super::requirements::{
     prepare_multi_requirements_file, prepare_windows_safe_quranic_phonemizer_source,
+    read_install_requirements, QURANIC_PHONEMIZER_ARCHIVE_FILE, QURANIC_PHONEMIZER_ARCHIVE_URL,
 };
-use super::types::LocalSegmentationEngine;
+use super::status::invalidate_local_readiness_cache;
+use super::types::{LocalSegmentationEngine, IS_APPLE_SILICON};
+use super::version_pins::{set_venv_unpinned, write_constraints_file};
 
-/// Installs Python dependencies for the selected local engine.
-/// Downloads a remote binary file and writes it locally.
-async fn download_binary_file(url: &str, destination_path: &std::path::Path) -> Result<(), String> {
-    let response = reqwest::get(url)
-        .await
-        .map_err(|e| format!("Failed to download '{}': {}", url, e))?;
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to download '{}': HTTP {}",
-            url,
-            response.status()
-        ));
-    }
+/// Lignes de sortie pip conservées pour les messages d'erreur.
+const PIP_OUTPUT_TAIL_LINES: usize = 40;
 
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read downloaded bytes from '{}': {}", url, e))?;
-    if bytes.is_empty() {
-        return Err(format!("Downloaded file from '{}' is empty", url));
+/// Erreur renvoyée par une installation interrompue via `cancel_local_install`.
+const INSTALL_CANCELLED_ERROR: &str = "Local dependency installation was cancelled";
+
+/// Moteurs dont l'installation en cours doit s'arrêter (vérifié avant chaque étape).
+static CANCELLED_INSTALLS: Mutex<Vec<LocalSegmentationEngine>> = Mutex::new(Vec::new());
+
+/// Identifiant du processus pip/python suivi pendant l'installation d'un moteur.
+fn install_process_id(engine: LocalSegmentationEngine) -> String {
+    format!("local-install-{}", engine.as_key())
+}
+
+/// Retire la demande d'annulation d'un moteur (début d'une nouvelle installation).
+fn clear_install_cancelled(engine: LocalSegmentationEngine) {
+    if let Ok(mut cancelled) = CANCELLED_INSTALLS.lock() {
+        cancelled.retain(|item| *item != engine);
     }
+}
 
-    fs::write(destination_path, &bytes).map_err(|e| {
-        format!(
-            "Failed to write '{}': {}",
-            destination_path.to_string_lossy(),
-            e
-        )
-    })?;
+/// Retourne l'erreur d'annulation si l'installation du moteur a été annulée.
+fn ensure_install_not_cancelled(engine: LocalSegmentationEngine) -> Result<(), String> {
+    let cancelled = CANCELLED_INSTALLS
+        .lock()
+        .map(|cancelled| cancelled.contains(&engine))
+        .unwrap_or(false);
+    if cancelled {
+        return Err(INSTALL_CANCELLED_ERROR.to_string());
+    }
     Ok(())
 }
 
+/// Annule l'installation en cours d'un moteur local.
+///
+/// Le processus pip/python actif (et ses descendants) est tué ; l'installation s'arrête
+/// avec une erreur d'annulation et le venv reste marqué incomplet jusqu'à la prochaine
+/// installation réussie.
+///
+/// @param engine - Moteur dont l'installation doit s'arrêter
+/// @returns `true` si un processus d'installation en cours a été arrêté
+pub fn cancel_local_install(engine: String) -> Result<bool, String> {
+    let selected_engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    CANCELLED_INSTALLS
+        .lock()
+        .map_err(|_| "Install cancellation lock poisoned".to_string())?
+        .push(selected_engine);
+    kill_tracked_process(&install_process_id(selected_engine))
+}
+
+/// Télécharge un fichier data Multi-Aligner puis le valide.
+///
+/// La source suivante (voir `MultiAlignerDataFile::download_urls`) est essayée quand une
+/// source échoue (après les reprises de `download_with_resume`) ou livre un fichier
+/// invalide ; la validation suit toujours la source qui a répondu.
+///
+/// @param data_mirror_url - Miroir choisi par l'utilisateur, essayé en premier
+/// @param on_status - Message de statut (passage à la source suivante)
+/// @param on_downloaded - Octets reçus pour la source en cours
+async fn download_multi_aligner_data_file(
+    client: &reqwest::Client,
+    data_file: &MultiAlignerDataFile,
+    file_path: &std::path::Path,
+    data_mirror_url: Option<&str>,
+    on_status: impl Fn(&str),
+    mut on_downloaded: impl FnMut(u64),
+) -> Result<(), String> {
+    let mut errors: Vec<String> = Vec::new();
+    for url in data_file.download_urls(data_mirror_url) {
+        if !errors.is_empty() {
+            on_status(&format!(
+                "Retrying {} from another source...",
+                data_file.name
+            ));
+        }
+        let mut downloaded_bytes: u64 = 0;
+        let result = match download_with_resume(client, &url, file_path, |delta| {
+            downloaded_bytes = downloaded_bytes.saturating_add_signed(delta);
+            on_downloaded(downloaded_bytes);
+        })
+        .await
+        {
+            Ok(_) => validate_multi_aligner_data_file(file_path.to_path_buf()).await,
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(error) => {
+                log::warn!(
+                    "[segmentation][install] {} from {} failed: {}",
+                    data_file.name,
+                    url,
+                    error
+                );
+                let _ = fs::remove_file(file_path);
+                errors.push(error);
+            }
+        }
+    }
+    Err(format!(
+        "Failed to download Multi-Aligner data file '{}': {}",
+        data_file.name,
+        errors.join(" | ")
+    ))
+}
+
 /// Validates Multi-Aligner data files and re-downloads invalid ones.
+///
+/// Un fichier est invalide si son en-tête, sa taille ou son SHA-256 ne correspondent pas.
+/// En mode hors ligne, les fichiers invalides sont copiés depuis `offline_dir` ; sinon ils
+/// sont téléchargés (miroir en repli), via `proxy` s'il est défini.
+///
+/// @param data_mirror_url - URL de base d'un miroir des fichiers data, essayée en premier
+/// @param on_progress - Avancement de la phase (0..1) et message de statut
 async fn ensure_multi_aligner_data_files(
     app_handle: &tauri::AppHandle,
+    offline_dir: Option<&std::path::Path>,
+    proxy: Option<&ProxySettings>,
+    data_mirror_url: Option<&str>,
+    on_progress: impl Fn(f64, &str),
 ) -> Result<Vec<String>, String> {
+    let client = match proxy {
+        Some(proxy) => proxy.http_client()?,
+        None => build_download_client()?,
+    };
     let data_dir = resolve_multi_aligner_data_dir(app_handle)?;
     fs::create_dir_all(&data_dir).map_err(|e| {
         format!(
@@ -64,64 +181,407 @@ async fn ensure_multi_aligner_data_files(
     })?;
 
     let mut repaired_files: Vec<String> = Vec::new();
-    for (file_name, url) in required_multi_aligner_data_files() {
-        let file_path = data_dir.join(file_name);
-        if validate_multi_aligner_data_file(&file_path).is_ok() {
-            continue;
+    let data_files = required_multi_aligner_data_files();
+    let total = data_files.len() as f64;
+    for (index, data_file) in data_files.iter().enumerate() {
+        let file_path = data_dir.join(data_file.name);
+        on_progress(
+            index as f64 / total,
+            &format!("Checking Multi-Aligner data file {}...", data_file.name),
+        );
+        if let Err(error) = validate_multi_aligner_data_file(file_path.clone()).await {
+            log::warn!("[segmentation][install] {}", error);
+            if let Some(offline_dir) = offline_dir {
+                fs::copy(offline_dir.join(data_file.name), &file_path).map_err(|e| {
+                    format!(
+                        "Failed to copy '{}' from offline wheels directory: {}",
+                        data_file.name, e
+                    )
+                })?;
+                if let Err(error) = validate_multi_aligner_data_file(file_path.clone()).await {
+                    let _ = fs::remove_file(&file_path);
+                    return Err(error);
+                }
+            } else {
+                let mut last_emit = Instant::now();
+                let on_status = |message: &str| on_progress(index as f64 / total, message);
+                let on_downloaded = |downloaded_bytes: u64| {
+                    if last_emit.elapsed() >= Duration::from_millis(250) {
+                        last_emit = Instant::now();
+                        let file_fraction =
+                            downloaded_bytes as f64 / data_file.size_bytes.max(1) as f64;
+                        on_progress(
+                            (index as f64 + file_fraction.min(1.0)) / total,
+                            &format!(
+                                "Downloading {} ({:.1} / {:.1} MB)...",
+                                data_file.name,
+                                downloaded_bytes as f64 / 1_048_576.0,
+                                data_file.size_bytes as f64 / 1_048_576.0
+                            ),
+                        );
+                    }
+                };
+                download_multi_aligner_data_file(
+                    &client,
+                    data_file,
+                    &file_path,
+                    data_mirror_url,
+                    on_status,
+                    on_downloaded,
+                )
+                .await?;
+            }
+            repaired_files.push(data_file.name.to_string());
         }
-
-        download_binary_file(url, &file_path).await?;
-        validate_multi_aligner_data_file(&file_path)?;
-        repaired_files.push((*file_name).to_string());
+        on_progress(
+            (index + 1) as f64 / total,
+            "Checking Multi-Aligner data files...",
+        );
     }
 
     Ok(repaired_files)
 }
 
+/// Résultat de l'installation d'un moteur dans `install_local_segmentation_engines`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalEngineInstallResult {
+    /// Clé du moteur (`legacy`, `multi`, ...).
+    pub engine: String,
+    /// `true` si l'installation a abouti.
+    pub success: bool,
+    /// Message de succès ou erreur.
+    pub message: String,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn install_local_segmentation_deps(
     app_handle: tauri::AppHandle,
     engine: String,
     hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: Option<bool>,
+    ignore_disk_check: Option<bool>,
 ) -> Result<String, String> {
     let selected_engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    install_engine_deps(
+        app_handle,
+        selected_engine,
+        hf_token,
+        offline_wheels_dir,
+        pip_index_url,
+        extra_index_url,
+        torch_index_url,
+        proxy_url,
+        data_mirror_url,
+        latest.unwrap_or(false),
+        ignore_disk_check.unwrap_or(false),
+        None,
+    )
+    .await
+}
+
+/// Installe plusieurs moteurs à la suite, chacun dans son venv.
+///
+/// Toutes les installations partagent le cache pip des données de l'application : les
+/// wheels PyTorch téléchargées pour le premier moteur sont reprises par les suivants.
+/// L'échec d'un moteur n'annule pas les autres ; `install-status` porte la progression
+/// du moteur courant (`percent`) et de l'ensemble (`overallPercent`).
+///
+/// @param engines - Moteurs à installer, dans l'ordre (`legacy`, `multi`, `muaalem`, `surah_splitter`)
+/// @returns Résultat de chaque moteur, dans l'ordre d'installation
+#[allow(clippy::too_many_arguments)]
+pub async fn install_local_segmentation_engines(
+    app_handle: tauri::AppHandle,
+    engines: Vec<String>,
+    hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: Option<bool>,
+    ignore_disk_check: Option<bool>,
+) -> Result<Vec<LocalEngineInstallResult>, String> {
+    let mut selected_engines: Vec<LocalSegmentationEngine> = Vec::new();
+    for raw in &engines {
+        let engine = LocalSegmentationEngine::from_raw(raw.as_str())?;
+        if !selected_engines.contains(&engine) {
+            selected_engines.push(engine);
+        }
+    }
+    if selected_engines.is_empty() {
+        return Err("No local engine selected for installation".to_string());
+    }
+
+    let engine_count = selected_engines.len();
+    let mut results = Vec::with_capacity(engine_count);
+    for (index, engine) in selected_engines.into_iter().enumerate() {
+        let result = install_engine_deps(
+            app_handle.clone(),
+            engine,
+            hf_token.clone(),
+            offline_wheels_dir.clone(),
+            pip_index_url.clone(),
+            extra_index_url.clone(),
+            torch_index_url.clone(),
+            proxy_url.clone(),
+            data_mirror_url.clone(),
+            latest.unwrap_or(false),
+            ignore_disk_check.unwrap_or(false),
+            Some((index, engine_count)),
+        )
+        .await;
+        log::info!(
+            "[segmentation][install] batch engine={} ({}/{}) success={}",
+            engine.as_key(),
+            index + 1,
+            engine_count,
+            result.is_ok()
+        );
+        results.push(LocalEngineInstallResult {
+            engine: engine.as_key().to_string(),
+            success: result.is_ok(),
+            message: result.unwrap_or_else(|error| error),
+        });
+    }
+    Ok(results)
+}
+
+/// Installe les dépendances d'un moteur ; `batch` situe le moteur dans une installation groupée.
+///
+/// Chaque commande pip/python est journalisée en entier dans `logs/install` ; le chemin du
+/// journal est ajouté au message de succès comme à toute erreur.
+#[allow(clippy::too_many_arguments)]
+async fn install_engine_deps(
+    app_handle: tauri::AppHandle,
+    selected_engine: LocalSegmentationEngine,
+    hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: bool,
+    ignore_disk_check: bool,
+    batch: Option<(usize, usize)>,
+) -> Result<String, String> {
+    // Un journal impossible à créer ne doit pas bloquer l'installation.
+    let install_log = match InstallLog::create(&app_handle, selected_engine.as_key()) {
+        Ok(install_log) => Some(install_log),
+        Err(error) => {
+            log::warn!("[segmentation][install] {}", error);
+            None
+        }
+    };
+    let result = run_engine_install(
+        app_handle,
+        selected_engine,
+        hf_token,
+        offline_wheels_dir,
+        pip_index_url,
+        extra_index_url,
+        torch_index_url,
+        proxy_url,
+        data_mirror_url,
+        latest,
+        ignore_disk_check,
+        batch,
+        install_log.as_ref(),
+    )
+    .await;
+    // Même un échec peut avoir modifié le venv : le prochain check doit être complet.
+    invalidate_local_readiness_cache();
+    match install_log {
+        Some(install_log) => install_log.finish(result),
+        None => result,
+    }
+}
+
+/// Corps de `install_engine_deps`, qui alimente le journal d'installation s'il existe.
+#[allow(clippy::too_many_arguments)]
+async fn run_engine_install(
+    app_handle: tauri::AppHandle,
+    selected_engine: LocalSegmentationEngine,
+    hf_token: Option<String>,
+    offline_wheels_dir: Option<String>,
+    pip_index_url: Option<String>,
+    extra_index_url: Option<String>,
+    torch_index_url: Option<String>,
+    proxy_url: Option<String>,
+    data_mirror_url: Option<String>,
+    latest: bool,
+    ignore_disk_check: bool,
+    batch: Option<(usize, usize)>,
+    install_log: Option<&InstallLog>,
+) -> Result<String, String> {
+    let offline_dir = offline_wheels_dir
+        .as_deref()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(resolve_offline_wheels_dir)
+        .transpose()?;
+    let index_options = PipIndexOptions::from_raw(
+        pip_index_url.as_deref(),
+        extra_index_url.as_deref(),
+        torch_index_url.as_deref(),
+    )?;
+    let proxy = ProxySettings::resolve(proxy_url.as_deref())?;
+    remember_install_proxy(proxy.as_ref());
+    let data_mirror_url = resolve_data_mirror_url(data_mirror_url.as_deref())?;
+    if offline_dir.is_some() && index_options.is_custom() {
+        return Err(
+            "Custom package index URLs cannot be combined with an offline wheels directory"
+                .to_string(),
+        );
+    }
+    check_install_disk_space(
+        selected_engine,
+        &get_engine_venv_path(&app_handle, selected_engine)?,
+        ignore_disk_check,
+    )?;
+    clear_install_cancelled(selected_engine);
+    let process_id = install_process_id(selected_engine);
+    let progress = Mutex::new(InstallProgress::new(selected_engine).with_batch(batch));
     let emit_status = |message: &str| {
-        let _ = app_handle.emit("install-status", serde_json::json!({ "message": message }));
+        if let Some(install_log) = install_log {
+            install_log.append_status(message);
+        }
+        if let Ok(progress) = progress.lock() {
+            let _ = app_handle.emit("install-status", progress.status(message));
+        }
+    };
+    let start_phase = |phase: InstallPhase, message: &str| -> Result<(), String> {
+        ensure_install_not_cancelled(selected_engine)?;
+        if let Ok(mut progress) = progress.lock() {
+            progress.start_phase(phase);
+        }
+        emit_status(message);
+        Ok(())
     };
 
-    // Validate system Python and prepare the dedicated venv.
-    let system_python = resolve_system_python(MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR)
-        .map_err(|e| {
-            format!(
-                "Python {}.{}+ is required to install local dependencies: {}",
-                MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR, e
-            )
-        })?;
+    // Validate the base Python (managed or system) and prepare the dedicated venv.
+    let base_python = resolve_base_python(&app_handle).map_err(|e| {
+        format!(
+            "Python {}.{}+ is required to install local dependencies: {}",
+            MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR, e
+        )
+    })?;
     emit_status(&format!(
         "Using Python {}.{}.{} ({})",
-        system_python.major, system_python.minor, system_python.patch, system_python.executable
+        base_python.major, base_python.minor, base_python.patch, base_python.executable
     ));
     emit_status(&format!(
         "Preparing {} local environment...",
         selected_engine.as_label()
     ));
     let venv_dir = create_venv_if_missing(&app_handle, selected_engine)?;
+    mark_venv_install_incomplete(&venv_dir)?;
     let python_exe = get_venv_python_exe(&venv_dir);
     let normalized_hf_token = hf_token
         .as_ref()
         .map(|token| token.trim().to_string())
         .filter(|token| !token.is_empty());
 
-    let run_python_cmd = |args: &[&str], context: &str| -> Result<(), String> {
+    // pip est lu en continu pour relayer la progression des téléchargements.
+    let run_python_cmd = |args: &[&str], context: &str, message: &str| -> Result<(), String> {
+        ensure_install_not_cancelled(selected_engine)?;
         let mut cmd = Command::new(&python_exe);
         cmd.args(args);
+        if let Some(proxy) = proxy.as_ref() {
+            proxy.apply_env(&mut cmd);
+        }
+        if let Some(install_log) = install_log {
+            // Les identifiants du proxy et des index ne doivent pas apparaître dans le journal.
+            let logged_args: Vec<String> = args
+                .iter()
+                .map(|arg| match proxy.as_ref() {
+                    Some(proxy) if *arg == proxy.url => proxy.redacted_url(),
+                    _ if arg.starts_with("https://") => redact_index_url(arg),
+                    _ => arg.to_string(),
+                })
+                .collect();
+            let logged_args: Vec<&str> = logged_args.iter().map(String::as_str).collect();
+            install_log.begin_command(&python_exe.to_string_lossy(), &logged_args);
+        }
         if let Some(token) = normalized_hf_token.as_deref() {
             apply_hf_token_env(&mut cmd, token);
         }
+        apply_hf_cache_env(&mut cmd);
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
         configure_command_no_window(&mut cmd);
-        let output = cmd
-            .output()
+        let mut child = cmd
+            .spawn()
             .map_err(|e| format!("{}: failed to run python: {}", context, e))?;
+        let stderr_handle = child.stderr.take().map(|mut stderr| {
+            std::thread::spawn(move || {
+                let mut buffer = Vec::new();
+                let _ = stderr.read_to_end(&mut buffer);
+                buffer
+            })
+        });
+
+        let stdout = child.stdout.take();
+        let process = track_process(&process_id, child);
+
+        let mut stdout_tail: Vec<String> = Vec::new();
+        if let Some(stdout) = stdout {
+            for_each_output_line(stdout, |line| {
+                let changed = progress
+                    .lock()
+                    .map(|mut progress| progress.apply_pip_line(line))
+                    .unwrap_or(false);
+                if changed {
+                    emit_status(message);
+                }
+                if let Some(install_log) = install_log {
+                    install_log.append_stdout_line(line);
+                }
+                if stdout_tail.len() >= PIP_OUTPUT_TAIL_LINES {
+                    stdout_tail.remove(0);
+                }
+                stdout_tail.push(line.to_string());
+            });
+        }
+        let status = wait_tracked_process(&process);
+        untrack_process(&process_id);
+        let Some(status) =
+            status.map_err(|e| format!("{}: failed to run python: {}", context, e))?
+        else {
+            if let Some(install_log) = install_log {
+                install_log.end_command(&[], "cancelled");
+            }
+            return Err(INSTALL_CANCELLED_ERROR.to_string());
+        };
+        let output = std::process::Output {
+            status,
+            stdout: stdout_tail.join("\n").into_bytes(),
+            stderr: stderr_handle
+                .and_then(|handle| handle.join().ok())
+                .unwrap_or_default(),
+        };
+        if let Some(install_log) = install_log {
+            install_log.end_command(&output.stderr, &output.status.to_string());
+        }
         if !output.status.success() {
+            if let Some(wheels_dir) = offline_dir.as_deref() {
+                let missing = parse_missing_pip_packages(&String::from_utf8_lossy(&output.stderr));
+                if !missing.is_empty() {
+                    return Err(format!(
+                        "{}: packages missing from offline wheels directory '{}': {}",
+                        context,
+                        wheels_dir.to_string_lossy(),
+                        missing.join(", ")
+                    ));
+                }
+            }
             return Err(format!(
                 "{}: {}",
                 context,
@@ -130,25 +590,175 @@ pub async fn install_local_segmentation_deps(
         }
         Ok(())
     };
+    // `pip install` avec la source de paquets choisie (index en ligne ou dossier hors ligne).
+    let pip_source_args: Vec<String> = offline_dir
+        .as_deref()
+        .map(offline_pip_args)
+        .unwrap_or_else(|| index_options.pip_args());
+    // Cache pip partagé par tous les moteurs (PyTorch n'est téléchargé qu'une fois).
+    let pip_cache_dir = get_pip_cache_dir(&app_handle)?
+        .to_string_lossy()
+        .to_string();
+    let proxy_args: Vec<String> = proxy
+        .as_ref()
+        .map(ProxySettings::pip_args)
+        .unwrap_or_default();
+    // Fichiers et dossiers temporaires de l'installation, supprimés même en cas d'échec.
+    let mut temp_paths = TempPathsGuard::default();
+    // Versions figées appliquées à chaque `pip install`, sauf avec `latest`.
+    let constraints_args: Vec<String> = if latest {
+        Vec::new()
+    } else {
+        let constraints_path = write_constraints_file(selected_engine)?;
+        temp_paths.add(&constraints_path);
+        vec![
+            "-c".to_string(),
+            constraints_path.to_string_lossy().to_string(),
+        ]
+    };
+    // `--progress-bar raw` n'existe qu'à partir de pip 24.1 : activé après la mise à jour
+    // de pip, si la version installée le permet.
+    let pip_raw_progress = AtomicBool::new(false);
+    let pip_install_from = |packages: &[&str],
+                            source_args: &[String],
+                            context: &str,
+                            message: &str|
+     -> Result<(), String> {
+        let mut args = vec!["-m", "pip", "install"];
+        args.extend_from_slice(packages);
+        if pip_raw_progress.load(Ordering::Relaxed) {
+            args.extend(["--progress-bar", "raw"]);
+        }
+        args.extend(["--cache-dir", pip_cache_dir.as_str()]);
+        args.extend(source_args.iter().map(String::as_str));
+        args.extend(proxy_args.iter().map(String::as_str));
+        args.extend(constraints_args.iter().map(String::as_str));
+        run_python_cmd(&args, context, message)
+    };
+    let pip_install = |packages: &[&str], context: &str, message: &str| -> Result<(), String> {
+        pip_install_from(packages, &pip_source_args, context, message)
+    };
+    // Les index PyTorch remplacent l'index principal (pas de cumul de `--index-url`).
+    let torch_index_args = |index_url: &str| vec!["--index-url".to_string(), index_url.to_string()];
+
+    // Requirements lus en amont : le mode hors ligne vérifie le dossier avant toute étape.
+    let requirements_path =
+        resolve_python_resource_path(&app_handle, selected_engine.requirements_relative_path())?;
+    let requirements_path = if matches!(selected_engine, LocalSegmentationEngine::MultiAligner) {
+        let patched_path = prepare_multi_requirements_file(&requirements_path)?;
+        temp_paths.add(&patched_path);
+        patched_path
+    } else {
+        requirements_path
+    };
+    let requirements = read_install_requirements(&requirements_path)?;
+    if let Some(wheels_dir) = offline_dir.as_deref() {
+        let mut invalid_data_files: Vec<&str> = Vec::new();
+        if matches!(selected_engine, LocalSegmentationEngine::MultiAligner) {
+            let data_dir = resolve_multi_aligner_data_dir(&app_handle)?;
+            for data_file in required_multi_aligner_data_files() {
+                if validate_multi_aligner_data_file(data_dir.join(data_file.name))
+                    .await
+                    .is_err()
+                {
+                    invalid_data_files.push(data_file.name);
+                }
+            }
+        }
+        check_offline_wheels_dir(
+            wheels_dir,
+            selected_engine,
+            &requirements,
+            &invalid_data_files,
+        )?;
+        emit_status(&format!(
+            "Installing from offline wheels directory {}",
+            wheels_dir.to_string_lossy()
+        ));
+    }
+    if index_options.is_custom() {
+        emit_status(&index_options.describe());
+    }
+    if let Some(proxy) = proxy.as_ref() {
+        emit_status(&proxy.describe());
+    }
+    if let (Some(mirror), LocalSegmentationEngine::MultiAligner) =
+        (data_mirror_url.as_deref(), selected_engine)
+    {
+        emit_status(&format!("Using Multi-Aligner data mirror {}", mirror));
+    }
+    if latest {
+        emit_status("Installing latest package versions (version pins disabled)");
+    }
 
     // Installation outillage pip + torch (CUDA si possible, CPU fallback).
-    emit_status("Upgrading pip...");
-    run_python_cmd(
-        &[
-            "-m",
-            "pip",
-            "install",
-            "--upgrade",
-            "pip",
-            "setuptools",
-            "wheel",
-            "--quiet",
-        ],
-        "Failed to upgrade pip",
-    )?;
+    start_phase(InstallPhase::PipUpgrade, "Upgrading pip...")?;
+    let mut pip_tooling = vec!["--upgrade"];
+    pip_tooling.extend(PIP_TOOLING_PACKAGES);
+    pip_install(&pip_tooling, "Failed to upgrade pip", "Upgrading pip...")?;
+    let mut pip_version_cmd = Command::new(&python_exe);
+    pip_version_cmd.args(["-m", "pip", "--version"]);
+    configure_command_no_window(&mut pip_version_cmd);
+    pip_raw_progress.store(
+        output_with_timeout(&mut pip_version_cmd, PROBE_TIMEOUT)
+            .map(|output| pip_supports_raw_progress_bar(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or(false),
+        Ordering::Relaxed,
+    );
 
-    if cfg!(target_os = "windows") {
-        emit_status("Installing PyTorch (CPU fallback available)...");
+    let mut torch_packages = vec!["--upgrade"];
+    torch_packages.extend(TORCH_PACKAGES);
+    // Vérifie que torch voit le GPU (CUDA, ou ROCm qui expose l'API CUDA).
+    let verify_torch_gpu = || {
+        let verify_args = [
+            "-c",
+            "import torch; assert torch.cuda.is_available(), 'cuda not available'",
+        ];
+        let mut verify_cmd = Command::new(&python_exe);
+        verify_cmd.args(verify_args);
+        configure_command_no_window(&mut verify_cmd);
+        // Borné : un import ROCm/CUDA bloqué ne doit pas figer l'installation.
+        let verify_output = output_with_timeout(&mut verify_cmd, PROBE_TIMEOUT);
+        match (install_log, &verify_output) {
+            (Some(install_log), Ok(output)) => {
+                install_log.append_output(&python_exe.to_string_lossy(), &verify_args, output)
+            }
+            (_, Err(error)) => {
+                log::warn!("[segmentation][install] torch GPU check failed: {}", error)
+            }
+            _ => {}
+        }
+        verify_output
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    };
+    if offline_dir.is_some() {
+        start_phase(
+            InstallPhase::Torch,
+            "Installing PyTorch from offline wheels...",
+        )?;
+        pip_install(
+            &torch_packages,
+            "Failed to install PyTorch",
+            "Installing PyTorch from offline wheels...",
+        )?;
+    } else if let Some(torch_index_url) = index_options.torch_index_url.as_deref() {
+        let message = format!(
+            "Installing PyTorch from {}...",
+            redact_index_url(torch_index_url)
+        );
+        start_phase(InstallPhase::Torch, &message)?;
+        pip_install_from(
+            &torch_packages,
+            &torch_index_args(torch_index_url),
+            "Failed to install PyTorch",
+            &message,
+        )?;
+    } else if cfg!(target_os = "windows") {
+        start_phase(
+            InstallPhase::Torch,
+            "Installing PyTorch (CPU fallback available)...",
+        )?;
         let mut cuda_installed = false;
         let mut nvidia_cmd = Command::new("nvidia-smi");
         configure_command_no_window(&mut nvidia_cmd);
@@ -164,111 +774,86 @@ pub async fn install_local_segmentation_deps(
                 "https://download.pytorch.org/whl/cu118",
             ] {
                 emit_status(&format!("Trying CUDA PyTorch from {}...", index_url));
-                let result = run_python_cmd(
-                    &[
-                        "-m",
-                        "pip",
-                        "install",
-                        "--upgrade",
-                        "torch",
-                        "torchvision",
-                        "torchaudio",
-                        "--index-url",
-                        index_url,
-                        "--quiet",
-                    ],
+                let result = pip_install_from(
+                    &torch_packages,
+                    &torch_index_args(index_url),
                     "Failed to install CUDA PyTorch",
+                    "Installing CUDA PyTorch...",
                 );
-                if result.is_ok() {
-                    let mut verify_cuda = Command::new(&python_exe);
-                    verify_cuda.args([
-                        "-c",
-                        "import torch; assert torch.cuda.is_available(), 'cuda not available'",
-                    ]);
-                    configure_command_no_window(&mut verify_cuda);
-                    if verify_cuda
-                        .output()
-                        .map(|output| output.status.success())
-                        .unwrap_or(false)
-                    {
-                        cuda_installed = true;
-                        break;
-                    }
+                if result.is_ok() && verify_torch_gpu() {
+                    cuda_installed = true;
+                    break;
                 }
             }
         }
 
         if !cuda_installed {
             emit_status("Installing PyTorch CPU build...");
-            run_python_cmd(
-                &[
-                    "-m",
-                    "pip",
-                    "install",
-                    "--upgrade",
-                    "torch",
-                    "torchvision",
-                    "torchaudio",
-                    "--index-url",
-                    "https://download.pytorch.org/whl/cpu",
-                    "--quiet",
-                ],
+            pip_install_from(
+                &torch_packages,
+                &torch_index_args(TORCH_CPU_INDEX_URL),
                 "Failed to install CPU PyTorch",
+                "Installing PyTorch CPU build...",
             )?;
         }
+    } else if cfg!(target_os = "linux") && has_rocm_gpu() {
+        start_phase(
+            InstallPhase::Torch,
+            "Installing ROCm PyTorch (CPU fallback available)...",
+        )?;
+        let mut rocm_installed = false;
+        for index_url in [
+            "https://download.pytorch.org/whl/rocm6.4",
+            "https://download.pytorch.org/whl/rocm6.3",
+            "https://download.pytorch.org/whl/rocm6.2.4",
+        ] {
+            emit_status(&format!("Trying ROCm PyTorch from {}...", index_url));
+            let result = pip_install_from(
+                &torch_packages,
+                &torch_index_args(index_url),
+                "Failed to install ROCm PyTorch",
+                "Installing ROCm PyTorch...",
+            );
+            if result.is_ok() && verify_torch_gpu() {
+                rocm_installed = true;
+                break;
+            }
+        }
+
+        if !rocm_installed {
+            emit_status("Installing PyTorch CPU build...");
+            pip_install_from(
+                &torch_packages,
+                &torch_index_args(TORCH_CPU_INDEX_URL),
+                "Failed to install CPU PyTorch",
+                "Installing PyTorch CPU build...",
+            )?;
+        }
+    } else if cfg!(target_os = "macos") {
+        // Les wheels macOS par défaut incluent le backend MPS : aucune sonde CUDA.
+        let message = if IS_APPLE_SILICON {
+            "Installing PyTorch (Apple Silicon MPS supported)..."
+        } else {
+            "Installing PyTorch..."
+        };
+        start_phase(InstallPhase::Torch, message)?;
+        pip_install(&torch_packages, "Failed to install PyTorch", message)?;
     } else {
-        emit_status("Installing PyTorch...");
-        run_python_cmd(
-            &[
-                "-m",
-                "pip",
-                "install",
-                "--upgrade",
-                "torch",
-                "torchvision",
-                "torchaudio",
-                "--quiet",
-            ],
+        start_phase(InstallPhase::Torch, "Installing PyTorch...")?;
+        pip_install(
+            &torch_packages,
             "Failed to install PyTorch",
+            "Installing PyTorch...",
         )?;
     }
 
     // Install non-torch requirements and skip phonemizer Git dependency.
-    let requirements_path =
-        resolve_python_resource_path(&app_handle, selected_engine.requirements_relative_path())?;
-    let requirements_path = if matches!(selected_engine, LocalSegmentationEngine::MultiAligner) {
-        prepare_multi_requirements_file(&requirements_path)?
-    } else {
-        requirements_path
-    };
-    let requirements_content = fs::read_to_string(&requirements_path).map_err(|e| {
-        format!(
-            "Failed to read requirements '{}': {}",
-            requirements_path.to_string_lossy(),
-            e
-        )
-    })?;
-    let filtered_requirements: String = requirements_content
-        .lines()
-        .filter(|line| {
-            let trimmed = line.trim().to_lowercase();
-            if trimmed.is_empty() || trimmed.starts_with('#') {
-                return false;
-            }
-            let is_quranic_phonemizer_dep = trimmed
-                .starts_with("git+https://github.com/hetchy/quranic-phonemizer.git@")
-                || trimmed.contains("quranic-phonemizer");
-            !(trimmed.starts_with("torch")
-                || trimmed.starts_with("torchvision")
-                || trimmed.starts_with("torchaudio")
-                || is_quranic_phonemizer_dep)
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+    let filtered_requirements = requirements.join("\n");
     let filtered_requirements_path = std::env::temp_dir().join(format!(
         "qurancaption_requirements_{}.txt",
         selected_engine.as_key()
     ));
+    temp_paths.add(&filtered_requirements_path);
     fs::write(&filtered_requirements_path, filtered_requirements).map_err(|e| {
         format!(
             "Failed to write filtered requirements '{}': {}",
@@ -277,23 +862,32 @@ pub async fn install_local_segmentation_deps(
         )
     })?;
 
-    emit_status("Installing Python packages...");
-    run_python_cmd(
-        &[
-            "-m",
-            "pip",
-            "install",
-            "-r",
-            filtered_requirements_path.to_string_lossy().as_ref(),
-            "--quiet",
-        ],
+    start_phase(InstallPhase::Requirements, "Installing Python packages...")?;
+    pip_install(
+        &["-r", filtered_requirements_path.to_string_lossy().as_ref()],
         "pip install failed",
+        "Installing Python packages...",
     )?;
 
     // Installation explicite de Quranic-Phonemizer pour multi-aligner.
     if matches!(selected_engine, LocalSegmentationEngine::MultiAligner) {
-        emit_status("Checking Multi-Aligner data files...");
-        let repaired_files = ensure_multi_aligner_data_files(&app_handle).await?;
+        start_phase(
+            InstallPhase::DataFiles,
+            "Checking Multi-Aligner data files...",
+        )?;
+        let repaired_files = ensure_multi_aligner_data_files(
+            &app_handle,
+            offline_dir.as_deref(),
+            proxy.as_ref(),
+            data_mirror_url.as_deref(),
+            |fraction, message| {
+                if let Ok(mut progress) = progress.lock() {
+                    progress.set_phase_fraction(fraction);
+                }
+                emit_status(message);
+            },
+        )
+        .await?;
         if !repaired_files.is_empty() {
             emit_status(&format!(
                 "Repaired Multi-Aligner data files: {}",
@@ -301,36 +895,44 @@ pub async fn install_local_segmentation_deps(
             ));
         }
 
-        emit_status("Installing Quranic-Phonemizer dependency...");
+        start_phase(
+            InstallPhase::Phonemizer,
+            "Installing Quranic-Phonemizer dependency...",
+        )?;
+        let offline_archive = offline_dir
+            .as_deref()
+            .map(|wheels_dir| wheels_dir.join(QURANIC_PHONEMIZER_ARCHIVE_FILE));
         if cfg!(target_os = "windows") {
-            let patched_source = prepare_windows_safe_quranic_phonemizer_source(&python_exe)?;
+            let patched_source = prepare_windows_safe_quranic_phonemizer_source(
+                &python_exe,
+                offline_archive.as_deref(),
+                proxy.as_ref(),
+            )?;
+            temp_paths.add(&patched_source);
             let patched_source_str = patched_source.to_string_lossy().to_string();
-            run_python_cmd(
-                &[
-                    "-m",
-                    "pip",
-                    "install",
-                    "--upgrade",
-                    patched_source_str.as_str(),
-                    "--quiet",
-                ],
+            pip_install(
+                &["--upgrade", patched_source_str.as_str()],
                 "Failed to install patched Quranic-Phonemizer",
+                "Installing Quranic-Phonemizer dependency...",
             )?;
         } else {
-            run_python_cmd(
-                &[
-                    "-m",
-                    "pip",
-                    "install",
-                    "--upgrade",
-                    "https://github.com/Hetchy/Quranic-Phonemizer/archive/1b6a8cc.zip",
-                    "--quiet",
-                ],
+            let archive_source = offline_archive
+                .map(|archive| archive.to_string_lossy().to_string())
+                .unwrap_or_else(|| QURANIC_PHONEMIZER_ARCHIVE_URL.to_string());
+            pip_install(
+                &["--upgrade", archive_source.as_str()],
                 "Failed to install Quranic-Phonemizer",
+                "Installing Quranic-Phonemizer dependency...",
             )?;
         }
     }
 
+    ensure_install_not_cancelled(selected_engine)?;
+    set_venv_unpinned(&venv_dir, latest)?;
+    clear_venv_install_incomplete(&venv_dir)?;
+    if let Ok(mut progress) = progress.lock() {
+        progress.complete_phase();
+    }
     emit_status("Local dependencies installed successfully.");
     Ok(format!(
         "{} dependencies installed successfully",
@@ -0,0 +1,234 @@
+//! Journal complet des installations de dépendances locales (`logs/install`).
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Output;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::Manager;
+
+use super::run_log::{list_logs, rotate_logs};
+
+/// Nombre maximal de journaux d'installation conservés.
+const MAX_INSTALL_LOG_FILES: usize = 10;
+/// Taille cumulée maximale des journaux d'installation conservés (octets).
+const MAX_INSTALL_LOG_TOTAL_BYTES: u64 = 50 * 1024 * 1024;
+/// Préfixe des journaux d'installation (suivi d'un horodatage en ms).
+const INSTALL_LOG_FILE_PREFIX: &str = "install-";
+
+/// Journal complet d'une installation de dépendances locales (commandes, stdout, stderr).
+pub(super) struct InstallLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+/// Retourne le dossier `logs/install` des données de l'application (créé au besoin).
+fn install_logs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let logs_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("logs")
+        .join("install");
+    fs::create_dir_all(&logs_dir).map_err(|e| {
+        format!(
+            "Failed to create install log directory '{}': {}",
+            logs_dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(logs_dir)
+}
+
+impl InstallLog {
+    /// Crée le journal d'installation d'un moteur et écrit son en-tête.
+    pub(super) fn create(app_handle: &tauri::AppHandle, engine_key: &str) -> Result<Self, String> {
+        let logs_dir = install_logs_dir(app_handle)?;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        let path = logs_dir.join(format!(
+            "{}{}-{}.log",
+            INSTALL_LOG_FILE_PREFIX, stamp, engine_key
+        ));
+        let mut file = File::create(&path).map_err(|e| {
+            format!(
+                "Failed to create install log '{}': {}",
+                path.to_string_lossy(),
+                e
+            )
+        })?;
+        let _ = write!(
+            file,
+            "QuranCaption Local Dependencies Install Log\n\
+             ===========================================\n\
+             Timestamp: {}\n\
+             Engine: {}\n\
+             App version: {}\n\
+             Platform: {}/{}\n",
+            stamp,
+            engine_key,
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Chemin du journal, avec des `/` pour l'affichage.
+    pub(super) fn display_path(&self) -> String {
+        self.path.to_string_lossy().replace('\\', "/")
+    }
+
+    /// Écrit du texte brut dans le journal.
+    fn write(&self, text: &str) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(text.as_bytes());
+        }
+    }
+
+    /// Ajoute un message d'étape (`install-status`).
+    pub(super) fn append_status(&self, message: &str) {
+        self.write(&format!("[status] {}\n", message));
+    }
+
+    /// Ouvre la section d'une commande lancée.
+    pub(super) fn begin_command(&self, program: &str, args: &[&str]) {
+        self.write(&format!(
+            "\n--- command ---\n{} {}\n--- stdout ---\n",
+            program,
+            args.join(" ")
+        ));
+    }
+
+    /// Ajoute une ligne de sortie standard de la commande en cours.
+    pub(super) fn append_stdout_line(&self, line: &str) {
+        self.write(&format!("{}\n", line));
+    }
+
+    /// Termine la section d'une commande (stderr complet et code de sortie).
+    pub(super) fn end_command(&self, stderr: &[u8], status: &str) {
+        self.write(&format!(
+            "--- stderr ---\n{}\n--- exit ---\n{}\n",
+            String::from_utf8_lossy(stderr).trim_end(),
+            status
+        ));
+    }
+
+    /// Journalise une commande exécutée d'un bloc (`Command::output`).
+    pub(super) fn append_output(&self, program: &str, args: &[&str], output: &Output) {
+        self.begin_command(program, args);
+        self.write(&format!(
+            "{}\n",
+            String::from_utf8_lossy(&output.stdout).trim_end()
+        ));
+        self.end_command(&output.stderr, &output.status.to_string());
+    }
+
+    /// Écrit le résultat final, applique la rotation puis joint le chemin au résultat.
+    pub(super) fn finish(&self, outcome: Result<String, String>) -> Result<String, String> {
+        self.write(&format!(
+            "\n--- result ---\n{}\n",
+            match &outcome {
+                Ok(message) => format!("success: {}", message),
+                Err(error) => format!("failure: {}", error),
+            }
+        ));
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+        if let Some(logs_dir) = self.path.parent() {
+            rotate_logs(
+                logs_dir,
+                INSTALL_LOG_FILE_PREFIX,
+                MAX_INSTALL_LOG_FILES,
+                MAX_INSTALL_LOG_TOTAL_BYTES,
+            );
+        }
+        match outcome {
+            Ok(message) => Ok(format!("{} (log: {})", message, self.display_path())),
+            Err(error) => Err(format!(
+                "{}\n\nSee the install log: {}",
+                error,
+                self.display_path()
+            )),
+        }
+    }
+}
+
+/// Journal d'installation le plus récent, pour un rapport de bug.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLogContent {
+    /// Chemin du journal.
+    pub path: String,
+    /// Contenu complet du journal.
+    pub content: String,
+}
+
+/// Retourne le dernier journal d'installation (chemin et contenu), `None` s'il n'y en a pas.
+pub async fn get_latest_install_log(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<InstallLogContent>, String> {
+    let logs_dir = install_logs_dir(&app_handle)?;
+    let Some((path, _)) = list_logs(&logs_dir, INSTALL_LOG_FILE_PREFIX)
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "Failed to read install log '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(Some(InstallLogContent {
+        path: path.to_string_lossy().replace('\\', "/"),
+        content,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstallLog;
+    use std::fs::{self, File};
+    use std::sync::Mutex;
+
+    #[test]
+    fn finish_records_the_outcome_and_points_to_the_log() {
+        let logs_dir = std::env::temp_dir().join(format!(
+            "qurancaption-install-logs-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&logs_dir);
+        fs::create_dir_all(&logs_dir).unwrap();
+        let path = logs_dir.join("install-1700000000000-legacy.log");
+        let install_log = InstallLog {
+            file: Mutex::new(File::create(&path).unwrap()),
+            path: path.clone(),
+        };
+
+        install_log.begin_command("python", &["-m", "pip", "install", "numpy"]);
+        install_log.append_stdout_line("Collecting numpy");
+        install_log.end_command(b"ERROR: No matching distribution", "exit status: 1");
+        let error = install_log
+            .finish(Err("pip install failed".to_string()))
+            .unwrap_err();
+        assert!(error.starts_with("pip install failed\n\nSee the install log: "));
+        assert!(error.ends_with("install-1700000000000-legacy.log"));
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("python -m pip install numpy\n--- stdout ---\nCollecting numpy"));
+        assert!(content.contains("ERROR: No matching distribution\n--- exit ---\nexit status: 1"));
+        assert!(content.contains("failure: pip install failed"));
+        let _ = fs::remove_dir_all(&logs_dir);
+    }
+}
@@ -0,0 +1,522 @@
+//! Progression de l'installation des dépendances d'un moteur local.
+//!
+//! pip est lancé avec `--progress-bar raw` (pip 24.1+, après sa mise à jour) et sa sortie
+//! standard est lue ligne à ligne :
+//! - `Collecting <paquet>` / `Downloading <fichier> (<taille>)` : paquet en cours
+//! - `Progress <octets> of <total>` (mode raw) ou `━━━━ 1.1/2.5 GB ...` (barre rich) : octets
+//!
+//! Chaque événement `install-status` garde son champ `message` et ajoute `phase`,
+//! `package`, `packagePercent` (paquet en cours) et `percent` (global, pondéré par phase).
+
+use std::io::{BufRead, BufReader, Read};
+
+use super::types::LocalSegmentationEngine;
+
+/// Phase connue de l'installation d'un moteur local.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum InstallPhase {
+    PipUpgrade,
+    Torch,
+    Requirements,
+    DataFiles,
+    Phonemizer,
+}
+
+impl InstallPhase {
+    /// Identifiant publié dans le champ `phase`.
+    fn key(&self) -> &'static str {
+        match self {
+            Self::PipUpgrade => "pip",
+            Self::Torch => "torch",
+            Self::Requirements => "requirements",
+            Self::DataFiles => "data_files",
+            Self::Phonemizer => "phonemizer",
+        }
+    }
+
+    /// Poids relatif de la phase dans le pourcentage global (torch domine : ~2.5 GB).
+    fn weight(&self) -> f64 {
+        match self {
+            Self::PipUpgrade => 3.0,
+            Self::Torch => 60.0,
+            Self::Requirements => 27.0,
+            Self::DataFiles => 5.0,
+            Self::Phonemizer => 5.0,
+        }
+    }
+
+    /// Phases exécutées pour un moteur, dans l'ordre d'installation.
+    fn for_engine(engine: LocalSegmentationEngine) -> Vec<Self> {
+        let mut phases = vec![Self::PipUpgrade, Self::Torch, Self::Requirements];
+        if matches!(engine, LocalSegmentationEngine::MultiAligner) {
+            phases.extend([Self::DataFiles, Self::Phonemizer]);
+        }
+        phases
+    }
+}
+
+/// Ligne de sortie pip utile au suivi de progression.
+#[derive(Debug, PartialEq)]
+pub(super) enum PipOutputLine {
+    /// Résolution d'un paquet (`Collecting torch`).
+    Collecting(String),
+    /// Début du téléchargement d'un fichier, avec sa taille annoncée.
+    Downloading {
+        file_name: String,
+        total_bytes: Option<u64>,
+    },
+    /// Octets téléchargés du fichier en cours.
+    Bytes { current: u64, total: u64 },
+    /// Téléchargements terminés, installation des paquets.
+    Installing,
+}
+
+/// Convertit une taille pip (`2.5`, `GB`) en octets.
+fn parse_pip_size(value: &str, unit: &str) -> Option<u64> {
+    let value: f64 = value.trim().parse().ok()?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "bytes" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * multiplier).round() as u64)
+}
+
+/// Indique si la sortie de `pip --version` désigne une version acceptant
+/// `--progress-bar raw` (pip 24.1+) ; les versions antérieures rejettent l'option.
+pub(super) fn pip_supports_raw_progress_bar(version_output: &str) -> bool {
+    let Some(version) = version_output.trim().strip_prefix("pip ") else {
+        return false;
+    };
+    let mut parts = version
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (24, 1)
+}
+
+/// Analyse une ligne de sortie pip (Windows ou Linux, avec ou sans barre rich).
+pub(super) fn parse_pip_output_line(line: &str) -> Option<PipOutputLine> {
+    let line = line.trim();
+    if let Some(package) = line.strip_prefix("Collecting ") {
+        let package = package.split_whitespace().next()?;
+        return Some(PipOutputLine::Collecting(package.to_string()));
+    }
+    if line.starts_with("Installing collected packages") {
+        return Some(PipOutputLine::Installing);
+    }
+    if let Some(rest) = line.strip_prefix("Downloading ") {
+        let (target, size) = match rest.rsplit_once(" (") {
+            Some((target, size)) => (target, Some(size.trim_end_matches(')'))),
+            None => (rest, None),
+        };
+        let file_name = target
+            .trim()
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(target)
+            .replace("%2B", "+");
+        // Les fichiers `.metadata` (quelques ko) ne sont pas des téléchargements suivis.
+        if file_name.ends_with(".metadata") {
+            return None;
+        }
+        let total_bytes = size.and_then(|size| {
+            let (value, unit) = size.split_once(' ')?;
+            parse_pip_size(value, unit)
+        });
+        return Some(PipOutputLine::Downloading {
+            file_name,
+            total_bytes,
+        });
+    }
+    if let Some(rest) = line.strip_prefix("Progress ") {
+        let (current, total) = rest.split_once(" of ")?;
+        return Some(PipOutputLine::Bytes {
+            current: current.trim().parse().ok()?,
+            total: total.trim().parse().ok()?,
+        });
+    }
+
+    // Barre rich : "━━━━━━╸━━━━ 1.1/2.5 GB 10.2 MB/s eta 0:02:10".
+    let words: Vec<&str> = line.split_whitespace().collect();
+    words.windows(2).find_map(|pair| {
+        let (current, total) = pair[0].split_once('/')?;
+        let current = parse_pip_size(current, pair[1])?;
+        let total = parse_pip_size(total, pair[1])?;
+        (total > 0).then_some(PipOutputLine::Bytes { current, total })
+    })
+}
+
+/// Lit une sortie de process ligne à ligne, en coupant aussi sur `\r` (barres de progression).
+pub(super) fn for_each_output_line(reader: impl Read, mut on_line: impl FnMut(&str)) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    while matches!(reader.read_until(b'\n', &mut buffer), Ok(read) if read > 0) {
+        for segment in String::from_utf8_lossy(&buffer).split(['\r', '\n']) {
+            if !segment.trim().is_empty() {
+                on_line(segment);
+            }
+        }
+        buffer.clear();
+    }
+}
+
+/// Suivi de l'installation : phase courante, paquet en cours et pourcentage global monotone.
+pub(super) struct InstallProgress {
+    engine: LocalSegmentationEngine,
+    /// Position du moteur dans une installation groupée (index, nombre de moteurs).
+    batch: Option<(usize, usize)>,
+    phases: Vec<InstallPhase>,
+    current_phase: Option<InstallPhase>,
+    completed_weight: f64,
+    phase_fraction: f64,
+    package: Option<String>,
+    package_percent: Option<f64>,
+    /// Octets des fichiers terminés et annoncés dans la phase courante.
+    finished_bytes: u64,
+    announced_bytes: u64,
+    /// Taille annoncée du fichier en cours de téléchargement.
+    current_file_bytes: Option<u64>,
+    percent: f64,
+}
+
+impl InstallProgress {
+    pub(super) fn new(engine: LocalSegmentationEngine) -> Self {
+        Self {
+            engine,
+            batch: None,
+            phases: InstallPhase::for_engine(engine),
+            current_phase: None,
+            completed_weight: 0.0,
+            phase_fraction: 0.0,
+            package: None,
+            package_percent: None,
+            finished_bytes: 0,
+            announced_bytes: 0,
+            current_file_bytes: None,
+            percent: 0.0,
+        }
+    }
+
+    /// Rattache la progression à une installation groupée de `count` moteurs.
+    pub(super) fn with_batch(mut self, batch: Option<(usize, usize)>) -> Self {
+        self.batch = batch.filter(|(index, count)| index < count);
+        self
+    }
+
+    /// Progression globale d'une installation groupée (moteurs terminés + moteur courant).
+    fn overall_percent(&self) -> f64 {
+        match self.batch {
+            Some((index, count)) => {
+                ((index as f64 * 100.0 + self.percent) / count as f64 * 10.0).round() / 10.0
+            }
+            None => self.percent,
+        }
+    }
+
+    /// Démarre une phase ; la phase précédente est considérée comme terminée.
+    pub(super) fn start_phase(&mut self, phase: InstallPhase) {
+        self.complete_phase();
+        self.current_phase = Some(phase);
+    }
+
+    /// Termine la phase courante (100 % de son poids).
+    pub(super) fn complete_phase(&mut self) {
+        if let Some(phase) = self.current_phase.take() {
+            self.completed_weight += phase.weight();
+        }
+        self.phase_fraction = 0.0;
+        self.package = None;
+        self.package_percent = None;
+        self.finished_bytes = 0;
+        self.announced_bytes = 0;
+        self.current_file_bytes = None;
+        self.refresh_percent();
+    }
+
+    /// Fixe l'avancement de la phase courante (`fraction` entre 0 et 1).
+    pub(super) fn set_phase_fraction(&mut self, fraction: f64) {
+        self.phase_fraction = self.phase_fraction.max(fraction.clamp(0.0, 1.0));
+        self.refresh_percent();
+    }
+
+    /// Intègre une ligne pip ; retourne `true` si un événement doit être publié.
+    pub(super) fn apply_pip_line(&mut self, line: &str) -> bool {
+        match parse_pip_output_line(line) {
+            Some(PipOutputLine::Collecting(package)) => {
+                self.package = Some(package);
+                self.package_percent = None;
+                true
+            }
+            Some(PipOutputLine::Downloading {
+                file_name,
+                total_bytes,
+            }) => {
+                self.finished_bytes += self.current_file_bytes.take().unwrap_or(0);
+                self.announced_bytes += total_bytes.unwrap_or(0);
+                self.current_file_bytes = total_bytes;
+                self.package = Some(file_name);
+                self.package_percent = Some(0.0);
+                true
+            }
+            Some(PipOutputLine::Bytes { current, total }) => {
+                let previous = self.package_percent.map(f64::floor);
+                let percent = (current as f64 / total as f64 * 100.0).clamp(0.0, 100.0);
+                self.package_percent = Some(percent);
+                if self.announced_bytes > 0 {
+                    let downloaded = self.finished_bytes + current.min(total);
+                    self.set_phase_fraction(downloaded as f64 / self.announced_bytes as f64 * 0.95);
+                }
+                previous != Some(percent.floor())
+            }
+            Some(PipOutputLine::Installing) => {
+                self.package = None;
+                self.package_percent = None;
+                self.set_phase_fraction(0.95);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn refresh_percent(&mut self) {
+        let total_weight: f64 = self.phases.iter().map(InstallPhase::weight).sum();
+        if total_weight <= 0.0 {
+            return;
+        }
+        let current_weight = self
+            .current_phase
+            .map(|phase| phase.weight() * self.phase_fraction)
+            .unwrap_or(0.0);
+        let percent = ((self.completed_weight + current_weight) / total_weight * 100.0).min(100.0);
+        self.percent = self.percent.max((percent * 10.0).round() / 10.0);
+    }
+
+    /// Payload de l'événement `install-status`.
+    pub(super) fn status(&self, message: &str) -> serde_json::Value {
+        let message = match (&self.package, self.package_percent) {
+            (Some(package), Some(package_percent)) => {
+                format!("{} ({}: {:.0}%)", message, package, package_percent)
+            }
+            _ => message.to_string(),
+        };
+        serde_json::json!({
+            "message": message,
+            "phase": self.current_phase.map(|phase| phase.key()),
+            "package": self.package,
+            "packagePercent": self.package_percent.map(|percent| percent.round()),
+            "percent": self.percent,
+            "engine": self.engine.as_key(),
+            "engineIndex": self.batch.map(|(index, _)| index),
+            "engineCount": self.batch.map(|(_, count)| count),
+            "overallPercent": self.overall_percent(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        for_each_output_line, parse_pip_output_line, pip_supports_raw_progress_bar, InstallPhase,
+        InstallProgress, PipOutputLine,
+    };
+    use crate::segmentation::types::LocalSegmentationEngine;
+
+    /// Sortie réelle de `pip download` (pip 24.2, Linux, stdout redirigé, index local servant
+    /// une roue de 31,5 Mo) : en mode non interactif, rich n'affiche la barre qu'une fois.
+    const RICH_PIP_OUTPUT: &str = "Looking in indexes: http://127.0.0.1:8765/simple
+Collecting demo-pkg
+  Downloading http://127.0.0.1:8765/simple/demo-pkg/demo_pkg-1.0-py3-none-any.whl (31.5 MB)
+     ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━ 31.5/31.5 MB 962.0 MB/s eta 0:00:00
+Saved ./dl3/demo_pkg-1.0-py3-none-any.whl
+Successfully downloaded demo-pkg";
+
+    /// Sortie réelle de `pip install --progress-bar raw --cache-dir ...` (pip 24.2, Linux,
+    /// même index local), soit les options passées par l'installation.
+    const RAW_PIP_INSTALL_OUTPUT: &str = "Looking in indexes: http://127.0.0.1:8765/simple
+Collecting demo-pkg
+  Downloading http://127.0.0.1:8765/simple/demo-pkg/demo_pkg-1.0-py3-none-any.whl (31.5 MB)
+Progress 0 of 31458054
+Progress 262144 of 31458054
+Progress 31458054 of 31458054
+Installing collected packages: demo-pkg
+Successfully installed demo-pkg-1.0
+";
+
+    /// Lignes parsées d'une sortie pip, découpées comme pendant l'installation.
+    fn parse_output(output: &str) -> Vec<Option<PipOutputLine>> {
+        let mut lines = Vec::new();
+        for_each_output_line(output.as_bytes(), |line| {
+            lines.push(parse_pip_output_line(line))
+        });
+        lines
+    }
+
+    #[test]
+    fn rich_pip_output_is_parsed() {
+        let lines = parse_output(RICH_PIP_OUTPUT);
+        assert_eq!(lines[0], None);
+        assert_eq!(
+            lines[1],
+            Some(PipOutputLine::Collecting("demo-pkg".to_string()))
+        );
+        assert_eq!(
+            lines[2],
+            Some(PipOutputLine::Downloading {
+                file_name: "demo_pkg-1.0-py3-none-any.whl".to_string(),
+                total_bytes: Some(31_500_000),
+            })
+        );
+        assert_eq!(
+            lines[3],
+            Some(PipOutputLine::Bytes {
+                current: 31_500_000,
+                total: 31_500_000,
+            })
+        );
+        assert_eq!(lines[4..], [None, None]);
+    }
+
+    #[test]
+    fn raw_pip_install_output_is_parsed_with_lf_and_crlf_endings() {
+        // pip écrit des fins de ligne CRLF sous Windows : le découpage doit donner le même résultat.
+        for output in [
+            RAW_PIP_INSTALL_OUTPUT.to_string(),
+            RAW_PIP_INSTALL_OUTPUT.replace('\n', "\r\n"),
+        ] {
+            let lines = parse_output(&output);
+            assert_eq!(lines.len(), 8);
+            assert_eq!(lines[0], None);
+            assert_eq!(
+                lines[1],
+                Some(PipOutputLine::Collecting("demo-pkg".to_string()))
+            );
+            assert_eq!(
+                lines[2],
+                Some(PipOutputLine::Downloading {
+                    file_name: "demo_pkg-1.0-py3-none-any.whl".to_string(),
+                    total_bytes: Some(31_500_000),
+                })
+            );
+            assert_eq!(
+                lines[4],
+                Some(PipOutputLine::Bytes {
+                    current: 262_144,
+                    total: 31_458_054,
+                })
+            );
+            assert_eq!(
+                lines[5],
+                Some(PipOutputLine::Bytes {
+                    current: 31_458_054,
+                    total: 31_458_054,
+                })
+            );
+            assert_eq!(lines[6], Some(PipOutputLine::Installing));
+            assert_eq!(lines[7], None);
+        }
+    }
+
+    #[test]
+    fn torch_specific_lines_are_parsed() {
+        // Lignes écrites à la main : roue avec `+` encodé, barre partielle, métadonnées ignorées.
+        assert_eq!(
+            parse_pip_output_line(
+                "  Downloading https://download.pytorch.org/whl/cu121/torch-2.3.1%2Bcu121-cp311-cp311-win_amd64.whl (2423.5 MB)"
+            ),
+            Some(PipOutputLine::Downloading {
+                file_name: "torch-2.3.1+cu121-cp311-cp311-win_amd64.whl".to_string(),
+                total_bytes: Some(2_423_500_000),
+            })
+        );
+        assert_eq!(
+            parse_pip_output_line("     ━━━━━━━━━╸━━━━━━━━━━ 366.2/779.1 MB 11.4 MB/s eta 0:00:37"),
+            Some(PipOutputLine::Bytes {
+                current: 366_200_000,
+                total: 779_100_000,
+            })
+        );
+        assert_eq!(
+            parse_pip_output_line(
+                "  Downloading filelock-3.15.4-py3-none-any.whl.metadata (2.9 kB)"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn overall_percent_is_weighted_across_phases() {
+        let mut progress = InstallProgress::new(LocalSegmentationEngine::LegacyWhisper);
+        progress.start_phase(InstallPhase::PipUpgrade);
+        progress.start_phase(InstallPhase::Torch);
+        assert_eq!(progress.status("Installing PyTorch...")["percent"], 3.3);
+
+        for line in [
+            "Collecting torch",
+            "  Downloading https://download.pytorch.org/whl/cu121/torch-2.3.1%2Bcu121-cp311-cp311-win_amd64.whl (2423.5 MB)",
+            "Progress 1211760000 of 2423500000",
+        ] {
+            progress.apply_pip_line(line);
+        }
+        let status = progress.status("Installing PyTorch...");
+        assert_eq!(status["phase"], "torch");
+        assert_eq!(status["packagePercent"], 50.0);
+        assert_eq!(
+            status["message"],
+            "Installing PyTorch... (torch-2.3.1+cu121-cp311-cp311-win_amd64.whl: 50%)"
+        );
+        assert_eq!(status["percent"], 35.0);
+
+        progress.start_phase(InstallPhase::Requirements);
+        progress.complete_phase();
+        assert_eq!(progress.status("Done")["percent"], 100.0);
+    }
+
+    #[test]
+    fn batch_status_reports_engine_and_overall_percent() {
+        let mut progress =
+            InstallProgress::new(LocalSegmentationEngine::MultiAligner).with_batch(Some((1, 2)));
+        let status = progress.status("Starting...");
+        assert_eq!(status["engine"], "multi");
+        assert_eq!(status["engineIndex"], 1);
+        assert_eq!(status["engineCount"], 2);
+        assert_eq!(status["overallPercent"], 50.0);
+
+        progress.start_phase(InstallPhase::PipUpgrade);
+        progress.complete_phase();
+        let status = progress.status("Done");
+        assert_eq!(
+            status["overallPercent"],
+            50.0 + status["percent"].as_f64().unwrap() / 2.0
+        );
+
+        let single = InstallProgress::new(LocalSegmentationEngine::LegacyWhisper).with_batch(None);
+        assert!(single.status("Done")["engineIndex"].is_null());
+    }
+
+    #[test]
+    fn raw_progress_bar_requires_pip_24_1() {
+        assert!(pip_supports_raw_progress_bar(
+            "pip 24.1.2 from /venv/lib/python3.11/site-packages/pip (python 3.11)\n"
+        ));
+        assert!(pip_supports_raw_progress_bar(
+            "pip 25.0 from C:\\venv\\pip (python 3.12)"
+        ));
+        assert!(!pip_supports_raw_progress_bar(
+            "pip 23.0.1 from /venv/lib/python3.10/site-packages/pip (python 3.10)"
+        ));
+        assert!(!pip_supports_raw_progress_bar(
+            "pip 24.0 from /venv/pip (python 3.11)"
+        ));
+        assert!(!pip_supports_raw_progress_bar("No module named pip"));
+    }
+}
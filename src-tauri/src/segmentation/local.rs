@@ -1,37 +1,344 @@
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::process::{Command, Stdio};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tauri::Emitter;
 
 use crate::binaries;
+use crate::exporter::ffmpeg_utils::ffprobe_duration_sec;
 use crate::path_utils;
-use crate::utils::process::configure_command_no_window;
+use crate::utils::ffmpeg;
+use crate::utils::process::{
+    configure_command_no_window, kill_process_tree, spawn_command, track_process, untrack_process,
+    TrackedChild,
+};
 use crate::utils::temp_file::TempFileGuard;
 
 use super::audio_merge::merge_audio_clips_for_segmentation;
+use super::debug_audio::{attach_debug_audio_path, keep_preprocessed_audio};
+use super::local_estimate::record_local_run_speed;
+use super::memory_check::{
+    attach_memory_warning, check_local_memory, detect_out_of_memory, is_gpu_failure,
+};
+use super::mock::mock_segmentation_result;
+use super::model_cache::apply_hf_cache_env;
+use super::preprocess::AudioPreprocess;
+use super::progress::LocalProgressTracker;
+use super::proxy::runtime_proxy;
 use super::python_env::{
     apply_hf_token_env, resolve_engine_python_exe, resolve_python_resource_path,
 };
-use super::types::{LocalSegmentationEngine, SegmentationAudioClip};
+use super::result::{offset_segmentation_payload, validate_segmentation_payload};
+use super::run_log::SegmentationRunLog;
+use super::types::{
+    resolve_compute_device, AudioPreprocessOptions, ConfidenceFilter, LocalProcessLimits,
+    LocalRunTimeouts, LocalSegmentationEngine, ScriptExit, SegmentationAudioClip, SegmentationHint,
+    SegmentationRange,
+};
+use super::watchdog::{format_minutes, RunWatchdog, WATCHDOG_POLL_INTERVAL};
+use super::worker::{is_worker_mode_enabled, run_script_in_worker};
+
+/// Tailles de modeles Whisper nommees acceptees par le moteur legacy.
+const LEGACY_WHISPER_MODEL_NAMES: [&str; 4] = ["tiny", "base", "medium", "large"];
+
+/// Groupes de fichiers requis dans un dossier de checkpoint Whisper (un fichier par groupe).
+const WHISPER_CHECKPOINT_REQUIRED_FILES: [&[&str]; 4] = [
+    &["config.json"],
+    &[
+        "model.safetensors",
+        "pytorch_model.bin",
+        "model.safetensors.index.json",
+        "pytorch_model.bin.index.json",
+    ],
+    &["preprocessor_config.json"],
+    &["tokenizer.json", "vocab.json"],
+];
+
+/// Moteurs dont une segmentation locale est en cours (une entrée par exécution).
+static ACTIVE_LOCAL_RUNS: Mutex<Vec<LocalSegmentationEngine>> = Mutex::new(Vec::new());
+
+/// Déclare une exécution locale active jusqu'à sa destruction.
+struct ActiveRunGuard(LocalSegmentationEngine);
+
+impl ActiveRunGuard {
+    fn register(engine: LocalSegmentationEngine) -> Self {
+        if let Ok(mut runs) = ACTIVE_LOCAL_RUNS.lock() {
+            runs.push(engine);
+        }
+        Self(engine)
+    }
+}
+
+impl Drop for ActiveRunGuard {
+    fn drop(&mut self) {
+        if let Ok(mut runs) = ACTIVE_LOCAL_RUNS.lock() {
+            if let Some(index) = runs.iter().position(|engine| *engine == self.0) {
+                runs.remove(index);
+            }
+        }
+    }
+}
+
+/// Exécute `action` si aucune segmentation n'utilise `engine`.
+///
+/// Le registre reste verrouillé pendant `action` : aucune exécution du moteur ne peut
+/// démarrer entre la vérification et la fin de l'opération (ex: désinstallation).
+pub(super) fn with_engine_idle<T>(
+    engine: LocalSegmentationEngine,
+    action: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let runs = ACTIVE_LOCAL_RUNS
+        .lock()
+        .map_err(|_| "Local segmentation run registry lock poisoned".to_string())?;
+    if runs.contains(&engine) {
+        return Err(format!(
+            "A {} segmentation is currently running; wait for it to finish or cancel it first",
+            engine.as_label()
+        ));
+    }
+    action()
+}
+
+/// Valide le `whisper_model` du moteur legacy : taille nommee ou dossier de checkpoint local.
+///
+/// Un dossier est normalise via `path_utils` et doit contenir config, poids, preprocessor
+/// et tokenizer ; l'erreur liste les fichiers manquants.
+fn resolve_legacy_whisper_model(raw: &str) -> Result<String, String> {
+    let raw = raw.trim();
+    if let Some(name) = LEGACY_WHISPER_MODEL_NAMES
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(raw))
+    {
+        return Ok(name.to_string());
+    }
+
+    let looks_like_path =
+        raw.contains('/') || raw.contains('\\') || std::path::Path::new(raw).is_absolute();
+    if !looks_like_path {
+        return Err(format!(
+            "Invalid whisper_model '{}'. Expected 'tiny', 'base', 'medium', 'large' or a local model folder.",
+            raw
+        ));
+    }
+
+    let model_dir = path_utils::normalize_existing_path(raw);
+    if !model_dir.is_dir() {
+        return Err(format!("Custom Whisper model folder not found: {}", raw));
+    }
+    let missing: Vec<String> = WHISPER_CHECKPOINT_REQUIRED_FILES
+        .iter()
+        .filter(|candidates| !candidates.iter().any(|file| model_dir.join(file).is_file()))
+        .map(|candidates| candidates.join(" or "))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "'{}' is not a valid Whisper model folder. Missing: {}",
+            raw,
+            missing.join(", ")
+        ));
+    }
+    Ok(model_dir.to_string_lossy().to_string())
+}
+
+/// Nombre de coeurs CPU logiques disponibles (borne haute de `cpu_threads`).
+fn available_cpu_cores() -> usize {
+    std::thread::available_parallelism()
+        .map(|cores| cores.get())
+        .unwrap_or(1)
+}
+
+/// Construit le traitement des lignes stderr du script : les `STATUS:` deviennent des
+/// events `segmentation-status`, les autres lignes sont gardées pour les messages d'erreur ;
+/// toutes sont recopiées dans le journal de l'exécution.
+fn script_stderr_handler(
+    app_handle: tauri::AppHandle,
+    engine_key: String,
+    progress_tracker: Arc<Mutex<LocalProgressTracker>>,
+    stderr_lines: Arc<Mutex<Vec<String>>>,
+    run_log: Option<Arc<SegmentationRunLog>>,
+) -> impl FnMut(String) + Send + 'static {
+    move |line: String| {
+        if let Some(run_log) = run_log.as_ref() {
+            run_log.append_stderr_line(&line);
+        }
+        if line.starts_with("STATUS:") {
+            let json_str = line.trim_start_matches("STATUS:");
+            if let Ok(status_data) = serde_json::from_str::<serde_json::Value>(json_str) {
+                let status_data = match progress_tracker.lock() {
+                    Ok(mut tracker) => tracker.apply(status_data),
+                    Err(_) => status_data,
+                };
+                let _ = app_handle.emit("segmentation-status", status_data);
+            }
+            log::info!("[segmentation][local][status][{}] {}", engine_key, line);
+        } else if !line.trim().is_empty() {
+            log::warn!("[segmentation][local][stderr][{}] {}", engine_key, line);
+            if let Ok(mut locked) = stderr_lines.lock() {
+                locked.push(line);
+                if locked.len() > 120 {
+                    let drain_count = locked.len() - 120;
+                    locked.drain(0..drain_count);
+                }
+            }
+        }
+    }
+}
+
+/// Identifiant d'une segmentation locale dans le registre des processus (un run par moteur).
+fn local_run_process_id(engine_key: &str) -> String {
+    format!("segmentation:{}", engine_key)
+}
+
+/// Attend la fin du processus Python suivi, en le tuant si `watchdog` signale un délai dépassé.
+fn wait_script_process(
+    process: &TrackedChild,
+    engine_key: &str,
+    watchdog: &mut RunWatchdog,
+) -> Result<ExitStatus, String> {
+    loop {
+        {
+            let mut guard = process
+                .lock()
+                .map_err(|_| "Python process lock poisoned".to_string())?;
+            let Some(child) = guard.as_mut() else {
+                return Err("Local segmentation was cancelled".to_string());
+            };
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|e| format!("Failed to wait for Python: {}", e))?
+            {
+                return Ok(status);
+            }
+            if let Err(error) = watchdog.check() {
+                log::warn!(
+                    "[segmentation][local][debug] killing python engine={}: {}",
+                    engine_key,
+                    error
+                );
+                let _ = kill_process_tree(child);
+                let _ = child.wait();
+                guard.take();
+                return Err(error);
+            }
+        }
+        std::thread::sleep(WATCHDOG_POLL_INTERVAL);
+    }
+}
+
+/// Lance le script dans un processus Python dédié et attend sa fin.
+///
+/// Le processus est tué si `watchdog` signale un délai dépassé.
+///
+/// @returns Statut de fin du processus et sa sortie standard
+fn run_script_process(
+    python_exe: &Path,
+    args: &[String],
+    hf_token: Option<String>,
+    limits: LocalProcessLimits,
+    engine_key: &str,
+    mut on_stderr_line: impl FnMut(String) + Send + 'static,
+    watchdog: &mut RunWatchdog,
+) -> Result<(ScriptExit, String), String> {
+    let mut cmd = Command::new(python_exe);
+    cmd.args(args);
+    if let Some(token) = hf_token {
+        if !token.trim().is_empty() {
+            apply_hf_token_env(&mut cmd, token.trim());
+        }
+    }
+    apply_hf_cache_env(&mut cmd);
+    // Les modèles HF manquants sont téléchargés au lancement : même proxy que l'installation.
+    if let Some(proxy) = runtime_proxy() {
+        proxy.apply_env(&mut cmd);
+    }
+    cmd.envs(limits.env_vars());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    configure_command_no_window(&mut cmd);
+
+    let mut child = spawn_command(&mut cmd, limits.background)
+        .map_err(|e| format!("Failed to spawn Python: {}", e))?;
+    let pid = child.id();
+    log::info!(
+        "[segmentation][local][debug] spawned python pid={} engine={}",
+        pid,
+        engine_key
+    );
+
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let stderr_handle = std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            on_stderr_line(line);
+        }
+    });
+    let mut stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stdout_handle = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = stdout.read_to_end(&mut buffer);
+        buffer
+    });
+
+    // Enregistré pour que `kill_tracked_process` puisse arrêter la segmentation en cours.
+    let process_id = local_run_process_id(engine_key);
+    let process = track_process(&process_id, child);
+    let status = wait_script_process(&process, engine_key, watchdog);
+    untrack_process(&process_id);
+    let status = status?;
+    log::info!(
+        "[segmentation][local][debug] python process finished engine={} status={:?}",
+        engine_key,
+        status.code()
+    );
+    let stdout = stdout_handle.join().unwrap_or_default();
+    let _ = stderr_handle.join();
+    Ok((
+        ScriptExit::from_status(status, pid),
+        String::from_utf8_lossy(&stdout).to_string(),
+    ))
+}
 
 /// ExÃ©cute le script Python local d'un moteur donnÃ© et retourne le JSON de segmentation.
+///
+/// En mode worker (`set_segmentation_worker_mode`), le script tourne dans un processus
+/// Python résident qui garde les modèles chargés entre deux segmentations.
+///
+/// Le script est arrêté au-delà du délai global de `timeouts` ou après un silence prolongé
+/// (ni STATUS ni stderr) ; un statut `stalled` avertit l'utilisateur avant l'arrêt.
 fn run_local_segmentation_script(
     app_handle: tauri::AppHandle,
     engine: LocalSegmentationEngine,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range: Option<SegmentationRange>,
+    confidence_filter: Option<ConfidenceFilter>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     mut extra_args: Vec<String>,
     hf_token: Option<String>,
+    limits: LocalProcessLimits,
+    timeouts: LocalRunTimeouts,
+    keep_temp_audio: bool,
+    preprocess: AudioPreprocess,
 ) -> Result<serde_json::Value, String> {
-    println!(
-        "[segmentation][local][debug] engine={} min_silence_ms={:?} min_speech_ms={:?} pad_ms={:?} extra_args={:?} hf_token_present={}",
+    if let Some(mock_result) =
+        mock_segmentation_result(&app_handle, &format!("local-{}", engine.as_key()))
+    {
+        return mock_result;
+    }
+    let _active_run = ActiveRunGuard::register(engine);
+    let started_at = Instant::now();
+
+    log::info!(
+        "[segmentation][local][debug] engine={} range={:?} confidence_filter={:?} limits={:?} timeouts={:?} min_silence_ms={:?} min_speech_ms={:?} pad_ms={:?} extra_args={:?} hf_token_present={}",
         engine.as_key(),
+        range,
+        confidence_filter,
+        limits,
+        timeouts,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
@@ -45,28 +352,31 @@ fn run_local_segmentation_script(
     // PrÃ©-traitement audio local identique au cloud: merge Ã©ventuel puis resample.
     let ffmpeg_path =
         binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
-    println!(
+    log::info!(
         "[segmentation][local][debug] resolved ffmpeg path={}",
         ffmpeg_path
     );
 
     let mut _merged_guard: Option<TempFileGuard> = None;
     let audio_path = if let Some(clips) = audio_clips.as_ref().filter(|c| !c.is_empty()) {
-        println!(
+        log::info!(
             "[segmentation][local][debug] received {} audio clip(s)",
             clips.len()
         );
         for (idx, clip) in clips.iter().enumerate() {
-            println!(
+            log::info!(
                 "[segmentation] clip[{}] path={} start_ms={} end_ms={}",
-                idx, clip.path, clip.start_ms, clip.end_ms
+                idx,
+                clip.path,
+                clip.start_ms,
+                clip.end_ms
             );
         }
         let needs_merge = clips.len() > 1 || clips[0].start_ms > 0;
         if needs_merge {
             let (merged_path, guard) = merge_audio_clips_for_segmentation(&ffmpeg_path, clips)?;
             _merged_guard = Some(guard);
-            println!(
+            log::info!(
                 "[segmentation] Using merged audio for local: {}",
                 merged_path.to_string_lossy()
             );
@@ -84,7 +394,7 @@ fn run_local_segmentation_script(
     if !audio_path.exists() {
         return Err(format!("Audio file not found: {}", audio_path_str));
     }
-    println!(
+    log::info!(
         "[segmentation][local][debug] normalized audio path={} (exists={})",
         audio_path_str,
         audio_path.exists()
@@ -101,56 +411,85 @@ fn run_local_segmentation_script(
     ));
     let _temp_guard = TempFileGuard(temp_path.clone());
 
-    let mut resample_cmd = Command::new(&ffmpeg_path);
-    resample_cmd.args([
-        "-y",
-        "-hide_banner",
-        "-loglevel",
-        "error",
-        "-i",
-        &audio_path_str,
-        "-ac",
-        "1",
-        "-ar",
-        "16000",
-        "-c:a",
-        "pcm_s16le",
-        "-vn",
-        temp_path.to_string_lossy().as_ref(),
-    ]);
-    configure_command_no_window(&mut resample_cmd);
-    println!(
-        "[segmentation][local][debug] running ffmpeg preprocess -> {}",
+    let progress_tracker = Arc::new(Mutex::new(LocalProgressTracker::default()));
+    let mut input_duration_s = ffprobe_duration_sec(&audio_path_str);
+    if let Some(range) = range {
+        let range_start_s = range.start_ms as f64 / 1000.0;
+        if input_duration_s > 0.0 && range_start_s >= input_duration_s {
+            return Err(format!(
+                "range_start_ms ({}) is beyond the end of the audio ({:.3}s)",
+                range.start_ms, input_duration_s
+            ));
+        }
+        input_duration_s -= range_start_s;
+        if let Some(duration_ms) = range.duration_ms() {
+            input_duration_s = input_duration_s.min(duration_ms as f64 / 1000.0);
+        }
+    }
+
+    let mut resample_args: Vec<String> = ["-y", "-hide_banner", "-loglevel", "error"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    if let Some(range) = range {
+        resample_args.extend(range.to_ffmpeg_input_args());
+    }
+    resample_args.push("-i".to_string());
+    resample_args.push(audio_path_str.clone());
+    if let Some(range) = range {
+        resample_args.extend(range.to_ffmpeg_output_args());
+    }
+    resample_args.extend(preprocess.local_ffmpeg_args());
+    resample_args.push("-vn".to_string());
+    resample_args.push(temp_path.to_string_lossy().to_string());
+    log::info!(
+        "[segmentation][local][debug] running ffmpeg preprocess {:?} -> {}",
+        preprocess,
         temp_path.to_string_lossy()
     );
 
-    let resample_output = resample_cmd
-        .output()
-        .map_err(|e| format!("Unable to execute ffmpeg for preprocessing: {}", e))?;
-    if !resample_output.status.success() {
-        let stderr = String::from_utf8_lossy(&resample_output.stderr);
-        eprintln!(
-            "[segmentation][local][debug] ffmpeg preprocessing failed (status={:?}): {}",
-            resample_output.status.code(),
-            stderr
+    // Progression du pré-traitement dérivée de la sortie `-progress` de ffmpeg.
+    let mut last_emitted = -1.0;
+    let input_duration_ms = (input_duration_s.max(0.0) * 1000.0) as u64;
+    ffmpeg::run_with_progress_callback(&resample_args, input_duration_ms, None, |progress| {
+        if input_duration_ms == 0 {
+            return;
+        }
+        let fraction = progress.percent / 100.0;
+        if fraction - last_emitted >= 0.05 {
+            last_emitted = fraction;
+            if let Ok(mut tracker) = progress_tracker.lock() {
+                let _ = app_handle.emit("segmentation-status", tracker.preprocess_status(fraction));
+            }
+        }
+    })
+    .map_err(|e| {
+        log::warn!(
+            "[segmentation][local][debug] ffmpeg preprocessing failed: {}",
+            e
         );
-        return Err(format!("ffmpeg preprocessing error: {}", stderr));
-    }
+        format!("ffmpeg preprocessing failed: {}", e)
+    })?;
     let temp_size = fs::metadata(&temp_path).map(|m| m.len()).unwrap_or(0);
-    println!(
+    log::info!(
         "[segmentation][local][debug] ffmpeg preprocessing ok temp_wav={} size={}B",
         temp_path.to_string_lossy(),
         temp_size
     );
+    let debug_audio_path = if keep_temp_audio {
+        keep_preprocessed_audio(&app_handle, &temp_path)
+    } else {
+        None
+    };
 
     let python_exe = resolve_engine_python_exe(&app_handle, engine)?;
     let script_path = resolve_python_resource_path(&app_handle, engine.script_relative_path())?;
-    println!(
+    log::info!(
         "[segmentation][local][debug] python_exe={} script_path={}",
         python_exe.to_string_lossy(),
         script_path.to_string_lossy()
     );
-    println!(
+    log::info!(
         "[segmentation][local][debug] script_exists={} temp_exists={}",
         script_path.exists(),
         temp_path.exists()
@@ -173,7 +512,7 @@ fn run_local_segmentation_script(
         args.push(ms.to_string());
     }
     args.append(&mut extra_args);
-    println!("[segmentation][local][debug] python args={:?}", args);
+    log::info!("[segmentation][local][debug] python args={:?}", args);
 
     let mut version_cmd = Command::new(&python_exe);
     version_cmd.arg("--version");
@@ -183,93 +522,163 @@ fn run_local_segmentation_script(
             let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
             let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
             let text = if !stdout.is_empty() { stdout } else { stderr };
-            println!(
+            log::info!(
                 "[segmentation][local][debug] python --version status={:?} value={}",
                 output.status.code(),
                 text
             );
         }
-        Err(err) => eprintln!(
+        Err(err) => log::warn!(
             "[segmentation][local][debug] python --version failed: {}",
             err
         ),
     }
 
-    // ExÃ©cution Python + thread de lecture stderr pour status/events de progression.
-    let mut cmd = Command::new(&python_exe);
-    cmd.args(&args);
-    if let Some(token) = hf_token {
-        if !token.trim().is_empty() {
-            apply_hf_token_env(&mut cmd, token.trim());
-        }
-    }
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-    configure_command_no_window(&mut cmd);
-
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn Python: {}", e))?;
-    println!(
-        "[segmentation][local][debug] spawned python pid={} engine={}",
-        child.id(),
-        engine.as_key()
-    );
-
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    let app_handle_clone = app_handle.clone();
+    // Exécution Python (processus dédié ou worker résident) ; le stderr alimente status/events
+    // de progression et le journal de l'exécution.
     let engine_key = engine.as_key().to_string();
-    let engine_key_for_thread = engine_key.clone();
+    let use_worker = is_worker_mode_enabled();
+    let run_log = match SegmentationRunLog::create(&app_handle, &engine_key, &args, use_worker) {
+        Ok(run_log) => Some(Arc::new(run_log)),
+        Err(error) => {
+            log::warn!("[segmentation][local][debug] run log disabled: {}", error);
+            None
+        }
+    };
     let stderr_lines = Arc::new(Mutex::new(Vec::<String>::new()));
-    let stderr_lines_clone = Arc::clone(&stderr_lines);
-
-    let stderr_handle = std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                if line.starts_with("STATUS:") {
-                    let json_str = line.trim_start_matches("STATUS:");
-                    if let Ok(status_data) = serde_json::from_str::<serde_json::Value>(json_str) {
-                        let _ = app_handle_clone.emit("segmentation-status", status_data);
-                    }
-                    println!(
-                        "[segmentation][local][status][{}] {}",
-                        engine_key_for_thread, line
-                    );
-                } else if !line.trim().is_empty() {
-                    eprintln!(
-                        "[segmentation][local][stderr][{}] {}",
-                        engine_key_for_thread, line
-                    );
-                    if let Ok(mut locked) = stderr_lines_clone.lock() {
-                        locked.push(line);
-                        if locked.len() > 120 {
-                            let drain_count = locked.len() - 120;
-                            locked.drain(0..drain_count);
-                        }
-                    }
-                }
+    let device = args
+        .iter()
+        .position(|arg| arg == "--device")
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str)
+        .unwrap_or("CPU");
+    let deadlines = timeouts.resolve(input_duration_s, device);
+    log::info!(
+        "[segmentation][local][debug] watchdog deadlines={:?}",
+        deadlines
+    );
+    let stall_timeout = deadlines.stall_timeout;
+    let stall_app_handle = app_handle.clone();
+    let stall_tracker = Arc::clone(&progress_tracker);
+    let stall_run_log = run_log.clone();
+    let mut watchdog = RunWatchdog::new(
+        deadlines,
+        Arc::clone(&stderr_lines),
+        Box::new(move |idle: Duration| {
+            let message = format!(
+                "No output from the local engine for {}; it will be stopped after {} without output",
+                format_minutes(idle),
+                format_minutes(stall_timeout)
+            );
+            log::warn!("[segmentation][local][watchdog] {}", message);
+            if let Some(run_log) = stall_run_log.as_ref() {
+                run_log.append_stderr_line(&format!("[watchdog] {}", message));
             }
+            let status = serde_json::json!({ "step": "stalled", "message": message });
+            let status = match stall_tracker.lock() {
+                Ok(mut tracker) => tracker.apply(status),
+                Err(_) => status,
+            };
+            let _ = stall_app_handle.emit("segmentation-status", status);
+        }),
+    );
+    let activity = watchdog.activity();
+    let mut handle_stderr_line = script_stderr_handler(
+        app_handle.clone(),
+        engine_key.clone(),
+        Arc::clone(&progress_tracker),
+        Arc::clone(&stderr_lines),
+        run_log.clone(),
+    );
+    let on_stderr_line = move |line: String| {
+        activity.touch();
+        handle_stderr_line(line);
+    };
+    let run_output = if use_worker {
+        run_script_in_worker(
+            &app_handle,
+            engine,
+            &python_exe,
+            &script_path,
+            &args[1..],
+            hf_token.as_deref(),
+            limits,
+            Box::new(on_stderr_line),
+            &mut watchdog,
+        )
+    } else {
+        run_script_process(
+            &python_exe,
+            &args,
+            hf_token,
+            limits,
+            &engine_key,
+            on_stderr_line,
+            &mut watchdog,
+        )
+    };
+    if let Some(run_log) = run_log.as_ref() {
+        match &run_output {
+            Ok((exit, stdout)) => run_log.finish(exit.success(), stdout),
+            Err(_) => run_log.finish(false, ""),
         }
-    });
+    }
 
-    let output = child
-        .wait_with_output()
-        .map_err(|e| format!("Failed to wait for Python: {}", e))?;
-    println!(
-        "[segmentation][local][debug] python process finished engine={} status={:?}",
-        engine_key,
-        output.status.code()
-    );
-    let _ = stderr_handle.join();
+    let outcome = run_output
+        .and_then(|(exit, stdout)| {
+            interpret_script_output(
+                &engine_key,
+                exit,
+                &stdout,
+                &stderr_lines,
+                &temp_path,
+                range,
+                confidence_filter,
+            )
+        })
+        .map(|mut result| {
+            attach_debug_audio_path(&mut result, debug_audio_path.as_deref());
+            result
+        });
+    if let Ok(result) = &outcome {
+        // Legacy ne reçoit pas `--device` : son script rapporte le device réellement utilisé.
+        let used_device = result
+            .get("device")
+            .and_then(|device| device.as_str())
+            .filter(|device| matches!(*device, "GPU" | "CPU" | "MPS"))
+            .unwrap_or(device);
+        record_local_run_speed(
+            &app_handle,
+            engine,
+            used_device,
+            input_duration_s,
+            started_at.elapsed(),
+        );
+    }
+    match run_log {
+        Some(run_log) => run_log.attach_to_outcome(outcome),
+        None => outcome,
+    }
+}
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        println!(
+/// Interprète la sortie d'un script local : JSON de segmentation validé, ou message d'erreur.
+///
+/// @param output_audio_path - WAV pré-traité passé au script (durée de référence)
+fn interpret_script_output(
+    engine_key: &str,
+    exit: ScriptExit,
+    stdout: &str,
+    stderr_lines: &Mutex<Vec<String>>,
+    output_audio_path: &Path,
+    range: Option<SegmentationRange>,
+    confidence_filter: Option<ConfidenceFilter>,
+) -> Result<serde_json::Value, String> {
+    if exit.success() {
+        log::info!(
             "[segmentation][local][debug] python stdout bytes={} (success path)",
-            output.stdout.len()
+            stdout.len()
         );
-        let result: serde_json::Value = serde_json::from_str(&stdout).map_err(|e| {
+        let result: serde_json::Value = serde_json::from_str(stdout).map_err(|e| {
             let stderr_text = stderr_lines
                 .lock()
                 .ok()
@@ -287,33 +696,45 @@ fn run_local_segmentation_script(
         if let Some(error) = result.get("error") {
             return Err(error.as_str().unwrap_or("Unknown error").to_string());
         }
+        let audio_duration_s = Some(ffprobe_duration_sec(&output_audio_path.to_string_lossy()))
+            .filter(|duration| *duration > 0.0);
+        let mut result = validate_segmentation_payload(result, audio_duration_s, confidence_filter);
+        if let Some(range) = range {
+            offset_segmentation_payload(&mut result, range);
+            range.attach_to_result(&mut result);
+        }
         Ok(result)
     } else {
-        let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr_text = stderr_lines
             .lock()
             .ok()
             .map(|lines| lines.join("\n"))
             .unwrap_or_default();
-        eprintln!(
-            "[segmentation][local][debug] python failure engine={} stdout_bytes={} stderr_buffered_lines={}",
+        log::warn!(
+            "[segmentation][local][debug] python failure engine={} exit={:?} stdout_bytes={} stderr_buffered_lines={}",
             engine_key,
-            output.stdout.len(),
+            exit,
+            stdout.len(),
             stderr_lines.lock().map(|lines| lines.len()).unwrap_or(0)
         );
         if !stdout.trim().is_empty() {
-            eprintln!(
+            log::warn!(
                 "[segmentation][local][debug] python failure stdout: {}",
                 stdout
             );
         }
         if !stderr_text.trim().is_empty() {
-            eprintln!(
+            log::warn!(
                 "[segmentation][local][debug] python failure stderr: {}",
                 stderr_text
             );
         }
-        if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&stdout) {
+        // Le traceback Python (stdout JSON ou stderr) trahit une erreur mémoire ; sinon le
+        // statut de sortie (SIGKILL, codes Windows) signale un arrêt par le système.
+        if let Some(error) = detect_out_of_memory(exit, &format!("{}\n{}", stdout, stderr_text)) {
+            return Err(error);
+        }
+        if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(stdout) {
             if let Some(error) = error_json.get("error") {
                 return Err(error.as_str().unwrap_or("Unknown error").to_string());
             }
@@ -330,32 +751,93 @@ fn run_local_segmentation_script(
 }
 
 /// ExÃ©cute la segmentation locale via moteur legacy Whisper.
+///
+/// `include_wbw_timestamps` ajoute des horodatages mot a mot estimes depuis les references Quran.
 pub async fn segment_quran_audio_local(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     whisper_model: Option<String>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
+    let range = SegmentationRange::from_raw(range_start_ms, range_end_ms)?;
+    let confidence_filter = ConfidenceFilter::from_raw(min_confidence, mark_low_confidence)?;
+    let preprocess = AudioPreprocess::local(preprocess)?;
+    let limits = LocalProcessLimits::from_raw(cpu_threads, background, available_cpu_cores())?;
+    let timeouts = LocalRunTimeouts::from_raw(
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+    )?;
+    let segmentation_hint = SegmentationHint::from_raw(surah_hint, ayah_range_hint)?;
+    let whisper_model = whisper_model
+        .map(|model| resolve_legacy_whisper_model(&model))
+        .transpose()?;
+    let memory_warning = check_local_memory(
+        &app_handle,
+        LocalSegmentationEngine::LegacyWhisper,
+        whisper_model.as_deref().unwrap_or("base"),
+        "CPU",
+        ignore_memory_check.unwrap_or(false),
+    )
+    .await?;
     let mut extra_args: Vec<String> = Vec::new();
     if let Some(model) = whisper_model {
         extra_args.push("--whisper-model".to_string());
         extra_args.push(model);
     }
+    if let Some(hint) = segmentation_hint {
+        extra_args.extend(hint.to_cli_args());
+    }
+    extra_args.push("--include-wbw-timestamps".to_string());
+    extra_args.push(
+        if include_wbw_timestamps.unwrap_or(false) {
+            "true"
+        } else {
+            "false"
+        }
+        .to_string(),
+    );
 
-    run_local_segmentation_script(
+    let mut result = run_local_segmentation_script(
         app_handle,
         LocalSegmentationEngine::LegacyWhisper,
         audio_path,
         audio_clips,
+        range,
+        confidence_filter,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         extra_args,
         None,
-    )
+        limits,
+        timeouts,
+        keep_temp_audio.unwrap_or(false),
+        preprocess,
+    )?;
+    if let Some(hint) = segmentation_hint {
+        hint.attach_to_result(&mut result);
+    }
+    attach_memory_warning(&mut result, memory_warning);
+    Ok(result)
 }
 
 /// ExÃ©cute la segmentation locale via moteur Multi-Aligner avec token HF obligatoire.
@@ -363,13 +845,38 @@ pub async fn segment_quran_audio_local_multi(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
     hf_token: Option<String>,
+    surah_hint: Option<u32>,
+    ayah_range_hint: Option<(u32, u32)>,
+    include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
+    let range = SegmentationRange::from_raw(range_start_ms, range_end_ms)?;
+    let confidence_filter = ConfidenceFilter::from_raw(min_confidence, mark_low_confidence)?;
+    let preprocess = AudioPreprocess::local(preprocess)?;
+    let limits = LocalProcessLimits::from_raw(cpu_threads, background, available_cpu_cores())?;
+    let timeouts = LocalRunTimeouts::from_raw(
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+    )?;
+    let segmentation_hint = SegmentationHint::from_raw(surah_hint, ayah_range_hint)?;
     let selected_model = model_name.unwrap_or_else(|| "Base".to_string());
     if selected_model != "Base" && selected_model != "Large" {
         return Err(format!(
@@ -377,15 +884,15 @@ pub async fn segment_quran_audio_local_multi(
             selected_model
         ));
     }
-
-    let selected_device = device.unwrap_or_else(|| "GPU".to_string()).to_uppercase();
-    if selected_device != "GPU" && selected_device != "CPU" {
-        return Err(format!(
-            "Invalid device '{}'. Expected 'GPU' or 'CPU'.",
-            selected_device
-        ));
+    if include_wbw_timestamps.unwrap_or(false) {
+        return Err(
+            "Word timestamps are not available with the local Multi-Aligner engine. Use Legacy Whisper, Muaalem Local or Surah Splitter, or request MFA timestamps after segmentation."
+                .to_string(),
+        );
     }
 
+    let selected_device = resolve_compute_device(device)?;
+
     let token_present = hf_token
         .as_ref()
         .map(|token| !token.trim().is_empty())
@@ -397,24 +904,85 @@ pub async fn segment_quran_audio_local_multi(
         );
     }
 
-    let extra_args = vec![
-        "--model-name".to_string(),
-        selected_model,
-        "--device".to_string(),
-        selected_device,
-    ];
-
-    run_local_segmentation_script(
-        app_handle,
+    let memory_warning = check_local_memory(
+        &app_handle,
         LocalSegmentationEngine::MultiAligner,
-        audio_path,
-        audio_clips,
-        min_silence_ms,
-        min_speech_ms,
-        pad_ms,
-        extra_args,
-        hf_token,
+        &selected_model,
+        &selected_device,
+        ignore_memory_check.unwrap_or(false),
     )
+    .await?;
+
+    let run_on_device = |device: &str| {
+        let mut extra_args = vec![
+            "--model-name".to_string(),
+            selected_model.clone(),
+            "--device".to_string(),
+            device.to_string(),
+        ];
+        if let Some(hint) = segmentation_hint {
+            extra_args.extend(hint.to_cli_args());
+        }
+        run_local_segmentation_script(
+            app_handle.clone(),
+            LocalSegmentationEngine::MultiAligner,
+            audio_path.clone(),
+            audio_clips.clone(),
+            range,
+            confidence_filter,
+            min_silence_ms,
+            min_speech_ms,
+            pad_ms,
+            extra_args,
+            hf_token.clone(),
+            limits,
+            timeouts,
+            keep_temp_audio.unwrap_or(false),
+            preprocess,
+        )
+    };
+
+    // Un OOM CUDA ou un pilote instable ne doit pas faire echouer tout le run : une seule relance sur CPU.
+    let mut result = match run_on_device(&selected_device) {
+        Err(error) if selected_device != "CPU" && is_gpu_failure(&error) => {
+            log::warn!(
+                "[segmentation][local] {} segmentation failed, retrying on CPU: {}",
+                selected_device,
+                error
+            );
+            let _ = app_handle.emit(
+                "segmentation-status",
+                serde_json::json!({
+                    "step": "device_fallback",
+                    "message": format!(
+                        "{} segmentation failed ({}). Retrying on CPU, this will be slower...",
+                        selected_device,
+                        error.lines().next().unwrap_or_default()
+                    ),
+                    "fromDevice": selected_device,
+                    "toDevice": "CPU",
+                }),
+            );
+            let mut result = run_on_device("CPU")?;
+            if let Some(object) = result.as_object_mut() {
+                object.insert(
+                    "deviceFallback".to_string(),
+                    serde_json::json!({
+                        "from": selected_device,
+                        "to": "CPU",
+                        "reason": error,
+                    }),
+                );
+            }
+            result
+        }
+        other => other?,
+    };
+    if let Some(hint) = segmentation_hint {
+        hint.attach_to_result(&mut result);
+    }
+    attach_memory_warning(&mut result, memory_warning);
+    Ok(result)
 }
 
 /// Exécute la segmentation locale via moteur Muaalem sans token HF.
@@ -422,13 +990,34 @@ pub async fn segment_quran_audio_local_muaalem(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
     model_name: Option<String>,
     device: Option<String>,
     include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
+    let range = SegmentationRange::from_raw(range_start_ms, range_end_ms)?;
+    let confidence_filter = ConfidenceFilter::from_raw(min_confidence, mark_low_confidence)?;
+    let preprocess = AudioPreprocess::local(preprocess)?;
+    let limits = LocalProcessLimits::from_raw(cpu_threads, background, available_cpu_cores())?;
+    let timeouts = LocalRunTimeouts::from_raw(
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+    )?;
     let selected_model = model_name.unwrap_or_else(|| "Muaalem-v3.2".to_string());
     let valid_models = [
         "Muaalem-v3.2",
@@ -446,13 +1035,16 @@ pub async fn segment_quran_audio_local_muaalem(
         return Err(format!("Invalid model_name '{}'.", selected_model));
     }
 
-    let selected_device = device.unwrap_or_else(|| "GPU".to_string()).to_uppercase();
-    if selected_device != "GPU" && selected_device != "CPU" {
-        return Err(format!(
-            "Invalid device '{}'. Expected 'GPU' or 'CPU'.",
-            selected_device
-        ));
-    }
+    let selected_device = resolve_compute_device(device)?;
+
+    let memory_warning = check_local_memory(
+        &app_handle,
+        LocalSegmentationEngine::MuaalemLocal,
+        &selected_model,
+        &selected_device,
+        ignore_memory_check.unwrap_or(false),
+    )
+    .await?;
 
     let extra_args = vec![
         "--model-name".to_string(),
@@ -467,17 +1059,25 @@ pub async fn segment_quran_audio_local_muaalem(
         },
     ];
 
-    run_local_segmentation_script(
+    let mut result = run_local_segmentation_script(
         app_handle,
         LocalSegmentationEngine::MuaalemLocal,
         audio_path,
         audio_clips,
+        range,
+        confidence_filter,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         extra_args,
         None,
-    )
+        limits,
+        timeouts,
+        keep_temp_audio.unwrap_or(false),
+        preprocess,
+    )?;
+    attach_memory_warning(&mut result, memory_warning);
+    Ok(result)
 }
 
 /// Exécute la segmentation locale via Surah Splitter sans token HF.
@@ -485,6 +1085,10 @@ pub async fn segment_quran_audio_local_surah_splitter(
     app_handle: tauri::AppHandle,
     audio_path: Option<String>,
     audio_clips: Option<Vec<SegmentationAudioClip>>,
+    range_start_ms: Option<u64>,
+    range_end_ms: Option<u64>,
+    min_confidence: Option<f64>,
+    mark_low_confidence: Option<bool>,
     min_silence_ms: Option<u32>,
     min_speech_ms: Option<u32>,
     pad_ms: Option<u32>,
@@ -492,19 +1096,30 @@ pub async fn segment_quran_audio_local_surah_splitter(
     device: Option<String>,
     surah: Option<u32>,
     include_wbw_timestamps: Option<bool>,
+    cpu_threads: Option<u32>,
+    background: Option<bool>,
+    timeout_minutes: Option<u64>,
+    stall_warning_minutes: Option<u64>,
+    stall_timeout_minutes: Option<u64>,
+    ignore_memory_check: Option<bool>,
+    keep_temp_audio: Option<bool>,
+    preprocess: Option<AudioPreprocessOptions>,
 ) -> Result<serde_json::Value, String> {
+    let range = SegmentationRange::from_raw(range_start_ms, range_end_ms)?;
+    let confidence_filter = ConfidenceFilter::from_raw(min_confidence, mark_low_confidence)?;
+    let preprocess = AudioPreprocess::local(preprocess)?;
+    let limits = LocalProcessLimits::from_raw(cpu_threads, background, available_cpu_cores())?;
+    let timeouts = LocalRunTimeouts::from_raw(
+        timeout_minutes,
+        stall_warning_minutes,
+        stall_timeout_minutes,
+    )?;
     let selected_model = model_name.unwrap_or_else(|| "SurahSplitter-Base-Quran".to_string());
     if selected_model != "SurahSplitter-Base-Quran" {
         return Err(format!("Invalid model_name '{}'.", selected_model));
     }
 
-    let selected_device = device.unwrap_or_else(|| "GPU".to_string()).to_uppercase();
-    if selected_device != "GPU" && selected_device != "CPU" {
-        return Err(format!(
-            "Invalid device '{}'. Expected 'GPU' or 'CPU'.",
-            selected_device
-        ));
-    }
+    let selected_device = resolve_compute_device(device)?;
 
     if let Some(surah_number) = surah {
         if !(1..=114).contains(&surah_number) {
@@ -515,6 +1130,15 @@ pub async fn segment_quran_audio_local_surah_splitter(
         }
     }
 
+    let memory_warning = check_local_memory(
+        &app_handle,
+        LocalSegmentationEngine::SurahSplitter,
+        &selected_model,
+        &selected_device,
+        ignore_memory_check.unwrap_or(false),
+    )
+    .await?;
+
     let extra_args = vec![
         "--model-name".to_string(),
         selected_model,
@@ -530,15 +1154,57 @@ pub async fn segment_quran_audio_local_surah_splitter(
         },
     ];
 
-    run_local_segmentation_script(
+    let mut result = run_local_segmentation_script(
         app_handle,
         LocalSegmentationEngine::SurahSplitter,
         audio_path,
         audio_clips,
+        range,
+        confidence_filter,
         min_silence_ms,
         min_speech_ms,
         pad_ms,
         extra_args,
         None,
-    )
+        limits,
+        timeouts,
+        keep_temp_audio.unwrap_or(false),
+        preprocess,
+    )?;
+    attach_memory_warning(&mut result, memory_warning);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_legacy_whisper_model;
+    use std::fs;
+
+    #[test]
+    fn custom_whisper_folder_reports_missing_files() {
+        assert_eq!(resolve_legacy_whisper_model("Base").unwrap(), "base");
+        assert!(resolve_legacy_whisper_model("huge").is_err());
+
+        let model_dir = std::env::temp_dir().join(format!(
+            "qurancaption-whisper-model-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&model_dir);
+        fs::create_dir_all(&model_dir).unwrap();
+        fs::write(model_dir.join("config.json"), "{}").unwrap();
+        fs::write(model_dir.join("model.safetensors"), "").unwrap();
+
+        let model_path = model_dir.to_string_lossy().to_string();
+        let error = resolve_legacy_whisper_model(&model_path).unwrap_err();
+        assert!(error.ends_with("Missing: preprocessor_config.json, tokenizer.json or vocab.json"));
+
+        fs::write(model_dir.join("preprocessor_config.json"), "{}").unwrap();
+        fs::write(model_dir.join("vocab.json"), "{}").unwrap();
+        assert_eq!(
+            resolve_legacy_whisper_model(&model_path).unwrap(),
+            model_path
+        );
+
+        let _ = fs::remove_dir_all(&model_dir);
+    }
 }
@@ -0,0 +1,267 @@
+//! Estimation de la durée d'une segmentation locale avant son lancement.
+//!
+//! Les facteurs temps réel mesurés sur nos machines de référence servent de point de départ ;
+//! chaque exécution réussie affine ensuite le facteur du couple moteur/device sur cette machine.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::Manager;
+
+use super::types::{resolve_compute_device, LocalSegmentationEngine};
+
+/// Fichier des vitesses observées, dans les données de l'application.
+const SPEED_STATS_FILE_NAME: &str = "segmentation_speed_stats.json";
+/// Chargement des modèles et pré-traitement, indépendants de la durée audio (secondes).
+const MODEL_LOAD_OVERHEAD_S: f64 = 20.0;
+/// En dessous de cette durée audio, le temps mesuré est dominé par le chargement : ignoré.
+const MIN_RECORDED_AUDIO_S: f64 = 30.0;
+/// Poids d'une nouvelle mesure dans la moyenne glissante du facteur observé.
+const OBSERVED_FACTOR_WEIGHT: f64 = 0.3;
+/// Poids de la première mesure face à la référence (téléchargement des modèles, cache froid...).
+const FIRST_OBSERVATION_WEIGHT: f64 = 0.5;
+
+/// Sérialise les lectures/écritures du fichier des vitesses observées.
+static SPEED_STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Vitesse observée d'un couple moteur/device sur cette machine.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct ObservedSpeed {
+    /// Secondes de traitement par seconde d'audio (moyenne glissante).
+    factor: f64,
+    /// Nombre d'exécutions mesurées.
+    runs: u32,
+}
+
+/// Estimation de la durée d'une segmentation locale.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSegmentationEstimate {
+    /// Moteur estimé (`legacy`, `multi`, `muaalem`, `surah_splitter`).
+    pub engine: String,
+    /// Device effectif (`GPU`, `CPU` ou `MPS`).
+    pub device: String,
+    /// Durée estimée en secondes, chargement des modèles compris.
+    pub estimated_seconds: f64,
+    /// Secondes de traitement par seconde d'audio utilisées pour l'estimation.
+    pub real_time_factor: f64,
+    /// `observed` si le facteur vient d'exécutions sur cette machine, sinon `benchmark`.
+    pub source: String,
+    /// Nombre d'exécutions mesurées pour ce moteur et ce device.
+    pub observed_runs: u32,
+}
+
+/// Facteur temps réel de référence d'un moteur sur un device.
+fn benchmark_real_time_factor(engine: LocalSegmentationEngine, device: &str) -> f64 {
+    let gpu = device != "CPU";
+    match engine {
+        LocalSegmentationEngine::LegacyWhisper => {
+            if gpu {
+                0.15
+            } else {
+                1.0
+            }
+        }
+        LocalSegmentationEngine::MultiAligner => {
+            if gpu {
+                0.3
+            } else {
+                3.0
+            }
+        }
+        LocalSegmentationEngine::MuaalemLocal => {
+            if gpu {
+                0.2
+            } else {
+                2.0
+            }
+        }
+        LocalSegmentationEngine::SurahSplitter => {
+            if gpu {
+                0.25
+            } else {
+                2.5
+            }
+        }
+    }
+}
+
+/// Clé d'un couple moteur/device dans le fichier des vitesses observées.
+fn speed_key(engine: LocalSegmentationEngine, device: &str) -> String {
+    format!("{}:{}", engine.as_key(), device)
+}
+
+/// Intègre une mesure dans la vitesse observée (moyenne glissante).
+///
+/// La première mesure est mélangée à la référence : elle inclut souvent le téléchargement
+/// des modèles et ne doit pas la remplacer à elle seule.
+fn update_observed_speed(
+    previous: Option<ObservedSpeed>,
+    factor: f64,
+    benchmark_factor: f64,
+) -> ObservedSpeed {
+    match previous {
+        Some(previous) => ObservedSpeed {
+            factor: previous.factor * (1.0 - OBSERVED_FACTOR_WEIGHT)
+                + factor * OBSERVED_FACTOR_WEIGHT,
+            runs: previous.runs.saturating_add(1),
+        },
+        None => ObservedSpeed {
+            factor: benchmark_factor * (1.0 - FIRST_OBSERVATION_WEIGHT)
+                + factor * FIRST_OBSERVATION_WEIGHT,
+            runs: 1,
+        },
+    }
+}
+
+/// Calcule l'estimation à partir du facteur observé s'il existe, sinon de la référence.
+fn build_estimate(
+    engine: LocalSegmentationEngine,
+    device: String,
+    audio_duration_s: f64,
+    observed: Option<ObservedSpeed>,
+) -> LocalSegmentationEstimate {
+    let (real_time_factor, source, observed_runs) = match observed {
+        Some(speed) => (speed.factor, "observed", speed.runs),
+        None => (benchmark_real_time_factor(engine, &device), "benchmark", 0),
+    };
+    LocalSegmentationEstimate {
+        engine: engine.as_key().to_string(),
+        device,
+        estimated_seconds: (MODEL_LOAD_OVERHEAD_S + audio_duration_s * real_time_factor).round(),
+        real_time_factor,
+        source: source.to_string(),
+        observed_runs,
+    }
+}
+
+/// Chemin du fichier des vitesses observées.
+fn speed_stats_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(SPEED_STATS_FILE_NAME))
+}
+
+/// Lit les vitesses observées (vide si le fichier est absent ou illisible).
+fn read_speed_stats(path: &Path) -> BTreeMap<String, ObservedSpeed> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Enregistre la vitesse d'une segmentation locale réussie pour affiner les estimations.
+///
+/// Les exécutions trop courtes sont ignorées ; un échec d'écriture est seulement journalisé.
+pub(crate) fn record_local_run_speed(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+    device: &str,
+    audio_duration_s: f64,
+    elapsed: Duration,
+) {
+    if !audio_duration_s.is_finite() || audio_duration_s < MIN_RECORDED_AUDIO_S {
+        return;
+    }
+    let factor = (elapsed.as_secs_f64() - MODEL_LOAD_OVERHEAD_S).max(0.0) / audio_duration_s;
+    let saved = speed_stats_path(app_handle).and_then(|path| {
+        let _guard = SPEED_STATS_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut stats = read_speed_stats(&path);
+        let key = speed_key(engine, device);
+        let speed = update_observed_speed(
+            stats.get(&key).copied(),
+            factor,
+            benchmark_real_time_factor(engine, device),
+        );
+        stats.insert(key, speed);
+        let content = serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| e.to_string())?;
+        Ok(speed)
+    });
+    match saved {
+        Ok(speed) => log::info!(
+            "[segmentation][estimate] engine={} device={} observed_factor={:.2} average={:.2} runs={}",
+            engine.as_key(),
+            device,
+            factor,
+            speed.factor,
+            speed.runs
+        ),
+        Err(error) => log::warn!(
+            "[segmentation][estimate] failed to save observed speed: {}",
+            error
+        ),
+    }
+}
+
+/// Estime la durée d'une segmentation locale.
+///
+/// @param audio_duration_s - Durée de l'audio à segmenter (secondes)
+/// @param engine - Moteur local (`legacy`, `multi`, `muaalem`, `surah_splitter`)
+/// @param device - `GPU` (défaut), `CPU` ou `MPS`
+pub fn estimate_local_segmentation(
+    app_handle: tauri::AppHandle,
+    audio_duration_s: f64,
+    engine: String,
+    device: Option<String>,
+) -> Result<LocalSegmentationEstimate, String> {
+    if !audio_duration_s.is_finite() || audio_duration_s <= 0.0 {
+        return Err("audio_duration_s must be a positive finite number.".to_string());
+    }
+    let engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    let device = resolve_compute_device(device)?;
+    let observed = speed_stats_path(&app_handle).ok().and_then(|path| {
+        let _guard = SPEED_STATS_LOCK.lock().ok()?;
+        read_speed_stats(&path)
+            .get(&speed_key(engine, &device))
+            .copied()
+    });
+    Ok(build_estimate(engine, device, audio_duration_s, observed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_estimate, update_observed_speed, ObservedSpeed};
+    use crate::segmentation::types::LocalSegmentationEngine;
+
+    #[test]
+    fn estimate_uses_benchmark_then_observed_speed() {
+        let estimate = build_estimate(
+            LocalSegmentationEngine::MultiAligner,
+            "CPU".to_string(),
+            600.0,
+            None,
+        );
+        assert_eq!(estimate.real_time_factor, 3.0);
+        assert_eq!(estimate.estimated_seconds, 1820.0);
+        assert_eq!(estimate.source, "benchmark");
+
+        // La première mesure (1.0) est mélangée à la référence CPU (3.0).
+        let first = update_observed_speed(None, 1.0, 3.0);
+        assert_eq!(
+            first,
+            ObservedSpeed {
+                factor: 2.0,
+                runs: 1
+            }
+        );
+        let second = update_observed_speed(Some(first), 3.0, 3.0);
+        assert!((second.factor - 2.3).abs() < 1e-9);
+        assert_eq!(second.runs, 2);
+
+        let estimate = build_estimate(
+            LocalSegmentationEngine::MultiAligner,
+            "CPU".to_string(),
+            600.0,
+            Some(second),
+        );
+        assert_eq!(estimate.source, "observed");
+        assert_eq!(estimate.observed_runs, 2);
+        assert_eq!(estimate.estimated_seconds, 1400.0);
+    }
+}
@@ -0,0 +1,262 @@
+//! Python autonome (python-build-standalone) téléchargé dans les données de l'application.
+//!
+//! Quand il est installé, il sert d'interpréteur de base aux venvs des moteurs locaux à la
+//! place du Python système.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use tauri::{Emitter, Manager};
+
+use crate::commands::files::{build_download_client, download_with_resume, file_sha256};
+use crate::utils::process::{configure_command_no_window, sanitize_cmd_error};
+
+use super::python_env::{
+    python_version_meets_min, read_python_version, resolve_system_python, PythonInterpreter,
+    MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR,
+};
+use super::status::invalidate_local_readiness_cache;
+
+/// Version CPython distribuée.
+const MANAGED_PYTHON_VERSION: &str = "3.11.10";
+/// Release python-build-standalone contenant cette version.
+const MANAGED_PYTHON_RELEASE: &str = "20241016";
+/// URL de base des releases python-build-standalone.
+const MANAGED_PYTHON_DOWNLOAD_URL: &str =
+    "https://github.com/astral-sh/python-build-standalone/releases/download";
+
+/// Triplets des distributions `install_only` disponibles (OS, architecture, triplet).
+const MANAGED_PYTHON_TARGETS: [(&str, &str, &str); 5] = [
+    ("windows", "x86_64", "x86_64-pc-windows-msvc"),
+    ("linux", "x86_64", "x86_64-unknown-linux-gnu"),
+    ("linux", "aarch64", "aarch64-unknown-linux-gnu"),
+    ("macos", "x86_64", "x86_64-apple-darwin"),
+    ("macos", "aarch64", "aarch64-apple-darwin"),
+];
+
+/// Nom de l'archive `install_only` pour un OS et une architecture.
+fn managed_python_archive_name(os: &str, arch: &str) -> Option<String> {
+    MANAGED_PYTHON_TARGETS
+        .iter()
+        .find(|(target_os, target_arch, _)| *target_os == os && *target_arch == arch)
+        .map(|(_, _, triple)| {
+            format!(
+                "cpython-{}+{}-{}-install_only.tar.gz",
+                MANAGED_PYTHON_VERSION, MANAGED_PYTHON_RELEASE, triple
+            )
+        })
+}
+
+/// Extrait l'empreinte d'un fichier depuis un `SHA256SUMS` (`<hash>  <fichier>`).
+fn find_sha256_in_sums(sums: &str, file_name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim().trim_start_matches('*');
+        (name == file_name && hash.len() == 64).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+/// Dossier racine du Python autonome dans les données de l'application.
+pub(super) fn managed_python_root(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join("python_runtime"))
+}
+
+/// Dossier d'installation de la version distribuée.
+fn managed_python_install_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(managed_python_root(app_handle)?.join(format!("cpython-{}", MANAGED_PYTHON_VERSION)))
+}
+
+/// Exécutable Python d'une distribution `install_only` extraite.
+fn managed_python_exe(install_dir: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        install_dir.join("python.exe")
+    } else {
+        install_dir.join("bin").join("python3")
+    }
+}
+
+/// Retourne le Python autonome s'il est installé et compatible.
+pub(crate) fn resolve_managed_python(app_handle: &tauri::AppHandle) -> Option<PythonInterpreter> {
+    let python_exe = managed_python_exe(&managed_python_install_dir(app_handle).ok()?);
+    if !python_exe.is_file() {
+        return None;
+    }
+    let (major, minor, patch) = read_python_version(&python_exe)?;
+    if !python_version_meets_min(major, minor, MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR) {
+        return None;
+    }
+    let executable = python_exe.to_string_lossy().to_string();
+    Some(PythonInterpreter {
+        command: executable.clone(),
+        executable,
+        major,
+        minor,
+        patch,
+        source: "managed",
+    })
+}
+
+/// Interpréteur de base des venvs : le Python autonome s'il est installé, sinon le système.
+pub(crate) fn resolve_base_python(
+    app_handle: &tauri::AppHandle,
+) -> Result<PythonInterpreter, String> {
+    if let Some(interpreter) = resolve_managed_python(app_handle) {
+        return Ok(interpreter);
+    }
+    resolve_system_python(MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR)
+        .map_err(|e| format!("{} You can also download the managed Python runtime.", e))
+}
+
+/// Émet la progression de l'installation du Python autonome.
+fn emit_managed_python_progress(app_handle: &tauri::AppHandle, phase: &str, downloaded_bytes: u64) {
+    let _ = app_handle.emit(
+        "managed-python-progress",
+        serde_json::json!({
+            "phase": phase,
+            "downloadedBytes": downloaded_bytes,
+        }),
+    );
+}
+
+/// Extrait une archive `.tar.gz` avec le `tar` du système (présent sur Windows 10+).
+fn extract_tar_gz(archive_path: &Path, destination: &Path) -> Result<(), String> {
+    let mut cmd = Command::new("tar");
+    cmd.arg("-xzf").arg(archive_path).arg("-C").arg(destination);
+    configure_command_no_window(&mut cmd);
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract managed Python archive: {}",
+            sanitize_cmd_error(&output)
+        ));
+    }
+    Ok(())
+}
+
+/// Télécharge, vérifie et extrait le Python autonome dans les données de l'application.
+///
+/// Le téléchargement reprend un `.part` existant ; l'empreinte est vérifiée contre le
+/// `SHA256SUMS` de la release. La progression est émise sur `managed-python-progress`.
+///
+/// @returns Chemin de l'exécutable Python installé
+pub async fn install_managed_python(app_handle: tauri::AppHandle) -> Result<String, String> {
+    if let Some(interpreter) = resolve_managed_python(&app_handle) {
+        return Ok(interpreter.executable);
+    }
+    let archive_name = managed_python_archive_name(std::env::consts::OS, std::env::consts::ARCH)
+        .ok_or_else(|| {
+            format!(
+                "Managed Python is not available for {}/{}",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )
+        })?;
+    let root = managed_python_root(&app_handle)?;
+    fs::create_dir_all(&root).map_err(|e| {
+        format!(
+            "Failed to create managed Python directory '{}': {}",
+            root.to_string_lossy(),
+            e
+        )
+    })?;
+
+    let client = build_download_client()?;
+    emit_managed_python_progress(&app_handle, "checksum", 0);
+    let release_url = format!("{}/{}", MANAGED_PYTHON_DOWNLOAD_URL, MANAGED_PYTHON_RELEASE);
+    let sums_url = format!("{}/SHA256SUMS", release_url);
+    let sums = client
+        .get(&sums_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download managed Python checksums: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read managed Python checksums: {}", e))?;
+    let expected_sha256 = find_sha256_in_sums(&sums, &archive_name)
+        .ok_or_else(|| format!("No checksum published for {}", archive_name))?;
+
+    let archive_path = root.join(&archive_name);
+    let archive_url = format!("{}/{}", release_url, archive_name);
+    let mut downloaded_bytes = 0u64;
+    let mut last_emit = Instant::now();
+    emit_managed_python_progress(&app_handle, "download", 0);
+    download_with_resume(&client, &archive_url, &archive_path, |delta| {
+        downloaded_bytes = downloaded_bytes.saturating_add_signed(delta);
+        if last_emit.elapsed() >= Duration::from_millis(250) {
+            last_emit = Instant::now();
+            emit_managed_python_progress(&app_handle, "download", downloaded_bytes);
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to download managed Python: {}", e))?;
+
+    emit_managed_python_progress(&app_handle, "verify", downloaded_bytes);
+    let install_dir = managed_python_install_dir(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let actual_sha256 = file_sha256(&archive_path)?;
+        if !actual_sha256.eq_ignore_ascii_case(&expected_sha256) {
+            let _ = fs::remove_file(&archive_path);
+            return Err(format!(
+                "Managed Python checksum mismatch for {} (expected {}, got {})",
+                archive_name, expected_sha256, actual_sha256
+            ));
+        }
+
+        // L'archive contient un dossier `python/`, extrait à part puis renommé.
+        let staging_dir = root.join(format!("cpython-{}.staging", MANAGED_PYTHON_VERSION));
+        let _ = fs::remove_dir_all(&staging_dir);
+        fs::create_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to create extraction directory: {}", e))?;
+        let extracted = extract_tar_gz(&archive_path, &staging_dir).and_then(|_| {
+            let _ = fs::remove_dir_all(&install_dir);
+            fs::rename(staging_dir.join("python"), &install_dir)
+                .map_err(|e| format!("Failed to finalize managed Python: {}", e))
+        });
+        let _ = fs::remove_dir_all(&staging_dir);
+        extracted?;
+        let _ = fs::remove_file(&archive_path);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Managed Python extraction task failed: {}", e))??;
+    invalidate_local_readiness_cache();
+
+    emit_managed_python_progress(&app_handle, "done", downloaded_bytes);
+    resolve_managed_python(&app_handle)
+        .map(|interpreter| interpreter.executable)
+        .ok_or_else(|| "Managed Python was extracted but is not usable".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_sha256_in_sums, managed_python_archive_name};
+
+    #[test]
+    fn archive_names_and_checksums_are_resolved_per_target() {
+        let archive = managed_python_archive_name("linux", "x86_64").unwrap();
+        assert_eq!(
+            archive,
+            "cpython-3.11.10+20241016-x86_64-unknown-linux-gnu-install_only.tar.gz"
+        );
+        assert!(managed_python_archive_name("windows", "aarch64").is_none());
+
+        let hash = "a".repeat(64);
+        let sums = format!(
+            "{}  cpython-3.11.10+20241016-aarch64-apple-darwin-install_only.tar.gz\n{}  {}\n",
+            "b".repeat(64),
+            hash.to_uppercase(),
+            archive
+        );
+        assert_eq!(find_sha256_in_sums(&sums, &archive), Some(hash));
+        assert_eq!(find_sha256_in_sums(&sums, "missing.tar.gz"), None);
+    }
+}
@@ -0,0 +1,365 @@
+use std::process::Command;
+
+use tauri::Emitter;
+
+use crate::utils::process::configure_command_no_window;
+
+use super::devices::detect_nvidia_free_vram_mb;
+use super::types::{LocalSegmentationEngine, ScriptExit};
+
+/// Marge (en %) au-dessus du besoin estimé en dessous de laquelle on avertit seulement.
+const MEMORY_WARNING_MARGIN_PERCENT: u64 = 25;
+
+/// Code d'erreur d'une segmentation locale interrompue faute de mémoire.
+pub(crate) const SEGMENTATION_OUT_OF_MEMORY_ERROR: &str = "SEGMENTATION_OUT_OF_MEMORY";
+/// Fragments (en minuscules) des erreurs de mémoire GPU dans la sortie Python.
+const GPU_OUT_OF_MEMORY_MARKERS: [&str; 4] = [
+    "cuda out of memory",
+    "cuda error: out of memory",
+    "cublas_status_alloc_failed",
+    "hip out of memory",
+];
+/// Fragments (en minuscules) des autres erreurs GPU (pilote, assertion CUDA) dans une erreur.
+const GPU_FAILURE_MARKERS: [&str; 8] = [
+    "the gpu ran out of memory",
+    "device-side assert",
+    "cuda error",
+    "cudnn_status",
+    "cublas_status",
+    "hip error",
+    "no cuda gpus are available",
+    "mps backend out of memory",
+];
+/// Fragments (en minuscules) des erreurs de mémoire système dans la sortie Python.
+const RAM_OUT_OF_MEMORY_MARKERS: [&str; 6] = [
+    "memoryerror",
+    "can't allocate memory",
+    "cannot allocate memory",
+    "unable to allocate",
+    "std::bad_alloc",
+    "not enough memory",
+];
+/// Codes de sortie Windows d'un processus à court de mémoire (`STATUS_NO_MEMORY`,
+/// `STATUS_COMMITMENT_LIMIT`).
+const WINDOWS_OUT_OF_MEMORY_EXIT_CODES: [u32; 2] = [0xC000_0017, 0xC000_012D];
+/// Signal envoyé par l'OOM killer Linux.
+const SIGKILL: i32 = 9;
+
+/// Besoin mémoire estimé d'un couple moteur/modèle.
+#[derive(Clone, Copy)]
+pub(crate) struct LocalMemoryRequirement {
+    /// RAM système nécessaire (Mo).
+    pub ram_mb: u64,
+    /// VRAM nécessaire en mode GPU (Mo).
+    pub vram_mb: u64,
+}
+
+/// Estime la mémoire nécessaire à un moteur local pour un modèle donné.
+pub(crate) fn estimate_local_memory_requirement(
+    engine: LocalSegmentationEngine,
+    model: &str,
+) -> LocalMemoryRequirement {
+    let model = model.to_lowercase();
+    let (ram_mb, vram_mb) = match engine {
+        LocalSegmentationEngine::LegacyWhisper => match model.as_str() {
+            "tiny" => (1_500, 1_000),
+            "medium" => (5_500, 5_000),
+            "large" => (10_000, 10_000),
+            _ => (2_000, 1_000),
+        },
+        LocalSegmentationEngine::MultiAligner => {
+            if model == "large" {
+                (8_000, 4_000)
+            } else {
+                (4_000, 2_000)
+            }
+        }
+        LocalSegmentationEngine::MuaalemLocal => {
+            if model.contains("large") {
+                (8_000, 6_000)
+            } else if model.contains("medium") {
+                (5_500, 4_000)
+            } else {
+                (3_000, 2_000)
+            }
+        }
+        LocalSegmentationEngine::SurahSplitter => (4_000, 3_000),
+    };
+    LocalMemoryRequirement { ram_mb, vram_mb }
+}
+
+/// Formate une quantité de mémoire en Go lisibles.
+fn format_gb(mb: u64) -> String {
+    format!("{:.1} GB", mb as f64 / 1024.0)
+}
+
+/// Compare un besoin à la mémoire disponible.
+///
+/// Retourne `Err` si la mémoire est insuffisante, `Ok(Some(warning))` si la marge est faible.
+/// En mode GPU/MPS les poids quittent la RAM une fois chargés : un manque de RAM n'y est
+/// qu'un avertissement.
+fn evaluate_memory(
+    requirement: LocalMemoryRequirement,
+    model: &str,
+    device: &str,
+    available_ram_mb: u64,
+    free_vram_mb: Option<u64>,
+) -> Result<Option<String>, String> {
+    let gpu = !device.eq_ignore_ascii_case("CPU");
+    if available_ram_mb < requirement.ram_mb && !gpu {
+        return Err(format!(
+            "{} model needs ~{} RAM, you have {} free. Use a smaller model or cloud mode.",
+            model,
+            format_gb(requirement.ram_mb),
+            format_gb(available_ram_mb)
+        ));
+    }
+    if let Some(free_vram_mb) = free_vram_mb {
+        if free_vram_mb < requirement.vram_mb {
+            return Err(format!(
+                "{} model needs ~{} of GPU memory, your GPU has {} free. Switch the device to CPU, use a smaller model or cloud mode.",
+                model,
+                format_gb(requirement.vram_mb),
+                format_gb(free_vram_mb)
+            ));
+        }
+    }
+
+    if available_ram_mb < requirement.ram_mb {
+        return Ok(Some(format!(
+            "{} model needs ~{} RAM and only {} is free. Loading it on the GPU may still work; close other applications or use cloud mode if segmentation fails.",
+            model,
+            format_gb(requirement.ram_mb),
+            format_gb(available_ram_mb)
+        )));
+    }
+
+    let comfortable_ram_mb = requirement.ram_mb * (100 + MEMORY_WARNING_MARGIN_PERCENT) / 100;
+    if available_ram_mb < comfortable_ram_mb {
+        return Ok(Some(format!(
+            "{} model needs ~{} RAM and only {} is free. Close other applications if segmentation fails.",
+            model,
+            format_gb(requirement.ram_mb),
+            format_gb(available_ram_mb)
+        )));
+    }
+    Ok(None)
+}
+
+/// Indique si le journal noyau (Linux, s'il est lisible) attribue l'arrêt du PID à l'OOM killer.
+fn kernel_reports_oom_kill(pid: u32) -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    let mut dmesg = Command::new("dmesg");
+    configure_command_no_window(&mut dmesg);
+    let Ok(output) = dmesg.output() else {
+        return false;
+    };
+    let needle = format!("killed process {} ", pid);
+    String::from_utf8_lossy(&output.stdout)
+        .to_lowercase()
+        .lines()
+        .any(|line| line.contains(&needle) && line.contains("out of memory"))
+}
+
+/// Détecte un script Python arrêté faute de mémoire (statut de sortie puis sortie texte).
+///
+/// @param output - Sortie standard et stderr capturées du script
+/// @returns Message `SEGMENTATION_OUT_OF_MEMORY: ...` avec des conseils, si détecté
+pub(crate) fn detect_out_of_memory(exit: ScriptExit, output: &str) -> Option<String> {
+    let output = output.to_lowercase();
+    let detail = if GPU_OUT_OF_MEMORY_MARKERS
+        .iter()
+        .any(|marker| output.contains(marker))
+    {
+        "the GPU ran out of memory. Switch the device to CPU, use the Base model or cloud mode."
+            .to_string()
+    } else if RAM_OUT_OF_MEMORY_MARKERS
+        .iter()
+        .any(|marker| output.contains(marker))
+    {
+        "Python ran out of memory. Use the Base model, close other applications or use cloud mode."
+            .to_string()
+    } else if exit.signal == Some(SIGKILL) {
+        let cause = if exit.pid.is_some_and(kernel_reports_oom_kill) {
+            "the system killed the process because it ran out of memory"
+        } else {
+            "the process was killed by the system (SIGKILL), most likely because it ran out of memory"
+        };
+        format!(
+            "{}. Use the Base model, switch the device to CPU or use cloud mode.",
+            cause
+        )
+    } else if exit
+        .code
+        .is_some_and(|code| WINDOWS_OUT_OF_MEMORY_EXIT_CODES.contains(&(code as u32)))
+    {
+        "the process ran out of memory. Use the Base model, switch the device to CPU or use cloud mode."
+            .to_string()
+    } else {
+        return None;
+    };
+    Some(format!(
+        "{}: Local segmentation failed because {}",
+        SEGMENTATION_OUT_OF_MEMORY_ERROR, detail
+    ))
+}
+
+/// Vérifie la mémoire disponible avant de lancer un moteur local.
+///
+/// Les erreurs bloquantes sont retournées telles quelles ; un avertissement est émis sur
+/// `segmentation-status` (step `memory_warning`) et retourné pour être joint au résultat.
+///
+/// @param engine - Moteur local ciblé
+/// @param model - Modèle sélectionné
+/// @param device - `GPU`, `CPU` ou `MPS` (mémoire unifiée : seule la RAM est contrôlée)
+/// @param ignore_memory_check - Désactive la vérification (utilisateurs avancés)
+pub(crate) async fn check_local_memory(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+    model: &str,
+    device: &str,
+    ignore_memory_check: bool,
+) -> Result<Option<String>, String> {
+    if ignore_memory_check {
+        return Ok(None);
+    }
+
+    let requirement = estimate_local_memory_requirement(engine, model);
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let available_ram_mb = system.available_memory() / (1024 * 1024);
+    if available_ram_mb == 0 {
+        // Mémoire non mesurable sur cette plateforme : on ne bloque pas.
+        return Ok(None);
+    }
+    let free_vram_mb = if device.eq_ignore_ascii_case("GPU") {
+        tauri::async_runtime::spawn_blocking(detect_nvidia_free_vram_mb)
+            .await
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+
+    log::info!(
+        "[segmentation][local] memory check engine={} model={} need_ram={}MB need_vram={}MB available_ram={}MB free_vram={:?}",
+        engine.as_key(),
+        model,
+        requirement.ram_mb,
+        requirement.vram_mb,
+        available_ram_mb,
+        free_vram_mb
+    );
+    let warning = evaluate_memory(requirement, model, device, available_ram_mb, free_vram_mb)?;
+    if let Some(message) = warning.as_ref() {
+        let _ = app_handle.emit(
+            "segmentation-status",
+            serde_json::json!({
+                "step": "memory_warning",
+                "message": message,
+                "requiredRamMb": requirement.ram_mb,
+                "availableRamMb": available_ram_mb,
+            }),
+        );
+    }
+    Ok(warning)
+}
+
+/// Indique si l'erreur d'une segmentation locale provient du GPU (mémoire, pilote, assertion).
+///
+/// Sert à relancer la segmentation une fois sur CPU plutôt que d'échouer.
+pub(crate) fn is_gpu_failure(error: &str) -> bool {
+    let error = error.to_lowercase();
+    GPU_OUT_OF_MEMORY_MARKERS
+        .iter()
+        .chain(GPU_FAILURE_MARKERS.iter())
+        .any(|marker| error.contains(marker))
+}
+
+/// Joint l'avertissement mémoire éventuel au résultat de segmentation.
+pub(crate) fn attach_memory_warning(result: &mut serde_json::Value, warning: Option<String>) {
+    if let (Some(object), Some(warning)) = (result.as_object_mut(), warning) {
+        object.insert(
+            "memoryWarning".to_string(),
+            serde_json::Value::String(warning),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        detect_out_of_memory, estimate_local_memory_requirement, evaluate_memory, is_gpu_failure,
+    };
+    use crate::segmentation::types::{LocalSegmentationEngine, ScriptExit};
+
+    #[test]
+    fn memory_check_blocks_warns_or_passes() {
+        let large =
+            estimate_local_memory_requirement(LocalSegmentationEngine::MultiAligner, "Large");
+        let error = evaluate_memory(large, "Large", "CPU", 3_277, None).unwrap_err();
+        assert!(error.contains("~7.8 GB RAM, you have 3.2 GB free"));
+        // Sur GPU/MPS, le manque de RAM n'est qu'un avertissement.
+        for device in ["GPU", "MPS"] {
+            assert!(evaluate_memory(large, "Large", device, 3_277, None)
+                .unwrap()
+                .is_some_and(|warning| warning.contains("Loading it on the GPU may still work")));
+        }
+
+        assert!(evaluate_memory(large, "Large", "CPU", 9_000, None)
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            evaluate_memory(large, "Large", "CPU", 16_000, None),
+            Ok(None)
+        );
+        assert!(evaluate_memory(large, "Large", "GPU", 16_000, Some(2_048))
+            .unwrap_err()
+            .contains("Switch the device to CPU"));
+    }
+
+    #[test]
+    fn out_of_memory_is_detected_from_exit_status_and_output() {
+        let failed = ScriptExit {
+            code: Some(1),
+            signal: None,
+            pid: None,
+        };
+        let gpu = detect_out_of_memory(
+            failed,
+            "torch.OutOfMemoryError: CUDA out of memory. Tried to allocate 2.00 GiB",
+        )
+        .unwrap();
+        assert!(gpu.starts_with("SEGMENTATION_OUT_OF_MEMORY: "));
+        assert!(gpu.contains("Switch the device to CPU"));
+        assert!(is_gpu_failure(&gpu));
+        assert!(is_gpu_failure(
+            "Python script failed: RuntimeError: CUDA error: device-side assert triggered"
+        ));
+        assert!(!is_gpu_failure(
+            "Python script failed: FileNotFoundError: audio.wav"
+        ));
+        assert!(detect_out_of_memory(failed, "Traceback ...\nMemoryError").is_some());
+
+        let killed = ScriptExit {
+            code: None,
+            signal: Some(9),
+            pid: None,
+        };
+        assert!(detect_out_of_memory(killed, "")
+            .unwrap()
+            .contains("SIGKILL"));
+        let windows_no_memory = ScriptExit {
+            code: Some(0xC000_0017_u32 as i32),
+            signal: None,
+            pid: None,
+        };
+        assert!(detect_out_of_memory(windows_no_memory, "").is_some());
+        assert_eq!(
+            detect_out_of_memory(failed, "ValueError: invalid audio"),
+            None
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::fs;
+use std::sync::Mutex;
+
+use tauri::Emitter;
+
+use crate::path_utils;
+
+use super::types::QURAN_SEGMENTATION_MOCK_PAYLOAD;
+
+/// Variable d'environnement activant le mode mock (`1`, `true`, `yes`).
+const MOCK_ENV_VAR: &str = "QC_SEGMENTATION_MOCK";
+/// Variable d'environnement optionnelle pointant vers un payload JSON mock.
+const MOCK_PAYLOAD_ENV_VAR: &str = "QC_SEGMENTATION_MOCK_PAYLOAD";
+
+/// Configuration du mode mock définie à chaud depuis le frontend.
+#[derive(Clone)]
+struct SegmentationMockOverride {
+    enabled: bool,
+    payload_path: Option<String>,
+}
+
+/// Surcharge runtime du mode mock (prioritaire sur les variables d'environnement).
+static MOCK_OVERRIDE: Mutex<Option<SegmentationMockOverride>> = Mutex::new(None);
+
+/// Active ou désactive le mode mock de segmentation sans recompiler le backend.
+///
+/// @param enabled - `true` pour renvoyer un payload mock au lieu d'appeler les moteurs
+/// @param payload_path - Fichier JSON à renvoyer (payload intégré si absent)
+pub fn set_segmentation_mock(enabled: bool, payload_path: Option<String>) -> Result<(), String> {
+    let payload_path = payload_path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    if let Some(path) = payload_path.as_deref() {
+        if !path_utils::normalize_existing_path(path).is_file() {
+            return Err(format!("Mock payload file not found: {}", path));
+        }
+    }
+
+    let mut state = MOCK_OVERRIDE
+        .lock()
+        .map_err(|_| "Segmentation mock state is poisoned".to_string())?;
+    *state = Some(SegmentationMockOverride {
+        enabled,
+        payload_path,
+    });
+    Ok(())
+}
+
+/// Résout la configuration mock active (surcharge runtime puis variables d'environnement).
+fn active_mock_config() -> Option<SegmentationMockOverride> {
+    if let Some(config) = MOCK_OVERRIDE.lock().ok().and_then(|state| state.clone()) {
+        return config.enabled.then_some(config);
+    }
+
+    let enabled = std::env::var(MOCK_ENV_VAR)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    enabled.then(|| SegmentationMockOverride {
+        enabled,
+        payload_path: std::env::var(MOCK_PAYLOAD_ENV_VAR)
+            .ok()
+            .filter(|path| !path.trim().is_empty()),
+    })
+}
+
+/// Retourne un résultat mock si le mode mock est actif, `None` sinon.
+///
+/// Un événement `segmentation-status` signale explicitement que le résultat est factice
+/// et le payload retourné porte `"mocked": true`.
+///
+/// @param app_handle - Handle Tauri pour émettre le statut
+/// @param source - Moteur appelant (cloud, local-multi, ...)
+pub(crate) fn mock_segmentation_result(
+    app_handle: &tauri::AppHandle,
+    source: &str,
+) -> Option<Result<serde_json::Value, String>> {
+    let config = active_mock_config()?;
+    let raw_payload = match config.payload_path.as_deref() {
+        Some(path) => match fs::read_to_string(path_utils::normalize_existing_path(path)) {
+            Ok(content) => content,
+            Err(e) => {
+                return Some(Err(format!(
+                    "Failed to read mock payload '{}': {}",
+                    path, e
+                )))
+            }
+        },
+        None => QURAN_SEGMENTATION_MOCK_PAYLOAD.to_string(),
+    };
+
+    let mut payload: serde_json::Value = match serde_json::from_str(&raw_payload) {
+        Ok(payload) => payload,
+        Err(e) => return Some(Err(format!("Mock segmentation JSON invalid: {}", e))),
+    };
+    if let Some(object) = payload.as_object_mut() {
+        object.insert("mocked".to_string(), serde_json::Value::Bool(true));
+    }
+
+    log::info!(
+        "[segmentation] MOCK mode active for {} (payload: {})",
+        source,
+        config.payload_path.as_deref().unwrap_or("built-in")
+    );
+    let _ = app_handle.emit(
+        "segmentation-status",
+        serde_json::json!({
+            "step": "mock",
+            "message": "MOCK MODE: returning fake segmentation data, do not use these captions.",
+            "progress": 100.0,
+            "mocked": true,
+            "source": source,
+        }),
+    );
+    Some(Ok(payload))
+}
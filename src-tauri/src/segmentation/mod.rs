@@ -1,24 +1,86 @@
 /// Types et constantes du domaine segmentation.
 pub mod types;
 
+mod app_storage;
 mod audio_merge;
+mod batch;
 mod cloud;
 mod data_files;
+mod debug_audio;
+mod devices;
+mod disk_check;
+mod fallback;
+mod health_monitor;
 mod hifz;
 mod install;
+mod install_log;
+mod install_progress;
 mod local;
+mod local_estimate;
+mod managed_python;
+mod memory_check;
+mod mock;
+mod model_cache;
+mod offline;
+mod pip_index;
+mod preprocess;
+mod progress;
+mod proxy;
 mod python_env;
 mod requirements;
+mod result;
+mod run_log;
+mod srt_export;
 mod status;
+mod storage;
+mod uninstall;
+mod venv_migration;
+mod version_pins;
+mod watchdog;
+mod worker;
 
+pub use app_storage::{
+    clear_app_cache, get_app_storage_report, AppCacheClearReport, AppStorageReport,
+};
+pub use batch::{cancel_segmentation_batch, segment_quran_audio_batch, SegmentationBatchReport};
 pub use cloud::{
-    estimate_duration, mfa_timestamps_direct, mfa_timestamps_session, preload_audio,
-    preload_audio_recitations, preload_recitations, preload_segments, segment_quran_audio,
+    estimate_duration, mfa_timestamps_direct, mfa_timestamps_session, ping_segmentation_cloud,
+    preload_audio, preload_audio_recitations, preload_recitations, preload_segments,
+    CloudPingResult,
 };
+pub use data_files::{verify_multi_aligner_data, MultiAlignerDataFileStatus};
+pub use devices::{detect_compute_devices, ComputeDevices};
+pub use fallback::segment_quran_audio_with_fallback;
+pub use health_monitor::{start_engine_health_monitor, stop_engine_health_monitor};
 pub use hifz::{generate_hifz_audio, GeneratedHifzAudio};
-pub use install::install_local_segmentation_deps;
+pub use install::{
+    cancel_local_install, install_local_segmentation_deps, install_local_segmentation_engines,
+    LocalEngineInstallResult,
+};
+pub use install_log::{get_latest_install_log, InstallLogContent};
 pub use local::{
     segment_quran_audio_local, segment_quran_audio_local_muaalem, segment_quran_audio_local_multi,
     segment_quran_audio_local_surah_splitter,
 };
+pub use local_estimate::{estimate_local_segmentation, LocalSegmentationEstimate};
+pub use managed_python::install_managed_python;
+pub use mock::set_segmentation_mock;
+pub use model_cache::{
+    apply_saved_hf_cache_dir, clear_model_cache, get_model_cache_info, set_hf_cache_dir,
+    ModelCacheClearReport, ModelCacheInfo,
+};
+pub use offline::{get_local_install_manifest, OfflineInstallManifest};
+pub use result::merge_segmentation_results;
+pub use run_log::get_segmentation_logs;
+pub use srt_export::segmentation_to_srt;
 pub use status::check_local_segmentation_ready;
+pub use storage::{get_local_engine_storage_info, LocalEngineStorageInfo};
+pub use uninstall::{uninstall_local_segmentation_deps, LocalDepsUninstallReport};
+pub use venv_migration::{migrate_local_envs, VenvMigrationReport};
+pub use version_pins::{get_local_engine_versions, LocalEngineVersions};
+pub use worker::{set_segmentation_worker_mode, shutdown_segmentation_worker};
+
+pub(crate) use python_env::{
+    probe_system_python_candidates, python_version_meets_min, MIN_LOCAL_PYTHON_MAJOR,
+    MIN_LOCAL_PYTHON_MINOR,
+};
@@ -0,0 +1,537 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use tauri::Manager;
+
+use crate::path_utils;
+
+/// Dépôts Hugging Face téléchargés par les moteurs de segmentation locaux.
+const QURAN_MODEL_REPOS: [&str; 14] = [
+    "hetchyy/r15_95m",
+    "hetchyy/r7",
+    "hetchyy/quran-aligner-aoti",
+    "obadx/recitation-segmenter-v2",
+    "obadx/muaalem-model-v3_2",
+    "tarteel-ai/whisper-base-ar-quran",
+    "tarteel-ai/whisper-tiny-ar-quran",
+    "OdyAsh/faster-whisper-base-ar-quran",
+    "IJyad/whisper-large-v3-Tarteel",
+    "naazimsnh02/whisper-large-v3-turbo-ar-quran",
+    "deepdml/whisper-small-ar-quran-mix",
+    "deepdml/whisper-medium-ar-quran-mix-norm",
+    "HamzaSidhu786/wav2vec2-base-word-by-word-quran-asr",
+    "openai/whisper-medium",
+];
+
+/// Préfixe des dossiers de modèles dans le cache hub (`models--org--name`).
+const HUB_MODEL_DIR_PREFIX: &str = "models--";
+
+/// Fichier des données de l'application qui mémorise le dossier `HF_HOME` personnalisé.
+const HF_CACHE_DIR_FILE: &str = "hf_cache_dir";
+
+/// Dossier `HF_HOME` personnalisé choisi depuis le frontend (cache par défaut si absent).
+static CUSTOM_HF_HOME: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Dépôt de modèle présent dans le cache Hugging Face.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCacheEntry {
+    /// Identifiant du dépôt (`org/name`).
+    pub repo_id: String,
+    /// Dossier du dépôt dans le cache hub.
+    pub path: String,
+    /// Taille totale sur disque en octets.
+    pub size_bytes: u64,
+    /// Dernière utilisation connue (ms depuis epoch).
+    pub last_used_ms: Option<u64>,
+}
+
+/// Contenu du cache de modèles Hugging Face lié à la segmentation.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCacheInfo {
+    /// Dossier du cache hub résolu (dossier personnalisé s'il est défini).
+    pub cache_dir: String,
+    /// Cache hub par défaut, aussi parcouru quand un dossier personnalisé est actif.
+    pub default_cache_dir: Option<String>,
+    /// `false` si le cache n'a encore jamais été créé.
+    pub exists: bool,
+    /// Taille cumulée des dépôts listés.
+    pub total_bytes: u64,
+    /// Dépôts Quran présents, du plus volumineux au plus petit.
+    pub repos: Vec<ModelCacheEntry>,
+}
+
+/// Résultat d'un nettoyage du cache de modèles.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCacheClearReport {
+    /// Dépôts supprimés.
+    pub deleted_repos: Vec<String>,
+    /// Octets libérés.
+    pub freed_bytes: u64,
+}
+
+/// Chemin du fichier qui mémorise le dossier de cache personnalisé.
+fn hf_cache_dir_file(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(HF_CACHE_DIR_FILE))
+}
+
+/// Valide et crée un dossier de cache personnalisé.
+fn prepare_hf_cache_dir(raw: &str) -> Result<PathBuf, String> {
+    let dir = path_utils::normalize_input_path(raw);
+    if dir.exists() && !dir.is_dir() {
+        return Err(format!(
+            "Hugging Face cache path is not a directory: {}",
+            dir.to_string_lossy()
+        ));
+    }
+    fs::create_dir_all(&dir).map_err(|e| {
+        format!(
+            "Failed to create Hugging Face cache directory '{}': {}",
+            dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(dir.canonicalize().unwrap_or(dir))
+}
+
+/// Remplace le dossier personnalisé actif.
+fn store_custom_hf_home(dir: Option<PathBuf>) -> Result<(), String> {
+    let mut state = CUSTOM_HF_HOME
+        .lock()
+        .map_err(|_| "Hugging Face cache state is poisoned".to_string())?;
+    *state = dir;
+    Ok(())
+}
+
+/// Définit le dossier de cache Hugging Face utilisé par les scripts Python locaux.
+///
+/// Le dossier est créé au besoin, mémorisé pour les prochains lancements puis exporté en
+/// `HF_HOME`/`HF_HUB_CACHE` sur les processus Python lancés ensuite. Les modèles déjà
+/// présents dans le cache par défaut restent utilisés (voir `apply_hf_cache_env`), listés
+/// et supprimables par `get_model_cache_info`/`clear_model_cache`.
+///
+/// @param path - Dossier à utiliser (`None` ou vide pour revenir au cache par défaut)
+/// @returns Dossier résolu, ou `None` si le cache par défaut est rétabli
+pub fn set_hf_cache_dir(
+    app_handle: &tauri::AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let resolved = path.as_deref().map(prepare_hf_cache_dir).transpose()?;
+
+    let config_path = hf_cache_dir_file(app_handle)?;
+    match &resolved {
+        Some(dir) => {
+            if let Some(parent) = config_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(&config_path, dir.to_string_lossy().as_bytes())
+                .map_err(|e| format!("Failed to save Hugging Face cache directory: {}", e))?;
+        }
+        None => match fs::remove_file(&config_path) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(format!(
+                    "Failed to reset Hugging Face cache directory: {}",
+                    error
+                ))
+            }
+        },
+    }
+
+    store_custom_hf_home(resolved.clone())?;
+    Ok(resolved.map(|dir| dir.to_string_lossy().to_string()))
+}
+
+/// Restaure le dossier de cache mémorisé au démarrage de l'application.
+///
+/// Un dossier devenu inutilisable (disque externe débranché...) est ignoré pour cette
+/// session : le cache par défaut est utilisé sans effacer le réglage.
+pub fn apply_saved_hf_cache_dir(app_handle: &tauri::AppHandle) {
+    let Some(raw) = hf_cache_dir_file(app_handle)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|raw| raw.trim().to_string())
+        .filter(|raw| !raw.is_empty())
+    else {
+        return;
+    };
+    match prepare_hf_cache_dir(&raw) {
+        Ok(dir) => {
+            let _ = store_custom_hf_home(Some(dir));
+        }
+        Err(error) => log::warn!(
+            "[segmentation][model-cache] saved cache directory ignored: {}",
+            error
+        ),
+    }
+}
+
+/// Retourne le dossier `HF_HOME` personnalisé actif, s'il y en a un.
+pub(super) fn custom_hf_home() -> Option<PathBuf> {
+    CUSTOM_HF_HOME.lock().ok().and_then(|state| state.clone())
+}
+
+/// Exporte le dossier de cache personnalisé sur une commande Python (sans effet sinon).
+///
+/// `huggingface_hub` ne consulte qu'un seul cache : les modèles Quran présents uniquement
+/// dans le cache par défaut y sont liés avant l'export pour ne pas être retéléchargés.
+pub(super) fn apply_hf_cache_env(cmd: &mut Command) {
+    if let Some(hf_home) = custom_hf_home() {
+        let hub_cache = hf_home.join("hub");
+        if let Some(default_dir) = default_hf_hub_cache_dir().filter(|dir| *dir != hub_cache) {
+            link_default_cache_repos(&hub_cache, &default_dir);
+        }
+        cmd.env("HF_HOME", &hf_home);
+        cmd.env("HF_HUB_CACHE", &hub_cache);
+        cmd.env("HUGGINGFACE_HUB_CACHE", &hub_cache);
+    }
+}
+
+/// Résout le dossier du cache hub actif (dossier personnalisé puis cache par défaut).
+pub(super) fn resolve_hf_hub_cache_dir() -> Option<PathBuf> {
+    custom_hf_home()
+        .map(|hf_home| hf_home.join("hub"))
+        .or_else(default_hf_hub_cache_dir)
+}
+
+/// Dossiers de cache hub pouvant contenir des modèles : le cache actif, puis le cache par
+/// défaut s'il diffère (modèles téléchargés avant le choix d'un dossier personnalisé).
+pub(super) fn hf_hub_cache_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = resolve_hf_hub_cache_dir().into_iter().collect();
+    if let Some(default_dir) = default_hf_hub_cache_dir().filter(|dir| !dirs.contains(dir)) {
+        dirs.push(default_dir);
+    }
+    dirs
+}
+
+/// Crée un lien symbolique de répertoire (nécessite le mode développeur sous Windows).
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_dir(target, link)
+    }
+    #[cfg(not(windows))]
+    {
+        std::os::unix::fs::symlink(target, link)
+    }
+}
+
+/// Lie dans le cache personnalisé les dépôts Quran présents seulement dans le cache par défaut.
+///
+/// Un lien impossible à créer est journalisé : le modèle sera retéléchargé.
+fn link_default_cache_repos(hub_cache: &Path, default_dir: &Path) {
+    let missing: Vec<(String, PathBuf)> = quran_model_repo_dirs(default_dir)
+        .into_iter()
+        .filter(|(_, repo_dir)| {
+            repo_dir
+                .file_name()
+                .is_some_and(|name| fs::symlink_metadata(hub_cache.join(name)).is_err())
+        })
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    if let Err(error) = fs::create_dir_all(hub_cache) {
+        log::warn!(
+            "[segmentation][model-cache] unable to create '{}': {}",
+            hub_cache.to_string_lossy(),
+            error
+        );
+        return;
+    }
+    for (repo_id, repo_dir) in missing {
+        let Some(name) = repo_dir.file_name() else {
+            continue;
+        };
+        if let Err(error) = symlink_dir(&repo_dir, &hub_cache.join(name)) {
+            log::warn!(
+                "[segmentation][model-cache] unable to reuse cached model '{}' from the default cache: {}",
+                repo_id,
+                error
+            );
+        }
+    }
+}
+
+/// Supprime les liens du cache personnalisé qui pointent vers des dépôts supprimés.
+fn remove_dangling_repo_links(hub_cache: &Path) {
+    let Ok(entries) = fs::read_dir(hub_cache) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let is_link = entry.file_type().is_ok_and(|kind| kind.is_symlink());
+        if is_link && !entry.path().exists() {
+            let _ = fs::remove_dir(entry.path()).or_else(|_| fs::remove_file(entry.path()));
+        }
+    }
+}
+
+/// Résout le cache hub par défaut comme `huggingface_hub` (HF_HUB_CACHE, HF_HOME, XDG).
+fn default_hf_hub_cache_dir() -> Option<PathBuf> {
+    let env_path = |name: &str| {
+        std::env::var_os(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(hub_cache) = env_path("HF_HUB_CACHE").or_else(|| env_path("HUGGINGFACE_HUB_CACHE"))
+    {
+        return Some(hub_cache);
+    }
+    if let Some(hf_home) = env_path("HF_HOME") {
+        return Some(hf_home.join("hub"));
+    }
+    let cache_root =
+        env_path("XDG_CACHE_HOME").or_else(|| dirs::home_dir().map(|home| home.join(".cache")))?;
+    Some(cache_root.join("huggingface").join("hub"))
+}
+
+/// Convertit un nom de dossier `models--org--name` en identifiant `org/name`.
+fn repo_id_from_dir_name(dir_name: &str) -> Option<String> {
+    let (org, name) = dir_name
+        .strip_prefix(HUB_MODEL_DIR_PREFIX)?
+        .split_once("--")?;
+    (!org.is_empty() && !name.is_empty()).then(|| format!("{}/{}", org, name))
+}
+
+/// Indique si un dépôt appartient aux modèles de segmentation Quran.
+fn is_quran_model_repo(repo_id: &str) -> bool {
+    QURAN_MODEL_REPOS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(repo_id))
+        || repo_id.to_lowercase().contains("quran")
+}
+
+/// Calcule la taille et la dernière utilisation d'un dossier (liens symboliques non suivis).
+pub(super) fn directory_usage(path: &Path) -> (u64, Option<u64>) {
+    let mut size = 0u64;
+    let mut last_used: Option<u64> = None;
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, None);
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            let (child_size, child_last_used) = directory_usage(&entry.path());
+            size += child_size;
+            last_used = last_used.max(child_last_used);
+        } else if metadata.is_file() {
+            size += metadata.len();
+            let used = metadata
+                .accessed()
+                .or_else(|_| metadata.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis() as u64);
+            last_used = last_used.max(used);
+        }
+    }
+    (size, last_used)
+}
+
+/// Retourne les dossiers des dépôts Quran présents dans un dossier de cache hub.
+///
+/// Les liens vers le cache par défaut sont ignorés : le dépôt y est déjà compté.
+pub(super) fn quran_model_repo_dirs(cache_dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter_map(|entry| {
+            let repo_id = repo_id_from_dir_name(&entry.file_name().to_string_lossy())?;
+            is_quran_model_repo(&repo_id).then(|| (repo_id, entry.path()))
+        })
+        .collect()
+}
+
+/// Liste les dépôts Quran présents dans un dossier de cache hub.
+fn list_quran_model_repos(cache_dir: &Path) -> Vec<ModelCacheEntry> {
+    let mut repos: Vec<ModelCacheEntry> = quran_model_repo_dirs(cache_dir)
+        .into_iter()
+        .map(|(repo_id, path)| {
+            let (size_bytes, last_used_ms) = directory_usage(&path);
+            ModelCacheEntry {
+                repo_id,
+                path: path.to_string_lossy().to_string(),
+                size_bytes,
+                last_used_ms,
+            }
+        })
+        .collect();
+    repos.sort_by_key(|repo| std::cmp::Reverse(repo.size_bytes));
+    repos
+}
+
+/// Supprime des dépôts du cache hub (uniquement des dossiers `models--*` Quran).
+fn clear_quran_model_repos(
+    cache_dir: &Path,
+    repo_ids: &[String],
+    all: bool,
+) -> Result<ModelCacheClearReport, String> {
+    let mut report = ModelCacheClearReport {
+        deleted_repos: Vec::new(),
+        freed_bytes: 0,
+    };
+    for repo in list_quran_model_repos(cache_dir) {
+        let selected = all
+            || repo_ids
+                .iter()
+                .any(|repo_id| repo_id.trim().eq_ignore_ascii_case(&repo.repo_id));
+        if !selected {
+            continue;
+        }
+
+        let repo_path = PathBuf::from(&repo.path);
+        // Garde-fou : le dossier doit être un enfant direct du cache hub.
+        if repo_path.parent() != Some(cache_dir) {
+            continue;
+        }
+        fs::remove_dir_all(&repo_path)
+            .map_err(|e| format!("Failed to delete cached model '{}': {}", repo.repo_id, e))?;
+        if let Some(dir_name) = repo_path.file_name() {
+            let _ = fs::remove_dir_all(cache_dir.join(".locks").join(dir_name));
+        }
+        report.freed_bytes += repo.size_bytes;
+        report.deleted_repos.push(repo.repo_id);
+    }
+    Ok(report)
+}
+
+/// Retourne les modèles de segmentation présents dans le cache Hugging Face.
+pub async fn get_model_cache_info() -> Result<ModelCacheInfo, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let cache_dir = resolve_hf_hub_cache_dir()
+            .ok_or_else(|| "Unable to resolve the Hugging Face cache directory".to_string())?;
+        let default_cache_dir = default_hf_hub_cache_dir().filter(|dir| *dir != cache_dir);
+        let mut repos = list_quran_model_repos(&cache_dir);
+        if let Some(default_dir) = default_cache_dir.as_deref() {
+            repos.extend(list_quran_model_repos(default_dir));
+            repos.sort_by_key(|repo| std::cmp::Reverse(repo.size_bytes));
+        }
+        Ok(ModelCacheInfo {
+            cache_dir: cache_dir.to_string_lossy().to_string(),
+            default_cache_dir: default_cache_dir.map(|dir| dir.to_string_lossy().to_string()),
+            exists: cache_dir.is_dir(),
+            total_bytes: repos.iter().map(|repo| repo.size_bytes).sum(),
+            repos,
+        })
+    })
+    .await
+    .map_err(|e| format!("Model cache scan task failed: {}", e))?
+}
+
+/// Supprime les modèles sélectionnés (ou tous les modèles Quran) du cache Hugging Face.
+///
+/// @param repo_ids - Dépôts à supprimer (`org/name`)
+/// @param all - Supprime tous les dépôts Quran listés par `get_model_cache_info`
+pub async fn clear_model_cache(
+    repo_ids: Vec<String>,
+    all: bool,
+) -> Result<ModelCacheClearReport, String> {
+    if repo_ids.is_empty() && !all {
+        return Err("No model selected for deletion".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let cache_dir = resolve_hf_hub_cache_dir()
+            .ok_or_else(|| "Unable to resolve the Hugging Face cache directory".to_string())?;
+        let mut report = clear_quran_model_repos(&cache_dir, &repo_ids, all)?;
+        // Un dépôt peut aussi rester dans le cache par défaut après un changement de dossier.
+        if let Some(default_dir) = default_hf_hub_cache_dir().filter(|dir| *dir != cache_dir) {
+            let default_report = clear_quran_model_repos(&default_dir, &repo_ids, all)?;
+            report.deleted_repos.extend(default_report.deleted_repos);
+            report.freed_bytes += default_report.freed_bytes;
+            remove_dangling_repo_links(&cache_dir);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Model cache cleanup task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clear_quran_model_repos, link_default_cache_repos, list_quran_model_repos,
+        remove_dangling_repo_links, repo_id_from_dir_name,
+    };
+    use std::fs;
+
+    #[test]
+    fn hub_dir_names_map_to_repo_ids() {
+        assert_eq!(
+            repo_id_from_dir_name("models--hetchyy--r15_95m").as_deref(),
+            Some("hetchyy/r15_95m")
+        );
+        assert_eq!(repo_id_from_dir_name("datasets--a--b"), None);
+        assert_eq!(repo_id_from_dir_name("models--broken"), None);
+    }
+
+    #[test]
+    fn only_quran_repos_are_listed_and_cleared() {
+        let cache_dir = std::env::temp_dir().join(format!(
+            "qurancaption-hub-cache-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&cache_dir);
+        for repo_dir in ["models--hetchyy--r7", "models--someone--llama"] {
+            let blobs = cache_dir.join(repo_dir).join("blobs");
+            fs::create_dir_all(&blobs).unwrap();
+            fs::write(blobs.join("weights"), vec![0u8; 64]).unwrap();
+        }
+
+        let repos = list_quran_model_repos(&cache_dir);
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].repo_id, "hetchyy/r7");
+        assert_eq!(repos[0].size_bytes, 64);
+
+        let report = clear_quran_model_repos(&cache_dir, &[], true).unwrap();
+        assert_eq!(report.deleted_repos, vec!["hetchyy/r7".to_string()]);
+        assert_eq!(report.freed_bytes, 64);
+        assert!(!cache_dir.join("models--hetchyy--r7").exists());
+        assert!(cache_dir.join("models--someone--llama").exists());
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn default_cache_repos_are_linked_into_custom_cache() {
+        let root =
+            std::env::temp_dir().join(format!("qurancaption-hub-link-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let default_dir = root.join("default");
+        let custom_dir = root.join("custom").join("hub");
+        let blobs = default_dir.join("models--hetchyy--r7").join("blobs");
+        fs::create_dir_all(&blobs).unwrap();
+        fs::write(blobs.join("weights"), vec![0u8; 16]).unwrap();
+
+        link_default_cache_repos(&custom_dir, &default_dir);
+        assert!(custom_dir
+            .join("models--hetchyy--r7/blobs/weights")
+            .exists());
+        // Le lien n'est pas recompté dans le cache personnalisé.
+        assert!(list_quran_model_repos(&custom_dir).is_empty());
+
+        clear_quran_model_repos(&default_dir, &[], true).unwrap();
+        remove_dangling_repo_links(&custom_dir);
+        assert!(fs::symlink_metadata(custom_dir.join("models--hetchyy--r7")).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
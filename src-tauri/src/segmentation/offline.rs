@@ -0,0 +1,315 @@
+//! Installation hors ligne des dépendances locales depuis un dossier de wheels.
+//!
+//! pip est lancé avec `--no-index --find-links <dossier>` ; l'archive Quranic-Phonemizer
+//! et les fichiers data Multi-Aligner sont attendus dans ce même dossier.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::path_utils;
+
+use super::data_files::required_multi_aligner_data_files;
+use super::python_env::resolve_python_resource_path;
+use super::requirements::{
+    read_install_requirements, QURANIC_PHONEMIZER_ARCHIVE_FILE, QURANIC_PHONEMIZER_ARCHIVE_URL,
+};
+use super::types::LocalSegmentationEngine;
+use super::version_pins::engine_version_pins;
+
+/// Outillage pip mis à jour au début de chaque installation.
+pub(crate) const PIP_TOOLING_PACKAGES: [&str; 3] = ["pip", "setuptools", "wheel"];
+/// Paquets PyTorch installés avant les requirements du moteur.
+pub(crate) const TORCH_PACKAGES: [&str; 3] = ["torch", "torchvision", "torchaudio"];
+/// Index PyTorch CPU (repli Windows/ROCm et préparation d'un dossier hors ligne).
+pub(crate) const TORCH_CPU_INDEX_URL: &str = "https://download.pytorch.org/whl/cpu";
+
+/// Fichier à placer dans le dossier hors ligne en plus des wheels.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineInstallFile {
+    /// Nom attendu dans le dossier hors ligne.
+    pub file_name: String,
+    /// URL de téléchargement.
+    pub url: String,
+}
+
+/// Contenu attendu d'un dossier d'installation hors ligne pour un moteur.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineInstallManifest {
+    /// Clé du moteur (`legacy`, `multi`, ...).
+    pub engine: String,
+    /// Paquets pip requis (outillage, PyTorch puis requirements du moteur).
+    pub packages: Vec<String>,
+    /// Index PyTorch à utiliser pour télécharger les wheels torch.
+    pub torch_index_url: String,
+    /// Archives et fichiers data à copier tels quels dans le dossier.
+    pub files: Vec<OfflineInstallFile>,
+    /// Commandes `pip download` à lancer sur une machine connectée de même OS et même Python.
+    pub download_commands: Vec<String>,
+}
+
+/// Options pip d'une installation hors ligne.
+pub(crate) fn offline_pip_args(wheels_dir: &Path) -> Vec<String> {
+    vec![
+        "--no-index".to_string(),
+        "--find-links".to_string(),
+        wheels_dir.to_string_lossy().to_string(),
+    ]
+}
+
+/// Résout et valide le dossier de wheels fourni par l'utilisateur.
+pub(crate) fn resolve_offline_wheels_dir(raw: &str) -> Result<PathBuf, String> {
+    let wheels_dir = path_utils::normalize_existing_path(raw.trim());
+    if !wheels_dir.is_dir() {
+        return Err(format!(
+            "Offline wheels directory not found: {}",
+            wheels_dir.to_string_lossy()
+        ));
+    }
+    Ok(wheels_dir)
+}
+
+/// Normalise un nom de distribution (PEP 503 : minuscules, `-`/`_`/`.` équivalents).
+fn normalize_distribution_name(name: &str) -> String {
+    name.trim().to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+/// Extrait le nom de distribution d'une ligne de requirements (`numpy<2`, `foo[extra]==1.0`).
+pub(crate) fn requirement_name(requirement: &str) -> Option<String> {
+    let requirement = requirement.split(';').next()?.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    (!name.is_empty()).then(|| normalize_distribution_name(name))
+}
+
+/// Nom de distribution d'un fichier wheel (`name-ver-...whl`) ou sdist (`name-ver.tar.gz`).
+fn distribution_name_from_file(file_name: &str) -> Option<String> {
+    let lowered = file_name.to_ascii_lowercase();
+    let stem = lowered
+        .strip_suffix(".whl")
+        .or_else(|| lowered.strip_suffix(".tar.gz"))
+        .or_else(|| lowered.strip_suffix(".zip"))?;
+    // Le nom s'arrête au premier segment qui commence par un chiffre (la version).
+    let parts: Vec<&str> = stem.split('-').collect();
+    let version_index = parts
+        .iter()
+        .position(|part| part.starts_with(|c: char| c.is_ascii_digit()))?;
+    (version_index > 0).then(|| normalize_distribution_name(&parts[..version_index].join("-")))
+}
+
+/// Retourne les requirements dont aucune wheel/sdist n'est présente dans `file_names`.
+pub(crate) fn missing_offline_packages(
+    requirements: &[String],
+    file_names: &[String],
+) -> Vec<String> {
+    let available: Vec<String> = file_names
+        .iter()
+        .filter_map(|file_name| distribution_name_from_file(file_name))
+        .collect();
+    requirements
+        .iter()
+        .filter(|requirement| {
+            requirement_name(requirement).is_some_and(|name| !available.contains(&name))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Extrait les paquets introuvables d'une sortie pip `--no-index` en échec.
+pub(crate) fn parse_missing_pip_packages(pip_output: &str) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for line in pip_output.lines() {
+        let Some((_, requirement)) = line.split_once("No matching distribution found for ") else {
+            continue;
+        };
+        let requirement = requirement.trim().to_string();
+        if !requirement.is_empty() && !missing.contains(&requirement) {
+            missing.push(requirement);
+        }
+    }
+    missing
+}
+
+/// Liste les fichiers d'un dossier (noms seuls, sous-dossiers ignorés).
+pub(crate) fn list_offline_files(wheels_dir: &Path) -> Vec<String> {
+    fs::read_dir(wheels_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fichiers non-pip attendus dans le dossier hors ligne (archive phonemizer, data Multi-Aligner).
+fn offline_extra_files(engine: LocalSegmentationEngine) -> Vec<OfflineInstallFile> {
+    if engine != LocalSegmentationEngine::MultiAligner {
+        return Vec::new();
+    }
+    let mut files = vec![OfflineInstallFile {
+        file_name: QURANIC_PHONEMIZER_ARCHIVE_FILE.to_string(),
+        url: QURANIC_PHONEMIZER_ARCHIVE_URL.to_string(),
+    }];
+    files.extend(
+        required_multi_aligner_data_files()
+            .iter()
+            .map(|data_file| OfflineInstallFile {
+                file_name: data_file.name.to_string(),
+                url: data_file.url.to_string(),
+            }),
+    );
+    files
+}
+
+/// Retourne la liste des paquets et fichiers à réunir pour installer un moteur hors ligne.
+///
+/// @param engine - Moteur à installer (`legacy`, `multi`, `muaalem`, `surah_splitter`)
+pub fn get_local_install_manifest(
+    app_handle: tauri::AppHandle,
+    engine: String,
+) -> Result<OfflineInstallManifest, String> {
+    let selected_engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    let requirements_path =
+        resolve_python_resource_path(&app_handle, selected_engine.requirements_relative_path())?;
+    let requirements = read_install_requirements(&requirements_path)?;
+
+    let mut packages: Vec<String> = PIP_TOOLING_PACKAGES.iter().map(|p| p.to_string()).collect();
+    packages.extend(TORCH_PACKAGES.iter().map(|p| p.to_string()));
+    packages.extend(requirements.iter().cloned());
+
+    let quote = |value: &str| format!("\"{}\"", value);
+    let download_commands = vec![
+        format!(
+            "python -m pip download --dest <dir> {}",
+            PIP_TOOLING_PACKAGES.join(" ")
+        ),
+        format!(
+            "python -m pip download --dest <dir> {} --index-url {}",
+            TORCH_PACKAGES.join(" "),
+            TORCH_CPU_INDEX_URL
+        ),
+        format!(
+            "python -m pip download --dest <dir> {}",
+            requirements
+                .iter()
+                .map(|requirement| quote(requirement))
+                .chain(
+                    engine_version_pins(selected_engine)
+                        .iter()
+                        .map(|(package, version)| quote(&format!("{}=={}", package, version)))
+                )
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+    ];
+
+    Ok(OfflineInstallManifest {
+        engine: selected_engine.as_key().to_string(),
+        packages,
+        torch_index_url: TORCH_CPU_INDEX_URL.to_string(),
+        files: offline_extra_files(selected_engine),
+        download_commands,
+    })
+}
+
+/// Vérifie qu'un dossier hors ligne contient tout ce qu'il faut pour installer le moteur.
+///
+/// Les fichiers data Multi-Aligner ne sont exigés que s'ils sont absents ou invalides localement.
+///
+/// @param invalid_data_files - Fichiers data à reprendre depuis le dossier hors ligne
+/// @returns Erreur listant précisément les paquets et fichiers absents
+pub(crate) fn check_offline_wheels_dir(
+    wheels_dir: &Path,
+    engine: LocalSegmentationEngine,
+    requirements: &[String],
+    invalid_data_files: &[&str],
+) -> Result<(), String> {
+    let file_names = list_offline_files(wheels_dir);
+    let mut expected: Vec<String> = PIP_TOOLING_PACKAGES.iter().map(|p| p.to_string()).collect();
+    expected.extend(TORCH_PACKAGES.iter().map(|p| p.to_string()));
+    expected.extend(requirements.iter().cloned());
+
+    let mut missing = missing_offline_packages(&expected, &file_names);
+    let mut expected_files: Vec<String> = Vec::new();
+    if engine == LocalSegmentationEngine::MultiAligner {
+        expected_files.push(QURANIC_PHONEMIZER_ARCHIVE_FILE.to_string());
+    }
+    expected_files.extend(
+        invalid_data_files
+            .iter()
+            .map(|file_name| file_name.to_string()),
+    );
+    missing.extend(
+        expected_files
+            .into_iter()
+            .filter(|file_name| !file_names.contains(file_name)),
+    );
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Offline wheels directory '{}' is missing: {}",
+        wheels_dir.to_string_lossy(),
+        missing.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{missing_offline_packages, parse_missing_pip_packages, requirement_name};
+
+    #[test]
+    fn requirement_names_are_normalized() {
+        assert_eq!(requirement_name("numpy<2"), Some("numpy".to_string()));
+        assert_eq!(
+            requirement_name("Soundfile==0.12.1 ; sys_platform == 'win32'"),
+            Some("soundfile".to_string())
+        );
+        assert_eq!(
+            requirement_name("huggingface_hub[cli]>=0.20"),
+            Some("huggingface-hub".to_string())
+        );
+        assert_eq!(requirement_name(">=1.0"), None);
+    }
+
+    #[test]
+    fn missing_packages_are_detected_from_file_names() {
+        let requirements = vec![
+            "torch".to_string(),
+            "numpy<2".to_string(),
+            "huggingface_hub>=0.20".to_string(),
+            "librosa==0.10.1".to_string(),
+            "ruamel.yaml".to_string(),
+        ];
+        let file_names = vec![
+            "torch-2.3.1+cpu-cp311-cp311-win_amd64.whl".to_string(),
+            "numpy-1.26.4-cp311-cp311-manylinux_2_17_x86_64.whl".to_string(),
+            "huggingface_hub-0.23.0-py3-none-any.whl".to_string(),
+            "ruamel.yaml-0.18.6-py3-none-any.whl".to_string(),
+            "README.txt".to_string(),
+        ];
+        assert_eq!(
+            missing_offline_packages(&requirements, &file_names),
+            vec!["librosa==0.10.1".to_string()]
+        );
+    }
+
+    #[test]
+    fn missing_packages_are_parsed_from_pip_errors() {
+        let output = "Looking in links: /mnt/wheels\n\
+ERROR: Could not find a version that satisfies the requirement audioread>=2.1.9 (from librosa) (from versions: none)\n\
+ERROR: No matching distribution found for audioread>=2.1.9\n";
+        assert_eq!(
+            parse_missing_pip_packages(output),
+            vec!["audioread>=2.1.9".to_string()]
+        );
+        assert!(parse_missing_pip_packages("Successfully installed numpy-1.26.4").is_empty());
+    }
+}
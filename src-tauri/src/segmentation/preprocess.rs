@@ -0,0 +1,178 @@
+//! Pré-traitement ffmpeg de l'audio envoyé aux moteurs (upload cloud ou WAV local).
+
+use super::types::AudioPreprocessOptions;
+
+/// Fréquences acceptées par l'encodeur Opus de l'upload cloud.
+const OPUS_SAMPLE_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+/// Fréquences acceptées pour le WAV local (les scripts ré-échantillonnent en 16 kHz).
+const LOCAL_SAMPLE_RATES: [u32; 6] = [8000, 16000, 22050, 24000, 44100, 48000];
+/// Débits Opus acceptés (kbit/s).
+const BITRATE_RANGE_KBPS: std::ops::RangeInclusive<u32> = 24..=128;
+/// Débit Opus par défaut de l'upload cloud (kbit/s).
+const DEFAULT_CLOUD_BITRATE_KBPS: u32 = 64;
+/// Fréquence par défaut du WAV local, celle attendue par les modèles.
+const DEFAULT_LOCAL_SAMPLE_RATE: u32 = 16000;
+
+/// Pré-traitement validé d'une segmentation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AudioPreprocess {
+    /// Mixe l'audio en mono.
+    pub mono: bool,
+    /// Fréquence de sortie (Hz) ; fréquence d'origine si absente.
+    pub sample_rate: Option<u32>,
+    /// Débit Opus (kbit/s), utilisé par le cloud uniquement.
+    pub bitrate_kbps: u32,
+}
+
+/// Valide un débit Opus explicite.
+fn validate_bitrate(bitrate: Option<u32>) -> Result<Option<u32>, String> {
+    match bitrate {
+        Some(kbps) if !BITRATE_RANGE_KBPS.contains(&kbps) => Err(format!(
+            "Invalid preprocess bitrate {}. Expected a value between {} and {} kbps.",
+            kbps,
+            BITRATE_RANGE_KBPS.start(),
+            BITRATE_RANGE_KBPS.end()
+        )),
+        other => Ok(other),
+    }
+}
+
+/// Valide une fréquence d'échantillonnage parmi `allowed`.
+fn validate_sample_rate(sample_rate: Option<u32>, allowed: &[u32]) -> Result<Option<u32>, String> {
+    match sample_rate {
+        Some(rate) if !allowed.contains(&rate) => Err(format!(
+            "Invalid preprocess sample rate {}. Expected one of {:?}.",
+            rate, allowed
+        )),
+        other => Ok(other),
+    }
+}
+
+impl AudioPreprocess {
+    /// Pré-traitement de l'upload cloud : canaux et fréquence d'origine, Opus 64 kbit/s.
+    ///
+    /// `preprocess` prime sur les anciens paramètres `upload_bitrate_kbps` (borné, comme
+    /// avant) et `upload_sample_rate`.
+    pub fn cloud(
+        preprocess: Option<AudioPreprocessOptions>,
+        upload_bitrate_kbps: Option<u32>,
+        upload_sample_rate: Option<u32>,
+    ) -> Result<Self, String> {
+        let options = preprocess.unwrap_or_default();
+        let bitrate_kbps = validate_bitrate(options.bitrate)?
+            .or_else(|| {
+                upload_bitrate_kbps
+                    .map(|kbps| kbps.clamp(*BITRATE_RANGE_KBPS.start(), *BITRATE_RANGE_KBPS.end()))
+            })
+            .unwrap_or(DEFAULT_CLOUD_BITRATE_KBPS);
+        Ok(Self {
+            mono: options.mono.unwrap_or(false),
+            sample_rate: validate_sample_rate(
+                options.sample_rate.or(upload_sample_rate),
+                &OPUS_SAMPLE_RATES,
+            )?,
+            bitrate_kbps,
+        })
+    }
+
+    /// Pré-traitement du WAV local : mono 16 kHz par défaut.
+    pub fn local(preprocess: Option<AudioPreprocessOptions>) -> Result<Self, String> {
+        let options = preprocess.unwrap_or_default();
+        validate_bitrate(options.bitrate)?;
+        Ok(Self {
+            mono: options.mono.unwrap_or(true),
+            sample_rate: Some(
+                validate_sample_rate(options.sample_rate, &LOCAL_SAMPLE_RATES)?
+                    .unwrap_or(DEFAULT_LOCAL_SAMPLE_RATE),
+            ),
+            bitrate_kbps: DEFAULT_CLOUD_BITRATE_KBPS,
+        })
+    }
+
+    /// Canaux et fréquence de sortie (`-ac`, `-ar`).
+    fn channel_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.mono {
+            args.extend(["-ac".to_string(), "1".to_string()]);
+        }
+        if let Some(sample_rate) = self.sample_rate {
+            args.extend(["-ar".to_string(), sample_rate.to_string()]);
+        }
+        args
+    }
+
+    /// Arguments ffmpeg de sortie de l'upload cloud (OGG/Opus).
+    pub fn cloud_ffmpeg_args(self) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-c:a".to_string(),
+            "libopus".to_string(),
+            "-b:a".to_string(),
+            format!("{}k", self.bitrate_kbps),
+            "-vbr".to_string(),
+            "on".to_string(),
+        ];
+        args.extend(self.channel_args());
+        args
+    }
+
+    /// Arguments ffmpeg de sortie du WAV local (PCM 16 bits).
+    pub fn local_ffmpeg_args(self) -> Vec<String> {
+        let mut args = self.channel_args();
+        args.extend(["-c:a".to_string(), "pcm_s16le".to_string()]);
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AudioPreprocess;
+    use crate::segmentation::types::AudioPreprocessOptions;
+
+    #[test]
+    fn preprocess_defaults_and_validation_differ_per_path() {
+        let cloud = AudioPreprocess::cloud(None, None, None).unwrap();
+        assert_eq!(
+            cloud.cloud_ffmpeg_args(),
+            vec!["-c:a", "libopus", "-b:a", "64k", "-vbr", "on"]
+        );
+        assert_eq!(
+            AudioPreprocess::local(None).unwrap().local_ffmpeg_args(),
+            vec!["-ac", "1", "-ar", "16000", "-c:a", "pcm_s16le"]
+        );
+
+        let stereo = AudioPreprocessOptions {
+            mono: Some(false),
+            sample_rate: Some(48000),
+            bitrate: Some(96),
+        };
+        let cloud = AudioPreprocess::cloud(Some(stereo), Some(32), Some(16000)).unwrap();
+        assert_eq!(cloud.bitrate_kbps, 96);
+        assert_eq!(cloud.sample_rate, Some(48000));
+        assert_eq!(
+            AudioPreprocess::local(Some(stereo))
+                .unwrap()
+                .local_ffmpeg_args(),
+            vec!["-ar", "48000", "-c:a", "pcm_s16le"]
+        );
+        assert_eq!(
+            AudioPreprocess::cloud(None, Some(500), None)
+                .unwrap()
+                .bitrate_kbps,
+            128
+        );
+
+        let invalid = |options: AudioPreprocessOptions| {
+            AudioPreprocess::cloud(Some(options), None, None).is_err()
+                && AudioPreprocess::local(Some(options)).is_err()
+        };
+        assert!(invalid(AudioPreprocessOptions {
+            bitrate: Some(8),
+            ..Default::default()
+        }));
+        assert!(invalid(AudioPreprocessOptions {
+            sample_rate: Some(11025),
+            ..Default::default()
+        }));
+        assert!(AudioPreprocess::cloud(None, None, Some(44100)).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Contrat de progression entre les scripts de segmentation locaux et le frontend.
+//!
+//! Les scripts écrivent sur stderr des lignes `STATUS:{json}` avec :
+//! - `step` (obligatoire) : identifiant de phase (`loading`, `model_download`, `whisper`, ...)
+//! - `message` (obligatoire) : texte affiché à l'utilisateur
+//! - `percent` (optionnel) : progression globale 0-100 imposée par le script
+//! - `current` / `total` (optionnels) : avancement dans la phase (ex: chunk 3/12, octets)
+//! - `model` (optionnel, `model_download`) : nom du modèle en cours de téléchargement
+//!
+//! Le backend convertit chaque statut en un pourcentage global monotone publié dans
+//! le champ `progress` de l'événement `segmentation-status` ; `current` / `total` y sont
+//! transmis tels quels (lot ou segment en cours).
+
+/// Plages de pourcentage global attribuées aux phases connues d'une segmentation locale.
+const LOCAL_PROGRESS_PHASES: [(&[&str], f64, f64); 4] = [
+    (&["preprocess"], 0.0, 10.0),
+    (
+        &[
+            "auth",
+            "data",
+            "deps",
+            "loading",
+            "model",
+            "model_load",
+            "model_download",
+            "segmenter",
+            "stt",
+        ],
+        10.0,
+        25.0,
+    ),
+    (
+        &[
+            "pipeline",
+            "vad",
+            "whisper",
+            "inference",
+            "chunk",
+            "anchor",
+            "segmenting",
+            "transcribing",
+            "detecting",
+        ],
+        25.0,
+        90.0,
+    ),
+    (
+        &[
+            "matching",
+            "split",
+            "aligner",
+            "aligning",
+            "wbw",
+            "postprocess",
+            "done",
+        ],
+        90.0,
+        100.0,
+    ),
+];
+
+/// Retourne la plage `(début, fin)` de la phase associée à un `step`, si connue.
+fn phase_range(step: &str) -> Option<(f64, f64)> {
+    let step = step.trim().to_lowercase();
+    LOCAL_PROGRESS_PHASES
+        .iter()
+        .find(|(steps, _, _)| steps.contains(&step.as_str()))
+        .map(|(_, start, end)| (*start, *end))
+}
+
+/// Extrait une fraction `courant/total` d'un message (ex: "Transcribing segment 3/12...").
+fn parse_message_fraction(message: &str) -> Option<f64> {
+    message.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| !c.is_ascii_digit() && c != '/');
+        let (current, total) = word.split_once('/')?;
+        let current: f64 = current.parse().ok()?;
+        let total: f64 = total.parse().ok()?;
+        (total > 0.0).then(|| (current / total).clamp(0.0, 1.0))
+    })
+}
+
+/// Formate le message d'un téléchargement de modèle HF (ex: "Downloading model r7: 43% (1.2/2.8 GB)").
+fn format_model_download_message(status: &serde_json::Value) -> Option<String> {
+    if status.get("step").and_then(|value| value.as_str()) != Some("model_download") {
+        return None;
+    }
+    let model = status.get("model").and_then(|value| value.as_str())?;
+    let current = status.get("current").and_then(|value| value.as_f64())?;
+    let total = status.get("total").and_then(|value| value.as_f64())?;
+    if total <= 0.0 {
+        return None;
+    }
+    let gb = |bytes: f64| bytes / (1024.0 * 1024.0 * 1024.0);
+    Some(format!(
+        "Downloading model {}: {:.0}% ({:.1}/{:.1} GB)",
+        model,
+        (current / total * 100.0).clamp(0.0, 100.0),
+        gb(current),
+        gb(total)
+    ))
+}
+
+/// Suivi de la progression globale d'une segmentation locale (jamais décroissante).
+#[derive(Default)]
+pub(crate) struct LocalProgressTracker {
+    last_percent: f64,
+}
+
+impl LocalProgressTracker {
+    /// Calcule le pourcentage global d'un statut brut sans mettre à jour le suivi.
+    fn compute_percent(&self, status: &serde_json::Value) -> f64 {
+        if let Some(percent) = status.get("percent").and_then(|value| value.as_f64()) {
+            return percent.clamp(0.0, 100.0);
+        }
+
+        let step = status
+            .get("step")
+            .and_then(|value| value.as_str())
+            .unwrap_or("");
+        let Some((start, end)) = phase_range(step) else {
+            return self.last_percent;
+        };
+
+        let current = status.get("current").and_then(|value| value.as_f64());
+        let total = status.get("total").and_then(|value| value.as_f64());
+        let fraction = match (current, total) {
+            (Some(current), Some(total)) if total > 0.0 => Some((current / total).clamp(0.0, 1.0)),
+            _ => status
+                .get("message")
+                .and_then(|value| value.as_str())
+                .and_then(parse_message_fraction),
+        };
+        start + (end - start) * fraction.unwrap_or(0.0)
+    }
+
+    /// Complète un statut brut du script avec un champ `progress` global et monotone.
+    pub(crate) fn apply(&mut self, mut status: serde_json::Value) -> serde_json::Value {
+        let percent = self.compute_percent(&status).max(self.last_percent);
+        self.last_percent = percent;
+        let download_message = format_model_download_message(&status);
+        if let Some(object) = status.as_object_mut() {
+            if let Some(message) = download_message {
+                object.insert("message".to_string(), serde_json::Value::String(message));
+            }
+            object.insert(
+                "progress".to_string(),
+                serde_json::json!((percent * 10.0).round() / 10.0),
+            );
+        }
+        status
+    }
+
+    /// Construit le statut de la phase de pré-traitement ffmpeg (`fraction` entre 0 et 1).
+    pub(crate) fn preprocess_status(&mut self, fraction: f64) -> serde_json::Value {
+        self.apply(serde_json::json!({
+            "step": "preprocess",
+            "message": "Preparing audio for local segmentation...",
+            "current": fraction.clamp(0.0, 1.0),
+            "total": 1.0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalProgressTracker;
+
+    #[test]
+    fn known_phases_map_to_monotonic_percent() {
+        let mut tracker = LocalProgressTracker::default();
+        let status = tracker.preprocess_status(0.5);
+        assert_eq!(status["progress"], 5.0);
+
+        let status = tracker.apply(serde_json::json!({
+            "step": "whisper",
+            "message": "Transcribing segment 6/12...",
+        }));
+        assert_eq!(status["progress"], 57.5);
+
+        // Avancement par lot transmis tel quel au frontend.
+        let status = tracker.apply(serde_json::json!({
+            "step": "inference",
+            "message": "Transcribing batch 8/10...",
+            "current": 7,
+            "total": 10,
+        }));
+        assert_eq!(status["progress"], 70.5);
+        assert_eq!(status["current"], 7);
+        assert_eq!(status["total"], 10);
+
+        let status = tracker.apply(serde_json::json!({
+            "step": "loading",
+            "message": "Loading model...",
+        }));
+        assert_eq!(status["progress"], 70.5);
+
+        let status = tracker.apply(serde_json::json!({
+            "step": "split",
+            "message": "Refining...",
+            "current": 1,
+            "total": 2,
+        }));
+        assert_eq!(status["progress"], 95.0);
+        assert_eq!(status["step"], "split");
+    }
+
+    #[test]
+    fn model_download_status_gets_byte_message() {
+        let mut tracker = LocalProgressTracker::default();
+        let status = tracker.apply(serde_json::json!({
+            "step": "model_download",
+            "message": "Downloading model r15_95m...",
+            "model": "r15_95m",
+            "current": 1_288_490_189u64,
+            "total": 3_006_477_107u64,
+        }));
+        assert_eq!(
+            status["message"],
+            "Downloading model r15_95m: 43% (1.2/2.8 GB)"
+        );
+        assert_eq!(status["progress"], 16.4);
+    }
+}
@@ -5,13 +5,19 @@ use std::process::Command;
 
 use tauri::Manager;
 
-use crate::utils::process::{configure_command_no_window, sanitize_cmd_error};
+use crate::utils::process::{
+    configure_command_no_window, output_with_timeout, sanitize_cmd_error, PROBE_TIMEOUT,
+};
 
+use super::managed_python::resolve_base_python;
 use super::types::LocalSegmentationEngine;
 
 pub(crate) const MIN_LOCAL_PYTHON_MAJOR: u8 = 3;
 pub(crate) const MIN_LOCAL_PYTHON_MINOR: u8 = 10;
 
+/// Marqueur présent dans un venv tant que son installation n'a pas abouti.
+const INSTALL_INCOMPLETE_MARKER: &str = ".install-incomplete";
+
 #[derive(Clone, Debug)]
 pub(crate) struct PythonInterpreter {
     pub command: String,
@@ -19,6 +25,27 @@ pub(crate) struct PythonInterpreter {
     pub major: u8,
     pub minor: u8,
     pub patch: u8,
+    /// Where the candidate came from (`system_path`, `known_path`, `conda`, `pyenv`, `managed`).
+    pub source: &'static str,
+}
+
+impl PythonInterpreter {
+    /// Summary reported by the readiness check so support can see which Python is used.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "command": self.command,
+            "executable": self.executable,
+            "version": format!("{}.{}.{}", self.major, self.minor, self.patch),
+            "source": self.source,
+        })
+    }
+}
+
+/// A Python command to probe, with where it was discovered.
+#[derive(Clone, Debug, PartialEq)]
+struct PythonCandidate {
+    command: String,
+    source: &'static str,
 }
 
 /// Checks whether a Python version satisfies a required minimum.
@@ -34,7 +61,7 @@ pub(crate) fn read_python_version(python_exe: &Path) -> Option<(u8, u8, u8)> {
     let mut cmd = Command::new(python_exe);
     cmd.args(["-c", check_script]);
     configure_command_no_window(&mut cmd);
-    let output = cmd.output().ok()?;
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT).ok()?;
     if !output.status.success() {
         return None;
     }
@@ -48,7 +75,77 @@ pub(crate) fn read_python_version(python_exe: &Path) -> Option<(u8, u8, u8)> {
     ))
 }
 
-fn python_command_candidates() -> Vec<String> {
+/// Reads a non-empty environment variable as a path.
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Python executable inside a conda installation or environment root.
+fn conda_python_exe(root: &Path) -> PathBuf {
+    if cfg!(target_os = "windows") {
+        root.join("python.exe")
+    } else {
+        root.join("bin").join("python3")
+    }
+}
+
+/// Conda interpreters: the active environment, the install behind `CONDA_EXE`, then the
+/// default Anaconda/Miniconda/Miniforge locations of the user.
+fn conda_python_paths(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    roots.extend(env_path("CONDA_PREFIX"));
+    // `CONDA_EXE` is `<root>/Scripts/conda.exe` on Windows and `<root>/bin/conda` elsewhere.
+    roots.extend(
+        env_path("CONDA_EXE")
+            .and_then(|conda_exe| Some(conda_exe.parent()?.parent()?.to_path_buf())),
+    );
+    if let Some(home) = home {
+        roots.extend(
+            ["anaconda3", "miniconda3", "miniforge3"]
+                .iter()
+                .map(|dir| home.join(dir)),
+        );
+    }
+    if cfg!(target_os = "windows") {
+        for base in [env_path("LOCALAPPDATA"), env_path("ProgramData")]
+            .into_iter()
+            .flatten()
+        {
+            roots.extend(["anaconda3", "miniconda3"].iter().map(|dir| base.join(dir)));
+        }
+    }
+    roots.iter().map(|root| conda_python_exe(root)).collect()
+}
+
+/// pyenv interpreters: the shim on Unix, installed versions (newest first) for pyenv-win.
+fn pyenv_python_paths(home: Option<&Path>) -> Vec<PathBuf> {
+    let Some(root) = env_path("PYENV_ROOT").or_else(|| home.map(|home| home.join(".pyenv"))) else {
+        return Vec::new();
+    };
+    if !cfg!(target_os = "windows") {
+        return vec![root.join("shims").join("python3")];
+    }
+    // pyenv-win shims are batch files: the versions are probed directly instead.
+    let versions_dir = if root.join("pyenv-win").is_dir() {
+        root.join("pyenv-win").join("versions")
+    } else {
+        root.join("versions")
+    };
+    let mut versions: Vec<PathBuf> = fs::read_dir(versions_dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    versions.sort_by(|a, b| b.cmp(a));
+    versions
+        .into_iter()
+        .map(|version| version.join("python.exe"))
+        .collect()
+}
+
+/// Python candidates in resolution order: PATH and well-known paths first, then the
+/// conda and pyenv interpreters that exist on disk (no process is spawned here).
+fn python_command_candidates() -> Vec<PythonCandidate> {
     let mut candidates: Vec<String> = Vec::new();
 
     if cfg!(target_os = "windows") {
@@ -87,20 +184,48 @@ fn python_command_candidates() -> Vec<String> {
         );
     }
 
+    let mut candidates: Vec<PythonCandidate> = candidates
+        .into_iter()
+        .map(|command| PythonCandidate {
+            source: if command.contains('/') || command.contains('\\') {
+                "known_path"
+            } else {
+                "system_path"
+            },
+            command,
+        })
+        .collect();
+    let home = env_path(if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    });
+    for (source, paths) in [
+        ("conda", conda_python_paths(home.as_deref())),
+        ("pyenv", pyenv_python_paths(home.as_deref())),
+    ] {
+        candidates.extend(paths.into_iter().filter(|path| path.is_file()).map(|path| {
+            PythonCandidate {
+                command: path.to_string_lossy().to_string(),
+                source,
+            }
+        }));
+    }
+
     let mut seen: HashSet<String> = HashSet::new();
     candidates
         .into_iter()
-        .filter(|candidate| seen.insert(candidate.clone()))
+        .filter(|candidate| seen.insert(candidate.command.clone()))
         .collect()
 }
 
-fn probe_python_interpreter(command: &str) -> Option<PythonInterpreter> {
+fn probe_python_interpreter(command: &str, source: &'static str) -> Option<PythonInterpreter> {
     let check_script = "import json,sys; print(json.dumps({'executable':sys.executable,'major':sys.version_info[0],'minor':sys.version_info[1],'patch':sys.version_info[2]}))";
 
     let mut cmd = Command::new(command);
     cmd.args(["-c", check_script]);
     configure_command_no_window(&mut cmd);
-    let output = cmd.output().ok()?;
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT).ok()?;
     if !output.status.success() {
         return None;
     }
@@ -114,26 +239,62 @@ fn probe_python_interpreter(command: &str) -> Option<PythonInterpreter> {
         major: parsed.get("major")?.as_u64()? as u8,
         minor: parsed.get("minor")?.as_u64()? as u8,
         patch: parsed.get("patch")?.as_u64()? as u8,
+        source,
     })
 }
 
-/// Resolves a system Python executable compatible with the minimum required version.
-pub(crate) fn resolve_system_python(
+/// Result of probing one Python command candidate.
+#[derive(Clone, Debug)]
+pub(crate) struct PythonCandidateProbe {
+    pub command: String,
+    /// Where the candidate came from (see `PythonInterpreter::source`).
+    pub source: &'static str,
+    /// Interpreter reported by the candidate, `None` if it could not be executed.
+    pub interpreter: Option<PythonInterpreter>,
+}
+
+/// Probes Python candidates in resolution order until one meets the minimum version.
+///
+/// Candidates are probed lazily: later ones (conda, pyenv) only run if none before qualifies.
+/// Returns the selected interpreter (if any) and every probed candidate, selected one included.
+pub(crate) fn probe_system_python_candidates(
     min_major: u8,
     min_minor: u8,
-) -> Result<PythonInterpreter, String> {
-    let mut discovered: Vec<PythonInterpreter> = Vec::new();
+) -> (Option<PythonInterpreter>, Vec<PythonCandidateProbe>) {
+    let mut probes: Vec<PythonCandidateProbe> = Vec::new();
 
     for candidate in python_command_candidates() {
-        if let Some(interpreter) = probe_python_interpreter(&candidate) {
+        let interpreter = probe_python_interpreter(&candidate.command, candidate.source);
+        probes.push(PythonCandidateProbe {
+            command: candidate.command,
+            source: candidate.source,
+            interpreter: interpreter.clone(),
+        });
+        if let Some(interpreter) = interpreter {
             if python_version_meets_min(interpreter.major, interpreter.minor, min_major, min_minor)
             {
-                return Ok(interpreter);
+                return (Some(interpreter), probes);
             }
-            discovered.push(interpreter);
         }
     }
 
+    (None, probes)
+}
+
+/// Resolves a system Python executable compatible with the minimum required version.
+pub(crate) fn resolve_system_python(
+    min_major: u8,
+    min_minor: u8,
+) -> Result<PythonInterpreter, String> {
+    let (selected, probes) = probe_system_python_candidates(min_major, min_minor);
+    if let Some(interpreter) = selected {
+        return Ok(interpreter);
+    }
+    let discovered: Vec<PythonInterpreter> = probes
+        .into_iter()
+        .filter_map(|probe| probe.interpreter)
+        .collect();
+
     if discovered.is_empty() {
         Err(format!(
             "No usable Python interpreter found. Install Python {}.{}+ and ensure it is available in PATH.",
@@ -208,6 +369,23 @@ pub(crate) fn get_local_venv_root(app_handle: &tauri::AppHandle) -> Result<PathB
     Ok(venv_root)
 }
 
+/// Retourne le cache pip partagé par les installations des moteurs locaux (créé au besoin).
+pub(crate) fn get_pip_cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let cache_dir = app_data_dir.join("pip_cache");
+    fs::create_dir_all(&cache_dir).map_err(|e| {
+        format!(
+            "Failed to create pip cache directory '{}': {}",
+            cache_dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(cache_dir)
+}
+
 /// Retourne le dossier venv d'un moteur local.
 pub(crate) fn get_engine_venv_path(
     app_handle: &tauri::AppHandle,
@@ -216,6 +394,34 @@ pub(crate) fn get_engine_venv_path(
     Ok(get_local_venv_root(app_handle)?.join(format!("seg-{}", engine.as_key())))
 }
 
+/// Marque un venv comme incomplet pendant l'installation de ses dépendances.
+pub(crate) fn mark_venv_install_incomplete(venv_dir: &Path) -> Result<(), String> {
+    fs::write(venv_dir.join(INSTALL_INCOMPLETE_MARKER), b"").map_err(|e| {
+        format!(
+            "Failed to mark local environment '{}' as incomplete: {}",
+            venv_dir.to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Retire le marqueur d'installation incomplète une fois l'installation réussie.
+pub(crate) fn clear_venv_install_incomplete(venv_dir: &Path) -> Result<(), String> {
+    match fs::remove_file(venv_dir.join(INSTALL_INCOMPLETE_MARKER)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!(
+            "Failed to finalize local environment '{}': {}",
+            venv_dir.to_string_lossy(),
+            e
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Indique si l'installation du venv a été interrompue (annulation, crash).
+pub(crate) fn is_venv_install_incomplete(venv_dir: &Path) -> bool {
+    venv_dir.join(INSTALL_INCOMPLETE_MARKER).exists()
+}
+
 /// Returns the path of the Python executable inside a venv.
 pub(crate) fn get_venv_python_exe(venv_dir: &Path) -> PathBuf {
     if cfg!(target_os = "windows") {
@@ -237,6 +443,35 @@ pub(crate) fn apply_hf_token_env(cmd: &mut Command, token: &str) {
     cmd.env("HUGGING_FACE_HUB_TOKEN", trimmed);
 }
 
+/// Checks that a venv interpreter can still start (`python --version`).
+///
+/// A venv keeps pointing at the base interpreter it was created from: once that interpreter
+/// is upgraded or removed, the venv executable still exists but fails to run.
+pub(crate) fn check_venv_python_runs(python_exe: &Path) -> Result<(), String> {
+    let mut cmd = Command::new(python_exe);
+    cmd.arg("--version");
+    configure_command_no_window(&mut cmd);
+    let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+        .map_err(|e| format!("Failed to start '{}': {}", python_exe.to_string_lossy(), e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if stderr.is_empty() {
+        format!(
+            "'{}' exited with {}",
+            python_exe.to_string_lossy(),
+            output.status
+        )
+    } else {
+        format!(
+            "'{}' failed to run: {}",
+            python_exe.to_string_lossy(),
+            stderr
+        )
+    })
+}
+
 /// Checks that required Python modules are importable in the target environment.
 pub(crate) fn run_python_import_check(python_exe: &Path, modules: &[&str]) -> (bool, Vec<String>) {
     if !python_exe.exists() {
@@ -270,7 +505,7 @@ sys.exit(0 if not missing else 1)
     let mut cmd = Command::new(python_exe);
     cmd.args(["-c", &check_script]);
     configure_command_no_window(&mut cmd);
-    match cmd.output() {
+    match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
         Ok(output) => {
             let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
             let missing = serde_json::from_str::<Vec<String>>(&stdout).unwrap_or_default();
@@ -295,6 +530,8 @@ pub(crate) fn run_python_any_import_check(python_exe: &Path, candidates: &[&str]
 }
 
 /// Creates an engine venv if needed and returns its directory.
+///
+/// The managed Python runtime is used as base interpreter when installed.
 pub(crate) fn create_venv_if_missing(
     app_handle: &tauri::AppHandle,
     engine: LocalSegmentationEngine,
@@ -320,8 +557,8 @@ pub(crate) fn create_venv_if_missing(
         })?;
     }
 
-    let system_python = resolve_system_python(min_major, min_minor)?;
-    let mut cmd = Command::new(&system_python.command);
+    let base_python = resolve_base_python(app_handle)?;
+    let mut cmd = Command::new(&base_python.command);
     cmd.args(["-m", "venv", venv_dir.to_string_lossy().as_ref()]);
     configure_command_no_window(&mut cmd);
 
@@ -3,6 +3,45 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::utils::process::{configure_command_no_window, sanitize_cmd_error};
+use crate::utils::temp_file::TempPathsGuard;
+
+use super::proxy::ProxySettings;
+
+/// Archive Quranic-Phonemizer (commit figé) installée pour Multi-Aligner.
+pub(crate) const QURANIC_PHONEMIZER_ARCHIVE_URL: &str =
+    "https://github.com/Hetchy/Quranic-Phonemizer/archive/1b6a8cc.zip";
+/// Nom de l'archive Quranic-Phonemizer attendu dans un dossier d'installation hors ligne.
+pub(crate) const QURANIC_PHONEMIZER_ARCHIVE_FILE: &str = "Quranic-Phonemizer-1b6a8cc.zip";
+
+/// Lit un fichier requirements et retourne les lignes installées par `pip install -r`.
+///
+/// PyTorch (installé à part) et Quranic-Phonemizer (archive dédiée) sont exclus.
+pub(crate) fn read_install_requirements(requirements_path: &Path) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(requirements_path).map_err(|e| {
+        format!(
+            "Failed to read requirements '{}': {}",
+            requirements_path.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(content
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim().to_lowercase();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                return false;
+            }
+            let is_quranic_phonemizer_dep = trimmed
+                .starts_with("git+https://github.com/hetchy/quranic-phonemizer.git@")
+                || trimmed.contains("quranic-phonemizer");
+            !(trimmed.starts_with("torch")
+                || trimmed.starts_with("torchvision")
+                || trimmed.starts_with("torchaudio")
+                || is_quranic_phonemizer_dep)
+        })
+        .map(|line| line.trim().to_string())
+        .collect())
+}
 
 /// Patch le fichier requirements du moteur multi-aligner pour éviter la dépendance Git.
 pub(crate) fn prepare_multi_requirements_file(
@@ -21,7 +60,7 @@ pub(crate) fn prepare_multi_requirements_file(
         .map(|line| {
             let trimmed = line.trim();
             if trimmed.starts_with("git+https://github.com/Hetchy/Quranic-Phonemizer.git@") {
-                "https://github.com/Hetchy/Quranic-Phonemizer/archive/1b6a8cc.zip".to_string()
+                QURANIC_PHONEMIZER_ARCHIVE_URL.to_string()
             } else {
                 line.to_string()
             }
@@ -40,8 +79,13 @@ pub(crate) fn prepare_multi_requirements_file(
 }
 
 /// Prépare une copie Windows-safe de Quranic-Phonemizer en renommant les fichiers incompatibles.
+///
+/// @param local_archive - Archive déjà téléchargée (installation hors ligne) ; sinon téléchargée
+/// @param proxy - Proxy du téléchargement de l'archive
 pub(crate) fn prepare_windows_safe_quranic_phonemizer_source(
     python_exe: &Path,
+    local_archive: Option<&Path>,
+    proxy: Option<&ProxySettings>,
 ) -> Result<std::path::PathBuf, String> {
     let source_root = std::env::temp_dir().join("qurancaption_quranic_phonemizer_1b6a8cc");
     let setup_py = source_root.join("setup.py");
@@ -58,6 +102,9 @@ pub(crate) fn prepare_windows_safe_quranic_phonemizer_source(
             )
         })?;
     }
+    // Le dossier partiellement extrait est supprimé si une étape échoue.
+    let mut cleanup = TempPathsGuard::default();
+    cleanup.add(&source_root);
     fs::create_dir_all(&source_root).map_err(|e| {
         format!(
             "Failed to create Quranic-Phonemizer source directory '{}': {}",
@@ -77,8 +124,11 @@ import zipfile
 target = pathlib.Path(os.environ["QC_QP_TARGET"])
 target.mkdir(parents=True, exist_ok=True)
 
-url = "https://github.com/Hetchy/Quranic-Phonemizer/archive/1b6a8cc.zip"
-raw = urllib.request.urlopen(url, timeout=120).read()
+archive = os.environ.get("QC_QP_ARCHIVE")
+if archive:
+    raw = pathlib.Path(archive).read_bytes()
+else:
+    raw = urllib.request.urlopen(os.environ["QC_QP_URL"], timeout=120).read()
 
 with zipfile.ZipFile(io.BytesIO(raw)) as zf:
     root_prefix = None
@@ -99,6 +149,13 @@ with zipfile.ZipFile(io.BytesIO(raw)) as zf:
     let mut cmd = Command::new(python_exe);
     cmd.args(["-c", patch_script]);
     cmd.env("QC_QP_TARGET", source_root.to_string_lossy().to_string());
+    cmd.env("QC_QP_URL", QURANIC_PHONEMIZER_ARCHIVE_URL);
+    if let Some(archive) = local_archive {
+        cmd.env("QC_QP_ARCHIVE", archive.to_string_lossy().to_string());
+    }
+    if let Some(proxy) = proxy {
+        proxy.apply_env(&mut cmd);
+    }
     configure_command_no_window(&mut cmd);
     let output = cmd.output().map_err(|e| {
         format!(
@@ -120,5 +177,6 @@ with zipfile.ZipFile(io.BytesIO(raw)) as zf:
         ));
     }
 
+    cleanup.keep();
     Ok(source_root)
 }
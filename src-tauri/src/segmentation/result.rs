@@ -0,0 +1,744 @@
+use serde_json::{Map, Value};
+
+use super::types::{
+    ConfidenceFilter, ConfidenceFilterSummary, SegmentationChunkResult, SegmentationRange,
+    SegmentationResult, SegmentationSegment, SegmentationWord,
+};
+
+/// Tolerance (s) en dessous de laquelle un chevauchement est corrige silencieusement.
+const OVERLAP_TOLERANCE_S: f64 = 0.001;
+
+/// Part du plus court de deux segments de morceaux differents a partir de laquelle leur
+/// chevauchement en fait un doublon.
+const CHUNK_DUPLICATE_OVERLAP_RATIO: f64 = 0.5;
+
+/// Tolerance (ms) en dessous de laquelle un horodatage de mot est borne silencieusement.
+const WORD_TOLERANCE_MS: i64 = 1;
+
+/// Lit un temps en secondes (nombre ou chaine numerique), fini uniquement.
+fn read_time(segment: &Map<String, Value>, key: &str) -> Option<f64> {
+    let value = match segment.get(key)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    }?;
+    value.is_finite().then_some(value)
+}
+
+/// Lit les bornes d'un mot en ms absolues : `start_ms`/`end_ms`, sinon `start`/`end`
+/// en secondes relatives au debut du segment (format des scripts locaux et du cloud).
+fn read_word_times(word: &Map<String, Value>, segment_from_s: f64) -> Option<(i64, i64)> {
+    let absolute = read_time(word, "start_ms").zip(read_time(word, "end_ms"));
+    let (start_ms, end_ms) = absolute.or_else(|| {
+        let (start_s, end_s) = read_time(word, "start").zip(read_time(word, "end"))?;
+        Some((
+            (segment_from_s + start_s) * 1000.0,
+            (segment_from_s + end_s) * 1000.0,
+        ))
+    })?;
+    Some((start_ms.round() as i64, end_ms.round() as i64))
+}
+
+/// Convertit la liste `words` brute d'un segment ; les mots sans temps valides sont ignores.
+///
+/// @returns Mots types et nombre de mots ignores
+fn parse_words(raw_words: Vec<Value>, segment_from_s: f64) -> (Vec<SegmentationWord>, usize) {
+    let mut words = Vec::with_capacity(raw_words.len());
+    let mut dropped = 0;
+    for raw in raw_words {
+        let Value::Object(mut extra) = raw else {
+            dropped += 1;
+            continue;
+        };
+        let Some((start_ms, end_ms)) = read_word_times(&extra, segment_from_s) else {
+            dropped += 1;
+            continue;
+        };
+        let text = match extra.remove("text") {
+            Some(Value::String(text)) => text,
+            _ => extra
+                .get("word")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        };
+        extra.remove("start_ms");
+        extra.remove("end_ms");
+        words.push(SegmentationWord {
+            text,
+            start_ms,
+            end_ms,
+            extra,
+        });
+    }
+    (words, dropped)
+}
+
+/// Borne les mots d'un segment a ses temps finaux et impose un ordre monotone.
+///
+/// Les mots hors du segment ou inverses sont ignores, les chevauchements rognes ;
+/// `start`/`end` (secondes relatives) sont recalcules pour le frontend.
+fn validate_words(
+    label: usize,
+    segment: &mut SegmentationSegment,
+    dropped: usize,
+    warnings: &mut Vec<String>,
+) {
+    let Some(words) = segment.words.take() else {
+        return;
+    };
+    let segment_start_ms = (segment.time_from * 1000.0).round() as i64;
+    let segment_end_ms = (segment.time_to * 1000.0).round() as i64;
+    let mut dropped = dropped;
+    let mut clamped = 0;
+    let mut cursor_ms = segment_start_ms;
+    let mut validated = Vec::with_capacity(words.len());
+
+    for mut word in words {
+        if word.end_ms < word.start_ms
+            || word.end_ms < segment_start_ms
+            || word.start_ms > segment_end_ms
+        {
+            dropped += 1;
+            continue;
+        }
+        let start_ms = word.start_ms.max(cursor_ms);
+        let end_ms = word.end_ms.min(segment_end_ms);
+        if end_ms < start_ms {
+            dropped += 1;
+            continue;
+        }
+        if start_ms - word.start_ms > WORD_TOLERANCE_MS || word.end_ms - end_ms > WORD_TOLERANCE_MS
+        {
+            clamped += 1;
+        }
+        word.start_ms = start_ms;
+        word.end_ms = end_ms;
+        let relative_s = |ms: i64| (ms - segment_start_ms) as f64 / 1000.0;
+        word.extra
+            .insert("start".to_string(), Value::from(relative_s(start_ms)));
+        word.extra
+            .insert("end".to_string(), Value::from(relative_s(end_ms)));
+        cursor_ms = end_ms;
+        validated.push(word);
+    }
+
+    if clamped > 0 || dropped > 0 {
+        warnings.push(format!(
+            "Segment #{}: {} word timestamp(s) clamped, {} dropped",
+            label, clamped, dropped
+        ));
+    }
+    segment.words = Some(validated);
+}
+
+/// Convertit un segment brut en segment type, ou retourne la raison du rejet.
+///
+/// @returns Segment type et nombre de mots ignores faute de temps valides
+fn parse_segment(index: usize, raw: Value) -> Result<(SegmentationSegment, usize), String> {
+    let Value::Object(mut extra) = raw else {
+        return Err(format!("Segment #{} dropped: not an object", index + 1));
+    };
+    let Some(time_from) = read_time(&extra, "time_from") else {
+        return Err(format!(
+            "Segment #{} dropped: missing or invalid time_from",
+            index + 1
+        ));
+    };
+    let Some(time_to) = read_time(&extra, "time_to") else {
+        return Err(format!(
+            "Segment #{} dropped: missing or invalid time_to",
+            index + 1
+        ));
+    };
+    extra.remove("time_from");
+    extra.remove("time_to");
+    let (words, dropped_words) = match extra.remove("words") {
+        Some(Value::Array(raw_words)) => {
+            let (words, dropped) = parse_words(raw_words, time_from);
+            (Some(words), dropped)
+        }
+        _ => (None, 0),
+    };
+    Ok((
+        SegmentationSegment {
+            time_from,
+            time_to,
+            words,
+            extra,
+        },
+        dropped_words,
+    ))
+}
+
+/// Applique le seuil de confiance aux segments valides.
+///
+/// Les segments sous le seuil sont retires, ou marques `low_confidence: true` si
+/// `filter.mark_only` ; ceux sans `confidence` numerique sont gardes tels quels. Leur
+/// nombre est reporte dans `low_confidence_count` pour la relecture manuelle.
+fn apply_confidence_filter(result: &mut SegmentationResult, filter: ConfidenceFilter) {
+    let mut dropped = 0;
+    let mut flagged = 0;
+    result.segments.retain_mut(|segment| {
+        let Some(confidence) = segment.extra.get("confidence").and_then(Value::as_f64) else {
+            return true;
+        };
+        if confidence >= filter.min_confidence {
+            return true;
+        }
+        if filter.mark_only {
+            segment
+                .extra
+                .insert("low_confidence".to_string(), Value::Bool(true));
+            flagged += 1;
+            true
+        } else {
+            dropped += 1;
+            false
+        }
+    });
+    if dropped > 0 || flagged > 0 {
+        log::info!(
+            "[segmentation][validate] confidence < {}: {} segment(s) dropped, {} flagged",
+            filter.min_confidence,
+            dropped,
+            flagged
+        );
+    }
+    result.low_confidence_count = Some(dropped + flagged);
+    result.confidence_filter = Some(ConfidenceFilterSummary {
+        min_confidence: filter.min_confidence,
+        dropped,
+        flagged,
+    });
+}
+
+/// Parse et valide les segments d'un payload de segmentation.
+///
+/// Les temps negatifs sont ramenes a 0, les fins bornees a `audio_duration_s`,
+/// les segments tries puis rognes pour supprimer les chevauchements. Les segments
+/// inexploitables sont ignores et chaque correction produit un avertissement. Les
+/// horodatages de mots sont ensuite bornes au segment final (voir `validate_words`), puis
+/// le seuil de confiance eventuel est applique.
+///
+/// @param payload - Payload brut du cloud ou du script local
+/// @param audio_duration_s - Duree de l'audio segmente, si connue
+/// @param confidence_filter - Seuil de confiance a appliquer, si demande
+/// @returns Resultat type, ou `None` si le payload ne contient pas de liste `segments`
+pub(crate) fn parse_segmentation_result(
+    payload: Value,
+    audio_duration_s: Option<f64>,
+    confidence_filter: Option<ConfidenceFilter>,
+) -> Option<SegmentationResult> {
+    let Value::Object(mut extra) = payload else {
+        return None;
+    };
+    let Some(Value::Array(raw_segments)) = extra.remove("segments") else {
+        return None;
+    };
+    let audio_duration_s =
+        audio_duration_s.filter(|duration| duration.is_finite() && *duration > 0.0);
+
+    let mut warnings = Vec::new();
+    let mut segments = Vec::with_capacity(raw_segments.len());
+    for (index, raw) in raw_segments.into_iter().enumerate() {
+        match parse_segment(index, raw) {
+            Ok((segment, dropped_words)) => segments.push((index, segment, dropped_words)),
+            Err(warning) => warnings.push(warning),
+        }
+    }
+
+    if segments
+        .windows(2)
+        .any(|pair| pair[1].1.time_from < pair[0].1.time_from)
+    {
+        warnings.push("Segments were not in chronological order and have been sorted".to_string());
+        segments.sort_by(|a, b| a.1.time_from.total_cmp(&b.1.time_from));
+    }
+
+    let mut validated: Vec<SegmentationSegment> = Vec::with_capacity(segments.len());
+    for (index, mut segment, dropped_words) in segments {
+        let label = index + 1;
+        if segment.time_from < 0.0 {
+            warnings.push(format!(
+                "Segment #{}: negative start {:.3}s clamped to 0",
+                label, segment.time_from
+            ));
+            segment.time_from = 0.0;
+        }
+        if let Some(duration) = audio_duration_s {
+            if segment.time_from >= duration {
+                warnings.push(format!(
+                    "Segment #{} dropped: starts after the end of the audio ({:.3}s)",
+                    label, duration
+                ));
+                continue;
+            }
+            if segment.time_to > duration {
+                warnings.push(format!(
+                    "Segment #{}: end {:.3}s clamped to audio duration {:.3}s",
+                    label, segment.time_to, duration
+                ));
+                segment.time_to = duration;
+            }
+        }
+        if let Some(previous) = validated.last() {
+            if segment.time_from < previous.time_to {
+                if previous.time_to - segment.time_from > OVERLAP_TOLERANCE_S {
+                    warnings.push(format!(
+                        "Segment #{}: overlaps previous segment, start moved from {:.3}s to {:.3}s",
+                        label, segment.time_from, previous.time_to
+                    ));
+                }
+                segment.time_from = previous.time_to;
+            }
+        }
+        if segment.time_to <= segment.time_from {
+            warnings.push(format!(
+                "Segment #{} dropped: empty or inverted time range ({:.3}s -> {:.3}s)",
+                label, segment.time_from, segment.time_to
+            ));
+            continue;
+        }
+        validate_words(label, &mut segment, dropped_words, &mut warnings);
+        validated.push(segment);
+    }
+
+    for warning in &warnings {
+        log::info!("[segmentation][validate] {}", warning);
+    }
+    let mut result = SegmentationResult {
+        segments: validated,
+        validation_warnings: warnings,
+        confidence_filter: None,
+        low_confidence_count: None,
+        extra,
+    };
+    if let Some(filter) = confidence_filter {
+        apply_confidence_filter(&mut result, filter);
+    }
+    Some(result)
+}
+
+/// Valide un payload de segmentation et le reconvertit en JSON pour l'IPC.
+///
+/// Les payloads sans liste `segments` (erreurs, formats inattendus) sont renvoyes inchanges.
+pub(crate) fn validate_segmentation_payload(
+    payload: Value,
+    audio_duration_s: Option<f64>,
+    confidence_filter: Option<ConfidenceFilter>,
+) -> Value {
+    if !payload.get("segments").is_some_and(Value::is_array) {
+        return payload;
+    }
+    match parse_segmentation_result(payload.clone(), audio_duration_s, confidence_filter)
+        .map(serde_json::to_value)
+    {
+        Some(Ok(validated)) => validated,
+        _ => payload,
+    }
+}
+
+/// Reporte sur la timeline du projet les temps d'un resultat segmente sur une plage.
+///
+/// Les temps (relatifs a l'audio decoupe) sont bornes a la duree de la plage puis decales
+/// de `range.start_ms`. Les `start_ms`/`end_ms` des mots suivent ; leurs `start`/`end`
+/// restent relatifs au segment.
+pub(crate) fn offset_segmentation_payload(payload: &mut Value, range: SegmentationRange) {
+    let Some(segments) = payload.get_mut("segments").and_then(Value::as_array_mut) else {
+        return;
+    };
+    let range_duration_ms = range.duration_ms().map(|duration_ms| duration_ms as f64);
+    let offset_ms = |time_ms: f64| {
+        let time_ms = match range_duration_ms {
+            Some(duration_ms) => time_ms.clamp(0.0, duration_ms),
+            None => time_ms.max(0.0),
+        };
+        (time_ms + range.start_ms as f64).round()
+    };
+
+    for segment in segments.iter_mut().filter_map(Value::as_object_mut) {
+        for key in ["time_from", "time_to"] {
+            if let Some(time_s) = read_time(segment, key) {
+                let shifted_s = offset_ms(time_s * 1000.0) / 1000.0;
+                segment.insert(key.to_string(), Value::from(shifted_s));
+            }
+        }
+        let Some(words) = segment.get_mut("words").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for word in words.iter_mut().filter_map(Value::as_object_mut) {
+            for key in ["start_ms", "end_ms"] {
+                if let Some(time_ms) = read_time(word, key) {
+                    word.insert(key.to_string(), Value::from(offset_ms(time_ms) as i64));
+                }
+            }
+        }
+    }
+}
+
+/// Bornes `(time_from, time_to)` d'un segment brut, si valides.
+fn segment_times(segment: &Value) -> Option<(f64, f64)> {
+    let segment = segment.as_object()?;
+    read_time(segment, "time_from").zip(read_time(segment, "time_to"))
+}
+
+/// Indique si deux segments se recouvrent sur au moins `CHUNK_DUPLICATE_OVERLAP_RATIO`
+/// du plus court.
+fn is_chunk_duplicate(previous: &Value, segment: &Value) -> bool {
+    let (Some((from_a, to_a)), Some((from_b, to_b))) =
+        (segment_times(previous), segment_times(segment))
+    else {
+        return false;
+    };
+    let overlap = to_a.min(to_b) - from_a.max(from_b);
+    let shorter = (to_a - from_a).min(to_b - from_b);
+    overlap > 0.0 && overlap >= shorter * CHUNK_DUPLICATE_OVERLAP_RATIO
+}
+
+/// Confiance d'un segment brut (les segments sans confiance perdent les departages).
+fn segment_confidence(segment: &Value) -> f64 {
+    segment
+        .get("confidence")
+        .and_then(Value::as_f64)
+        .unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Fusionne les resultats de segmentation des morceaux d'un audio long en un seul payload.
+///
+/// Les temps de chaque morceau sont decales de son `offset_ms`, puis les segments sont
+/// concatenes par ordre chronologique. Deux segments de morceaux differents qui se recouvrent
+/// largement (voir `CHUNK_DUPLICATE_OVERLAP_RATIO`) sont un doublon : seul celui de plus forte
+/// `confidence` est garde. Le resultat est ensuite valide comme un payload unique et ses
+/// segments renumerotes (`segment`, a partir de 1).
+///
+/// @param results - Payloads des morceaux et leur position dans l'audio d'origine
+/// @returns Payload unifie, ou une erreur si un morceau n'a pas de liste `segments`
+pub fn merge_segmentation_results(
+    mut results: Vec<SegmentationChunkResult>,
+) -> Result<Value, String> {
+    if results.is_empty() {
+        return Err("No segmentation results to merge".to_string());
+    }
+    results.sort_by_key(|chunk| chunk.offset_ms);
+    let chunk_count = results.len();
+
+    let mut segments: Vec<(usize, Value)> = Vec::new();
+    for (chunk_index, mut chunk) in results.into_iter().enumerate() {
+        let range = SegmentationRange {
+            start_ms: chunk.offset_ms,
+            end_ms: None,
+        };
+        offset_segmentation_payload(&mut chunk.payload, range);
+        let Some(Value::Array(chunk_segments)) = chunk
+            .payload
+            .as_object_mut()
+            .and_then(|payload| payload.remove("segments"))
+        else {
+            return Err(match chunk.payload.get("error").and_then(Value::as_str) {
+                Some(error) => format!("Chunk #{} has no segments: {}", chunk_index + 1, error),
+                None => format!("Chunk #{} has no segments list", chunk_index + 1),
+            });
+        };
+        segments.extend(
+            chunk_segments
+                .into_iter()
+                .map(|segment| (chunk_index, segment)),
+        );
+    }
+    segments.sort_by(|a, b| {
+        let start = |segment: &Value| segment_times(segment).map_or(f64::INFINITY, |t| t.0);
+        start(&a.1).total_cmp(&start(&b.1))
+    });
+
+    let mut merged: Vec<(usize, Value)> = Vec::with_capacity(segments.len());
+    let mut duplicates = 0;
+    for (chunk_index, segment) in segments {
+        if let Some((previous_chunk, previous)) = merged.last_mut() {
+            if *previous_chunk != chunk_index && is_chunk_duplicate(previous, &segment) {
+                if segment_confidence(&segment) > segment_confidence(previous) {
+                    *previous_chunk = chunk_index;
+                    *previous = segment;
+                }
+                duplicates += 1;
+                continue;
+            }
+        }
+        merged.push((chunk_index, segment));
+    }
+
+    let payload = serde_json::json!({
+        "segments": merged.into_iter().map(|(_, segment)| segment).collect::<Vec<_>>(),
+        "merged_chunks": chunk_count,
+        "merged_duplicates": duplicates,
+    });
+    let mut payload = validate_segmentation_payload(payload, None, None);
+    if let Some(segments) = payload.get_mut("segments").and_then(Value::as_array_mut) {
+        for (index, segment) in segments.iter_mut().enumerate() {
+            if let Some(segment) = segment.as_object_mut() {
+                segment.insert("segment".to_string(), Value::from(index + 1));
+            }
+        }
+    }
+    log::info!(
+        "[segmentation][merge] {} chunk(s) merged, {} duplicate segment(s) dropped",
+        chunk_count,
+        duplicates
+    );
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        merge_segmentation_results, offset_segmentation_payload, parse_segmentation_result,
+        validate_segmentation_payload,
+    };
+    use crate::segmentation::types::{
+        ConfidenceFilter, ConfidenceFilterSummary, SegmentationChunkResult, SegmentationRange,
+    };
+
+    #[test]
+    fn drops_unusable_segments_and_keeps_extra_fields() {
+        let payload = json!({
+            "audio_id": "abc",
+            "segments": [
+                { "time_from": 0.5, "time_to": 2.0, "ref_from": "1:1:1", "words": [] },
+                null,
+                { "time_to": 3.0 },
+                { "time_from": "2.5", "time_to": "4" },
+                { "time_from": "oops", "time_to": 5.0 },
+                { "time_from": 6.0, "time_to": 5.0 }
+            ]
+        });
+        let result = parse_segmentation_result(payload, None, None).unwrap();
+
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[0].extra["ref_from"], "1:1:1");
+        assert_eq!(result.segments[1].time_from, 2.5);
+        assert_eq!(result.segments[1].time_to, 4.0);
+        assert_eq!(result.extra["audio_id"], "abc");
+        assert_eq!(result.validation_warnings.len(), 4);
+    }
+
+    #[test]
+    fn clamps_negative_overlapping_and_out_of_range_times() {
+        let payload = json!({
+            "segments": [
+                { "time_from": 4.0, "time_to": 9.0 },
+                { "time_from": -0.2, "time_to": 3.0 },
+                { "time_from": 2.5, "time_to": 5.0 },
+                { "time_from": 12.0, "time_to": 13.0 }
+            ]
+        });
+        let result = parse_segmentation_result(payload, Some(8.0), None).unwrap();
+        let times: Vec<(f64, f64)> = result
+            .segments
+            .iter()
+            .map(|segment| (segment.time_from, segment.time_to))
+            .collect();
+
+        assert_eq!(times, vec![(0.0, 3.0), (3.0, 5.0), (5.0, 8.0)]);
+        assert!(result
+            .validation_warnings
+            .iter()
+            .any(|warning| warning.contains("sorted")));
+    }
+
+    #[test]
+    fn clamps_word_timestamps_to_their_segment() {
+        let payload = json!({
+            "segments": [
+                {
+                    "time_from": 1.0,
+                    "time_to": 3.0,
+                    "words": [
+                        { "location": "1:1:1", "word": "a", "start": -0.2, "end": 0.5 },
+                        { "location": "1:1:2", "word": "b", "start": 0.4, "end": 1.2 },
+                        { "location": "1:1:3", "word": "c", "start": 1.0, "end": 0.9 },
+                        { "text": "d", "start_ms": 2_500, "end_ms": 3_400 },
+                        { "location": "1:1:5", "word": "e", "start": 2.5, "end": 2.8 },
+                        { "word": "f" }
+                    ]
+                },
+                { "time_from": 3.0, "time_to": 4.0 }
+            ]
+        });
+        let result = parse_segmentation_result(payload, None, None).unwrap();
+        let words = result.segments[0].words.as_ref().unwrap();
+        let times: Vec<(&str, i64, i64)> = words
+            .iter()
+            .map(|word| (word.text.as_str(), word.start_ms, word.end_ms))
+            .collect();
+
+        assert_eq!(
+            times,
+            vec![
+                ("a", 1_000, 1_500),
+                ("b", 1_500, 2_200),
+                ("d", 2_500, 3_000)
+            ]
+        );
+        assert_eq!(words[1].extra["start"], 0.5);
+        assert_eq!(words[1].extra["location"], "1:1:2");
+        assert_eq!(result.segments[1].words, None);
+        assert_eq!(
+            result.validation_warnings,
+            vec!["Segment #1: 3 word timestamp(s) clamped, 3 dropped".to_string()]
+        );
+    }
+
+    #[test]
+    fn filters_or_flags_low_confidence_segments() {
+        let payload = json!({
+            "segments": [
+                { "time_from": 0.0, "time_to": 1.0, "confidence": 0.92 },
+                { "time_from": 1.0, "time_to": 2.0, "confidence": 0.1 },
+                { "time_from": 2.0, "time_to": 3.0 },
+                { "time_from": 3.0, "time_to": 4.0, "confidence": 0.4 }
+            ]
+        });
+        let drop = ConfidenceFilter::from_raw(Some(0.5), None).unwrap();
+        let result = parse_segmentation_result(payload.clone(), None, drop).unwrap();
+        let starts: Vec<f64> = result.segments.iter().map(|s| s.time_from).collect();
+        assert_eq!(starts, vec![0.0, 2.0]);
+        assert_eq!(
+            result.confidence_filter,
+            Some(ConfidenceFilterSummary {
+                min_confidence: 0.5,
+                dropped: 2,
+                flagged: 0
+            })
+        );
+
+        let mark = ConfidenceFilter::from_raw(Some(0.5), Some(true)).unwrap();
+        let validated = validate_segmentation_payload(payload, None, mark);
+        assert_eq!(validated["segments"].as_array().unwrap().len(), 4);
+        assert_eq!(validated["segments"][1]["low_confidence"], true);
+        assert_eq!(validated["segments"][0].get("low_confidence"), None);
+        assert_eq!(validated["confidence_filter"]["flagged"], 2);
+        assert_eq!(validated["low_confidence_count"], 2);
+        assert_eq!(result.low_confidence_count, Some(2));
+
+        assert_eq!(ConfidenceFilter::from_raw(None, Some(true)), Ok(None));
+        assert!(ConfidenceFilter::from_raw(Some(1.5), None).is_err());
+    }
+
+    #[test]
+    fn leaves_error_payloads_untouched() {
+        let payload = json!({ "error": "GPU quota exceeded" });
+        assert_eq!(
+            validate_segmentation_payload(payload.clone(), Some(10.0), None),
+            payload
+        );
+
+        let validated = validate_segmentation_payload(
+            json!({ "segments": [{ "time_from": 1, "time_to": 2 }] }),
+            None,
+            None,
+        );
+        assert_eq!(
+            validated,
+            json!({ "segments": [{ "time_from": 1.0, "time_to": 2.0 }] })
+        );
+    }
+
+    #[test]
+    fn offsets_range_results_onto_the_timeline() {
+        let range = SegmentationRange::from_raw(Some(600_000), Some(660_000))
+            .unwrap()
+            .unwrap();
+        let mut payload = json!({
+            "segments": [
+                {
+                    "time_from": -0.04,
+                    "time_to": 2.5,
+                    "words": [{ "text": "a", "start_ms": 0, "end_ms": 1_200, "start": 0.0, "end": 1.2 }]
+                },
+                { "time_from": 58.25, "time_to": 60.08 }
+            ]
+        });
+        offset_segmentation_payload(&mut payload, range);
+
+        assert_eq!(payload["segments"][0]["time_from"], 600.0);
+        assert_eq!(payload["segments"][0]["time_to"], 602.5);
+        assert_eq!(payload["segments"][0]["words"][0]["start_ms"], 600_000);
+        assert_eq!(payload["segments"][0]["words"][0]["end_ms"], 601_200);
+        assert_eq!(payload["segments"][0]["words"][0]["end"], 1.2);
+        assert_eq!(payload["segments"][1]["time_from"], 658.25);
+        assert_eq!(payload["segments"][1]["time_to"], 660.0);
+
+        let open_ended = SegmentationRange::from_raw(Some(1_500), None)
+            .unwrap()
+            .unwrap();
+        let mut payload = json!({ "segments": [{ "time_from": 0.1, "time_to": 900.0 }] });
+        offset_segmentation_payload(&mut payload, open_ended);
+        assert_eq!(payload["segments"][0]["time_from"], 1.6);
+        assert_eq!(payload["segments"][0]["time_to"], 901.5);
+
+        assert_eq!(SegmentationRange::from_raw(Some(0), None), Ok(None));
+        assert!(SegmentationRange::from_raw(Some(5_000), Some(4_000)).is_err());
+    }
+
+    #[test]
+    fn merges_chunk_results_and_dedupes_boundary_segments() {
+        let chunk = |offset_ms: u64, payload: serde_json::Value| SegmentationChunkResult {
+            offset_ms,
+            payload,
+        };
+        let merged = merge_segmentation_results(vec![
+            chunk(
+                60_000,
+                json!({ "segments": [
+                    { "segment": 1, "time_from": 0.0, "time_to": 1.5, "ref_from": "1:3", "confidence": 0.9 },
+                    { "segment": 2, "time_from": 2.0, "time_to": 5.0, "ref_from": "1:4" }
+                ] }),
+            ),
+            chunk(
+                0,
+                json!({ "segments": [
+                    { "segment": 1, "time_from": 1.0, "time_to": 30.0, "ref_from": "1:1" },
+                    {
+                        "segment": 2,
+                        "time_from": 58.5,
+                        "time_to": 61.0,
+                        "ref_from": "1:2",
+                        "confidence": 0.4,
+                        "words": [{ "text": "a", "start_ms": 58_600, "end_ms": 59_000 }]
+                    }
+                ] }),
+            ),
+        ])
+        .unwrap();
+
+        let segments = merged["segments"].as_array().unwrap();
+        let summary: Vec<(u64, f64, f64, &str)> = segments
+            .iter()
+            .map(|segment| {
+                (
+                    segment["segment"].as_u64().unwrap(),
+                    segment["time_from"].as_f64().unwrap(),
+                    segment["time_to"].as_f64().unwrap(),
+                    segment["ref_from"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, 1.0, 30.0, "1:1"),
+                (2, 60.0, 61.5, "1:3"),
+                (3, 62.0, 65.0, "1:4")
+            ]
+        );
+        assert_eq!(merged["merged_chunks"], 2);
+        assert_eq!(merged["merged_duplicates"], 1);
+
+        assert!(merge_segmentation_results(Vec::new()).is_err());
+        let error = merge_segmentation_results(vec![chunk(0, json!({ "error": "GPU quota" }))])
+            .unwrap_err();
+        assert_eq!(error, "Chunk #1 has no segments: GPU quota");
+    }
+}
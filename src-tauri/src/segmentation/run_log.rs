@@ -0,0 +1,234 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::Manager;
+
+/// Nombre maximal de journaux de segmentation conservés.
+const MAX_LOG_FILES: usize = 20;
+/// Taille cumulée maximale des journaux conservés (octets).
+const MAX_LOG_TOTAL_BYTES: u64 = 20 * 1024 * 1024;
+/// Préfixe des fichiers journaux (suivi d'un horodatage en ms, ce qui les trie par date).
+const LOG_FILE_PREFIX: &str = "segmentation-";
+
+/// Journal complet (stderr puis stdout) d'une exécution de script de segmentation locale.
+pub(super) struct SegmentationRunLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+/// Retourne le dossier `logs/segmentation` des données de l'application (créé au besoin).
+fn segmentation_logs_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let logs_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("logs")
+        .join("segmentation");
+    fs::create_dir_all(&logs_dir).map_err(|e| {
+        format!(
+            "Failed to create segmentation log directory '{}': {}",
+            logs_dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(logs_dir)
+}
+
+impl SegmentationRunLog {
+    /// Crée le journal d'une exécution et écrit son en-tête.
+    ///
+    /// @param args - Commande Python (script et arguments)
+    /// @param worker - `true` si le script tourne dans le worker résident
+    pub(super) fn create(
+        app_handle: &tauri::AppHandle,
+        engine_key: &str,
+        args: &[String],
+        worker: bool,
+    ) -> Result<Self, String> {
+        let logs_dir = segmentation_logs_dir(app_handle)?;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        let path = logs_dir.join(format!("{}{}-{}.log", LOG_FILE_PREFIX, stamp, engine_key));
+        let mut file = File::create(&path).map_err(|e| {
+            format!(
+                "Failed to create segmentation log '{}': {}",
+                path.to_string_lossy(),
+                e
+            )
+        })?;
+        let _ = write!(
+            file,
+            "QuranCaption Local Segmentation Log\n\
+             ===================================\n\
+             Timestamp: {}\n\
+             Engine: {}\n\
+             Mode: {}\n\
+             Command: {}\n\n\
+             --- stderr ---\n",
+            stamp,
+            engine_key,
+            if worker { "worker" } else { "process" },
+            args.join(" ")
+        );
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Chemin du journal, avec des `/` pour l'affichage.
+    pub(super) fn display_path(&self) -> String {
+        self.path.to_string_lossy().replace('\\', "/")
+    }
+
+    /// Ajoute une ligne stderr du script (STATUS compris).
+    pub(super) fn append_stderr_line(&self, line: &str) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+
+    /// Écrit la sortie standard et le statut final, puis applique la rotation des journaux.
+    pub(super) fn finish(&self, success: bool, stdout: &str) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = write!(
+                file,
+                "\n--- stdout ---\n{}\n\n--- status ---\n{}\n",
+                stdout.trim_end(),
+                if success { "success" } else { "failure" }
+            );
+            let _ = file.flush();
+        }
+        if let Some(logs_dir) = self.path.parent() {
+            rotate_logs(
+                logs_dir,
+                LOG_FILE_PREFIX,
+                MAX_LOG_FILES,
+                MAX_LOG_TOTAL_BYTES,
+            );
+        }
+    }
+
+    /// Joint le chemin du journal au résultat (`logPath`) ou au message d'erreur.
+    pub(super) fn attach_to_outcome(
+        &self,
+        outcome: Result<serde_json::Value, String>,
+    ) -> Result<serde_json::Value, String> {
+        match outcome {
+            Ok(mut result) => {
+                if let Some(object) = result.as_object_mut() {
+                    object.insert(
+                        "logPath".to_string(),
+                        serde_json::Value::String(self.display_path()),
+                    );
+                }
+                Ok(result)
+            }
+            Err(error) => Err(format!(
+                "{}\n\nSee the log file: {}",
+                error,
+                self.display_path()
+            )),
+        }
+    }
+}
+
+/// Liste les journaux `<prefix>*.log` d'un dossier, du plus récent au plus ancien, avec leur taille.
+pub(super) fn list_logs(logs_dir: &Path, prefix: &str) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with(prefix) && name.ends_with(".log")
+        })
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.len())))
+        .collect();
+    logs.sort_by(|a, b| b.0.file_name().cmp(&a.0.file_name()));
+    logs
+}
+
+/// Supprime les journaux les plus anciens au-delà de `max_files` ou de `max_bytes` cumulés.
+///
+/// Le journal le plus récent est toujours conservé, même s'il dépasse seul la limite.
+pub(super) fn rotate_logs(logs_dir: &Path, prefix: &str, max_files: usize, max_bytes: u64) {
+    let mut total_bytes = 0;
+    for (index, (path, size)) in list_logs(logs_dir, prefix).into_iter().enumerate() {
+        total_bytes += size;
+        if index > 0 && (index >= max_files || total_bytes > max_bytes) {
+            if let Err(error) = fs::remove_file(&path) {
+                log::warn!(
+                    "[logs] failed to remove old log {}: {}",
+                    path.to_string_lossy(),
+                    error
+                );
+            }
+        }
+    }
+}
+
+/// Retourne les chemins des derniers journaux de segmentation locale (plus récent d'abord).
+///
+/// @param limit - Nombre maximal de chemins (tous les journaux conservés par défaut)
+pub async fn get_segmentation_logs(
+    app_handle: tauri::AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let logs_dir = segmentation_logs_dir(&app_handle)?;
+    Ok(list_logs(&logs_dir, LOG_FILE_PREFIX)
+        .into_iter()
+        .take(limit.unwrap_or(MAX_LOG_FILES))
+        .map(|(path, _)| path.to_string_lossy().replace('\\', "/"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_logs, rotate_logs, LOG_FILE_PREFIX};
+    use std::fs;
+
+    #[test]
+    fn rotation_keeps_the_newest_logs_within_limits() {
+        let logs_dir = std::env::temp_dir().join(format!(
+            "qurancaption-segmentation-logs-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&logs_dir);
+        fs::create_dir_all(&logs_dir).unwrap();
+        for stamp in 1..=5 {
+            let name = format!("segmentation-170000000000{}-legacy.log", stamp);
+            fs::write(logs_dir.join(name), vec![b'x'; 10]).unwrap();
+        }
+        fs::write(logs_dir.join("notes.txt"), "keep").unwrap();
+
+        rotate_logs(&logs_dir, LOG_FILE_PREFIX, 3, 1_000);
+        let names: Vec<String> = list_logs(&logs_dir, LOG_FILE_PREFIX)
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "segmentation-1700000000005-legacy.log",
+                "segmentation-1700000000004-legacy.log",
+                "segmentation-1700000000003-legacy.log",
+            ]
+        );
+
+        rotate_logs(&logs_dir, LOG_FILE_PREFIX, 3, 15);
+        assert_eq!(list_logs(&logs_dir, LOG_FILE_PREFIX).len(), 1);
+        rotate_logs(&logs_dir, LOG_FILE_PREFIX, 3, 5);
+        assert_eq!(list_logs(&logs_dir, LOG_FILE_PREFIX).len(), 1);
+        assert_eq!(list_logs(&logs_dir, "install-").len(), 0);
+        assert!(logs_dir.join("notes.txt").exists());
+
+        let _ = fs::remove_dir_all(&logs_dir);
+    }
+}
@@ -0,0 +1,171 @@
+//! Export direct d'un résultat de segmentation en sous-titres SRT (sans rendu vidéo).
+
+use std::fs;
+
+use serde_json::Value;
+
+use crate::path_utils;
+
+use super::result::parse_segmentation_result;
+use super::types::SegmentationSegment;
+
+/// Champ de texte utilisé par défaut pour les cues.
+const DEFAULT_TEXT_FIELD: &str = "matched_text";
+/// Marque droite-à-gauche placée en tête des lignes RTL pour les lecteurs qui l'ignorent.
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+
+/// Formate un temps en millisecondes au format SRT `HH:MM:SS,mmm`.
+fn format_srt_timestamp(time_ms: i64) -> String {
+    let time_ms = time_ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        time_ms / 3_600_000,
+        time_ms / 60_000 % 60,
+        time_ms / 1000 % 60,
+        time_ms % 1000
+    )
+}
+
+/// Indique si un caractère appartient à un alphabet écrit de droite à gauche.
+fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF)
+}
+
+/// Lignes non vides du texte d'un cue (une ligne vide terminerait le cue), marquées RTL au besoin.
+fn cue_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if line.chars().any(is_rtl_char) {
+                format!("{}{}", RIGHT_TO_LEFT_MARK, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Référence `ref_from - ref_to` d'un segment (une seule si identiques).
+fn segment_reference(segment: &SegmentationSegment) -> Option<String> {
+    let read = |key: &str| {
+        segment
+            .extra
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    match (read("ref_from"), read("ref_to")) {
+        (Some(from), Some(to)) if from != to => Some(format!("{} - {}", from, to)),
+        (Some(reference), _) | (None, Some(reference)) => Some(reference.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Construit le contenu SRT des segments ; ceux sans texte sont ignorés.
+///
+/// @returns Contenu SRT et nombre de cues écrits
+fn build_srt(
+    segments: &[SegmentationSegment],
+    text_field: &str,
+    include_refs: bool,
+) -> (String, usize) {
+    let mut content = String::new();
+    let mut cues = 0;
+    for segment in segments {
+        let text = segment
+            .extra
+            .get(text_field)
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let mut lines = cue_lines(text);
+        if lines.is_empty() {
+            continue;
+        }
+        // SRT n'a pas de commentaires : la référence devient la dernière ligne du cue.
+        if let Some(reference) = include_refs.then(|| segment_reference(segment)).flatten() {
+            lines.push(format!("[{}]", reference));
+        }
+        cues += 1;
+        content.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            cues,
+            format_srt_timestamp((segment.time_from * 1000.0).round() as i64),
+            format_srt_timestamp((segment.time_to * 1000.0).round() as i64),
+            lines.join("\n")
+        ));
+    }
+    (content, cues)
+}
+
+/// Exporte un payload de segmentation en fichier SRT.
+///
+/// Les segments sont validés comme pour l'IPC (tri, chevauchements rognés) avant l'export.
+///
+/// @param payload - Payload de segmentation (cloud, local ou fusionné)
+/// @param output_path - Chemin du fichier SRT à écrire
+/// @param text_field - Champ texte des segments (`matched_text` par défaut)
+/// @param include_refs - Ajoute `ref_from`/`ref_to` en dernière ligne de chaque cue
+/// @returns Nombre de cues écrits
+pub fn segmentation_to_srt(
+    payload: Value,
+    output_path: String,
+    text_field: Option<String>,
+    include_refs: Option<bool>,
+) -> Result<usize, String> {
+    let result = parse_segmentation_result(payload, None, None)
+        .ok_or_else(|| "Segmentation payload has no segments list".to_string())?;
+    let text_field = text_field
+        .as_deref()
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .unwrap_or(DEFAULT_TEXT_FIELD);
+    let (content, cues) = build_srt(&result.segments, text_field, include_refs.unwrap_or(false));
+    if cues == 0 {
+        return Err(format!("No segment has a '{}' text to export", text_field));
+    }
+
+    let path = path_utils::normalize_output_path(&output_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write SRT file: {}", e))?;
+    log::info!(
+        "[segmentation][srt] {} cue(s) written to {}",
+        cues,
+        path.to_string_lossy()
+    );
+    Ok(cues)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{build_srt, format_srt_timestamp};
+    use crate::segmentation::result::parse_segmentation_result;
+
+    #[test]
+    fn segments_become_srt_cues() {
+        assert_eq!(format_srt_timestamp(3_723_004), "01:02:03,004");
+
+        let payload = json!({
+            "segments": [
+                { "time_from": 0.63, "time_to": 6.11, "matched_text": "بسم الله", "ref_from": "1:1:1", "ref_to": "1:1:4" },
+                { "time_from": 6.2, "time_to": 7.0, "matched_text": "  " },
+                { "time_from": 7.0, "time_to": 9.5, "matched_text": "Amin\n\nAmin", "ref_from": "Amin", "ref_to": "Amin" }
+            ]
+        });
+        let result = parse_segmentation_result(payload, None, None).unwrap();
+
+        let (content, cues) = build_srt(&result.segments, "matched_text", true);
+        assert_eq!(cues, 2);
+        assert_eq!(
+            content,
+            "1\n00:00:00,630 --> 00:00:06,110\n\u{200F}بسم الله\n[1:1:1 - 1:1:4]\n\n\
+             2\n00:00:07,000 --> 00:00:09,500\nAmin\nAmin\n[Amin]\n\n"
+        );
+        assert_eq!(build_srt(&result.segments, "text", false).1, 0);
+    }
+}
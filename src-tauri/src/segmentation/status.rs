@@ -1,147 +1,284 @@
-use std::process::Command;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::utils::process::configure_command_no_window;
+use tauri::{Emitter, Manager};
 
 use super::data_files::{
     required_multi_aligner_data_files, resolve_multi_aligner_data_dir,
-    validate_multi_aligner_data_file,
+    verify_multi_aligner_data_file,
 };
+use super::managed_python::resolve_managed_python;
 use super::python_env::{
-    get_engine_venv_path, get_venv_python_exe, resolve_system_python, run_python_any_import_check,
-    run_python_import_check, MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR,
+    check_venv_python_runs, get_engine_venv_path, get_venv_python_exe, is_venv_install_incomplete,
+    resolve_system_python, run_python_any_import_check, run_python_import_check,
+    MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR,
 };
 use super::types::LocalSegmentationEngine;
+use super::venv_migration::{
+    find_previous_venv, previous_app_data_dirs, pyvenv_cfg_previous_app_data_dir,
+};
+use super::version_pins::pinned_version_mismatches;
 
-/// VÃ©rifie que les versions Python critiques du moteur legacy restent compatibles.
-fn check_legacy_python_versions(python_exe: &std::path::Path) -> (bool, Option<String>) {
-    if !python_exe.exists() {
-        return (
-            false,
-            Some("Legacy Python environment not found".to_string()),
-        );
-    }
-
-    let script = r#"
-import json
-from importlib import metadata
-
-def major(version: str) -> int:
-    try:
-        token = str(version).split('.', 1)[0]
-        return int(token)
-    except Exception:
-        return -1
-
-def package_version(name: str) -> str:
-    try:
-        return metadata.version(name)
-    except Exception:
-        return "unknown"
-
-try:
-    versions = {
-        "transformers": package_version("transformers"),
-        "numpy": package_version("numpy"),
-        "librosa": package_version("librosa"),
-        "soundfile": package_version("soundfile"),
-        "accelerate": package_version("accelerate"),
-    }
-    checks = [
-        major(versions["transformers"]) < 5,
-        major(versions["numpy"]) < 2,
-        major(versions["librosa"]) <= 0 or versions["librosa"].startswith("0.10."),
-        major(versions["soundfile"]) <= 0 or versions["soundfile"].startswith("0.12."),
-        major(versions["accelerate"]) < 1,
-    ]
-    print(json.dumps({"ok": all(checks), "versions": versions}))
-except Exception as e:
-    print(json.dumps({"ok": False, "error": str(e)}))
-"#;
+/// Evenement d'avancement du check de preparation (purement informatif).
+const READINESS_PROGRESS_EVENT: &str = "segmentation-readiness-progress";
 
-    let mut cmd = Command::new(python_exe);
-    cmd.args(["-c", script]);
-    configure_command_no_window(&mut cmd);
+/// Emet l'etape en cours du check de preparation avec un pourcentage approximatif.
+fn emit_readiness_progress(app_handle: &tauri::AppHandle, percent: u32, message: &str) {
+    let _ = app_handle.emit(
+        READINESS_PROGRESS_EVENT,
+        serde_json::json!({ "percent": percent, "message": message }),
+    );
+}
 
-    let output = match cmd.output() {
-        Ok(value) => value,
-        Err(error) => {
-            return (
-                false,
-                Some(format!(
-                    "Failed to validate legacy package versions: {}",
-                    error
-                )),
-            );
-        }
-    };
+/// Empreinte de l'etat disque dont depend le check de preparation.
+#[derive(Clone, Debug, PartialEq)]
+struct ReadinessFingerprint {
+    /// Date de modification du Python de chaque venv (`None` si absent).
+    python_mtimes: Vec<Option<SystemTime>>,
+    /// Taille de chaque fichier data Multi-Aligner (`None` si absent).
+    data_file_sizes: Vec<Option<u64>>,
+    /// Le resultat Multi-Aligner depend de la presence du token HF.
+    token_provided: bool,
+}
 
-    if !output.status.success() {
-        return (
-            false,
-            Some("Legacy package version check failed".to_string()),
-        );
-    }
+/// Dernier check de preparation complet, avec son empreinte et sa date (ms Unix).
+struct CachedReadiness {
+    fingerprint: ReadinessFingerprint,
+    result: serde_json::Value,
+    checked_at_ms: u64,
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    let parsed = match serde_json::from_str::<serde_json::Value>(&stdout) {
-        Ok(value) => value,
-        Err(_) => {
-            return (
-                false,
-                Some("Legacy package version check returned invalid JSON".to_string()),
-            );
-        }
-    };
+/// Cache memoire du check de preparation, invalide par les installations/desinstallations.
+static READINESS_CACHE: Mutex<Option<CachedReadiness>> = Mutex::new(None);
 
-    if parsed.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
-        return (true, None);
+/// Vide le cache du check de preparation (a appeler apres toute modification des venvs).
+pub(crate) fn invalidate_local_readiness_cache() {
+    if let Ok(mut cache) = READINESS_CACHE.lock() {
+        *cache = None;
     }
+}
 
-    if let Some(error) = parsed.get("error").and_then(|v| v.as_str()) {
-        return (
-            false,
-            Some(format!("Legacy package version check error: {}", error)),
+/// Calcule l'empreinte courante des venvs et fichiers data (quelques `stat`, sans Python).
+fn readiness_fingerprint(
+    app_handle: &tauri::AppHandle,
+    token_provided: bool,
+) -> Result<ReadinessFingerprint, String> {
+    let mut python_mtimes = Vec::new();
+    for engine in LocalSegmentationEngine::ALL {
+        let python = get_venv_python_exe(&get_engine_venv_path(app_handle, engine)?);
+        python_mtimes.push(
+            fs::metadata(python)
+                .and_then(|metadata| metadata.modified())
+                .ok(),
         );
     }
-
-    let versions_text = parsed
-        .get("versions")
-        .and_then(|v| v.as_object())
-        .map(|map| {
-            map.iter()
-                .map(|(key, value)| format!("{}={}", key, value.as_str().unwrap_or("unknown")))
-                .collect::<Vec<_>>()
-                .join(", ")
+    let data_dir = resolve_multi_aligner_data_dir(app_handle)?;
+    let data_file_sizes = required_multi_aligner_data_files()
+        .iter()
+        .map(|data_file| {
+            fs::metadata(data_dir.join(data_file.name))
+                .map(|metadata| metadata.len())
+                .ok()
         })
-        .unwrap_or_else(|| "unknown versions".to_string());
+        .collect();
+    Ok(ReadinessFingerprint {
+        python_mtimes,
+        data_file_sizes,
+        token_provided,
+    })
+}
 
-    (
-        false,
-        Some(format!(
-            "Incompatible legacy package versions: {}",
-            versions_text
-        )),
-    )
+/// Ajoute `fromCache` et `checkedAt` (ms Unix) a un resultat de check.
+fn with_cache_fields(
+    mut result: serde_json::Value,
+    from_cache: bool,
+    checked_at_ms: u64,
+) -> serde_json::Value {
+    result["fromCache"] = serde_json::json!(from_cache);
+    result["checkedAt"] = serde_json::json!(checked_at_ms);
+    result
 }
 
 /// VÃ©rifie l'Ã©tat de prÃ©paration des moteurs de segmentation locale.
+///
+/// Le check complet lance plusieurs processus Python (jusqu'a 25 s) : son resultat est
+/// garde en memoire et renvoye tel quel tant que l'empreinte des venvs et fichiers data
+/// n'a pas change. Un check expire n'est pas mis en cache. Le check complet emet ses etapes
+/// sur `segmentation-readiness-progress` (`percent`, `message`).
+///
+/// @param force - Ignore le cache et refait le check complet
 pub async fn check_local_segmentation_ready(
     app_handle: tauri::AppHandle,
     hf_token: Option<String>,
+    force: Option<bool>,
 ) -> Result<serde_json::Value, String> {
-    use tokio::time::{timeout, Duration};
-
     let token_provided = hf_token
         .as_ref()
         .map(|t| !t.trim().is_empty())
         .unwrap_or(false);
+    let fingerprint = readiness_fingerprint(&app_handle, token_provided).ok();
+
+    if !force.unwrap_or(false) {
+        if let (Some(fingerprint), Ok(cache)) = (fingerprint.as_ref(), READINESS_CACHE.lock()) {
+            if let Some(cached) = cache
+                .as_ref()
+                .filter(|cached| &cached.fingerprint == fingerprint)
+            {
+                return Ok(with_cache_fields(
+                    cached.result.clone(),
+                    true,
+                    cached.checked_at_ms,
+                ));
+            }
+        }
+    }
+
+    let (result, completed) =
+        run_local_segmentation_ready_check(app_handle, token_provided).await?;
+    let checked_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0);
+    if let (true, Some(fingerprint), Ok(mut cache)) =
+        (completed, fingerprint, READINESS_CACHE.lock())
+    {
+        *cache = Some(CachedReadiness {
+            fingerprint,
+            result: result.clone(),
+            checked_at_ms,
+        });
+    }
+    Ok(with_cache_fields(result, false, checked_at_ms))
+}
+
+/// Cle d'un moteur dans l'objet `engines` de la reponse du check.
+fn readiness_engine_key(engine: LocalSegmentationEngine) -> &'static str {
+    match engine {
+        LocalSegmentationEngine::LegacyWhisper => "legacy",
+        LocalSegmentationEngine::MultiAligner => "multi",
+        LocalSegmentationEngine::MuaalemLocal => "muaalem",
+        LocalSegmentationEngine::SurahSplitter => "surahSplitter",
+    }
+}
+
+/// Probleme de venv qui demande une reparation plutot qu'une simple installation.
+#[derive(Debug, PartialEq)]
+enum VenvIssue {
+    /// Pas de venv, mais une ancienne version de l'application en a laisse un.
+    MigrationAvailable { source: PathBuf },
+    /// L'executable Python du venv existe mais ne demarre plus (interpreteur de base
+    /// mis a jour ou supprime).
+    Broken {
+        error: String,
+        previous_app_data_dir: Option<PathBuf>,
+    },
+}
+
+/// Cherche un venv casse ou migrable pour un moteur qui n'est pas pret.
+fn diagnose_engine_venv(
+    app_data_dir: &Path,
+    engine: LocalSegmentationEngine,
+    venv_dir: &Path,
+) -> Option<VenvIssue> {
+    let python_exe = get_venv_python_exe(venv_dir);
+    if !python_exe.exists() {
+        return find_previous_venv(&previous_app_data_dirs(app_data_dir), engine)
+            .map(|source| VenvIssue::MigrationAvailable { source });
+    }
+    let error = check_venv_python_runs(&python_exe).err()?;
+    let previous_app_data_dir = fs::read_to_string(venv_dir.join("pyvenv.cfg"))
+        .ok()
+        .and_then(|pyvenv_cfg| pyvenv_cfg_previous_app_data_dir(&pyvenv_cfg, app_data_dir));
+    Some(VenvIssue::Broken {
+        error,
+        previous_app_data_dir,
+    })
+}
+
+/// Renseigne `state` pour un moteur (`ready`, `not_installed`, `needs_setup`,
+/// `migration_available` ou `venv_broken`) et, pour un venv a reparer, l'erreur et la
+/// commande de reparation conseillee (`repairCommand`, `repairHint`).
+fn apply_engine_state(
+    engine_status: &mut serde_json::Value,
+    engine: LocalSegmentationEngine,
+    issue: Option<VenvIssue>,
+) {
+    let label = engine.as_label();
+    let state = match issue {
+        Some(VenvIssue::MigrationAvailable { source }) => {
+            engine_status["message"] = serde_json::json!(format!(
+                "{} environment from a previous version of the app was found",
+                label
+            ));
+            engine_status["repairCommand"] = serde_json::json!("migrate_local_envs");
+            engine_status["repairHint"] = serde_json::json!(format!(
+                "Migrate the existing environment from '{}' instead of reinstalling",
+                source.to_string_lossy()
+            ));
+            "migration_available"
+        }
+        Some(VenvIssue::Broken {
+            error,
+            previous_app_data_dir,
+        }) => {
+            engine_status["message"] = serde_json::json!(format!(
+                "{} Python environment is broken and cannot start",
+                label
+            ));
+            engine_status["venvError"] = serde_json::json!(error);
+            engine_status["repairCommand"] = serde_json::json!("install_local_segmentation_deps");
+            engine_status["repairHint"] = serde_json::json!(match previous_app_data_dir {
+                Some(dir) => format!(
+                    "The environment still uses the Python of a previous version of the app ('{}'). Reinstall {} dependencies to recreate it",
+                    dir.to_string_lossy(),
+                    label
+                ),
+                None => format!(
+                    "The Python interpreter it was created with was probably upgraded or removed. Reinstall {} dependencies to recreate it",
+                    label
+                ),
+            });
+            "venv_broken"
+        }
+        None if engine_status["ready"].as_bool().unwrap_or(false) => "ready",
+        None if !engine_status["venvExists"].as_bool().unwrap_or(false) => "not_installed",
+        None => "needs_setup",
+    };
+    engine_status["state"] = serde_json::json!(state);
+}
+
+/// Execute le check complet ; le booleen vaut `false` si le check a expire.
+async fn run_local_segmentation_ready_check(
+    app_handle: tauri::AppHandle,
+    token_provided: bool,
+) -> Result<(serde_json::Value, bool), String> {
+    use tokio::time::{timeout, Duration};
+
+    // Le Python autonome (téléchargé par l'application) est signalé à part du Python système.
+    let managed_app_handle = app_handle.clone();
+    let managed_python =
+        tokio::task::spawn_blocking(move || resolve_managed_python(&managed_app_handle))
+            .await
+            .unwrap_or(None);
+    let managed_python_available = managed_python.is_some();
+    // Interpréteur retenu pour les venvs, renseigné même si la suite du check expire.
+    let selected_python = Arc::new(Mutex::new(managed_python));
+    let check_selected_python = Arc::clone(&selected_python);
 
     // Le check est exÃ©cutÃ© dans un thread bloquant avec timeout pour ne pas figer l'UI.
     let check_result = timeout(
         Duration::from_secs(25),
         tokio::task::spawn_blocking(move || {
+            emit_readiness_progress(&app_handle, 5, "Checking Python...");
             let python = resolve_system_python(MIN_LOCAL_PYTHON_MAJOR, MIN_LOCAL_PYTHON_MINOR);
-            if let Err(error) = python {
+            // Le runtime géré suffit à créer les venvs, même sans Python système.
+            let python_installed = python.is_ok() || managed_python_available;
+            if let (Ok(mut selected), Ok(interpreter)) = (check_selected_python.lock(), &python) {
+                selected.get_or_insert_with(|| interpreter.clone());
+            }
+            if let (Err(error), false) = (python, managed_python_available) {
                 return serde_json::json!({
                     "ready": false,
                     "pythonInstalled": false,
@@ -174,11 +311,12 @@ pub async fn check_local_segmentation_ready(
                     });
             }
 
+            emit_readiness_progress(&app_handle, 15, "Resolving local environments...");
             let legacy_venv = match get_engine_venv_path(&app_handle, LocalSegmentationEngine::LegacyWhisper) {
                 Ok(path) => path,
                 Err(error) => {
                     return serde_json::json!({
-                        "ready": false, "pythonInstalled": true, "packagesInstalled": false,
+                        "ready": false, "pythonInstalled": python_installed, "packagesInstalled": false,
                         "message": format!("Failed to resolve local env paths: {}", error),
                         "engines": {
                             "legacy": {
@@ -207,7 +345,7 @@ pub async fn check_local_segmentation_ready(
                 Ok(path) => path,
                 Err(error) => {
                     return serde_json::json!({
-                        "ready": false, "pythonInstalled": true, "packagesInstalled": false,
+                        "ready": false, "pythonInstalled": python_installed, "packagesInstalled": false,
                         "message": format!("Failed to resolve local env paths: {}", error),
                         "engines": {
                             "legacy": {
@@ -236,7 +374,7 @@ pub async fn check_local_segmentation_ready(
                     Ok(path) => path,
                     Err(error) => {
                         return serde_json::json!({
-                            "ready": false, "pythonInstalled": true, "packagesInstalled": false,
+                            "ready": false, "pythonInstalled": python_installed, "packagesInstalled": false,
                             "message": format!("Failed to resolve local env paths: {}", error),
                             "engines": {
                                 "legacy": {
@@ -267,7 +405,7 @@ pub async fn check_local_segmentation_ready(
                     Ok(path) => path,
                     Err(error) => {
                         return serde_json::json!({
-                            "ready": false, "pythonInstalled": true, "packagesInstalled": false,
+                            "ready": false, "pythonInstalled": python_installed, "packagesInstalled": false,
                             "message": format!("Failed to resolve local env paths: {}", error),
                             "engines": {
                                 "legacy": {
@@ -300,26 +438,34 @@ pub async fn check_local_segmentation_ready(
             let multi_venv_exists = multi_python.exists();
             let muaalem_venv_exists = muaalem_python.exists();
             let surah_splitter_venv_exists = surah_splitter_python.exists();
+            // Installation interrompue : les imports peuvent réussir sur un venv partiel.
+            let legacy_incomplete = is_venv_install_incomplete(&legacy_venv);
+            let multi_incomplete = is_venv_install_incomplete(&multi_venv);
+            let muaalem_incomplete = is_venv_install_incomplete(&muaalem_venv);
+            let surah_splitter_incomplete = is_venv_install_incomplete(&surah_splitter_venv);
 
+            emit_readiness_progress(&app_handle, 25, "Validating Legacy Whisper packages...");
             let (legacy_imports_ok, legacy_missing_modules) = run_python_import_check(
                 &legacy_python,
                 LocalSegmentationEngine::LegacyWhisper.required_import_modules(),
             );
-            let (legacy_versions_ok, legacy_versions_message) =
-                check_legacy_python_versions(&legacy_python);
+            emit_readiness_progress(&app_handle, 50, "Validating Multi-Aligner packages...");
             let (multi_imports_ok, multi_missing_modules) = run_python_import_check(
                 &multi_python,
                 LocalSegmentationEngine::MultiAligner.required_import_modules(),
             );
+            emit_readiness_progress(&app_handle, 60, "Validating Muaalem Local packages...");
             let (muaalem_imports_ok, muaalem_missing_modules) = run_python_import_check(
                 &muaalem_python,
                 LocalSegmentationEngine::MuaalemLocal.required_import_modules(),
             );
+            emit_readiness_progress(&app_handle, 70, "Validating Surah Splitter packages...");
             let (surah_splitter_imports_ok, surah_splitter_missing_modules) =
                 run_python_import_check(
                     &surah_splitter_python,
                     LocalSegmentationEngine::SurahSplitter.required_import_modules(),
                 );
+            emit_readiness_progress(&app_handle, 78, "Checking Multi-Aligner phonemizer...");
             let multi_phonemizer_ok = run_python_any_import_check(
                 &multi_python,
                 &["core.phonemizer", "quranic_phonemizer"],
@@ -327,19 +473,33 @@ pub async fn check_local_segmentation_ready(
             let multi_data_error = resolve_multi_aligner_data_dir(&app_handle)
                 .ok()
                 .and_then(|data_dir| {
-                    for (file_name, _) in required_multi_aligner_data_files() {
-                        let file_path = data_dir.join(file_name);
-                        if let Err(error) = validate_multi_aligner_data_file(&file_path) {
+                    let data_files = required_multi_aligner_data_files();
+                    for (index, data_file) in data_files.iter().enumerate() {
+                        emit_readiness_progress(
+                            &app_handle,
+                            80 + (20 * index / data_files.len()) as u32,
+                            &format!(
+                                "Validating Multi-Aligner data files ({}/{})...",
+                                index + 1,
+                                data_files.len()
+                            ),
+                        );
+                        let file_path = data_dir.join(data_file.name);
+                        if let Err(error) = verify_multi_aligner_data_file(&file_path) {
                             return Some(error);
                         }
                     }
                     None
                 });
 
-            let legacy_packages = legacy_imports_ok && legacy_versions_ok;
-            let multi_packages = multi_imports_ok && multi_phonemizer_ok && multi_data_error.is_none();
-            let muaalem_packages = muaalem_imports_ok;
-            let surah_splitter_packages = surah_splitter_imports_ok;
+            emit_readiness_progress(&app_handle, 100, "Readiness check complete");
+            let legacy_packages = legacy_imports_ok && !legacy_incomplete;
+            let multi_packages = multi_imports_ok
+                && multi_phonemizer_ok
+                && multi_data_error.is_none()
+                && !multi_incomplete;
+            let muaalem_packages = muaalem_imports_ok && !muaalem_incomplete;
+            let surah_splitter_packages = surah_splitter_imports_ok && !surah_splitter_incomplete;
             let legacy_ready = legacy_venv_exists && legacy_packages;
             let multi_ready = multi_venv_exists && multi_packages;
             let muaalem_ready = muaalem_venv_exists && muaalem_packages;
@@ -357,9 +517,9 @@ pub async fn check_local_segmentation_ready(
                 "Local engines need setup or a Hugging Face token with private model access for Multi-Aligner.".to_string()
             };
 
-            serde_json::json!({
+            let mut result = serde_json::json!({
                 "ready": any_ready,
-                "pythonInstalled": true,
+                "pythonInstalled": python_installed,
                 "packagesInstalled": legacy_ready || multi_ready || muaalem_ready || surah_splitter_ready,
                 "message": overall_message,
                 "engines": {
@@ -372,16 +532,13 @@ pub async fn check_local_segmentation_ready(
                             "Legacy Whisper local engine is ready".to_string()
                         } else if !legacy_venv_exists {
                             "Legacy Whisper dependencies are not installed".to_string()
+                        } else if legacy_incomplete {
+                            "Legacy Whisper installation was interrupted; reinstall dependencies".to_string()
                         } else if !legacy_missing_modules.is_empty() {
                             format!(
                                 "Legacy Whisper packages are incomplete (missing imports: {})",
                                 legacy_missing_modules.join(", ")
                             )
-                        } else if let Some(version_message) = legacy_versions_message {
-                            format!(
-                                "Legacy Whisper packages need reinstall ({})",
-                                version_message
-                            )
                         } else {
                             "Legacy Whisper packages are incomplete".to_string()
                         }
@@ -397,6 +554,8 @@ pub async fn check_local_segmentation_ready(
                             "Multi-Aligner local engine is ready".to_string()
                         } else if !multi_venv_exists {
                             "Multi-Aligner dependencies are not installed".to_string()
+                        } else if multi_incomplete {
+                            "Multi-Aligner installation was interrupted; reinstall dependencies".to_string()
                         } else if !multi_imports_ok {
                             if !multi_missing_modules.is_empty() {
                                 format!(
@@ -425,6 +584,8 @@ pub async fn check_local_segmentation_ready(
                             "Muaalem Local local engine is ready".to_string()
                         } else if !muaalem_venv_exists {
                             "Muaalem Local dependencies are not installed".to_string()
+                        } else if muaalem_incomplete {
+                            "Muaalem Local installation was interrupted; reinstall dependencies".to_string()
                         } else if !muaalem_missing_modules.is_empty() {
                             format!(
                                 "Muaalem Local packages are incomplete (missing imports: {})",
@@ -443,6 +604,8 @@ pub async fn check_local_segmentation_ready(
                             "Surah Splitter local engine is ready".to_string()
                         } else if !surah_splitter_venv_exists {
                             "Surah Splitter dependencies are not installed".to_string()
+                        } else if surah_splitter_incomplete {
+                            "Surah Splitter installation was interrupted; reinstall dependencies".to_string()
                         } else if !surah_splitter_missing_modules.is_empty() {
                             format!(
                                 "Surah Splitter packages are incomplete (missing imports: {})",
@@ -453,38 +616,84 @@ pub async fn check_local_segmentation_ready(
                         }
                     }
                 }
-            })
+            });
+
+            // Un venv casse ou reste dans un ancien dossier se repare autrement qu'une
+            // installation manquante : le signaler avec la commande adaptee.
+            let app_data_dir = app_handle.path().app_data_dir().ok();
+            for (engine, venv_dir) in [
+                (LocalSegmentationEngine::LegacyWhisper, &legacy_venv),
+                (LocalSegmentationEngine::MultiAligner, &multi_venv),
+                (LocalSegmentationEngine::MuaalemLocal, &muaalem_venv),
+                (LocalSegmentationEngine::SurahSplitter, &surah_splitter_venv),
+            ] {
+                let engine_status = &mut result["engines"][readiness_engine_key(engine)];
+                let issue = match &app_data_dir {
+                    Some(app_data_dir) if !engine_status["ready"].as_bool().unwrap_or(false) => {
+                        diagnose_engine_venv(app_data_dir, engine, venv_dir)
+                    }
+                    _ => None,
+                };
+                apply_engine_state(engine_status, engine, issue);
+
+                // Des versions differentes des pins restent utilisables : simple avertissement.
+                let mismatches = pinned_version_mismatches(venv_dir, engine);
+                engine_status["pinWarning"] = if mismatches.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    serde_json::json!(format!(
+                        "{} package versions differ from the tested pins; reinstall dependencies if segmentation fails",
+                        engine.as_label()
+                    ))
+                };
+                engine_status["pinMismatches"] = serde_json::json!(mismatches);
+            }
+            result
         }),
     )
     .await;
 
+    let python_interpreter = selected_python
+        .lock()
+        .ok()
+        .and_then(|selected| selected.as_ref().map(|interpreter| interpreter.to_json()));
     match check_result {
-        Ok(Ok(result)) => Ok(result),
+        Ok(Ok(mut result)) => {
+            result["managedPythonAvailable"] = serde_json::json!(managed_python_available);
+            result["pythonInterpreter"] = serde_json::json!(python_interpreter);
+            Ok((result, true))
+        }
         Ok(Err(e)) => Err(format!("Task failed: {}", e)),
-        Err(_) => Ok(serde_json::json!({
-            "ready": false,
-            "pythonInstalled": true,
-            "packagesInstalled": false,
-            "message": "Check timed out - packages may need to be installed",
-            "engines": {
-                "legacy": {
-                    "ready": false, "venvExists": false, "packagesInstalled": false,
-                    "usable": false, "message": "Check timed out"
-                },
-                "multi": {
-                    "ready": false, "venvExists": false, "packagesInstalled": false,
-                    "tokenRequired": true, "tokenProvided": token_provided, "usable": false,
-                    "message": "Check timed out"
-                },
-                "muaalem": {
-                    "ready": false, "venvExists": false, "packagesInstalled": false,
-                    "usable": false, "message": "Check timed out"
-                },
-                "surahSplitter": {
-                    "ready": false, "venvExists": false, "packagesInstalled": false,
-                    "usable": false, "message": "Check timed out"
+        // Sans interpréteur retenu, la recherche du Python système n'a pas abouti : inconnu.
+        Err(_) => Ok((
+            serde_json::json!({
+                "ready": false,
+                "pythonInstalled": python_interpreter.is_some().then_some(true),
+                "managedPythonAvailable": managed_python_available,
+                "pythonInterpreter": python_interpreter,
+                "packagesInstalled": false,
+                "message": "Check timed out - packages may need to be installed",
+                "engines": {
+                    "legacy": {
+                        "ready": false, "venvExists": false, "packagesInstalled": false,
+                        "usable": false, "message": "Check timed out"
+                    },
+                    "multi": {
+                        "ready": false, "venvExists": false, "packagesInstalled": false,
+                        "tokenRequired": true, "tokenProvided": token_provided, "usable": false,
+                        "message": "Check timed out"
+                    },
+                    "muaalem": {
+                        "ready": false, "venvExists": false, "packagesInstalled": false,
+                        "usable": false, "message": "Check timed out"
+                    },
+                    "surahSplitter": {
+                        "ready": false, "venvExists": false, "packagesInstalled": false,
+                        "usable": false, "message": "Check timed out"
+                    }
                 }
-            }
-        })),
+            }),
+            false,
+        )),
     }
 }
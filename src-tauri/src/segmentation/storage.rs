@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::data_files::{required_multi_aligner_data_files, resolve_multi_aligner_data_dir};
+use super::model_cache::{hf_hub_cache_dirs, quran_model_repo_dirs};
+use super::python_env::get_engine_venv_path;
+use super::types::LocalSegmentationEngine;
+
+/// Budget de parcours disque d'un calcul complet (tous dossiers confondus).
+const FULL_SCAN_BUDGET: Duration = Duration::from_secs(30);
+/// Budget de parcours disque du mode rapide (tailles approximatives).
+const FAST_SCAN_BUDGET: Duration = Duration::from_secs(2);
+
+/// Espace disque occupé par l'environnement Python d'un moteur local.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalEngineStorage {
+    /// Clé du moteur (`legacy`, `multi`, ...).
+    pub engine: String,
+    /// Libellé du moteur.
+    pub label: String,
+    /// Dossier du venv.
+    pub venv_path: String,
+    /// `true` si le venv existe.
+    pub exists: bool,
+    /// Taille du venv en octets.
+    pub size_bytes: u64,
+    /// `true` si le parcours a été interrompu (taille sous-estimée).
+    pub approximate: bool,
+}
+
+/// Espace disque occupé par la segmentation locale.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalEngineStorageInfo {
+    /// Environnements Python, un par moteur.
+    pub engines: Vec<LocalEngineStorage>,
+    /// Taille cumulée des fichiers data Multi-Aligner présents.
+    pub multi_aligner_data_bytes: u64,
+    /// Dossier du cache hub Hugging Face résolu.
+    pub model_cache_dir: Option<String>,
+    /// Estimation de la taille des modèles Quran du cache Hugging Face actif.
+    pub model_cache_bytes: u64,
+    /// Cache hub par défaut, quand un dossier personnalisé est actif.
+    pub default_model_cache_dir: Option<String>,
+    /// Estimation de la taille des modèles Quran restés dans le cache par défaut.
+    pub default_model_cache_bytes: u64,
+    /// Somme des tailles ci-dessus.
+    pub total_bytes: u64,
+    /// `true` si au moins une taille est sous-estimée (budget de parcours atteint).
+    pub approximate: bool,
+}
+
+/// Parcours disque borné dans le temps, partagé entre tous les dossiers mesurés.
+pub(super) struct SizeScan {
+    deadline: Instant,
+    pub(super) truncated: bool,
+}
+
+impl SizeScan {
+    pub(super) fn new(budget: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + budget,
+            truncated: false,
+        }
+    }
+
+    /// Taille d'un dossier (liens symboliques non suivis), interrompue à l'échéance.
+    fn directory_size(&mut self, path: &Path) -> u64 {
+        let Ok(entries) = fs::read_dir(path) else {
+            return 0;
+        };
+        let mut size = 0u64;
+        for entry in entries.flatten() {
+            if Instant::now() >= self.deadline {
+                self.truncated = true;
+                break;
+            }
+            let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if metadata.is_dir() {
+                size += self.directory_size(&entry.path());
+            } else if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+        size
+    }
+
+    /// Mesure un dossier et indique si la mesure a été interrompue.
+    pub(super) fn measure(&mut self, path: &Path) -> (u64, bool) {
+        let was_truncated = self.truncated;
+        self.truncated = false;
+        let size = self.directory_size(path);
+        let truncated = self.truncated;
+        self.truncated = was_truncated || truncated;
+        (size, truncated)
+    }
+}
+
+/// Retourne la taille disque des venvs locaux, des fichiers data et du cache de modèles.
+///
+/// Le calcul tourne sur un thread bloquant et reste borné dans le temps ; le mode
+/// `fast` réduit ce budget et renvoie des tailles approximatives sur les gros dossiers.
+///
+/// @param fast - Budget de parcours court (tailles marquées `approximate` si interrompues)
+pub async fn get_local_engine_storage_info(
+    app_handle: tauri::AppHandle,
+    fast: bool,
+) -> Result<LocalEngineStorageInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut scan = SizeScan::new(if fast {
+            FAST_SCAN_BUDGET
+        } else {
+            FULL_SCAN_BUDGET
+        });
+
+        let mut engines = Vec::new();
+        for engine in LocalSegmentationEngine::ALL {
+            let venv_dir = get_engine_venv_path(&app_handle, engine)?;
+            let exists = venv_dir.is_dir();
+            let (size_bytes, approximate) = if exists {
+                scan.measure(&venv_dir)
+            } else {
+                (0, false)
+            };
+            engines.push(LocalEngineStorage {
+                engine: engine.as_key().to_string(),
+                label: engine.as_label().to_string(),
+                venv_path: venv_dir.to_string_lossy().to_string(),
+                exists,
+                size_bytes,
+                approximate,
+            });
+        }
+
+        let multi_aligner_data_bytes = resolve_multi_aligner_data_dir(&app_handle)
+            .map(|data_dir| {
+                required_multi_aligner_data_files()
+                    .iter()
+                    .filter_map(|data_file| fs::metadata(data_dir.join(data_file.name)).ok())
+                    .map(|metadata| metadata.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        // Les fichiers réels du cache hub sont dans `blobs` (les snapshots sont des liens).
+        let mut cache_dirs = hf_hub_cache_dirs().into_iter();
+        let model_cache_dir = cache_dirs.next();
+        let default_model_cache_dir = cache_dirs.next();
+        let mut measure_models = |cache_dir: &Path| -> u64 {
+            quran_model_repo_dirs(cache_dir)
+                .iter()
+                .map(|(_, repo_dir)| scan.measure(&repo_dir.join("blobs")).0)
+                .sum()
+        };
+        let model_cache_bytes = model_cache_dir
+            .as_deref()
+            .map(&mut measure_models)
+            .unwrap_or(0);
+        let default_model_cache_bytes = default_model_cache_dir
+            .as_deref()
+            .map(&mut measure_models)
+            .unwrap_or(0);
+
+        let total_bytes = engines.iter().map(|engine| engine.size_bytes).sum::<u64>()
+            + multi_aligner_data_bytes
+            + model_cache_bytes
+            + default_model_cache_bytes;
+        Ok(LocalEngineStorageInfo {
+            engines,
+            multi_aligner_data_bytes,
+            model_cache_dir: model_cache_dir.map(|dir| dir.to_string_lossy().to_string()),
+            model_cache_bytes,
+            default_model_cache_dir: default_model_cache_dir
+                .map(|dir| dir.to_string_lossy().to_string()),
+            default_model_cache_bytes,
+            total_bytes,
+            approximate: scan.truncated,
+        })
+    })
+    .await
+    .map_err(|e| format!("Local engine storage scan task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SizeScan;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn size_scan_sums_nested_files_and_stops_at_the_deadline() {
+        let root =
+            std::env::temp_dir().join(format!("qurancaption-storage-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("lib").join("site-packages")).unwrap();
+        fs::write(root.join("pyvenv.cfg"), vec![0u8; 10]).unwrap();
+        fs::write(
+            root.join("lib").join("site-packages").join("torch.so"),
+            vec![0u8; 90],
+        )
+        .unwrap();
+
+        let mut scan = SizeScan::new(Duration::from_secs(30));
+        assert_eq!(scan.measure(&root), (100, false));
+        assert!(!scan.truncated);
+
+        let mut expired = SizeScan::new(Duration::ZERO);
+        assert_eq!(expired.measure(&root), (0, true));
+        assert!(expired.truncated);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
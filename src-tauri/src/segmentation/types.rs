@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 /// URL racine de l'API Gradio Quran Multi-Aligner.
 pub const QURAN_MULTI_ALIGNER_BASE_URL: &str =
     "https://hetchyy-quranic-universal-aligner.hf.space/gradio_api";
@@ -31,11 +33,15 @@ pub const QURAN_MULTI_ALIGNER_PRELOAD_AUDIO_RECITATIONS_CALL_URL: &str =
 /// Endpoint preload audio-only : URL audio directe d'un chapitre (sans segments).
 pub const QURAN_MULTI_ALIGNER_PRELOAD_AUDIO_CALL_URL: &str =
     "https://hetchyy-quranic-universal-aligner.hf.space/gradio_api/call/preload_audio";
+/// Racine de la Space Hugging Face (une requete GET suffit a la reveiller).
+pub const QURAN_MULTI_ALIGNER_SPACE_URL: &str =
+    "https://hetchyy-quranic-universal-aligner.hf.space/";
+/// API Hugging Face exposant l'etat d'execution (stage) de la Space.
+pub const QURAN_MULTI_ALIGNER_SPACE_RUNTIME_URL: &str =
+    "https://huggingface.co/api/spaces/hetchyy/quranic-universal-aligner/runtime";
 
-/// Flag de developpement pour forcer un payload mock au lieu d'appeler le cloud.
-pub const QURAN_SEGMENTATION_USE_MOCK: bool = false;
-
-/// Payload mock utilise quand `QURAN_SEGMENTATION_USE_MOCK` est active.
+/// Payload mock integre, renvoye quand le mode mock est actif sans fichier dedie
+/// (voir `QC_SEGMENTATION_MOCK` et la commande `set_segmentation_mock`).
 pub const QURAN_SEGMENTATION_MOCK_PAYLOAD: &str = r#"
 {
     "segments": [
@@ -54,7 +60,7 @@ pub const QURAN_SEGMENTATION_MOCK_PAYLOAD: &str = r#"
 "#;
 
 /// Clip audio transmis par le frontend pour une segmentation avec merge temporel.
-#[derive(serde::Deserialize)]
+#[derive(Clone, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SegmentationAudioClip {
     /// Chemin du fichier audio.
@@ -65,6 +71,31 @@ pub struct SegmentationAudioClip {
     pub end_ms: i64,
 }
 
+/// Resultat de segmentation d'un morceau d'un audio long, a fusionner avec les autres.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationChunkResult {
+    /// Position du morceau dans l'audio d'origine en millisecondes.
+    pub offset_ms: u64,
+    /// Payload de segmentation du morceau (temps relatifs au morceau).
+    pub payload: serde_json::Value,
+}
+
+/// Reglages du pre-traitement ffmpeg avant segmentation (tous optionnels).
+///
+/// Les valeurs absentes gardent les defauts du chemin : cloud = canaux et frequence
+/// d'origine, Opus 64 kbit/s ; local = mono 16 kHz en WAV PCM.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioPreprocessOptions {
+    /// Mixe l'audio en mono (`false` garde les canaux d'origine).
+    pub mono: Option<bool>,
+    /// Frequence d'echantillonnage de sortie (Hz).
+    pub sample_rate: Option<u32>,
+    /// Debit Opus de l'upload cloud (kbit/s) ; sans effet en local (WAV PCM).
+    pub bitrate: Option<u32>,
+}
+
 /// Segment audio a dupliquer pour generer une piste Hifz.
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -79,8 +110,63 @@ pub struct HifzAudioSegment {
     pub silence_between_repetitions_ms: Option<i64>,
 }
 
+/// Parametres de segmentation d'un element de lot (memes options que les commandes unitaires).
+#[derive(Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SegmentationBatchParams {
+    /// Silence minimal (ms) pour couper un segment.
+    pub min_silence_ms: Option<u32>,
+    /// Duree de parole minimale (ms) d'un segment.
+    pub min_speech_ms: Option<u32>,
+    /// Marge (ms) ajoutee autour des segments.
+    pub pad_ms: Option<u32>,
+    /// Modele du moteur (taille Whisper pour le moteur legacy).
+    pub model_name: Option<String>,
+    /// Device `GPU`, `CPU` ou `MPS`.
+    pub device: Option<String>,
+    /// Sourate connue (aussi utilisee comme sourate cible par Surah Splitter).
+    pub surah_hint: Option<u32>,
+    /// Plage de versets connue.
+    pub ayah_range_hint: Option<(u32, u32)>,
+    /// Debut de la plage audio a segmenter (ms).
+    pub range_start_ms: Option<u64>,
+    /// Fin de la plage audio a segmenter (ms).
+    pub range_end_ms: Option<u64>,
+    /// Confiance minimale des segments gardes.
+    pub min_confidence: Option<f64>,
+    /// Marque les segments sous le seuil au lieu de les retirer.
+    pub mark_low_confidence: Option<bool>,
+    /// Nombre de threads CPU du processus local.
+    pub cpu_threads: Option<u32>,
+    /// Priorite reduite du processus local.
+    pub background: Option<bool>,
+    /// Duree maximale d'un fichier (minutes).
+    pub timeout_minutes: Option<u64>,
+    /// Silence avant avertissement (minutes).
+    pub stall_warning_minutes: Option<u64>,
+    /// Silence avant arret (minutes).
+    pub stall_timeout_minutes: Option<u64>,
+    /// Demande les horodatages mot a mot (meme option que les commandes de segmentation).
+    pub include_wbw_timestamps: Option<bool>,
+    /// Pre-traitement ffmpeg de l'audio.
+    pub preprocess: Option<AudioPreprocessOptions>,
+}
+
+/// Element d'un lot de segmentation (un fichier audio, ex. un episode).
+#[derive(Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationBatchItem {
+    /// Identifiant choisi par le frontend, repris dans les evenements et le resultat.
+    pub id: String,
+    /// Chemin du fichier audio.
+    pub audio_path: String,
+    /// Parametres propres a cet element.
+    #[serde(default)]
+    pub params: SegmentationBatchParams,
+}
+
 /// Moteur de segmentation locale supporte.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LocalSegmentationEngine {
     /// Moteur historique base sur Whisper.
     LegacyWhisper,
@@ -93,6 +179,14 @@ pub enum LocalSegmentationEngine {
 }
 
 impl LocalSegmentationEngine {
+    /// Tous les moteurs locaux, dans l'ordre d'affichage.
+    pub const ALL: [Self; 4] = [
+        Self::LegacyWhisper,
+        Self::MultiAligner,
+        Self::MuaalemLocal,
+        Self::SurahSplitter,
+    ];
+
     /// Construit le moteur depuis la valeur brute recue du frontend.
     pub fn from_raw(raw: &str) -> Result<Self, String> {
         match raw {
@@ -191,3 +285,487 @@ impl LocalSegmentationEngine {
         }
     }
 }
+
+/// Nombre de versets de chaque sourate (index 0 = sourate 1).
+const SURAH_AYAH_COUNTS: [u32; 114] = [
+    7, 286, 200, 176, 120, 165, 206, 75, 129, 109, 123, 111, 43, 52, 99, 128, 111, 110, 98, 135,
+    112, 78, 118, 64, 77, 227, 93, 88, 69, 60, 34, 30, 73, 54, 45, 83, 182, 88, 75, 85, 54, 53, 89,
+    59, 37, 35, 38, 29, 18, 45, 60, 49, 62, 55, 78, 96, 29, 22, 24, 13, 14, 11, 11, 18, 12, 12, 30,
+    52, 52, 44, 28, 28, 20, 56, 40, 31, 50, 40, 46, 42, 29, 19, 36, 25, 22, 17, 19, 26, 30, 20, 15,
+    21, 11, 8, 8, 19, 5, 8, 8, 11, 11, 8, 3, 9, 5, 4, 7, 3, 6, 3, 5, 4, 5, 6,
+];
+
+/// Contrainte sourate/versets connue de l'utilisateur, transmise a l'aligneur.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SegmentationHint {
+    /// Numero de sourate (1-114).
+    pub surah: u32,
+    /// Plage de versets `(debut, fin)` incluse, si connue.
+    pub ayah_range: Option<(u32, u32)>,
+}
+
+impl SegmentationHint {
+    /// Valide les indices bruts recus du frontend.
+    ///
+    /// Retourne `None` si aucun indice n'est fourni. Une plage de versets sans sourate est refusee.
+    pub fn from_raw(
+        surah_hint: Option<u32>,
+        ayah_range_hint: Option<(u32, u32)>,
+    ) -> Result<Option<Self>, String> {
+        let Some(surah) = surah_hint else {
+            return match ayah_range_hint {
+                Some(_) => Err("ayah_range_hint requires surah_hint.".to_string()),
+                None => Ok(None),
+            };
+        };
+        if !(1..=114).contains(&surah) {
+            return Err(format!(
+                "Invalid surah_hint '{}'. Expected a value between 1 and 114.",
+                surah
+            ));
+        }
+
+        if let Some((from, to)) = ayah_range_hint {
+            let ayah_count = SURAH_AYAH_COUNTS[(surah - 1) as usize];
+            if from == 0 || from > to || to > ayah_count {
+                return Err(format!(
+                    "Invalid ayah_range_hint {}-{} for surah {}. Expected 1 <= start <= end <= {}.",
+                    from, to, surah, ayah_count
+                ));
+            }
+        }
+
+        Ok(Some(Self {
+            surah,
+            ayah_range: ayah_range_hint,
+        }))
+    }
+
+    /// Arguments CLI equivalents pour les scripts Python locaux.
+    pub fn to_cli_args(self) -> Vec<String> {
+        let mut args = vec!["--surah-hint".to_string(), self.surah.to_string()];
+        if let Some((from, to)) = self.ayah_range {
+            args.push("--ayah-range-hint".to_string());
+            args.push(format!("{}-{}", from, to));
+        }
+        args
+    }
+
+    /// Recopie l'indice dans le payload de resultat (`hint`) pour affichage cote frontend.
+    pub fn attach_to_result(self, result: &mut serde_json::Value) {
+        if let Some(object) = result.as_object_mut() {
+            object.insert(
+                "hint".to_string(),
+                serde_json::json!({
+                    "surah": self.surah,
+                    "ayahFrom": self.ayah_range.map(|(from, _)| from),
+                    "ayahTo": self.ayah_range.map(|(_, to)| to),
+                }),
+            );
+        }
+    }
+}
+
+/// Plage temporelle (ms) de l'audio a segmenter ; le reste de l'audio est ignore.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SegmentationRange {
+    /// Debut de la plage en millisecondes.
+    pub start_ms: u64,
+    /// Fin de la plage en millisecondes (fin de l'audio si absente).
+    pub end_ms: Option<u64>,
+}
+
+impl SegmentationRange {
+    /// Valide la plage brute recue du frontend.
+    ///
+    /// Retourne `None` si la plage couvre tout l'audio. Une fin avant le debut est refusee.
+    pub fn from_raw(
+        range_start_ms: Option<u64>,
+        range_end_ms: Option<u64>,
+    ) -> Result<Option<Self>, String> {
+        let start_ms = range_start_ms.unwrap_or(0);
+        if let Some(end_ms) = range_end_ms {
+            if end_ms <= start_ms {
+                return Err(format!(
+                    "Invalid range {}-{} ms. range_end_ms must be greater than range_start_ms.",
+                    start_ms, end_ms
+                ));
+            }
+        }
+        if start_ms == 0 && range_end_ms.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            start_ms,
+            end_ms: range_end_ms,
+        }))
+    }
+
+    /// Duree de la plage en ms, si sa fin est connue.
+    pub fn duration_ms(self) -> Option<u64> {
+        self.end_ms.map(|end_ms| end_ms - self.start_ms)
+    }
+
+    /// Arguments ffmpeg a placer avant `-i` (seek d'entree rapide).
+    pub fn to_ffmpeg_input_args(self) -> Vec<String> {
+        if self.start_ms == 0 {
+            return Vec::new();
+        }
+        vec![
+            "-ss".to_string(),
+            format!("{:.3}", self.start_ms as f64 / 1000.0),
+        ]
+    }
+
+    /// Arguments ffmpeg a placer apres `-i` (duree conservee depuis le point de seek).
+    pub fn to_ffmpeg_output_args(self) -> Vec<String> {
+        match self.duration_ms() {
+            Some(duration_ms) => vec![
+                "-t".to_string(),
+                format!("{:.3}", duration_ms as f64 / 1000.0),
+            ],
+            None => Vec::new(),
+        }
+    }
+
+    /// Recopie la plage dans le payload de resultat (`range`) pour le frontend.
+    pub fn attach_to_result(self, result: &mut serde_json::Value) {
+        if let Some(object) = result.as_object_mut() {
+            object.insert(
+                "range".to_string(),
+                serde_json::json!({
+                    "startMs": self.start_ms,
+                    "endMs": self.end_ms,
+                }),
+            );
+        }
+    }
+}
+
+/// Seuil de confiance applique aux segments valides (cloud et local).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfidenceFilter {
+    /// Confiance minimale (0 a 1) pour garder un segment tel quel.
+    pub min_confidence: f64,
+    /// Marque les segments sous le seuil (`low_confidence`) au lieu de les retirer.
+    pub mark_only: bool,
+}
+
+impl ConfidenceFilter {
+    /// Valide le seuil brut recu du frontend ; `None` si aucun filtrage n'est demande.
+    pub fn from_raw(
+        min_confidence: Option<f64>,
+        mark_low_confidence: Option<bool>,
+    ) -> Result<Option<Self>, String> {
+        let Some(min_confidence) = min_confidence else {
+            return Ok(None);
+        };
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(format!(
+                "Invalid min_confidence {}. Expected a value between 0 and 1.",
+                min_confidence
+            ));
+        }
+        Ok(Some(Self {
+            min_confidence,
+            mark_only: mark_low_confidence.unwrap_or(false),
+        }))
+    }
+}
+
+/// `true` sur Apple Silicon, ou torch utilise le backend MPS a la place de CUDA.
+pub const IS_APPLE_SILICON: bool = cfg!(all(target_os = "macos", target_arch = "aarch64"));
+
+/// Valide le device demande (`GPU` par defaut, `CPU` ou `MPS`).
+///
+/// Sur Apple Silicon, `GPU` designe le backend MPS ; `MPS` est refuse sur les autres machines.
+pub fn resolve_compute_device(device: Option<String>) -> Result<String, String> {
+    normalize_compute_device(device, IS_APPLE_SILICON)
+}
+
+/// Corps de `resolve_compute_device`, la plateforme etant passee en parametre.
+pub(crate) fn normalize_compute_device(
+    device: Option<String>,
+    apple_silicon: bool,
+) -> Result<String, String> {
+    let selected = device
+        .unwrap_or_else(|| "GPU".to_string())
+        .trim()
+        .to_uppercase();
+    match selected.as_str() {
+        "CPU" => Ok(selected),
+        "GPU" | "MPS" if apple_silicon => Ok("MPS".to_string()),
+        "GPU" => Ok(selected),
+        "MPS" => Err(
+            "Invalid device 'MPS'. The MPS backend is only available on Apple Silicon Macs."
+                .to_string(),
+        ),
+        _ => Err(format!(
+            "Invalid device '{}'. Expected 'GPU', 'CPU' or 'MPS'.",
+            selected
+        )),
+    }
+}
+
+/// Bilan du filtrage par confiance, joint au resultat de segmentation.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct ConfidenceFilterSummary {
+    /// Seuil applique.
+    pub min_confidence: f64,
+    /// Segments retires car sous le seuil.
+    pub dropped: usize,
+    /// Segments gardes mais marques `low_confidence`.
+    pub flagged: usize,
+}
+
+/// Variables d'environnement limitant les threads des bibliotheques de calcul (torch, BLAS).
+const THREAD_LIMIT_ENV_VARS: [&str; 5] = [
+    "OMP_NUM_THREADS",
+    "MKL_NUM_THREADS",
+    "OPENBLAS_NUM_THREADS",
+    "NUMEXPR_NUM_THREADS",
+    "VECLIB_MAXIMUM_THREADS",
+];
+
+/// Limites de ressources du processus Python d'une segmentation locale.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LocalProcessLimits {
+    /// Nombre de threads CPU autorises (tous les coeurs si absent).
+    pub cpu_threads: Option<usize>,
+    /// Lance le processus en priorite reduite.
+    pub background: bool,
+}
+
+impl LocalProcessLimits {
+    /// Valide les limites brutes recues du frontend.
+    ///
+    /// `cpu_threads` doit etre compris entre 1 et `available_cores` ; une valeur egale au
+    /// nombre de coeurs revient au comportement par defaut (aucune limite).
+    pub fn from_raw(
+        cpu_threads: Option<u32>,
+        background: Option<bool>,
+        available_cores: usize,
+    ) -> Result<Self, String> {
+        let cpu_threads = match cpu_threads {
+            None => None,
+            Some(threads) if threads == 0 || threads as usize > available_cores => {
+                return Err(format!(
+                    "Invalid cpu_threads {}. Expected a value between 1 and {}.",
+                    threads, available_cores
+                ));
+            }
+            Some(threads) => Some(threads as usize).filter(|threads| *threads < available_cores),
+        };
+        Ok(Self {
+            cpu_threads,
+            background: background.unwrap_or(false),
+        })
+    }
+
+    /// Variables d'environnement a definir sur la commande Python.
+    pub fn env_vars(self) -> Vec<(&'static str, String)> {
+        match self.cpu_threads {
+            Some(threads) => THREAD_LIMIT_ENV_VARS
+                .iter()
+                .map(|name| (*name, threads.to_string()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Statut de fin d'un script Python local (processus dedie ou requete du worker).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScriptExit {
+    /// Code de sortie (absent si le processus a ete tue par un signal).
+    pub code: Option<i32>,
+    /// Signal Unix ayant termine le processus.
+    pub signal: Option<i32>,
+    /// PID du processus Python.
+    pub pid: Option<u32>,
+}
+
+impl ScriptExit {
+    /// Construit le statut d'un processus termine.
+    pub fn from_status(status: std::process::ExitStatus, pid: u32) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+        Self {
+            code: status.code(),
+            signal,
+            pid: Some(pid),
+        }
+    }
+
+    /// Indique si le script s'est termine avec succes.
+    pub fn success(self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Delai de silence (ni STATUS ni stderr) avant un avertissement, par defaut.
+const DEFAULT_STALL_WARNING_MINUTES: u64 = 5;
+/// Delai de silence avant l'arret du processus, par defaut (telechargements de modeles compris).
+const DEFAULT_STALL_TIMEOUT_MINUTES: u64 = 30;
+/// Part fixe du delai global par defaut (chargement/telechargement des modeles).
+const LOCAL_TIMEOUT_BASE_MINUTES: f64 = 20.0;
+/// Delai global par defaut par seconde d'audio, sur GPU puis sur CPU.
+const LOCAL_TIMEOUT_FACTOR_GPU: f64 = 2.0;
+const LOCAL_TIMEOUT_FACTOR_CPU: f64 = 6.0;
+
+/// Surcharges des delais de surveillance d'une segmentation locale (minutes).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LocalRunTimeouts {
+    /// Duree maximale de l'execution Python (derivee de la duree audio si absente).
+    pub timeout_minutes: Option<u64>,
+    /// Silence avant l'emission d'un statut d'avertissement.
+    pub stall_warning_minutes: Option<u64>,
+    /// Silence avant l'arret du processus.
+    pub stall_timeout_minutes: Option<u64>,
+}
+
+/// Delais effectifs d'une segmentation locale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalRunDeadlines {
+    /// Duree maximale (aucune si la duree audio est inconnue et sans surcharge).
+    pub timeout: Option<Duration>,
+    /// Silence avant avertissement (aucun s'il depasse `stall_timeout`).
+    pub stall_warning: Option<Duration>,
+    /// Silence avant arret.
+    pub stall_timeout: Duration,
+}
+
+impl LocalRunTimeouts {
+    /// Valide les delais bruts recus du frontend (minutes, au moins 1).
+    pub fn from_raw(
+        timeout_minutes: Option<u64>,
+        stall_warning_minutes: Option<u64>,
+        stall_timeout_minutes: Option<u64>,
+    ) -> Result<Self, String> {
+        for (name, value) in [
+            ("timeout_minutes", timeout_minutes),
+            ("stall_warning_minutes", stall_warning_minutes),
+            ("stall_timeout_minutes", stall_timeout_minutes),
+        ] {
+            if value == Some(0) {
+                return Err(format!("{} must be at least 1", name));
+            }
+        }
+        if let (Some(warning), Some(stall)) = (stall_warning_minutes, stall_timeout_minutes) {
+            if warning >= stall {
+                return Err(format!(
+                    "stall_warning_minutes ({}) must be lower than stall_timeout_minutes ({})",
+                    warning, stall
+                ));
+            }
+        }
+        Ok(Self {
+            timeout_minutes,
+            stall_warning_minutes,
+            stall_timeout_minutes,
+        })
+    }
+
+    /// Calcule les delais effectifs.
+    ///
+    /// Sans surcharge, le delai global vaut une part fixe plus la duree audio multipliee
+    /// par un facteur dependant du device (`GPU`/`MPS` ou `CPU`).
+    pub fn resolve(self, audio_duration_s: f64, device: &str) -> LocalRunDeadlines {
+        let minutes = |value: u64| Duration::from_secs(value.saturating_mul(60));
+        let factor = if device.eq_ignore_ascii_case("GPU") || device.eq_ignore_ascii_case("MPS") {
+            LOCAL_TIMEOUT_FACTOR_GPU
+        } else {
+            LOCAL_TIMEOUT_FACTOR_CPU
+        };
+        let timeout = match self.timeout_minutes {
+            Some(value) => Some(minutes(value)),
+            None => (audio_duration_s > 0.0).then(|| {
+                Duration::from_secs_f64(
+                    LOCAL_TIMEOUT_BASE_MINUTES * 60.0 + audio_duration_s * factor,
+                )
+            }),
+        };
+        let stall_timeout = minutes(
+            self.stall_timeout_minutes
+                .unwrap_or(DEFAULT_STALL_TIMEOUT_MINUTES),
+        );
+        let stall_warning = Some(minutes(
+            self.stall_warning_minutes
+                .unwrap_or(DEFAULT_STALL_WARNING_MINUTES),
+        ))
+        .filter(|warning| *warning < stall_timeout);
+        LocalRunDeadlines {
+            timeout,
+            stall_warning,
+            stall_timeout,
+        }
+    }
+}
+
+/// Horodatage valide d'un mot (temps absolus en ms sur l'audio segmente).
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SegmentationWord {
+    /// Texte du mot.
+    pub text: String,
+    /// Debut du mot en millisecondes.
+    pub start_ms: i64,
+    /// Fin du mot en millisecondes.
+    pub end_ms: i64,
+    /// Champs restants (`location`, `word`, `start`/`end` relatifs au segment en secondes).
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Segment de segmentation valide (temps en secondes), autres champs conserves tels quels.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SegmentationSegment {
+    /// Debut du segment en secondes.
+    pub time_from: f64,
+    /// Fin du segment en secondes.
+    pub time_to: f64,
+    /// Horodatages mot a mot, bornes au segment et monotones (absent si non fournis).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<SegmentationWord>>,
+    /// Champs restants (`matched_text`, `ref_from`, `ref_to`, ...).
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Resultat de segmentation valide, renvoye au frontend a la place du JSON brut.
+#[derive(Debug, serde::Serialize)]
+pub struct SegmentationResult {
+    /// Segments valides, tries par temps de debut et sans chevauchement.
+    pub segments: Vec<SegmentationSegment>,
+    /// Avertissements sur les segments corriges ou ignores.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub validation_warnings: Vec<String>,
+    /// Bilan du filtrage par confiance, si un seuil a ete demande.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_filter: Option<ConfidenceFilterSummary>,
+    /// Segments sous le seuil de confiance (retires ou marques), si un seuil a ete demande.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_confidence_count: Option<usize>,
+    /// Champs restants du payload (`audio_id`, `warning`, ...).
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_compute_device;
+
+    #[test]
+    fn gpu_device_maps_to_mps_on_apple_silicon() {
+        let resolve = |device: &str, apple_silicon: bool| {
+            normalize_compute_device(Some(device.to_string()), apple_silicon)
+        };
+        assert_eq!(normalize_compute_device(None, false).unwrap(), "GPU");
+        assert_eq!(resolve("cpu", true).unwrap(), "CPU");
+        assert_eq!(resolve("GPU", true).unwrap(), "MPS");
+        assert_eq!(resolve("mps", true).unwrap(), "MPS");
+        assert!(resolve("MPS", false).is_err());
+        assert!(resolve("TPU", true).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::data_files::{required_multi_aligner_data_files, resolve_multi_aligner_data_dir};
+use super::local::with_engine_idle;
+use super::model_cache::directory_usage;
+use super::python_env::get_engine_venv_path;
+use super::status::invalidate_local_readiness_cache;
+use super::types::LocalSegmentationEngine;
+use super::worker::shutdown_worker_for_engine;
+
+/// Résultat de la désinstallation d'un moteur local.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalDepsUninstallReport {
+    /// Clé du moteur désinstallé (`legacy`, `multi`, ...).
+    pub engine: String,
+    /// `true` si un environnement virtuel a été supprimé.
+    pub venv_removed: bool,
+    /// Fichiers data Multi-Aligner supprimés.
+    pub removed_data_files: Vec<String>,
+    /// Espace disque libéré (octets).
+    pub freed_bytes: u64,
+}
+
+/// Supprime un dossier venv et retourne sa taille (0 s'il n'existe pas).
+fn remove_venv_dir(venv_dir: &Path) -> Result<u64, String> {
+    if !venv_dir.exists() {
+        return Ok(0);
+    }
+    let (size_bytes, _) = directory_usage(venv_dir);
+    fs::remove_dir_all(venv_dir).map_err(|e| {
+        format!(
+            "Failed to remove local environment '{}': {}",
+            venv_dir.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(size_bytes)
+}
+
+/// Supprime les fichiers data listés présents dans `data_dir`.
+pub(super) fn remove_listed_data_files(
+    data_dir: &Path,
+    file_names: &[&str],
+) -> Result<(Vec<String>, u64), String> {
+    let mut removed = Vec::new();
+    let mut freed_bytes = 0u64;
+    for file_name in file_names {
+        let file_path = data_dir.join(file_name);
+        let Ok(metadata) = fs::metadata(&file_path) else {
+            continue;
+        };
+        fs::remove_file(&file_path)
+            .map_err(|e| format!("Failed to remove data file '{}': {}", file_name, e))?;
+        freed_bytes += metadata.len();
+        removed.push(file_name.to_string());
+    }
+    Ok((removed, freed_bytes))
+}
+
+/// Supprime l'environnement Python d'un moteur local (et ses fichiers data si demandé).
+///
+/// Refusé tant qu'une segmentation utilise ce moteur ; le worker résident du moteur est
+/// arrêté avant la suppression.
+///
+/// @param engine - Moteur à désinstaller (`legacy`, `multi`, `muaalem`, `surah_splitter`)
+/// @param remove_data_files - Supprime aussi les fichiers data téléchargés (Multi-Aligner uniquement)
+pub async fn uninstall_local_segmentation_deps(
+    app_handle: tauri::AppHandle,
+    engine: String,
+    remove_data_files: bool,
+) -> Result<LocalDepsUninstallReport, String> {
+    let selected_engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        with_engine_idle(selected_engine, || {
+            let venv_dir = get_engine_venv_path(&app_handle, selected_engine)?;
+            shutdown_worker_for_engine(selected_engine)?;
+
+            let mut report = LocalDepsUninstallReport {
+                engine: selected_engine.as_key().to_string(),
+                venv_removed: venv_dir.exists(),
+                ..LocalDepsUninstallReport::default()
+            };
+            report.freed_bytes = remove_venv_dir(&venv_dir)?;
+
+            if remove_data_files && selected_engine == LocalSegmentationEngine::MultiAligner {
+                let data_dir = resolve_multi_aligner_data_dir(&app_handle)?;
+                let file_names: Vec<&str> = required_multi_aligner_data_files()
+                    .iter()
+                    .map(|data_file| data_file.name)
+                    .collect();
+                let (removed, freed_bytes) = remove_listed_data_files(&data_dir, &file_names)?;
+                report.removed_data_files = removed;
+                report.freed_bytes += freed_bytes;
+            }
+
+            log::info!(
+                "[segmentation][uninstall] engine={} venv_removed={} data_files={:?} freed_bytes={}",
+                report.engine, report.venv_removed, report.removed_data_files, report.freed_bytes
+            );
+            Ok(report)
+        })
+    })
+    .await
+    .map_err(|e| format!("Local engine uninstall task failed: {}", e))?;
+    // Une suppression partielle modifie aussi l'état : le prochain check doit être complet.
+    invalidate_local_readiness_cache();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{remove_listed_data_files, remove_venv_dir};
+    use std::fs;
+
+    #[test]
+    fn venv_and_data_files_are_removed_with_their_size() {
+        let root = std::env::temp_dir().join(format!(
+            "qurancaption-uninstall-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let venv_dir = root.join("seg-multi");
+        fs::create_dir_all(venv_dir.join("lib")).unwrap();
+        fs::write(venv_dir.join("lib").join("torch.so"), vec![0u8; 100]).unwrap();
+        fs::write(venv_dir.join("pyvenv.cfg"), vec![0u8; 20]).unwrap();
+        let data_dir = root.join("data");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("phoneme_index.pkl"), vec![0u8; 30]).unwrap();
+        fs::write(data_dir.join("README.md"), b"bundled").unwrap();
+
+        assert_eq!(remove_venv_dir(&venv_dir).unwrap(), 120);
+        assert!(!venv_dir.exists());
+        assert_eq!(remove_venv_dir(&venv_dir).unwrap(), 0);
+
+        let (removed, freed_bytes) =
+            remove_listed_data_files(&data_dir, &["phoneme_index.pkl", "missing.json"]).unwrap();
+        assert_eq!(removed, vec!["phoneme_index.pkl".to_string()]);
+        assert_eq!(freed_bytes, 30);
+        assert!(data_dir.join("README.md").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
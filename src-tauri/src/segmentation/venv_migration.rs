@@ -0,0 +1,369 @@
+//! Reprise des venvs restés dans le dossier de données d'une ancienne version de l'application.
+//!
+//! Quand l'identifiant de l'application change, le dossier de données change aussi et les
+//! venvs (plusieurs Go) ne sont plus trouvés. Ils sont déplacés vers le dossier courant au
+//! lieu d'être réinstallés.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use super::model_cache::directory_usage;
+use super::python_env::{
+    get_engine_venv_path, get_venv_python_exe, is_venv_install_incomplete, run_python_import_check,
+};
+use super::types::LocalSegmentationEngine;
+
+/// Résultat de la reprise du venv d'un moteur.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VenvMigrationReport {
+    /// Clé du moteur (`legacy`, `multi`, ...).
+    pub engine: String,
+    /// Ancien emplacement du venv.
+    pub source_path: String,
+    /// `true` si le venv a été déplacé dans le dossier courant.
+    pub moved: bool,
+    /// `true` si les modules du moteur s'importent depuis le venv déplacé.
+    pub verified: bool,
+    /// Modules introuvables après le déplacement.
+    pub missing_modules: Vec<String>,
+    /// Erreur éventuelle du déplacement.
+    pub error: Option<String>,
+}
+
+/// Normalise un nom de dossier pour la comparaison (minuscules, alphanumériques seuls).
+fn normalize_dir_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Indique si `dir` est le dossier de données d'une autre version de l'application.
+fn is_previous_app_data_dir(app_data_dir: &Path, dir: &Path) -> bool {
+    dir != app_data_dir
+        && dir.parent().is_some()
+        && dir.parent() == app_data_dir.parent()
+        && dir
+            .file_name()
+            .map(|name| normalize_dir_name(&name.to_string_lossy()).contains("qurancaption"))
+            .unwrap_or(false)
+}
+
+/// Dossiers de données des anciennes versions : dossiers voisins du dossier courant dont le
+/// nom désigne QuranCaption (`com.qurancaption`, `Quran Caption`, ...).
+pub(crate) fn previous_app_data_dirs(app_data_dir: &Path) -> Vec<PathBuf> {
+    let Some(parent) = app_data_dir.parent() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && is_previous_app_data_dir(app_data_dir, path))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Ancien dossier de données référencé par le `pyvenv.cfg` d'un venv (`home` ou
+/// `executable`).
+///
+/// Un venv créé avec le Python autonome d'une ancienne version pointe encore vers son
+/// dossier de données : il cesse de fonctionner dès que ce dossier est supprimé.
+pub(crate) fn pyvenv_cfg_previous_app_data_dir(
+    pyvenv_cfg: &str,
+    app_data_dir: &Path,
+) -> Option<PathBuf> {
+    pyvenv_cfg
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .filter(|(key, _)| matches!(key.trim(), "home" | "executable"))
+        .find_map(|(_, value)| {
+            Path::new(value.trim())
+                .ancestors()
+                .find(|dir| is_previous_app_data_dir(app_data_dir, dir))
+                .map(Path::to_path_buf)
+        })
+}
+
+/// Premier venv complet d'un moteur trouvé dans les anciens dossiers de données.
+pub(crate) fn find_previous_venv(
+    previous_dirs: &[PathBuf],
+    engine: LocalSegmentationEngine,
+) -> Option<PathBuf> {
+    previous_dirs
+        .iter()
+        .map(|dir| {
+            dir.join("python_envs")
+                .join(format!("seg-{}", engine.as_key()))
+        })
+        .find(|venv_dir| {
+            get_venv_python_exe(venv_dir).exists() && !is_venv_install_incomplete(venv_dir)
+        })
+}
+
+/// Copie récursive d'un dossier ; les liens symboliques (ex. `bin/python`) sont recréés.
+fn copy_dir_recursive(
+    source: &Path,
+    destination: &Path,
+    on_copied: &mut dyn FnMut(u64),
+) -> Result<(), String> {
+    fs::create_dir_all(destination).map_err(|e| {
+        format!(
+            "Failed to create '{}': {}",
+            destination.to_string_lossy(),
+            e
+        )
+    })?;
+    let entries = fs::read_dir(source)
+        .map_err(|e| format!("Failed to read '{}': {}", source.to_string_lossy(), e))?;
+    for entry in entries.flatten() {
+        let source_path = entry.path();
+        let destination_path = destination.join(entry.file_name());
+        let metadata = fs::symlink_metadata(&source_path)
+            .map_err(|e| format!("Failed to read '{}': {}", source_path.to_string_lossy(), e))?;
+        if metadata.is_dir() {
+            copy_dir_recursive(&source_path, &destination_path, on_copied)?;
+            continue;
+        }
+        #[cfg(unix)]
+        if metadata.file_type().is_symlink() {
+            let target = fs::read_link(&source_path).map_err(|e| e.to_string())?;
+            std::os::unix::fs::symlink(&target, &destination_path).map_err(|e| {
+                format!(
+                    "Failed to link '{}': {}",
+                    destination_path.to_string_lossy(),
+                    e
+                )
+            })?;
+            continue;
+        }
+        let copied = fs::copy(&source_path, &destination_path)
+            .map_err(|e| format!("Failed to copy '{}': {}", source_path.to_string_lossy(), e))?;
+        on_copied(copied);
+    }
+    Ok(())
+}
+
+/// Déplace un dossier : renommage, sinon copie puis suppression (autre volume).
+///
+/// Une copie partielle est supprimée en cas d'échec ; la source n'est retirée qu'après
+/// une copie complète.
+fn move_dir(
+    source: &Path,
+    destination: &Path,
+    on_copied: &mut dyn FnMut(u64),
+) -> Result<(), String> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+    if let Err(error) = copy_dir_recursive(source, destination, on_copied) {
+        let _ = fs::remove_dir_all(destination);
+        return Err(error);
+    }
+    if let Err(error) = fs::remove_dir_all(source) {
+        log::warn!(
+            "[segmentation][migration] copied venv but failed to remove '{}': {}",
+            source.to_string_lossy(),
+            error
+        );
+    }
+    Ok(())
+}
+
+/// Émet la progression de la reprise d'un venv sur `venv-migration-status`.
+fn emit_migration_status(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+    message: &str,
+    copied_bytes: u64,
+    total_bytes: u64,
+) {
+    let _ = app_handle.emit(
+        "venv-migration-status",
+        serde_json::json!({
+            "engine": engine.as_key(),
+            "message": message,
+            "copiedBytes": copied_bytes,
+            "totalBytes": total_bytes,
+        }),
+    );
+}
+
+/// Reprend un venv trouvé dans un ancien dossier de données et vérifie ses imports.
+fn migrate_engine_venv(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+    source: &Path,
+    destination: &Path,
+) -> VenvMigrationReport {
+    let mut report = VenvMigrationReport {
+        engine: engine.as_key().to_string(),
+        source_path: source.to_string_lossy().to_string(),
+        moved: false,
+        verified: false,
+        missing_modules: Vec::new(),
+        error: None,
+    };
+    let (total_bytes, _) = directory_usage(source);
+    emit_migration_status(
+        app_handle,
+        engine,
+        &format!("Moving existing {} environment...", engine.as_label()),
+        0,
+        total_bytes,
+    );
+    let mut copied_bytes = 0u64;
+    let mut last_emit = Instant::now();
+    let moved = move_dir(source, destination, &mut |delta| {
+        copied_bytes += delta;
+        if last_emit.elapsed() >= Duration::from_millis(250) {
+            last_emit = Instant::now();
+            emit_migration_status(
+                app_handle,
+                engine,
+                &format!("Moving existing {} environment...", engine.as_label()),
+                copied_bytes,
+                total_bytes,
+            );
+        }
+    });
+    if let Err(error) = moved {
+        report.error = Some(error);
+        return report;
+    }
+    report.moved = true;
+
+    emit_migration_status(
+        app_handle,
+        engine,
+        &format!("Verifying {} environment...", engine.as_label()),
+        total_bytes,
+        total_bytes,
+    );
+    let (imports_ok, missing_modules) = run_python_import_check(
+        &get_venv_python_exe(destination),
+        engine.required_import_modules(),
+    );
+    report.verified = imports_ok;
+    report.missing_modules = missing_modules;
+    report
+}
+
+/// Déplace vers le dossier de données courant les venvs laissés par une ancienne version.
+///
+/// Seuls les moteurs sans venv dans le dossier courant sont repris. La progression est
+/// émise sur `venv-migration-status` ; chaque venv déplacé est vérifié en important les
+/// modules du moteur.
+///
+/// @returns Un rapport par venv repris (vide si rien à migrer)
+pub async fn migrate_local_envs(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<VenvMigrationReport>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?;
+        let previous_dirs = previous_app_data_dirs(&app_data_dir);
+        let mut reports = Vec::new();
+        if previous_dirs.is_empty() {
+            return Ok(reports);
+        }
+        for engine in LocalSegmentationEngine::ALL {
+            let destination = get_engine_venv_path(&app_handle, engine)?;
+            if destination.exists() {
+                continue;
+            }
+            let Some(source) = find_previous_venv(&previous_dirs, engine) else {
+                continue;
+            };
+            let report = migrate_engine_venv(&app_handle, engine, &source, &destination);
+            log::info!(
+                "[segmentation][migration] engine={} source={} moved={} verified={} error={:?}",
+                report.engine,
+                report.source_path,
+                report.moved,
+                report.verified,
+                report.error
+            );
+            reports.push(report);
+        }
+        Ok(reports)
+    })
+    .await
+    .map_err(|e| format!("Local env migration task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        copy_dir_recursive, move_dir, previous_app_data_dirs, pyvenv_cfg_previous_app_data_dir,
+    };
+    use std::fs;
+
+    #[test]
+    fn previous_data_dirs_and_directory_moves_are_handled() {
+        let root = std::env::temp_dir().join(format!(
+            "qurancaption-venv-migration-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let current = root.join("com.qurancaption");
+        let previous = root.join("Quran Caption");
+        fs::create_dir_all(&current).unwrap();
+        fs::create_dir_all(previous.join("python_envs").join("seg-legacy").join("lib")).unwrap();
+        fs::create_dir_all(root.join("com.other.app")).unwrap();
+        assert_eq!(previous_app_data_dirs(&current), vec![previous.clone()]);
+
+        let source = previous.join("python_envs").join("seg-legacy");
+        fs::write(source.join("pyvenv.cfg"), "home = /usr/bin").unwrap();
+        fs::write(source.join("lib").join("module.py"), vec![b'x'; 10]).unwrap();
+        let mut copied = 0u64;
+        copy_dir_recursive(&source, &root.join("copy"), &mut |delta| copied += delta).unwrap();
+        assert_eq!(copied, 25);
+        assert!(root.join("copy").join("lib").join("module.py").is_file());
+
+        let destination = current.join("python_envs").join("seg-legacy");
+        move_dir(&source, &destination, &mut |_| {}).unwrap();
+        assert!(!source.exists());
+        assert_eq!(
+            fs::read_to_string(destination.join("pyvenv.cfg")).unwrap(),
+            "home = /usr/bin"
+        );
+        assert!(destination.join("lib").join("module.py").is_file());
+
+        let python_home = previous.join("python").join("bin");
+        let pyvenv_cfg = format!(
+            "home = {}\ninclude-system-site-packages = false\nversion = 3.11.9\n",
+            python_home.to_string_lossy()
+        );
+        assert_eq!(
+            pyvenv_cfg_previous_app_data_dir(&pyvenv_cfg, &current),
+            Some(previous.clone())
+        );
+        let current_home = format!(
+            "home = {}\n",
+            current.join("python").join("bin").to_string_lossy()
+        );
+        assert_eq!(
+            pyvenv_cfg_previous_app_data_dir(&current_home, &current),
+            None
+        );
+        assert_eq!(
+            pyvenv_cfg_previous_app_data_dir("home = /usr/bin", &current),
+            None
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,418 @@
+//! Versions figées des paquets Python de chaque moteur local.
+//!
+//! Les mêmes versions servent de contraintes pip (`-c`) à l'installation et de référence au
+//! contrôle de préparation. PyTorch n'est pas figé : sa version dépend de l'index CUDA/CPU
+//! retenu à l'installation.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::process::{configure_command_no_window, output_with_timeout, PROBE_TIMEOUT};
+
+use super::python_env::{get_engine_venv_path, get_venv_python_exe};
+use super::types::LocalSegmentationEngine;
+
+/// Marqueur d'un venv installé avec `latest` : ses versions ne sont pas contrôlées.
+const UNPINNED_INSTALL_MARKER: &str = ".unpinned-install";
+
+const LEGACY_PINS: &[(&str, &str)] = &[
+    ("transformers", "4.46.3"),
+    ("numpy", "1.26.4"),
+    ("librosa", "0.10.2.post1"),
+    ("soundfile", "0.12.1"),
+    ("accelerate", "0.34.2"),
+    ("recitations_segmenter", "1.0.0"),
+];
+
+const MULTI_ALIGNER_PINS: &[(&str, &str)] = &[
+    ("transformers", "5.0.0"),
+    ("accelerate", "1.11.0"),
+    ("librosa", "0.10.2"),
+    ("numpy", "1.26.4"),
+    ("soundfile", "0.12.1"),
+    ("recitations_segmenter", "1.0.0"),
+];
+
+const MUAALEM_PINS: &[(&str, &str)] = &[
+    ("transformers", "4.55.4"),
+    ("numpy", "2.2.6"),
+    ("librosa", "0.11.0"),
+    ("soundfile", "0.12.1"),
+    ("accelerate", "1.6.0"),
+    ("protobuf", "5.29.5"),
+    ("recitations-segmenter", "1.0.0"),
+    ("python-Levenshtein", "0.27.1"),
+];
+
+const SURAH_SPLITTER_PINS: &[(&str, &str)] = &[
+    ("whisperx", "3.4.2"),
+    ("numpy", "2.0.2"),
+    ("loguru", "0.7.3"),
+    ("pydub", "0.25.1"),
+];
+
+/// Paquets clés rapportés pour tous les moteurs (en plus des paquets figés du moteur).
+const REPORTED_PACKAGES: [&str; 7] = [
+    "torch",
+    "transformers",
+    "numpy",
+    "librosa",
+    "soundfile",
+    "accelerate",
+    "recitations_segmenter",
+];
+/// Version affichée pour un paquet absent du venv.
+const PACKAGE_NOT_INSTALLED: &str = "not installed";
+
+/// Collecte en une seule exécution les versions des paquets (`argv`), de Python et les
+/// backends visibles par torch.
+const ENGINE_VERSIONS_SCRIPT: &str = r#"
+import json
+import platform
+import sys
+from importlib import metadata
+
+packages = {}
+for name in sys.argv[1:]:
+    try:
+        packages[name] = metadata.version(name)
+    except Exception:
+        packages[name] = None
+cuda = False
+mps = False
+try:
+    import torch
+    cuda = bool(torch.cuda.is_available())
+    mps_backend = getattr(torch.backends, "mps", None)
+    mps = bool(mps_backend is not None and mps_backend.is_available())
+except Exception:
+    pass
+print(json.dumps({
+    "pythonVersion": platform.python_version(),
+    "packages": packages,
+    "cudaAvailable": cuda,
+    "mpsAvailable": mps,
+}))
+"#;
+
+/// Versions installées dans le venv d'un moteur local (pour les rapports de bug).
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalEngineVersions {
+    /// Clé du moteur dont le venv a été inspecté.
+    pub engine: String,
+    /// Version de Python du venv.
+    pub python_version: Option<String>,
+    /// Version de chaque paquet clé, `not installed` si absent.
+    pub packages: BTreeMap<String, String>,
+    /// `true` si torch voit un GPU CUDA (ou ROCm).
+    pub cuda_available: bool,
+    /// `true` si torch peut utiliser le backend Apple MPS.
+    pub mps_available: bool,
+}
+
+/// Versions figées `(paquet, version)` d'un moteur.
+pub(crate) fn engine_version_pins(
+    engine: LocalSegmentationEngine,
+) -> &'static [(&'static str, &'static str)] {
+    match engine {
+        LocalSegmentationEngine::LegacyWhisper => LEGACY_PINS,
+        LocalSegmentationEngine::MultiAligner => MULTI_ALIGNER_PINS,
+        LocalSegmentationEngine::MuaalemLocal => MUAALEM_PINS,
+        LocalSegmentationEngine::SurahSplitter => SURAH_SPLITTER_PINS,
+    }
+}
+
+/// Contenu du fichier de contraintes pip d'un moteur.
+fn constraints_file_content(engine: LocalSegmentationEngine) -> String {
+    let mut content = format!("# Version pins for the {} engine\n", engine.as_label());
+    for (package, version) in engine_version_pins(engine) {
+        content.push_str(&format!("{}=={}\n", package, version));
+    }
+    content
+}
+
+/// Écrit le fichier de contraintes pip d'un moteur dans le dossier temporaire.
+pub(crate) fn write_constraints_file(engine: LocalSegmentationEngine) -> Result<PathBuf, String> {
+    let path =
+        std::env::temp_dir().join(format!("qurancaption_constraints_{}.txt", engine.as_key()));
+    fs::write(&path, constraints_file_content(engine)).map_err(|e| {
+        format!(
+            "Failed to write constraints file '{}': {}",
+            path.to_string_lossy(),
+            e
+        )
+    })?;
+    Ok(path)
+}
+
+/// Enregistre si le venv a été installé sans versions figées (`latest`).
+pub(crate) fn set_venv_unpinned(venv_dir: &Path, unpinned: bool) -> Result<(), String> {
+    let marker = venv_dir.join(UNPINNED_INSTALL_MARKER);
+    let result = if unpinned {
+        fs::write(&marker, b"")
+    } else {
+        match fs::remove_file(&marker) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    };
+    result.map_err(|e| {
+        format!(
+            "Failed to update version pin marker of '{}': {}",
+            venv_dir.to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Indique si le venv a été installé sans versions figées.
+pub(crate) fn is_venv_unpinned(venv_dir: &Path) -> bool {
+    venv_dir.join(UNPINNED_INSTALL_MARKER).exists()
+}
+
+/// Normalise un nom de paquet (PEP 503) : minuscules, `-`, `_` et `.` équivalents.
+fn normalize_package_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Paquets dont la version installée diffère de la version figée (`paquet=installée (attendu X)`).
+///
+/// Le suffixe local (`+cpu`, `+cu121`) est ignoré, comme le fait pip pour `==`.
+fn pin_mismatches(
+    pins: &[(&str, &str)],
+    installed: &HashMap<String, Option<String>>,
+) -> Vec<String> {
+    pins.iter()
+        .filter_map(|(package, expected)| {
+            let version = installed
+                .get(&normalize_package_name(package))
+                .cloned()
+                .flatten();
+            match version {
+                Some(version) if version.split('+').next() == Some(*expected) => None,
+                Some(version) => Some(format!("{}={} (expected {})", package, version, expected)),
+                None => Some(format!("{}=missing (expected {})", package, expected)),
+            }
+        })
+        .collect()
+}
+
+/// Liste les paquets du venv dont la version diffère des versions figées du moteur.
+///
+/// Un écart n'empêche pas le moteur de fonctionner : il est seulement signalé. Un venv
+/// installé avec `latest`, absent ou impossible à interroger ne rapporte aucun écart
+/// (les imports sont vérifiés séparément).
+///
+/// @returns les écarts au format `paquet=installé (expected attendu)`
+pub(crate) fn pinned_version_mismatches(
+    venv_dir: &Path,
+    engine: LocalSegmentationEngine,
+) -> Vec<String> {
+    let python_exe = get_venv_python_exe(venv_dir);
+    if !python_exe.exists() || is_venv_unpinned(venv_dir) {
+        return Vec::new();
+    }
+
+    let script = r#"
+import json
+import sys
+from importlib import metadata
+
+versions = {}
+for name in sys.argv[1:]:
+    try:
+        versions[name] = metadata.version(name)
+    except Exception:
+        versions[name] = None
+print(json.dumps(versions))
+"#;
+    let pins = engine_version_pins(engine);
+    let mut cmd = Command::new(&python_exe);
+    cmd.args(["-c", script]);
+    cmd.args(pins.iter().map(|(package, _)| *package));
+    configure_command_no_window(&mut cmd);
+
+    let output = match output_with_timeout(&mut cmd, PROBE_TIMEOUT) {
+        Ok(output) if output.status.success() => output,
+        Ok(_) => {
+            log::warn!(
+                "[segmentation] Package version check failed for {}",
+                engine.as_label()
+            );
+            return Vec::new();
+        }
+        Err(error) => {
+            log::warn!(
+                "[segmentation] Failed to validate package versions for {}: {}",
+                engine.as_label(),
+                error
+            );
+            return Vec::new();
+        }
+    };
+    let Ok(versions) = serde_json::from_slice::<HashMap<String, Option<String>>>(&output.stdout)
+    else {
+        return Vec::new();
+    };
+    let installed: HashMap<String, Option<String>> = versions
+        .into_iter()
+        .map(|(package, version)| (normalize_package_name(&package), version))
+        .collect();
+
+    pin_mismatches(pins, &installed)
+}
+
+/// Paquets rapportés pour un moteur : paquets clés puis paquets figés, sans doublon.
+fn reported_packages(engine: LocalSegmentationEngine) -> Vec<&'static str> {
+    let mut packages: Vec<&'static str> = Vec::new();
+    let pinned = engine_version_pins(engine)
+        .iter()
+        .map(|(package, _)| *package);
+    for package in REPORTED_PACKAGES.into_iter().chain(pinned) {
+        let normalized = normalize_package_name(package);
+        if !packages
+            .iter()
+            .any(|known| normalize_package_name(known) == normalized)
+        {
+            packages.push(package);
+        }
+    }
+    packages
+}
+
+/// Construit le rapport de versions depuis la sortie JSON du script de collecte.
+fn parse_engine_versions(
+    engine: LocalSegmentationEngine,
+    packages: &[&str],
+    stdout: &[u8],
+) -> Result<LocalEngineVersions, String> {
+    let payload = serde_json::from_slice::<serde_json::Value>(stdout)
+        .map_err(|e| format!("Package version report returned invalid JSON: {}", e))?;
+    let installed = payload.get("packages");
+    let flag = |key: &str| {
+        payload
+            .get(key)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    };
+    Ok(LocalEngineVersions {
+        engine: engine.as_key().to_string(),
+        python_version: payload
+            .get("pythonVersion")
+            .and_then(|value| value.as_str())
+            .map(str::to_string),
+        packages: packages
+            .iter()
+            .map(|package| {
+                let version = installed
+                    .and_then(|installed| installed.get(*package))
+                    .and_then(|version| version.as_str())
+                    .unwrap_or(PACKAGE_NOT_INSTALLED);
+                (package.to_string(), version.to_string())
+            })
+            .collect(),
+        cuda_available: flag("cudaAvailable"),
+        mps_available: flag("mpsAvailable"),
+    })
+}
+
+/// Retourne les versions des paquets clés installés dans le venv d'un moteur local.
+///
+/// Un paquet absent est signalé `not installed` sans faire échouer l'appel.
+///
+/// @param engine - Moteur à inspecter (`legacy`, `multi`, `muaalem`, `surah_splitter`)
+pub async fn get_local_engine_versions(
+    app_handle: tauri::AppHandle,
+    engine: String,
+) -> Result<LocalEngineVersions, String> {
+    let selected_engine = LocalSegmentationEngine::from_raw(engine.as_str())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let venv_dir = get_engine_venv_path(&app_handle, selected_engine)?;
+        let python_exe = get_venv_python_exe(&venv_dir);
+        if !python_exe.exists() {
+            return Err(format!(
+                "{} dependencies are not installed",
+                selected_engine.as_label()
+            ));
+        }
+
+        let packages = reported_packages(selected_engine);
+        let mut cmd = Command::new(&python_exe);
+        cmd.args(["-c", ENGINE_VERSIONS_SCRIPT]);
+        cmd.args(&packages);
+        configure_command_no_window(&mut cmd);
+        let output = output_with_timeout(&mut cmd, PROBE_TIMEOUT)
+            .map_err(|e| format!("Failed to collect package versions: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Package version report failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        parse_engine_versions(selected_engine, &packages, &output.stdout)
+    })
+    .await
+    .map_err(|e| format!("Package version task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        constraints_file_content, parse_engine_versions, pin_mismatches, reported_packages,
+    };
+    use crate::segmentation::types::LocalSegmentationEngine;
+    use std::collections::HashMap;
+
+    #[test]
+    fn installed_versions_are_compared_to_pins() {
+        let content = constraints_file_content(LocalSegmentationEngine::LegacyWhisper);
+        assert!(content.lines().any(|line| line == "numpy==1.26.4"));
+        assert!(!content.contains("torch"));
+
+        let pins = [
+            ("recitations_segmenter", "1.0.0"),
+            ("numpy", "1.26.4"),
+            ("torch", "2.8.0"),
+        ];
+        let installed: HashMap<String, Option<String>> = [
+            ("recitations-segmenter", Some("1.0.0")),
+            ("numpy", Some("2.1.0")),
+            ("torch", Some("2.8.0+cpu")),
+        ]
+        .into_iter()
+        .map(|(name, version)| (name.to_string(), version.map(str::to_string)))
+        .collect();
+        assert_eq!(
+            pin_mismatches(&pins, &installed),
+            vec!["numpy=2.1.0 (expected 1.26.4)".to_string()]
+        );
+        assert_eq!(
+            pin_mismatches(&[("accelerate", "0.34.2")], &installed),
+            vec!["accelerate=missing (expected 0.34.2)".to_string()]
+        );
+    }
+
+    #[test]
+    fn engine_versions_report_missing_packages() {
+        let packages = reported_packages(LocalSegmentationEngine::MuaalemLocal);
+        assert_eq!(packages[0], "torch");
+        assert!(packages.contains(&"python-Levenshtein"));
+        assert!(!packages.contains(&"recitations-segmenter"));
+
+        let stdout = br#"{"pythonVersion": "3.11.10", "packages": {"torch": "2.8.0+cpu", "numpy": null}, "cudaAvailable": false, "mpsAvailable": true}"#;
+        let versions = parse_engine_versions(
+            LocalSegmentationEngine::MultiAligner,
+            &["torch", "numpy", "librosa"],
+            stdout,
+        )
+        .unwrap();
+        assert_eq!(versions.python_version.as_deref(), Some("3.11.10"));
+        assert_eq!(versions.packages["torch"], "2.8.0+cpu");
+        assert_eq!(versions.packages["numpy"], "not installed");
+        assert_eq!(versions.packages["librosa"], "not installed");
+        assert!(versions.mps_available && !versions.cuda_available);
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::LocalRunDeadlines;
+
+/// Intervalle de vérification des délais pendant l'attente du script.
+pub(super) const WATCHDOG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Nombre de dernières lignes stderr jointes à une erreur de délai.
+const RECENT_LINES_IN_ERROR: usize = 10;
+
+/// Reçoit la durée de silence quand le seuil d'avertissement est franchi.
+type StallWarningHandler = Box<dyn FnMut(Duration) + Send>;
+
+/// Horodatage de la dernière sortie du script (STATUS ou stderr), partagé avec le lecteur stderr.
+#[derive(Clone)]
+pub(super) struct ActivityMarker(Arc<Mutex<Instant>>);
+
+impl ActivityMarker {
+    /// Signale une sortie du script.
+    pub(super) fn touch(&self) {
+        if let Ok(mut last_activity) = self.0.lock() {
+            *last_activity = Instant::now();
+        }
+    }
+
+    /// Durée écoulée depuis la dernière sortie.
+    fn idle(&self) -> Duration {
+        self.0
+            .lock()
+            .map(|last_activity| last_activity.elapsed())
+            .unwrap_or_default()
+    }
+}
+
+/// Surveille une exécution locale : délai global et absence prolongée de sortie.
+pub(super) struct RunWatchdog {
+    deadlines: LocalRunDeadlines,
+    started: Instant,
+    activity: ActivityMarker,
+    /// Dernières lignes stderr du script, reprises dans le message d'erreur.
+    recent_lines: Arc<Mutex<Vec<String>>>,
+    on_stall_warning: StallWarningHandler,
+    warned: bool,
+}
+
+impl RunWatchdog {
+    pub(super) fn new(
+        deadlines: LocalRunDeadlines,
+        recent_lines: Arc<Mutex<Vec<String>>>,
+        on_stall_warning: StallWarningHandler,
+    ) -> Self {
+        Self {
+            deadlines,
+            started: Instant::now(),
+            activity: ActivityMarker(Arc::new(Mutex::new(Instant::now()))),
+            recent_lines,
+            on_stall_warning,
+            warned: false,
+        }
+    }
+
+    /// Marqueur à toucher à chaque ligne émise par le script.
+    pub(super) fn activity(&self) -> ActivityMarker {
+        self.activity.clone()
+    }
+
+    /// Vérifie les délais ; une erreur signifie que le script doit être arrêté.
+    ///
+    /// L'avertissement de silence est émis une fois par période de silence.
+    pub(super) fn check(&mut self) -> Result<(), String> {
+        if let Some(timeout) = self.deadlines.timeout {
+            if self.started.elapsed() >= timeout {
+                return Err(self.failure(format!(
+                    "Local segmentation timed out after {}",
+                    format_minutes(timeout)
+                )));
+            }
+        }
+
+        let idle = self.activity.idle();
+        if idle >= self.deadlines.stall_timeout {
+            return Err(self.failure(format!(
+                "Local segmentation stalled: no output for {}",
+                format_minutes(idle)
+            )));
+        }
+        match self.deadlines.stall_warning {
+            Some(warning) if idle >= warning => {
+                if !self.warned {
+                    self.warned = true;
+                    (self.on_stall_warning)(idle);
+                }
+            }
+            _ => self.warned = false,
+        }
+        Ok(())
+    }
+
+    /// Complète un message d'échec avec les dernières lignes capturées.
+    fn failure(&self, message: String) -> String {
+        let recent = self
+            .recent_lines
+            .lock()
+            .map(|lines| {
+                let start = lines.len().saturating_sub(RECENT_LINES_IN_ERROR);
+                lines[start..].join("\n")
+            })
+            .unwrap_or_default();
+        if recent.trim().is_empty() {
+            message
+        } else {
+            format!("{}. Last output:\n{}", message, recent)
+        }
+    }
+}
+
+/// Formate une durée en minutes pour les messages (ex: "30 min").
+pub(super) fn format_minutes(duration: Duration) -> String {
+    format!("{} min", (duration.as_secs() + 30) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunWatchdog;
+    use crate::segmentation::types::{LocalRunDeadlines, LocalRunTimeouts};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn timeouts_are_validated_and_derived_from_audio_duration() {
+        assert!(LocalRunTimeouts::from_raw(Some(0), None, None).is_err());
+        assert!(LocalRunTimeouts::from_raw(None, Some(10), Some(10)).is_err());
+
+        let defaults = LocalRunTimeouts::default();
+        let gpu = defaults.resolve(600.0, "GPU");
+        let cpu = defaults.resolve(600.0, "CPU");
+        assert_eq!(gpu.timeout, Some(Duration::from_secs(20 * 60 + 1200)));
+        assert!(cpu.timeout > gpu.timeout);
+        assert_eq!(defaults.resolve(0.0, "CPU").timeout, None);
+        assert_eq!(gpu.stall_warning, Some(Duration::from_secs(5 * 60)));
+
+        let custom = LocalRunTimeouts::from_raw(Some(90), None, Some(3)).unwrap();
+        let deadlines = custom.resolve(600.0, "GPU");
+        assert_eq!(deadlines.timeout, Some(Duration::from_secs(90 * 60)));
+        assert_eq!(deadlines.stall_timeout, Duration::from_secs(3 * 60));
+        assert_eq!(deadlines.stall_warning, None);
+    }
+
+    #[test]
+    fn watchdog_warns_once_then_fails_with_recent_output() {
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let warnings_clone = Arc::clone(&warnings);
+        let recent_lines = Arc::new(Mutex::new(vec![
+            "Loading model".to_string(),
+            "CUDA initialized".to_string(),
+        ]));
+        let mut watchdog = RunWatchdog::new(
+            LocalRunDeadlines {
+                timeout: None,
+                stall_warning: Some(Duration::from_millis(20)),
+                stall_timeout: Duration::from_millis(80),
+            },
+            recent_lines,
+            Box::new(move |_| {
+                warnings_clone.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        assert!(watchdog.check().is_ok());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(watchdog.check().is_ok());
+        assert!(watchdog.check().is_ok());
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+
+        watchdog.activity().touch();
+        assert!(watchdog.check().is_ok());
+        std::thread::sleep(Duration::from_millis(90));
+        let error = watchdog.check().unwrap_err();
+        assert!(error.starts_with("Local segmentation stalled"));
+        assert!(error.ends_with("Loading model\nCUDA initialized"));
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,656 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::utils::process::{
+    configure_command_no_window, kill_process_tree, kill_tracked_process, spawn_command,
+    track_process, untrack_process, TrackedChild,
+};
+
+use super::memory_check::detect_out_of_memory;
+use super::model_cache::{apply_hf_cache_env, custom_hf_home};
+use super::proxy::runtime_proxy;
+use super::python_env::{apply_hf_token_env, resolve_python_resource_path};
+use super::types::{LocalProcessLimits, LocalSegmentationEngine, ScriptExit};
+use super::watchdog::{RunWatchdog, WATCHDOG_POLL_INTERVAL};
+
+/// Script Python du worker résident (protocole JSON ligne à ligne).
+const WORKER_SCRIPT_RELATIVE_PATH: &str = "python/segmentation_worker.py";
+/// Délai d'inactivité par défaut avant l'arrêt du worker.
+const DEFAULT_IDLE_TIMEOUT_MINUTES: u64 = 10;
+/// Intervalle de vérification de l'inactivité.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Temps maximal pour que le worker importe le script du moteur.
+const WORKER_READY_TIMEOUT: Duration = Duration::from_secs(120);
+/// Délai laissé au worker pour quitter proprement avant d'être tué.
+const WORKER_SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+/// Identifiant du worker dans le registre des processus (`kill_tracked_process`).
+const WORKER_PROCESS_ID: &str = "segmentation:worker";
+
+/// `true` si les segmentations locales passent par le worker résident.
+static WORKER_MODE_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Délai d'inactivité (minutes) avant l'arrêt automatique du worker.
+static IDLE_TIMEOUT_MINUTES: AtomicU64 = AtomicU64::new(DEFAULT_IDLE_TIMEOUT_MINUTES);
+/// Évite de lancer plusieurs threads de surveillance d'inactivité.
+static IDLE_WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+/// Worker inactif (un seul à la fois, tous moteurs confondus).
+///
+/// Pendant une requête, le worker est sorti de cet emplacement : le verrou n'est jamais tenu
+/// plus que le temps d'un échange, et les commandes d'arrêt ne bloquent pas sur une segmentation.
+static WORKER: Mutex<Option<SegmentationWorker>> = Mutex::new(None);
+/// Sérialise les requêtes envoyées au worker.
+static WORKER_REQUEST: Mutex<()> = Mutex::new(());
+/// Clé du worker qui traite une requête (arrêté via `kill_tracked_process`).
+static ACTIVE_WORKER_KEY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Reçoit les lignes stderr du worker pendant une requête (STATUS et logs).
+type StderrHandler = Box<dyn FnMut(String) + Send>;
+
+/// Processus Python résident d'un moteur local.
+struct SegmentationWorker {
+    /// Moteur, device et token avec lesquels le worker a été lancé.
+    key: String,
+    /// Processus suivi sous `WORKER_PROCESS_ID` (`None` une fois tué via le registre).
+    child: TrackedChild,
+    pid: u32,
+    stdin: ChildStdin,
+    /// Lignes du canal de réponse ; déconnecté quand le worker ferme sa sortie.
+    responses: Receiver<String>,
+    /// Destinataire des lignes stderr de la requête en cours.
+    stderr_handler: Arc<Mutex<Option<StderrHandler>>>,
+    next_request_id: u64,
+    last_used: Instant,
+}
+
+/// Publie la clé du worker occupé par une requête et l'efface à la fin, même en cas d'erreur.
+struct ActiveWorkerGuard;
+
+impl ActiveWorkerGuard {
+    fn start(key: &str) -> Self {
+        if let Ok(mut active) = ACTIVE_WORKER_KEY.lock() {
+            *active = Some(key.to_string());
+        }
+        Self
+    }
+}
+
+impl Drop for ActiveWorkerGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_WORKER_KEY.lock() {
+            *active = None;
+        }
+    }
+}
+
+/// Tue le worker occupé par une requête si `matches` accepte sa clé ; la requête échoue.
+fn kill_active_worker(matches: impl Fn(&str) -> bool) -> Result<bool, String> {
+    let active_key = ACTIVE_WORKER_KEY
+        .lock()
+        .map_err(|_| "Segmentation worker lock poisoned".to_string())?
+        .clone();
+    match active_key {
+        Some(key) if matches(&key) => {
+            log::info!("[segmentation][worker] stopping active worker ({})", key);
+            kill_tracked_process(WORKER_PROCESS_ID)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl SegmentationWorker {
+    /// Indique si le processus tourne toujours.
+    fn is_alive(&mut self) -> bool {
+        self.child
+            .lock()
+            .ok()
+            .and_then(|mut child| {
+                child
+                    .as_mut()
+                    .map(|child| matches!(child.try_wait(), Ok(None)))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Attend brièvement la fin d'un worker qui a fermé sa sortie et retourne son statut.
+    fn wait_for_exit(&mut self) -> ScriptExit {
+        let deadline = Instant::now() + WORKER_SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            let status = self
+                .child
+                .lock()
+                .ok()
+                .and_then(|mut child| child.as_mut().and_then(|child| child.try_wait().ok()))
+                .flatten();
+            if let Some(status) = status {
+                return ScriptExit::from_status(status, self.pid);
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        ScriptExit {
+            pid: Some(self.pid),
+            ..ScriptExit::default()
+        }
+    }
+
+    /// Demande l'arrêt du worker, puis le tue s'il ne quitte pas à temps.
+    fn shutdown(mut self) {
+        untrack_process(WORKER_PROCESS_ID);
+        let _ = writeln!(self.stdin, "{}", serde_json::json!({ "shutdown": true }));
+        let _ = self.stdin.flush();
+        let deadline = Instant::now() + WORKER_SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            if !self.is_alive() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let child = self.child.lock().ok().and_then(|mut child| child.take());
+        if let Some(mut child) = child {
+            let _ = kill_process_tree(&mut child);
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Active ou désactive le worker résident pour les segmentations locales.
+///
+/// Désactiver le mode arrête immédiatement le worker en cours.
+///
+/// @param enabled - `true` pour garder Python et les modèles chargés entre deux segmentations
+/// @param idle_timeout_minutes - Arrêt automatique après cette inactivité (10 min par défaut)
+pub fn set_segmentation_worker_mode(
+    enabled: bool,
+    idle_timeout_minutes: Option<u64>,
+) -> Result<(), String> {
+    if idle_timeout_minutes == Some(0) {
+        return Err("idle_timeout_minutes must be at least 1".to_string());
+    }
+    if let Some(minutes) = idle_timeout_minutes {
+        IDLE_TIMEOUT_MINUTES.store(minutes, Ordering::SeqCst);
+    }
+    WORKER_MODE_ENABLED.store(enabled, Ordering::SeqCst);
+    log::info!(
+        "[segmentation][worker] mode enabled={} idle_timeout_minutes={}",
+        enabled,
+        IDLE_TIMEOUT_MINUTES.load(Ordering::SeqCst)
+    );
+    if !enabled {
+        shutdown_segmentation_worker()?;
+    }
+    Ok(())
+}
+
+/// Arrête le worker résident s'il tourne ; une requête en cours échoue.
+///
+/// @returns `true` si un worker a été arrêté
+pub fn shutdown_segmentation_worker() -> Result<bool, String> {
+    let worker = WORKER
+        .lock()
+        .map_err(|_| "Segmentation worker lock poisoned".to_string())?
+        .take();
+    match worker {
+        Some(worker) => {
+            log::info!("[segmentation][worker] shutting down ({})", worker.key);
+            worker.shutdown();
+            Ok(true)
+        }
+        None => kill_active_worker(|_| true),
+    }
+}
+
+/// Arrête le worker résident s'il a été lancé pour `engine` (avant de supprimer son venv).
+///
+/// @returns `true` si un worker a été arrêté
+pub(super) fn shutdown_worker_for_engine(engine: LocalSegmentationEngine) -> Result<bool, String> {
+    let engine_prefix = format!("{}|", engine.as_key());
+    let worker = {
+        let mut slot = WORKER
+            .lock()
+            .map_err(|_| "Segmentation worker lock poisoned".to_string())?;
+        if slot
+            .as_ref()
+            .is_some_and(|worker| worker.key.starts_with(&engine_prefix))
+        {
+            slot.take()
+        } else {
+            None
+        }
+    };
+    match worker {
+        Some(worker) => {
+            log::info!("[segmentation][worker] shutting down ({})", worker.key);
+            worker.shutdown();
+            Ok(true)
+        }
+        None => kill_active_worker(|key| key.starts_with(&engine_prefix)),
+    }
+}
+
+/// Indique si les segmentations locales doivent passer par le worker résident.
+pub(super) fn is_worker_mode_enabled() -> bool {
+    WORKER_MODE_ENABLED.load(Ordering::SeqCst)
+}
+
+/// Clé identifiant la configuration d'un worker.
+///
+/// Le device est figé au premier chargement de torch ; token HF, cache HF, threads et priorité
+/// sont fixés au lancement du processus : tout changement impose un nouveau worker.
+fn worker_key(
+    engine: LocalSegmentationEngine,
+    script_args: &[String],
+    hf_token: Option<&str>,
+    hf_home: Option<&Path>,
+    limits: LocalProcessLimits,
+) -> String {
+    let device = script_args
+        .iter()
+        .position(|arg| arg == "--device")
+        .and_then(|index| script_args.get(index + 1))
+        .map(String::as_str)
+        .unwrap_or("auto");
+    let mut hasher = DefaultHasher::new();
+    hf_token.unwrap_or_default().hash(&mut hasher);
+    let threads = limits
+        .cpu_threads
+        .map(|threads| threads.to_string())
+        .unwrap_or_else(|| "all".to_string());
+    let token_hash = hasher.finish();
+    let mut hasher = DefaultHasher::new();
+    hf_home.map(PathBuf::from).hash(&mut hasher);
+    format!(
+        "{}|device={}|threads={}|background={}|token={:x}|cache={:x}",
+        engine.as_key(),
+        device,
+        threads,
+        limits.background,
+        token_hash,
+        hasher.finish()
+    )
+}
+
+/// Lance le thread qui arrête le worker après le délai d'inactivité.
+fn ensure_idle_watcher() {
+    if IDLE_WATCHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        let timeout = Duration::from_secs(IDLE_TIMEOUT_MINUTES.load(Ordering::SeqCst) * 60);
+        // Un worker occupé est sorti de l'emplacement : seul un worker inactif est arrêté ici.
+        let Ok(mut slot) = WORKER.lock() else {
+            continue;
+        };
+        if slot
+            .as_ref()
+            .is_some_and(|worker| worker.last_used.elapsed() >= timeout)
+        {
+            if let Some(worker) = slot.take() {
+                log::info!(
+                    "[segmentation][worker] idle for {:?}, shutting down ({})",
+                    timeout,
+                    worker.key
+                );
+                worker.shutdown();
+            }
+        }
+    });
+}
+
+/// Démarre un worker pour le script du moteur et attend qu'il soit prêt.
+fn spawn_worker(
+    app_handle: &tauri::AppHandle,
+    key: String,
+    python_exe: &Path,
+    script_path: &Path,
+    hf_token: Option<&str>,
+    limits: LocalProcessLimits,
+) -> Result<SegmentationWorker, String> {
+    let worker_script = resolve_python_resource_path(app_handle, WORKER_SCRIPT_RELATIVE_PATH)?;
+    let mut cmd = Command::new(python_exe);
+    cmd.arg(&worker_script).arg(script_path);
+    if let Some(token) = hf_token {
+        apply_hf_token_env(&mut cmd, token);
+    }
+    apply_hf_cache_env(&mut cmd);
+    if let Some(proxy) = runtime_proxy() {
+        proxy.apply_env(&mut cmd);
+    }
+    cmd.envs(limits.env_vars());
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    configure_command_no_window(&mut cmd);
+
+    let mut child = spawn_command(&mut cmd, limits.background)
+        .map_err(|e| format!("Failed to spawn segmentation worker: {}", e))?;
+    log::info!(
+        "[segmentation][worker] spawned pid={} ({})",
+        child.id(),
+        key
+    );
+    let stdin = child.stdin.take().ok_or("Failed to capture worker stdin")?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or("Failed to capture worker stdout")?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or("Failed to capture worker stderr")?;
+
+    // La fin de ce thread (EOF) déconnecte le canal : c'est ainsi qu'un crash est détecté.
+    let (sender, responses) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let stderr_handler: Arc<Mutex<Option<StderrHandler>>> = Arc::new(Mutex::new(None));
+    let stderr_handler_clone = Arc::clone(&stderr_handler);
+    let key_for_thread = key.clone();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            let Ok(mut handler) = stderr_handler_clone.lock() else {
+                break;
+            };
+            match handler.as_mut() {
+                Some(handler) => handler(line),
+                None if !line.trim().is_empty() => {
+                    log::warn!(
+                        "[segmentation][worker][stderr][{}] {}",
+                        key_for_thread,
+                        line
+                    )
+                }
+                None => {}
+            }
+        }
+    });
+
+    let pid = child.id();
+    let worker = SegmentationWorker {
+        key,
+        child: track_process(WORKER_PROCESS_ID, child),
+        pid,
+        stdin,
+        responses,
+        stderr_handler,
+        next_request_id: 1,
+        last_used: Instant::now(),
+    };
+    let ready = loop {
+        match worker.responses.recv_timeout(WORKER_READY_TIMEOUT) {
+            Ok(line) => {
+                let is_ready = serde_json::from_str::<serde_json::Value>(&line)
+                    .ok()
+                    .and_then(|message| message.get("ready").and_then(|ready| ready.as_bool()))
+                    .unwrap_or(false);
+                if is_ready {
+                    break Ok(());
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                break Err("Segmentation worker did not start in time".to_string())
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                break Err("Segmentation worker exited during startup".to_string())
+            }
+        }
+    };
+    if let Err(error) = ready {
+        worker.shutdown();
+        return Err(error);
+    }
+    ensure_idle_watcher();
+    Ok(worker)
+}
+
+/// Décrit le statut de fin d'un worker pour les messages d'erreur.
+fn describe_exit(exit: ScriptExit) -> String {
+    match (exit.code, exit.signal) {
+        (Some(code), _) => format!("exit code {}", code),
+        (None, Some(signal)) => format!("signal {}", signal),
+        (None, None) => "unknown status".to_string(),
+    }
+}
+
+/// Envoie une requête au worker.
+///
+/// Une erreur de `watchdog` abandonne la requête : le worker est alors arrêté par l'appelant.
+///
+/// @returns `Err(None)` si le worker est mort avant d'accepter la requête (relançable)
+fn send_request(
+    worker: &mut SegmentationWorker,
+    script_args: &[String],
+    watchdog: &mut RunWatchdog,
+) -> Result<(ScriptExit, String), Option<String>> {
+    if !worker.is_alive() {
+        return Err(None);
+    }
+    let request_id = worker.next_request_id;
+    worker.next_request_id += 1;
+    let request = serde_json::json!({ "id": request_id, "args": script_args });
+    if writeln!(worker.stdin, "{}", request)
+        .and_then(|_| worker.stdin.flush())
+        .is_err()
+    {
+        return Err(None);
+    }
+
+    loop {
+        let line = match worker.responses.recv_timeout(WATCHDOG_POLL_INTERVAL) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => {
+                watchdog.check().map_err(Some)?;
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                // Un arrêt brutal (OOM killer, code Windows) se lit dans le statut du worker.
+                let exit = worker.wait_for_exit();
+                return Err(Some(detect_out_of_memory(exit, "").unwrap_or_else(|| {
+                    format!(
+                        "Segmentation worker exited unexpectedly ({}); it will be restarted on the next run",
+                        describe_exit(exit)
+                    )
+                })));
+            }
+        };
+        let Ok(response) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if response.get("id").and_then(|id| id.as_u64()) != Some(request_id) {
+            continue;
+        }
+        let exit_code = response
+            .get("exitCode")
+            .and_then(|code| code.as_i64())
+            .unwrap_or(1);
+        let output = response
+            .get("output")
+            .and_then(|output| output.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let exit = ScriptExit {
+            code: Some(exit_code as i32),
+            signal: None,
+            pid: Some(worker.pid),
+        };
+        return Ok((exit, output));
+    }
+}
+
+/// Exécute le script d'un moteur dans le worker résident (lancé ou relancé au besoin).
+///
+/// Un worker mort entre deux requêtes est relancé de façon transparente ; s'il meurt
+/// pendant une requête, celle-ci échoue et la suivante repart d'un nouveau processus.
+///
+/// @param script_args - Arguments du script (sans le chemin du script)
+/// @param on_stderr_line - Reçoit chaque ligne stderr émise pendant la requête
+/// @param watchdog - Délais de la requête ; s'ils sont dépassés, le worker est arrêté
+/// @returns Statut de fin du script et sa sortie standard (JSON de segmentation)
+pub(super) fn run_script_in_worker(
+    app_handle: &tauri::AppHandle,
+    engine: LocalSegmentationEngine,
+    python_exe: &Path,
+    script_path: &Path,
+    script_args: &[String],
+    hf_token: Option<&str>,
+    limits: LocalProcessLimits,
+    on_stderr_line: StderrHandler,
+    watchdog: &mut RunWatchdog,
+) -> Result<(ScriptExit, String), String> {
+    let hf_token = hf_token.map(str::trim).filter(|token| !token.is_empty());
+    let key = worker_key(
+        engine,
+        script_args,
+        hf_token,
+        custom_hf_home().as_deref(),
+        limits,
+    );
+    let _request = WORKER_REQUEST
+        .lock()
+        .map_err(|_| "Segmentation worker lock poisoned".to_string())?;
+    let _active = ActiveWorkerGuard::start(&key);
+    let mut current = WORKER
+        .lock()
+        .map_err(|_| "Segmentation worker lock poisoned".to_string())?
+        .take();
+
+    if current.as_ref().is_some_and(|worker| worker.key != key) {
+        if let Some(previous) = current.take() {
+            log::info!(
+                "[segmentation][worker] configuration changed, replacing ({})",
+                previous.key
+            );
+            previous.shutdown();
+        }
+    }
+
+    let mut on_stderr_line = Some(on_stderr_line);
+    for attempt in 0..2 {
+        let mut worker = match current.take() {
+            Some(worker) => worker,
+            None => spawn_worker(
+                app_handle,
+                key.clone(),
+                python_exe,
+                script_path,
+                hf_token,
+                limits,
+            )?,
+        };
+        if let Ok(mut handler) = worker.stderr_handler.lock() {
+            *handler = on_stderr_line.take();
+        }
+        let outcome = send_request(&mut worker, script_args, watchdog);
+        if let Ok(mut handler) = worker.stderr_handler.lock() {
+            on_stderr_line = handler.take();
+        }
+        worker.last_used = Instant::now();
+
+        match outcome {
+            Ok(output) => {
+                // Le mode a pu être désactivé pendant la requête : le worker n'est pas gardé.
+                match WORKER.lock() {
+                    Ok(mut slot) if is_worker_mode_enabled() => {
+                        if let Some(previous) = slot.replace(worker) {
+                            previous.shutdown();
+                        }
+                    }
+                    _ => worker.shutdown(),
+                }
+                return Ok(output);
+            }
+            Err(error) => {
+                worker.shutdown();
+                match error {
+                    Some(error) => return Err(error),
+                    None => log::info!(
+                        "[segmentation][worker] worker was not running, respawning (attempt {})",
+                        attempt + 1
+                    ),
+                }
+            }
+        }
+    }
+    Err("Segmentation worker could not be started".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kill_active_worker, worker_key, ActiveWorkerGuard, ACTIVE_WORKER_KEY};
+    use crate::segmentation::types::{LocalProcessLimits, LocalSegmentationEngine};
+    use std::path::Path;
+
+    #[test]
+    fn worker_key_changes_with_process_settings() {
+        let args = |device: &str| {
+            vec![
+                "audio.wav".to_string(),
+                "--device".to_string(),
+                device.to_string(),
+            ]
+        };
+        let engine = LocalSegmentationEngine::MultiAligner;
+        let default = LocalProcessLimits::default();
+        let cpu = worker_key(engine, &args("cpu"), None, None, default);
+
+        assert_eq!(cpu, worker_key(engine, &args("cpu"), None, None, default));
+        assert!(cpu.starts_with("multi|device=cpu|"));
+        assert_ne!(
+            cpu,
+            worker_key(engine, &args("cuda:0"), None, None, default)
+        );
+        let limited = LocalProcessLimits::from_raw(Some(4), Some(true), 16).unwrap();
+        assert_ne!(cpu, worker_key(engine, &args("cpu"), None, None, limited));
+        assert_eq!(limited.env_vars()[0], ("OMP_NUM_THREADS", "4".to_string()));
+        assert_eq!(
+            LocalProcessLimits::from_raw(Some(16), None, 16),
+            Ok(LocalProcessLimits::default())
+        );
+        assert!(LocalProcessLimits::from_raw(Some(0), None, 16).is_err());
+        assert!(LocalProcessLimits::from_raw(Some(17), None, 16).is_err());
+        assert_ne!(
+            cpu,
+            worker_key(engine, &args("cpu"), Some("hf_abc"), None, default)
+        );
+        assert_ne!(
+            cpu,
+            worker_key(engine, &args("cpu"), None, Some(Path::new("/hf")), default)
+        );
+        assert!(worker_key(
+            LocalSegmentationEngine::LegacyWhisper,
+            &[],
+            None,
+            None,
+            default
+        )
+        .starts_with("legacy|device=auto|threads=all|background=false|"));
+    }
+
+    #[test]
+    fn active_worker_key_is_published_during_a_request_only() {
+        {
+            let _active = ActiveWorkerGuard::start("multi|device=cpu");
+            assert_eq!(
+                ACTIVE_WORKER_KEY.lock().unwrap().as_deref(),
+                Some("multi|device=cpu")
+            );
+            assert_eq!(
+                kill_active_worker(|key| key.starts_with("legacy|")),
+                Ok(false)
+            );
+        }
+        assert!(ACTIVE_WORKER_KEY.lock().unwrap().is_none());
+        assert_eq!(kill_active_worker(|_| true), Ok(false));
+    }
+}
@@ -0,0 +1,276 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Emitter};
+
+use crate::binaries;
+use crate::utils::process::{
+    configure_command_no_window, kill_tracked_process, register_tracked_process, untrack_process,
+    wait_tracked_process,
+};
+
+/// Événement de progression générique des commandes média (cut, concat, normalisation).
+pub const FFMPEG_PROGRESS_EVENT: &str = "ffmpeg-progress";
+/// Part de stderr conservée par commande : un fichier corrompu peut produire une erreur
+/// par trame, soit des centaines de Mo sur un long média.
+const MAX_CAPTURED_STDERR_BYTES: usize = 64 * 1024;
+
+/// Avancement d'une commande ffmpeg lancée par `run_with_progress`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FfmpegProgress {
+    /// Position traitée dans la sortie (ms).
+    pub current_time_ms: u64,
+    /// Durée totale attendue (ms, 0 si inconnue).
+    pub total_time_ms: u64,
+    /// Pourcentage entre 0 et 100 (0 si la durée totale est inconnue).
+    pub percent: f64,
+}
+
+/// Extrait la position courante (ms) d'une ligne `-progress` de ffmpeg.
+///
+/// `out_time_us` et `out_time_ms` sont tous deux exprimés en microsecondes ;
+/// `out_time` est au format `HH:MM:SS.micro`.
+pub fn parse_progress_time_ms(line: &str) -> Option<u64> {
+    let (key, value) = line.trim().split_once('=')?;
+    let seconds = match key {
+        "out_time_us" | "out_time_ms" => value.trim().parse::<f64>().ok()? / 1_000_000.0,
+        "out_time" => {
+            let parts: Vec<&str> = value.trim().split(':').collect();
+            if parts.len() != 3 {
+                return None;
+            }
+            let hours = parts[0].parse::<f64>().ok()?;
+            let minutes = parts[1].parse::<f64>().ok()?;
+            let seconds = parts[2].parse::<f64>().ok()?;
+            hours * 3600.0 + minutes * 60.0 + seconds
+        }
+        _ => return None,
+    };
+    (seconds.is_finite() && seconds >= 0.0).then(|| (seconds * 1000.0).round() as u64)
+}
+
+/// Statut de fin et stderr (tronqué) d'une commande ffmpeg menée à son terme.
+pub struct FfmpegOutput {
+    /// Statut de sortie du processus.
+    pub status: ExitStatus,
+    /// Début de stderr, limité à `MAX_CAPTURED_STDERR_BYTES`.
+    pub stderr: String,
+}
+
+/// Lit toutes les lignes d'un flux en ne gardant que les `max_bytes` premiers octets.
+///
+/// Le flux est vidé jusqu'au bout pour ne pas bloquer le processus qui écrit dedans.
+fn read_capped_lines(reader: impl Read, max_bytes: usize) -> String {
+    let mut captured = String::new();
+    let mut truncated = false;
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        if captured.len() + line.len() + 1 > max_bytes {
+            truncated = true;
+            continue;
+        }
+        captured.push_str(&line);
+        captured.push('\n');
+    }
+    if truncated {
+        captured.push_str("... (stderr truncated)\n");
+    }
+    captured.trim_end().to_string()
+}
+
+/// Lance ffmpeg avec `-progress pipe:1`, transmet chaque avancement à `on_progress` et
+/// retourne son statut sans l'interpréter.
+///
+/// @returns Erreur si ffmpeg ne peut pas être lancé ou si le job a été annulé
+fn run_tracked(
+    args: &[String],
+    total_duration_ms: u64,
+    job_id: Option<&str>,
+    mut on_progress: impl FnMut(FfmpegProgress),
+) -> Result<FfmpegOutput, String> {
+    let ffmpeg_path =
+        binaries::resolve_binary("ffmpeg").ok_or_else(|| "ffmpeg binary not found".to_string())?;
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.args(["-nostdin", "-nostats", "-progress", "pipe:1"]);
+    cmd.args(args);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    configure_command_no_window(&mut cmd);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Unable to execute ffmpeg: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "Failed to capture ffmpeg progress".to_string())?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| "Failed to capture ffmpeg stderr".to_string())?;
+    let stderr_handle =
+        std::thread::spawn(move || read_capped_lines(stderr, MAX_CAPTURED_STDERR_BYTES));
+
+    let child = Arc::new(Mutex::new(Some(child)));
+    if let Some(job_id) = job_id {
+        register_tracked_process(&ffmpeg_process_id(job_id), Arc::clone(&child));
+    }
+
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        let Some(current_time_ms) = parse_progress_time_ms(&line) else {
+            continue;
+        };
+        let current_time_ms = if total_duration_ms > 0 {
+            current_time_ms.min(total_duration_ms)
+        } else {
+            current_time_ms
+        };
+        let percent = if total_duration_ms > 0 {
+            (current_time_ms as f64 / total_duration_ms as f64 * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+        on_progress(FfmpegProgress {
+            current_time_ms,
+            total_time_ms: total_duration_ms,
+            percent,
+        });
+    }
+
+    let status = wait_tracked_process(&child);
+    if let Some(job_id) = job_id {
+        untrack_process(&ffmpeg_process_id(job_id));
+    }
+    let stderr = stderr_handle.join().unwrap_or_default();
+
+    let Some(status) = status? else {
+        return Err(format!(
+            "ffmpeg job '{}' was cancelled",
+            job_id.unwrap_or_default()
+        ));
+    };
+    Ok(FfmpegOutput { status, stderr })
+}
+
+/// Lance ffmpeg avec `-progress pipe:1` et transmet chaque avancement à `on_progress`.
+///
+/// @param args - Arguments ffmpeg (sans `-progress`, ajouté automatiquement)
+/// @param total_duration_ms - Durée attendue de la sortie (0 si inconnue)
+/// @param job_id - Identifiant permettant d'annuler la commande via `cancel_ffmpeg_job`
+/// @returns Erreur `ffmpeg error: <stderr>` si ffmpeg échoue
+pub fn run_with_progress_callback(
+    args: &[String],
+    total_duration_ms: u64,
+    job_id: Option<&str>,
+    on_progress: impl FnMut(FfmpegProgress),
+) -> Result<(), String> {
+    let output = run_tracked(args, total_duration_ms, job_id, on_progress)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg error: {}", output.stderr))
+    }
+}
+
+/// Comme `run_with_progress`, mais retourne le statut et stderr au lieu d'échouer.
+///
+/// Sert aux commandes qui analysent elles-mêmes la sortie de ffmpeg (sonde de décodage).
+pub fn run_with_progress_output(
+    args: &[String],
+    total_duration_ms: u64,
+    event_name: &str,
+    app_handle: &AppHandle,
+    job_id: Option<&str>,
+) -> Result<FfmpegOutput, String> {
+    let emit = |progress: f64, current_time_ms: u64| {
+        let _ = app_handle.emit(
+            event_name,
+            serde_json::json!({
+                "jobId": job_id,
+                "progress": progress,
+                "currentTimeMs": current_time_ms,
+                "totalTimeMs": total_duration_ms,
+            }),
+        );
+    };
+    let output = run_tracked(args, total_duration_ms, job_id, |progress| {
+        emit(progress.percent, progress.current_time_ms)
+    })?;
+    if output.status.success() {
+        emit(100.0, total_duration_ms);
+    }
+    Ok(output)
+}
+
+/// Lance ffmpeg et publie son avancement sur l'événement `event_name`.
+///
+/// Le payload contient `jobId`, `progress` (0-100), `currentTimeMs` et `totalTimeMs` ;
+/// un dernier événement à 100 % est émis en cas de succès.
+///
+/// @param args - Arguments ffmpeg (sans `-progress`, ajouté automatiquement)
+/// @param total_duration_ms - Durée attendue de la sortie (0 si inconnue)
+/// @param job_id - Identifiant de corrélation et d'annulation (`cancel_ffmpeg_job`)
+pub fn run_with_progress(
+    args: &[String],
+    total_duration_ms: u64,
+    event_name: &str,
+    app_handle: &AppHandle,
+    job_id: Option<&str>,
+) -> Result<(), String> {
+    let output = run_with_progress_output(args, total_duration_ms, event_name, app_handle, job_id)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg error: {}", output.stderr))
+    }
+}
+
+/// Annule une commande ffmpeg lancée avec un `job_id`.
+///
+/// @returns `true` si une commande en cours a été arrêtée
+pub fn cancel_ffmpeg_job(job_id: &str) -> Result<bool, String> {
+    kill_tracked_process(&ffmpeg_process_id(job_id))
+}
+
+/// Identifiant d'un job ffmpeg dans le registre des processus, distinct des autres opérations.
+fn ffmpeg_process_id(job_id: &str) -> String {
+    format!("ffmpeg:{}", job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ffmpeg_process_id, parse_progress_time_ms, read_capped_lines};
+
+    #[test]
+    fn progress_lines_are_parsed_to_milliseconds() {
+        assert_eq!(parse_progress_time_ms("out_time_us=2500000"), Some(2_500));
+        assert_eq!(parse_progress_time_ms("out_time_ms=1000000"), Some(1_000));
+        assert_eq!(
+            parse_progress_time_ms("out_time=00:01:02.500000"),
+            Some(62_500)
+        );
+        assert_eq!(parse_progress_time_ms("out_time=invalid"), None);
+        assert_eq!(parse_progress_time_ms("out_time_us=N/A"), None);
+        assert_eq!(parse_progress_time_ms("out_time_us=-1000"), None);
+        assert_eq!(parse_progress_time_ms("frame=12"), None);
+        assert_eq!(parse_progress_time_ms("progress=end"), None);
+    }
+
+    #[test]
+    fn job_ids_are_namespaced_in_the_process_registry() {
+        assert_eq!(ffmpeg_process_id("cut-1"), "ffmpeg:cut-1");
+    }
+
+    #[test]
+    fn captured_stderr_is_capped_but_fully_drained() {
+        let stderr = "error 1\nerror 2\nerror 3\n";
+        assert_eq!(
+            read_capped_lines(stderr.as_bytes(), 1024),
+            "error 1\nerror 2\nerror 3"
+        );
+        assert_eq!(
+            read_capped_lines(stderr.as_bytes(), 16),
+            "error 1\nerror 2\n... (stderr truncated)"
+        );
+    }
+}
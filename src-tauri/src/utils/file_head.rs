@@ -0,0 +1,16 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Taille maximale acceptée pour une lecture d'en-tête (évite de charger un fichier entier).
+pub const MAX_FILE_HEAD_BYTES: usize = 1024 * 1024;
+
+/// Lit au plus `max_len` octets au début d'un fichier, sans charger le reste.
+///
+/// Retourne moins de `max_len` octets si le fichier est plus court.
+pub fn read_file_head(path: &Path, max_len: usize) -> std::io::Result<Vec<u8>> {
+    let file = fs::File::open(path)?;
+    let mut head = Vec::with_capacity(max_len.min(MAX_FILE_HEAD_BYTES));
+    file.take(max_len as u64).read_to_end(&mut head)?;
+    Ok(head)
+}
@@ -1,3 +1,7 @@
+/// Utilitaires transverses d'exécution de ffmpeg avec progression.
+pub mod ffmpeg;
+/// Utilitaires transverses de lecture d'en-tetes de fichiers.
+pub mod file_head;
 /// Utilitaires transverses de normalisation de chemins.
 pub mod path;
 /// Utilitaires transverses de gestion de process externes.
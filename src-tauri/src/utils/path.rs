@@ -31,13 +31,54 @@ fn percent_decode(input: &str) -> String {
     String::from_utf8_lossy(&out).to_string()
 }
 
+/// Préfixe Windows des chemins étendus (`\\?\C:\...`, `\\?\UNC\...`).
+const EXTENDED_LENGTH_PREFIX: &str = r"\\?\";
+
+/// Indique si un chemin utilise le préfixe Windows de longueur étendue.
+fn is_extended_length_path(path: &str) -> bool {
+    path.starts_with(EXTENDED_LENGTH_PREFIX)
+}
+
+/// Convertit la partie d'un URI `file://` qui suit le schéma en chemin UNC si elle
+/// désigne un hôte réseau (`server/share/x` ou `//server/share/x`).
+///
+/// @returns `\\server\share\x`, ou `None` pour un chemin local (`/...`, `C:/...`, `localhost/...`)
+fn unc_path_from_file_uri(rest: &str) -> Option<String> {
+    let rest = match rest.strip_prefix("//") {
+        Some(host_path) => host_path.trim_start_matches('/'),
+        None if rest.starts_with('/') => return None,
+        None => rest,
+    };
+    let (host, share_path) = rest.split_once('/')?;
+    let is_drive = host.len() == 2 && host.ends_with(':');
+    if host.is_empty() || is_drive || host.eq_ignore_ascii_case("localhost") {
+        return None;
+    }
+    let share_path = share_path.trim_end_matches('/');
+    if share_path.is_empty() {
+        return None;
+    }
+    Some(format!(r"\\{}\{}", host, share_path.replace('/', "\\")))
+}
+
 /// Normalise un chemin brut provenant de l'UI ou d'un URI `file://`.
+///
+/// Sous Windows, `file://server/share/x` devient le chemin UNC `\\server\share\x` et les
+/// chemins `\\?\...` sont conservés tels quels (ils ne sont jamais décodés).
 pub fn normalize_input_path(raw: &str) -> PathBuf {
     let trimmed = raw.trim();
+    if is_extended_length_path(trimmed) {
+        return PathBuf::from(trimmed);
+    }
     let mut path = trimmed;
 
     if let Some(rest) = trimmed.strip_prefix("file://") {
         path = rest;
+        if cfg!(target_os = "windows") {
+            if let Some(unc_path) = unc_path_from_file_uri(rest) {
+                return PathBuf::from(percent_decode(&unc_path));
+            }
+        }
     }
 
     if let Some(rest) = path.strip_prefix("localhost/") {
@@ -77,7 +118,179 @@ pub fn normalize_output_path(raw: &str) -> PathBuf {
     path
 }
 
-/// Échappe un chemin pour un fichier ffconcat.
+/// Longueur maximale (en caractères) conservée par `sanitize_filename`.
+const MAX_FILENAME_CHARS: usize = 180;
+
+/// Noms de fichiers réservés par Windows, quelle que soit l'extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Indique si un caractère est interdit dans un nom de fichier sur la plateforme courante.
+fn is_illegal_filename_char(c: char) -> bool {
+    if c == '/' || c.is_control() {
+        return true;
+    }
+    cfg!(target_os = "windows") && matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*')
+}
+
+/// Nettoie un nom de fichier (sans dossier) pour qu'il soit créable sur la plateforme courante.
+///
+/// Les caractères interdits sont remplacés par `_`, les points/espaces finaux sont retirés
+/// (refusés par Windows), les noms réservés Windows sont préfixés et la longueur est bornée
+/// en conservant l'extension.
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .trim()
+        .chars()
+        .map(|c| if is_illegal_filename_char(c) { '_' } else { c })
+        .collect();
+    let mut sanitized = replaced.trim_end_matches(['.', ' ']).to_string();
+
+    if sanitized.is_empty() {
+        return "file".to_string();
+    }
+
+    if cfg!(target_os = "windows") {
+        let stem = sanitized.split('.').next().unwrap_or_default();
+        if WINDOWS_RESERVED_NAMES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            sanitized.insert(0, '_');
+        }
+    }
+
+    if sanitized.chars().count() > MAX_FILENAME_CHARS {
+        let (stem, extension) = match sanitized.rfind('.') {
+            Some(idx) if idx > 0 && sanitized.len() - idx <= 10 => {
+                (&sanitized[..idx], &sanitized[idx..])
+            }
+            _ => (sanitized.as_str(), ""),
+        };
+        let keep = MAX_FILENAME_CHARS.saturating_sub(extension.chars().count());
+        let truncated: String = stem.chars().take(keep).collect();
+        sanitized = format!("{}{}", truncated.trim_end_matches(['.', ' ']), extension);
+    }
+
+    sanitized
+}
+
+/// Échappe un chemin pour une entrée `file '...'` d'un fichier ffconcat.
+///
+/// FFmpeg ne traite pas `\` entre apostrophes : une apostrophe ferme la chaîne, est
+/// échappée hors chaîne (`\'`) puis la chaîne est rouverte, soit `'\''`.
 pub fn escape_ffconcat_path(path: &str) -> String {
-    path.replace('\'', "\\'")
+    path.replace('\'', "'\\''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        escape_ffconcat_path, normalize_input_path, percent_decode, sanitize_filename,
+        unc_path_from_file_uri, MAX_FILENAME_CHARS,
+    };
+    use std::path::PathBuf;
+
+    #[test]
+    fn file_uri_with_host_maps_to_unc_path() {
+        assert_eq!(
+            unc_path_from_file_uri("server/share/dir/clip.mp3").as_deref(),
+            Some(r"\\server\share\dir\clip.mp3")
+        );
+        assert_eq!(
+            unc_path_from_file_uri("//server/share/").as_deref(),
+            Some(r"\\server\share")
+        );
+        assert_eq!(unc_path_from_file_uri("/home/user/clip.mp3"), None);
+        assert_eq!(unc_path_from_file_uri("C:/Users/clip.mp3"), None);
+        assert_eq!(unc_path_from_file_uri("localhost/tmp/clip.mp3"), None);
+        assert_eq!(unc_path_from_file_uri("server"), None);
+        if cfg!(target_os = "windows") {
+            assert_eq!(
+                normalize_input_path("file://nas/media/My%20Clip.mp3"),
+                PathBuf::from(r"\\nas\media\My Clip.mp3")
+            );
+            assert_eq!(
+                normalize_input_path("file:///C:/Users/clip.mp3"),
+                PathBuf::from("C:/Users/clip.mp3")
+            );
+        }
+    }
+
+    #[test]
+    fn extended_length_paths_are_kept_verbatim() {
+        assert_eq!(
+            normalize_input_path(r" \\?\C:\Quran\100%25 done.mp3 "),
+            PathBuf::from(r"\\?\C:\Quran\100%25 done.mp3")
+        );
+        assert_eq!(
+            normalize_input_path(r"\\?\UNC\server\share\clip.mp3"),
+            PathBuf::from(r"\\?\UNC\server\share\clip.mp3")
+        );
+    }
+
+    #[test]
+    fn percent_decode_handles_sequences_at_the_end() {
+        assert_eq!(percent_decode("Al%20Fatiha%20"), "Al Fatiha ");
+        assert_eq!(percent_decode("%41"), "A");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%2"), "a%2");
+        assert_eq!(percent_decode("a%zz"), "a%zz");
+        assert_eq!(
+            normalize_input_path("file:///tmp/my%20clip%20"),
+            PathBuf::from("/tmp/my clip ")
+        );
+    }
+
+    #[test]
+    fn sanitize_filename_replaces_separators_and_trailing_dots() {
+        assert_eq!(sanitize_filename("a/b.mp3"), "a_b.mp3");
+        assert_eq!(sanitize_filename("  title. . "), "title");
+        assert_eq!(sanitize_filename(""), "file");
+        if cfg!(target_os = "windows") {
+            assert_eq!(
+                sanitize_filename("Al-Fatiha: 1?*.mp3"),
+                "Al-Fatiha_ 1__.mp3"
+            );
+            assert_eq!(sanitize_filename("con.txt"), "_con.txt");
+        }
+    }
+
+    #[test]
+    fn sanitize_filename_bounds_length_and_keeps_extension() {
+        let long_name = format!("{}.mp4", "a".repeat(400));
+        let sanitized = sanitize_filename(&long_name);
+        assert_eq!(sanitized.chars().count(), MAX_FILENAME_CHARS);
+        assert!(sanitized.ends_with(".mp4"));
+    }
+
+    /// Relit un jeton comme `av_get_token` (FFmpeg) : `\` hors apostrophes, littéral dedans.
+    fn read_ffconcat_token(token: &str) -> String {
+        let mut out = String::new();
+        let mut chars = token.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => out.extend(chars.next()),
+                '\'' => out.extend(chars.by_ref().take_while(|c| *c != '\'')),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn ffconcat_paths_with_apostrophes_and_spaces_round_trip() {
+        for path in [
+            "/tmp/Al-Fatiha recitation.mp3",
+            "/tmp/Qur'an/l'audio d'Ali.mp3",
+            r"C:\Users\Me\Music\Sura 'Yasin'.wav",
+            "'''",
+        ] {
+            let token = format!("'{}'", escape_ffconcat_path(path));
+            assert_eq!(read_ffconcat_token(&token), path);
+        }
+        assert_eq!(escape_ffconcat_path("it's"), r"it'\''s");
+    }
 }
@@ -1,4 +1,14 @@
-﻿/// Configure la commande pour éviter l'ouverture d'une fenêtre console sur Windows.
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, ExitStatus, Output, Stdio};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use sysinfo::{Pid, System};
+
+/// Configure la commande pour éviter l'ouverture d'une fenêtre console sur Windows.
 pub fn configure_command_no_window(cmd: &mut std::process::Command) {
     #[cfg(target_os = "windows")]
     {
@@ -8,11 +18,362 @@ pub fn configure_command_no_window(cmd: &mut std::process::Command) {
     }
 }
 
+/// Lance la commande, en priorité réduite si `low_priority` pour garder la machine réactive.
+///
+/// Windows : classe `BELOW_NORMAL_PRIORITY_CLASS` (sans console). Unix : `renice` juste
+/// après le lancement ; les threads créés ensuite par le processus héritent de la priorité.
+pub fn spawn_command(
+    cmd: &mut std::process::Command,
+    low_priority: bool,
+) -> std::io::Result<std::process::Child> {
+    #[cfg(target_os = "windows")]
+    if low_priority {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x00004000;
+        cmd.creation_flags(CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS);
+    }
+    let child = cmd.spawn()?;
+    #[cfg(unix)]
+    if low_priority {
+        let reniced = std::process::Command::new("renice")
+            .args(["-n", "10", "-p", &child.id().to_string()])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+        if !reniced.is_ok_and(|status| status.success()) {
+            log::warn!("[process] failed to lower priority of pid {}", child.id());
+        }
+    }
+    Ok(child)
+}
+
+/// Délai maximal des sondes courtes (ffprobe, version de Python...).
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Intervalle de scrutation de `output_with_timeout`.
+const OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Lit un tube jusqu'à sa fermeture dans un thread dédié.
+fn read_pipe_in_thread<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+/// Équivalent de `Command::output()` borné dans le temps.
+///
+/// À l'échéance, le processus (et ses descendants) est tué et une erreur
+/// `TimedOut` dont le message commence par `TIMEOUT` est renvoyée.
+///
+/// @param timeout - Durée maximale d'exécution
+pub fn output_with_timeout(
+    cmd: &mut std::process::Command,
+    timeout: Duration,
+) -> std::io::Result<Output> {
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    let mut child = cmd.spawn()?;
+
+    // Lecture en parallèle : un tube plein bloquerait le processus avant sa fin.
+    let stdout_handle = read_pipe_in_thread(child.stdout.take());
+    let stderr_handle = read_pipe_in_thread(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = kill_process_tree(&mut child);
+            let _ = child.wait();
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!(
+                    "TIMEOUT: process did not finish within {}s",
+                    timeout.as_secs_f64()
+                ),
+            ));
+        }
+        std::thread::sleep(OUTPUT_POLL_INTERVAL);
+    };
+    Ok(Output {
+        status,
+        stdout: stdout_handle.join().unwrap_or_default(),
+        stderr: stderr_handle.join().unwrap_or_default(),
+    })
+}
+
+/// Processus enfant partagé entre son propriétaire et l'annulation (`None` une fois terminé ou tué).
+pub type TrackedChild = Arc<Mutex<Option<Child>>>;
+
+/// Processus longs annulables, indexés par identifiant d'opération (export, job ffmpeg, téléchargement...).
+static TRACKED_PROCESSES: LazyLock<Mutex<HashMap<String, TrackedChild>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Intervalle de scrutation de `wait_tracked_process`.
+const TRACKED_WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Enregistre un processus pour qu'il puisse être tué via `kill_tracked_process(id)`.
+pub fn register_tracked_process(id: &str, process: TrackedChild) {
+    if let Ok(mut processes) = TRACKED_PROCESSES.lock() {
+        processes.insert(id.to_string(), process);
+    }
+}
+
+/// Enregistre un processus qui vient d'être lancé et retourne son handle partagé.
+pub fn track_process(id: &str, child: Child) -> TrackedChild {
+    let process = Arc::new(Mutex::new(Some(child)));
+    register_tracked_process(id, Arc::clone(&process));
+    process
+}
+
+/// Retire un processus du registre (à appeler une fois l'opération terminée).
+pub fn untrack_process(id: &str) {
+    if let Ok(mut processes) = TRACKED_PROCESSES.lock() {
+        processes.remove(id);
+    }
+}
+
+/// Attend la fin d'un processus suivi sans bloquer son annulation.
+///
+/// @returns `None` si le processus a été tué par `kill_tracked_process`
+pub fn wait_tracked_process(process: &TrackedChild) -> Result<Option<ExitStatus>, String> {
+    loop {
+        {
+            let mut guard = process
+                .lock()
+                .map_err(|_| "Tracked process lock poisoned".to_string())?;
+            let Some(child) = guard.as_mut() else {
+                return Ok(None);
+            };
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|e| format!("Unable to wait for process: {}", e))?
+            {
+                guard.take();
+                return Ok(Some(status));
+            }
+        }
+        std::thread::sleep(TRACKED_WAIT_POLL_INTERVAL);
+    }
+}
+
+/// Descendants de `root_pid` (enfants, petits-enfants...) d'après les liens `(pid, parent)`.
+fn descendant_pids(root_pid: u32, parent_links: &[(u32, u32)]) -> Vec<u32> {
+    let mut descendants = Vec::new();
+    let mut pending = vec![root_pid];
+    while let Some(parent_pid) = pending.pop() {
+        for &(pid, parent) in parent_links {
+            if parent == parent_pid && pid != root_pid && !descendants.contains(&pid) {
+                descendants.push(pid);
+                pending.push(pid);
+            }
+        }
+    }
+    descendants
+}
+
+/// Tue un processus et tous ses descendants (workers Python, ffmpeg lancé par yt-dlp...).
+///
+/// Les descendants sont relevés avant l'arrêt du parent, qui ne peut donc plus en relancer.
+pub fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+    let mut system = System::new();
+    system.refresh_processes();
+    let parent_links: Vec<(u32, u32)> = system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| {
+            process
+                .parent()
+                .map(|parent| (pid.as_u32(), parent.as_u32()))
+        })
+        .collect();
+    let descendants = descendant_pids(child.id(), &parent_links);
+
+    let result = child.kill();
+    for pid in descendants {
+        if let Some(process) = system.process(Pid::from_u32(pid)) {
+            process.kill();
+        }
+    }
+    result
+}
+
+/// Tue le processus enregistré sous `id` (et ses descendants) puis le retire du registre.
+///
+/// @returns `true` si un processus en cours a été arrêté
+pub fn kill_tracked_process(id: &str) -> Result<bool, String> {
+    let process = TRACKED_PROCESSES
+        .lock()
+        .map_err(|_| "Tracked process registry lock poisoned".to_string())?
+        .remove(id);
+    let Some(process) = process else {
+        return Ok(false);
+    };
+    let child = process
+        .lock()
+        .map_err(|_| "Tracked process lock poisoned".to_string())?
+        .take();
+    let Some(mut child) = child else {
+        return Ok(false);
+    };
+    kill_process_tree(&mut child).map_err(|e| format!("Failed to stop process '{}': {}", id, e))?;
+    let _ = child.wait();
+    Ok(true)
+}
+
+/// Jetons Hugging Face (`hf_` suivi d'au moins 16 caractères alphanumériques).
+fn hf_token_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\bhf_[A-Za-z0-9]{16,}\b").expect("valid HF token regex"))
+}
+
+/// Caractère pouvant prolonger un nom de dossier (`alice` dans `/home/alice2`).
+fn is_path_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Remplace par `~` chaque occurrence du dossier `home` délimitée comme un chemin.
+///
+/// Les séparateurs `/` et `\` sont interchangeables ; un dossier Windows (`C:\Users\...`)
+/// est comparé sans tenir compte de la casse, comme le fait le système de fichiers.
+fn redact_home_dir(text: &str, home: &str) -> String {
+    let is_windows_path = home.contains('\\') || home.get(1..2) == Some(":");
+    let mut pattern = String::from(if is_windows_path { "(?i)" } else { "" });
+    for c in home.chars() {
+        if matches!(c, '/' | '\\') {
+            pattern.push_str(r"[/\\]");
+        } else {
+            pattern.push_str(&regex::escape(&c.to_string()));
+        }
+    }
+    let Ok(home_pattern) = Regex::new(&pattern) else {
+        return text.to_string();
+    };
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for found in home_pattern.find_iter(text) {
+        let before = text[..found.start()].chars().next_back();
+        let after = text[found.end()..].chars().next();
+        if before.is_some_and(is_path_name_char) || after.is_some_and(is_path_name_char) {
+            continue;
+        }
+        redacted.push_str(&text[last_end..found.start()]);
+        redacted.push('~');
+        last_end = found.end();
+    }
+    redacted.push_str(&text[last_end..]);
+    redacted
+}
+
+/// Masque les données personnelles d'un texte : dossier utilisateur remplacé par `~`
+/// et jetons Hugging Face remplacés par `hf_***`.
+fn redact_sensitive_text(text: &str, home_dir: Option<&Path>) -> String {
+    let mut redacted = text.to_string();
+    if let Some(home) = home_dir.map(|home| home.to_string_lossy().to_string()) {
+        let home = home.trim_end_matches(['/', '\\']);
+        // Un dossier racine (`/`, `C:`) masquerait tous les chemins.
+        if home.len() > 3 {
+            redacted = redact_home_dir(&redacted, home);
+        }
+    }
+    hf_token_pattern()
+        .replace_all(&redacted, "hf_***")
+        .into_owned()
+}
+
+/// Masque le dossier de l'utilisateur courant et les jetons Hugging Face d'un texte
+/// destiné à être partagé (rapport de diagnostic, message d'erreur).
+pub fn redact_user_data(text: &str) -> String {
+    redact_sensitive_text(text, dirs::home_dir().as_deref())
+}
+
 /// Extrait un message d'erreur lisible depuis la sortie d'un process.
+///
+/// Le dossier utilisateur et les jetons Hugging Face sont masqués : ces messages sont
+/// affichés dans l'interface et souvent recopiés dans des rapports de bug publics.
 pub fn sanitize_cmd_error(output: &std::process::Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    if !stderr.is_empty() {
-        return stderr;
+    let message = if !stderr.is_empty() {
+        stderr
+    } else {
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    redact_user_data(&message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{descendant_pids, output_with_timeout, redact_sensitive_text};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    #[cfg(unix)]
+    #[test]
+    fn output_with_timeout_kills_hung_commands() {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", "echo ready"]);
+        let output = output_with_timeout(&mut cmd, Duration::from_secs(10)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ready");
+
+        let started = Instant::now();
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", "sleep 30"]);
+        let error = output_with_timeout(&mut cmd, Duration::from_millis(200)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+        assert!(error.to_string().starts_with("TIMEOUT"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn descendants_include_grandchildren_only() {
+        // 10 -> 11 -> 13, 10 -> 12 ; 20 est un processus sans lien.
+        let links = [(11, 10), (12, 10), (13, 11), (20, 1), (10, 1)];
+        let mut descendants = descendant_pids(10, &links);
+        descendants.sort_unstable();
+        assert_eq!(descendants, vec![11, 12, 13]);
+        assert!(descendant_pids(13, &links).is_empty());
+    }
+
+    #[test]
+    fn home_directory_and_hf_tokens_are_redacted() {
+        let text =
+            "Failed to open /home/alice/.cache/huggingface (token hf_AbCdEfGhIjKlMnOpQrStUv)";
+        assert_eq!(
+            redact_sensitive_text(text, Some(Path::new("/home/alice/"))),
+            "Failed to open ~/.cache/huggingface (token hf_***)"
+        );
+
+        let text = r"Error in C:\Users\Alice\AppData\python_envs and C:/Users/Alice/venv";
+        assert_eq!(
+            redact_sensitive_text(text, Some(Path::new(r"C:\Users\Alice"))),
+            r"Error in ~\AppData\python_envs and ~/venv"
+        );
+
+        // Casse différente sous Windows, et dossier voisin au nom plus long conservé.
+        let text = r"Error in c:\users\alice\venv, C:\Users\Alice2\venv and C:\Users\ALICE";
+        assert_eq!(
+            redact_sensitive_text(text, Some(Path::new(r"C:\Users\Alice"))),
+            r"Error in ~\venv, C:\Users\Alice2\venv and ~"
+        );
+        let text = "Paths /home/alice /home/alicex /srv/home/alice/x /home/Alice";
+        assert_eq!(
+            redact_sensitive_text(text, Some(Path::new("/home/alice"))),
+            "Paths ~ /home/alicex /srv/home/alice/x /home/Alice"
+        );
+
+        // Ni préfixe `hf_` interne à un mot, ni identifiant trop court.
+        let text = "module transformers_hf_AbCdEfGhIjKlMnOpQrSt and hf_short";
+        assert_eq!(redact_sensitive_text(text, Some(Path::new("/"))), text);
     }
-    String::from_utf8_lossy(&output.stdout).trim().to_string()
 }
@@ -1,5 +1,16 @@
 ﻿use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Préfixes des fichiers temporaires créés par l'application dans `std::env::temp_dir()`.
+pub const APP_TEMP_FILE_PREFIXES: [&str; 6] = [
+    "qurancaption-seg-",
+    "qurancaption-local-",
+    "qurancaption-mfa-",
+    "qurancaption-hifz-",
+    "qurancaption_requirements_",
+    "concat_audio_",
+];
 
 /// Garde RAII qui supprime automatiquement un fichier temporaire à la sortie de scope.
 pub struct TempFileGuard(pub PathBuf);
@@ -10,3 +21,102 @@ impl Drop for TempFileGuard {
         let _ = fs::remove_file(&self.0);
     }
 }
+
+/// Supprime un fichier ou un dossier temporaire (récursivement) sans propager d'erreur.
+fn remove_temp_path(path: &Path) {
+    if path.is_dir() {
+        let _ = fs::remove_dir_all(path);
+    } else {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Garde RAII qui supprime plusieurs fichiers et dossiers temporaires à la sortie de scope.
+#[derive(Default)]
+pub struct TempPathsGuard(Vec<PathBuf>);
+
+impl TempPathsGuard {
+    /// Enregistre un fichier ou un dossier à supprimer.
+    pub fn add(&mut self, path: impl Into<PathBuf>) {
+        self.0.push(path.into());
+    }
+
+    /// Conserve les chemins enregistrés (aucune suppression à la sortie de scope).
+    pub fn keep(mut self) {
+        self.0.clear();
+    }
+}
+
+impl Drop for TempPathsGuard {
+    /// Supprime les chemins dans l'ordre inverse de leur enregistrement.
+    fn drop(&mut self) {
+        for path in self.0.iter().rev() {
+            remove_temp_path(path);
+        }
+    }
+}
+
+/// Liste les fichiers temporaires de l'application plus anciens que `min_age`.
+///
+/// Retourne le chemin et la taille de chaque fichier orphelin trouvé.
+pub fn find_orphaned_temp_files(min_age: Duration) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(std::env::temp_dir()) else {
+        return Vec::new();
+    };
+    let now = SystemTime::now();
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            APP_TEMP_FILE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let modified = metadata.modified().ok()?;
+            let age = now.duration_since(modified).unwrap_or_default();
+            (age >= min_age).then(|| (entry.path(), metadata.len()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TempPathsGuard;
+    use std::fs;
+
+    #[test]
+    fn temp_paths_guard_removes_files_and_directories_unless_kept() {
+        let root = std::env::temp_dir().join(format!(
+            "qurancaption-temp-guard-test-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join("phonemizer");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested").join("setup.py"), b"").unwrap();
+        let file = root.join("requirements.txt");
+        fs::write(&file, b"numpy").unwrap();
+
+        {
+            let mut guard = TempPathsGuard::default();
+            guard.add(&dir);
+            guard.add(&file);
+        }
+        assert!(!dir.exists());
+        assert!(!file.exists());
+
+        fs::write(&file, b"numpy").unwrap();
+        let mut guard = TempPathsGuard::default();
+        guard.add(&file);
+        guard.keep();
+        assert!(file.exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -130,7 +130,8 @@ export type LocalEngineStatus = {
  */
 export type LocalSegmentationStatus = {
 	ready: boolean;
-	pythonInstalled: boolean;
+	/** `null` si le check a expiré avant de savoir si Python est disponible. */
+	pythonInstalled: boolean | null;
 	packagesInstalled: boolean;
 	message: string;
 	engines?: {